@@ -1,6 +1,7 @@
 use crate::style;
-use crate::style::stylesheet::Stylesheet;
+use crate::style::stylesheet::{ImportContext, Stylesheet};
 use clap::{App, Arg, ArgMatches, SubCommand};
+use std::path::Path;
 use std::str::FromStr;
 
 pub fn setup_and_get_cli_args<'a>() -> ArgMatches<'a> {
@@ -50,6 +51,15 @@ pub fn setup_and_get_cli_args<'a>() -> ArgMatches<'a> {
                 .validator(is_num_validator)
                 .global(true),
         )
+        .arg(
+            Arg::with_name("scroll_y")
+                .long("scroll_y")
+                .value_name("SCROLL_Y")
+                .help(&format!("How far down the document starts out scrolled.  {}", headed_or_headless_applicable))
+                .takes_value(true)
+                .validator(is_num_validator)
+                .global(true),
+        )
         .subcommand(SubCommand::with_name("dump-layout").about(
             "Dumps layout-tree as text to stdout after first global layout, exiting afterwards.  Scale factor must be sup",
         ))
@@ -93,8 +103,8 @@ pub fn stylesheets_from_files<'a>(arg_matches: &'a ArgMatches<'a>) -> Option<Vec
                 style::stylesheet::parse_css_to_stylesheet(
                     Some(stylesheet_path.to_owned()),
                     &mut std::fs::read_to_string(stylesheet_path).expect("file fail"),
+                    ImportContext::for_file(Path::new(stylesheet_path)),
                 )
-                .expect("error parsing stylesheet")
             })
             .collect::<Vec<_>>()
     })
@@ -116,6 +126,10 @@ pub fn scale_factor(arg_matches: &ArgMatches) -> Option<f32> {
     try_get::<f32>(arg_matches, "scale_factor")
 }
 
+pub fn scroll_y(arg_matches: &ArgMatches) -> Option<f32> {
+    try_get::<f32>(arg_matches, "scroll_y")
+}
+
 fn try_get<'a, T: FromStr>(arg_matches: &ArgMatches, arg_name: &'a str) -> Option<T> {
     arg_matches.value_of(arg_name).map(|arg_str| {
         arg_str.parse::<T>().unwrap_or_else(|_| {
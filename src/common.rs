@@ -1,3 +1,5 @@
+use crate::style::values::computed::{Direction, WritingMode};
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Side {
     Bottom,
@@ -5,3 +7,69 @@ pub enum Side {
     Right,
     Top,
 }
+
+/// https://drafts.csswg.org/css-writing-modes-4/#logical-directions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogicalDirection {
+    BlockStart,
+    BlockEnd,
+    InlineStart,
+    InlineEnd,
+}
+
+impl LogicalDirection {
+    /// The physical side this flow-relative direction maps to for the given `writing-mode` and
+    /// `direction`.
+    ///
+    /// Maps to this table: https://drafts.csswg.org/css-writing-modes-4/#logical-to-physical
+    pub fn physical_side(self, writing_mode: WritingMode, direction: Direction) -> Side {
+        match (writing_mode, direction, self) {
+            (
+                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
+                Direction::Ltr,
+                LogicalDirection::InlineStart,
+            )
+            | (
+                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
+                Direction::Rtl,
+                LogicalDirection::InlineEnd,
+            )
+            | (WritingMode::SidewaysLr, Direction::Ltr, LogicalDirection::InlineEnd)
+            | (WritingMode::SidewaysLr, Direction::Rtl, LogicalDirection::InlineStart)
+            | (WritingMode::HorizontalTb, _, LogicalDirection::BlockStart) => Side::Top,
+            (
+                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
+                Direction::Ltr,
+                LogicalDirection::InlineEnd,
+            )
+            | (
+                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
+                Direction::Rtl,
+                LogicalDirection::InlineStart,
+            )
+            | (WritingMode::SidewaysLr, Direction::Ltr, LogicalDirection::InlineStart)
+            | (WritingMode::SidewaysLr, Direction::Rtl, LogicalDirection::InlineEnd)
+            | (WritingMode::HorizontalTb, _, LogicalDirection::BlockEnd) => Side::Bottom,
+            (WritingMode::VerticalRl | WritingMode::SidewaysRl, _, LogicalDirection::BlockEnd)
+            | (
+                WritingMode::VerticalLr | WritingMode::SidewaysLr,
+                _,
+                LogicalDirection::BlockStart,
+            )
+            | (WritingMode::HorizontalTb, Direction::Ltr, LogicalDirection::InlineStart)
+            | (WritingMode::HorizontalTb, Direction::Rtl, LogicalDirection::InlineEnd) => {
+                Side::Left
+            }
+            (
+                WritingMode::VerticalRl | WritingMode::SidewaysRl,
+                _,
+                LogicalDirection::BlockStart,
+            )
+            | (WritingMode::VerticalLr | WritingMode::SidewaysLr, _, LogicalDirection::BlockEnd)
+            | (WritingMode::HorizontalTb, Direction::Ltr, LogicalDirection::InlineEnd)
+            | (WritingMode::HorizontalTb, Direction::Rtl, LogicalDirection::InlineStart) => {
+                Side::Right
+            }
+        }
+    }
+}
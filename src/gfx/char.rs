@@ -178,16 +178,21 @@ impl CharHandle {
         Ok(char_ref)
     }
 
-    // TODO: Not sure these `prepare` functions belong here in CharHandle...
+    // TODO: Not sure this `prepare` function belongs here in CharHandle...
+    /// Prepares `glyph` to be painted.
     pub fn prepare_char(
         &self,
         display_list: &mut DisplayList,
-        ch: char,
-        color: RGBA,
-        font: &Font,
-        size: Au,
+        glyph: PositionedChar,
         scale_factor: f32,
     ) -> Result<(), CharError> {
+        let PositionedChar {
+            ch,
+            font,
+            size,
+            color,
+            origin,
+        } = glyph;
         let opengl_char = self.get_char(font, size, ch)?;
         display_list.push(DisplayCommand::Char(CharCommand::new(
             opengl_char.advance(),
@@ -195,27 +200,19 @@ impl CharHandle {
             ch,
             color,
             Vector2F::new(size.to_f32_px(), size.to_f32_px()) * scale_factor,
-            // TODO: These starting x and y coordinates need to be determined by layout once inline
-            // layout is implemented.  For now, all characters will be painted over the top of  each
-            // other at 0,0.
-            Vector2F::new(300., 300.),
+            origin,
             opengl_char.texture.id(),
         )));
         Ok(())
     }
+}
 
-    pub fn prepare_str(
-        &self,
-        display_list: &mut DisplayList,
-        color: RGBA,
-        font: &Font,
-        str: &str,
-        size: Au,
-        scale_factor: f32,
-    ) -> Result<(), CharError> {
-        for char in str.chars() {
-            self.prepare_char(display_list, char, color, font, size, scale_factor)?;
-        }
-        Ok(())
-    }
+/// A character to be painted, along with the font, size and color it's painted in.
+pub struct PositionedChar<'a> {
+    pub ch: char,
+    pub font: &'a Font,
+    pub size: Au,
+    pub color: RGBA,
+    /// Where the character's origin goes, in device pixels.
+    pub origin: Vector2F,
 }
@@ -1,8 +1,9 @@
-use crate::gfx::char::CharHandle;
+use crate::gfx::char::{CharHandle, PositionedChar};
 use crate::gfx::font::FontHandle;
+use crate::layout::inline::FragmentKind;
 use crate::layout::layout_box::{BoxType, LayoutBox};
-use crate::layout::rect::Rect;
-use crate::style::values::computed::LineStyle;
+use crate::layout::rect::{EdgeSizes, Rect};
+use crate::style::values::computed::{ComputedValues, LineStyle};
 use crate::Side;
 use app_units::Au;
 use cssparser::RGBA;
@@ -13,23 +14,17 @@ use pathfinder_geometry::vector::Vector2F;
 pub fn build_display_list(
     layout_box: &LayoutBox,
     char_handle: &CharHandle,
+    font_handle: &FontHandle,
     scale_factor: f32,
 ) -> DisplayList {
     let mut display_list = Vec::new();
-    // TODO: Remove the three preceeding statements once text rendering is fixed.
-    // let font_handle = FontHandle::new();
-    // let font = font_handle.get_font("Helvetica").unwrap();
-    // char_handle
-    //     .prepare_char(
-    //         &mut display_list,
-    //         'A',
-    //         RGBA::new(0, 0, 0, 1),
-    //         &font,
-    //         Au::from_f32_px(12.),
-    //         scale_factor,
-    //     )
-    //     .unwrap();
-    prepare_layout_box(&mut display_list, &layout_box);
+    prepare_layout_box(
+        &mut display_list,
+        &layout_box,
+        char_handle,
+        font_handle,
+        scale_factor,
+    );
     display_list
 }
 
@@ -133,7 +128,13 @@ impl CharCommand {
 
 /// Prepares a layout box for display in the correct order.  The order in which each part of a box
 /// is painted is defined here: https://www.w3.org/TR/CSS22/zindex.html
-fn prepare_layout_box(display_list: &mut DisplayList, layout_box: &LayoutBox) {
+fn prepare_layout_box(
+    display_list: &mut DisplayList,
+    layout_box: &LayoutBox,
+    char_handle: &CharHandle,
+    font_handle: &FontHandle,
+    scale_factor: f32,
+) {
     // Step 1 of painting order
     if layout_box.is_root() {
         // Step 1.1
@@ -146,55 +147,119 @@ fn prepare_layout_box(display_list: &mut DisplayList, layout_box: &LayoutBox) {
 
     #[allow(clippy::single_match)]
     match layout_box.box_type() {
-        BoxType::Block => {
+        // Inline-blocks paint their own background and borders like blocks, as part of the
+        // inline content they're placed in.
+        BoxType::Block | BoxType::InlineBlock => {
             // Step 2 of painting order
             prepare_block_listitem_block_equiv(display_list, layout_box)
         }
         BoxType::Anonymous | BoxType::AnonymousInline | BoxType::Inline => {
+            // Step 7.2.1 of painting order, for each line the box was placed on.  This paints
+            // boxes in tree order rather than line by line, which only differs when a later box
+            // overlaps an earlier line.
             // TODO: Implement other steps of painting order, 3 -> 10
-            // println!("skipping render of non-block box")
+            prepare_fragments(
+                display_list,
+                layout_box,
+                char_handle,
+                font_handle,
+                scale_factor,
+            );
         }
     }
 
     for child in layout_box.children() {
-        prepare_layout_box(display_list, child);
+        prepare_layout_box(display_list, child, char_handle, font_handle, scale_factor);
     }
 }
 
 fn prepare_block_listitem_block_equiv(display_list: &mut DisplayList, layout_box: &LayoutBox) {
-    prepare_background(display_list, layout_box);
-    prepare_borders(display_list, layout_box);
-    // TODO: Render text - https://learnopengl.com/In-Practice/Text-Rendering
+    let cvs = layout_box.computed_values();
+    let d = layout_box.physical_dimensions();
+    prepare_background(display_list, &cvs, d.border_box());
+    prepare_borders(display_list, &cvs, d.border_box(), d.border);
 }
 
-/// Prepares the background of `layout_box` for display by converting it to display command(s).
-fn prepare_background(display_list: &mut DisplayList, layout_box: &LayoutBox) {
-    let bg_color = layout_box.computed_values().background_color.rgba();
+/// Prepares the fragments inline layout placed `layout_box` into for display: the backgrounds and
+/// borders of inline box fragments, and the glyphs of text fragments.
+fn prepare_fragments(
+    display_list: &mut DisplayList,
+    layout_box: &LayoutBox,
+    char_handle: &CharHandle,
+    font_handle: &FontHandle,
+    scale_factor: f32,
+) {
+    let cvs = layout_box.computed_values();
+    for fragment in layout_box.fragments() {
+        match &fragment.kind {
+            FragmentKind::InlineBox { border, .. } => {
+                let border_box = fragment.border_box();
+                prepare_background(display_list, &cvs, border_box);
+                prepare_borders(display_list, &cvs, border_box, *border);
+            }
+            FragmentKind::Text(text) => {
+                let size = Au::from_f32_px(text.font_size.px());
+                for glyph in &text.glyphs {
+                    let font = match font_handle.loaded_font(&glyph.font) {
+                        Some(font) => font,
+                        None => continue,
+                    };
+                    // Glyphs the font can't rasterize are left unpainted rather than failing the
+                    // whole paint.
+                    let _ = char_handle.prepare_char(
+                        display_list,
+                        PositionedChar {
+                            ch: glyph.ch,
+                            font: &font,
+                            size,
+                            color: cvs.color.rgba(),
+                            origin: Vector2F::new(glyph.origin_x, glyph.origin_y),
+                        },
+                        scale_factor,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Prepares the background of the box (or box fragment) with the given border box for display by
+/// converting it to display command(s).
+fn prepare_background(display_list: &mut DisplayList, cvs: &ComputedValues, border_box: Rect) {
+    let bg_color = cvs.background_color.rgba();
     if bg_color != RGBA::transparent() {
-        display_list.push(DisplayCommand::RectSolidColor(
-            bg_color,
-            layout_box.physical_dimensions().border_box(),
-        ))
+        display_list.push(DisplayCommand::RectSolidColor(bg_color, border_box))
     }
 }
 
-/// Prepares the borders of `layout_box` for display by converting them to display commands.
-fn prepare_borders(display_list: &mut DisplayList, layout_box: &LayoutBox) {
-    prepare_border(display_list, layout_box, Side::Bottom);
-    prepare_border(display_list, layout_box, Side::Left);
-    prepare_border(display_list, layout_box, Side::Right);
-    prepare_border(display_list, layout_box, Side::Top);
+/// Prepares the borders of the box (or box fragment) with the given border box for display by
+/// converting them to display commands.
+fn prepare_borders(
+    display_list: &mut DisplayList,
+    cvs: &ComputedValues,
+    border_box: Rect,
+    border: EdgeSizes,
+) {
+    prepare_border(display_list, cvs, border_box, border, Side::Bottom);
+    prepare_border(display_list, cvs, border_box, border, Side::Left);
+    prepare_border(display_list, cvs, border_box, border, Side::Right);
+    prepare_border(display_list, cvs, border_box, border, Side::Top);
 }
 
-/// Prepares the border `side` of `layout_box` for display by converting it to a display command.
-fn prepare_border(display_list: &mut DisplayList, layout_box: &LayoutBox, side: Side) {
-    let cvs = layout_box.computed_values();
-    let d = layout_box.physical_dimensions();
+/// Prepares the border `side` of the box (or box fragment) with the given border box for display
+/// by converting it to a display command.
+fn prepare_border(
+    display_list: &mut DisplayList,
+    cvs: &ComputedValues,
+    border_box: Rect,
+    border: EdgeSizes,
+    side: Side,
+) {
     let border_style = cvs.border_style(side);
     let border_color_rgba = cvs.border_color_rgba(side);
     // The border size has already been calculated during layout, so we don't need to get it from
     // the computed values here.
-    let border_size_px = d.border_size(side);
+    let border_size_px = border.get(side);
     if border_style == LineStyle::None
         || border_style == LineStyle::Hidden
         || border_color_rgba == RGBA::transparent()
@@ -203,7 +268,6 @@ fn prepare_border(display_list: &mut DisplayList, layout_box: &LayoutBox, side:
         return;
     }
 
-    let border_box = d.border_box();
     match side {
         Side::Bottom => display_list.push(DisplayCommand::RectSolidColor(
             border_color_rgba,
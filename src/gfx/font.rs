@@ -1,44 +1,352 @@
+use crate::style::font_face::{FontFaceRule, FontFaceSource};
+use crate::style::values::computed::font::{GenericFontFamily, SingleFontFamily};
+use crate::style::values::computed::{ComputedValues, FontStyle};
 use accountable_refcell::{Ref, RefCell};
 use font_kit::error::{FontLoadingError, SelectionError};
+use font_kit::family_name::FamilyName;
 use font_kit::loaders::default::Font;
+use font_kit::properties::{Properties, Stretch, Style, Weight};
 use font_kit::source::SystemSource;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Provides a handle for loading and caching fonts that abstracts over all different font loaders
 /// and sources.
 #[derive(Default)]
 pub struct FontHandle {
-    cached_fonts: RefCell<HashMap<String, Font>>,
+    cached_fonts: RefCell<HashMap<PostscriptName, Font>>,
+    /// The system font each family and set of properties previously matched, or `None` if the
+    /// family isn't installed.  Pages use few distinct combinations of these, so a linear search
+    /// is cheap compared to matching against the system's fonts.
+    matched_system_fonts: RefCell<Vec<(FamilyName, Properties, Option<PostscriptName>)>>,
+    /// The faces loaded from @font-face rules, in the order of the rules.
+    web_fonts: Vec<WebFont>,
 }
 
 pub type PostscriptName = String;
 
+/// A font face loaded from an @font-face rule.
+struct WebFont {
+    rule: FontFaceRule,
+    /// The face's key into the cached fonts.
+    postscript_name: PostscriptName,
+}
+
+impl WebFont {
+    /// How well this face's style fits `style`, lower being better.
+    ///
+    /// https://drafts.csswg.org/css-fonts-4/#font-style-matching (step 4.b)
+    fn style_rank(&self, style: Style) -> usize {
+        let preference = match style {
+            Style::Normal => [Style::Normal, Style::Oblique, Style::Italic],
+            Style::Italic => [Style::Italic, Style::Oblique, Style::Normal],
+            Style::Oblique => [Style::Oblique, Style::Italic, Style::Normal],
+        };
+        let face_style = font_kit_style(self.rule.style);
+        preference
+            .iter()
+            .position(|preferred| *preferred == face_style)
+            .unwrap()
+    }
+
+    /// How well this face's weight range fits `weight`, lower being better.  Faces whose range
+    /// includes the weight fit best.  Otherwise, for weights of 400 to 500 the next heavier faces
+    /// up to 500 are preferred, then lighter faces, then heavier ones; lighter weights prefer
+    /// lighter faces, and heavier weights prefer heavier faces.
+    ///
+    /// https://drafts.csswg.org/css-fonts-4/#font-style-matching (step 4.c)
+    fn weight_rank(&self, weight: f32) -> (usize, f32) {
+        let (min, max) = self.rule.weight;
+        if (min..=max).contains(&weight) {
+            return (0, 0.);
+        }
+        let lighter = max < weight;
+        let distance = if lighter { weight - max } else { min - weight };
+        let group = if (400. ..=500.).contains(&weight) {
+            match (lighter, min <= 500.) {
+                (false, true) => 1,
+                (true, _) => 2,
+                (false, false) => 3,
+            }
+        } else if lighter == (weight < 400.) {
+            1
+        } else {
+            2
+        };
+        (group, distance)
+    }
+}
+
 impl FontHandle {
     pub fn new() -> FontHandle {
         FontHandle {
             cached_fonts: RefCell::new(HashMap::new()),
+            matched_system_fonts: RefCell::new(Vec::new()),
+            web_fonts: Vec::new(),
         }
     }
 
-    pub fn get_font(&self, postscript_name: &str) -> Result<Ref<Font>, FontError> {
-        let key = postscript_name.to_owned();
-        {
-            let mut cached_fonts = self.cached_fonts.borrow_mut();
-            if !cached_fonts.contains_key(&key) {
-                let font = load_font(postscript_name)?;
-                cached_fonts.insert(key.clone(), font);
+    /// Loads the faces of the given @font-face rules, replacing any previously loaded.  A face is
+    /// loaded from the first of its sources that can be; faces with no family name or no
+    /// loadable source are skipped.
+    pub fn load_font_faces<'a>(&mut self, rules: impl IntoIterator<Item = &'a FontFaceRule>) {
+        self.web_fonts.clear();
+        for rule in rules {
+            if rule.family.is_none() {
+                continue;
             }
+            let font = match rule.sources.iter().find_map(|source| {
+                load_font_face_source(source)
+                    .map_err(|err| dbg!(source, err))
+                    .ok()
+            }) {
+                Some(font) => font,
+                None => continue,
+            };
+            let postscript_name = font.postscript_name().unwrap_or_else(|| font.full_name());
+            self.cached_fonts
+                .borrow_mut()
+                .insert(postscript_name.clone(), font);
+            self.web_fonts.push(WebFont {
+                rule: rule.clone(),
+                postscript_name,
+            });
         }
+    }
+
+    /// Returns the first available font for `query`, which is used for font metrics.
+    ///
+    /// https://drafts.csswg.org/css-fonts-4/#first-available-font
+    pub fn get_font(&self, query: &FontQuery) -> Result<Ref<'_, Font>, FontError> {
+        self.get_font_for_char(query, ' ')
+    }
+
+    /// Returns the font best matching `query` that may be used to render `ch`.  See
+    /// `match_font_for_char`.
+    pub fn get_font_for_char(
+        &self,
+        query: &FontQuery,
+        ch: char,
+    ) -> Result<Ref<'_, Font>, FontError> {
+        let postscript_name = self.match_font_for_char(query, ch)?;
+        Ok(self.loaded_font(&postscript_name).unwrap())
+    }
+
+    /// Returns the name of the font best matching `query` that may be used to render `ch`,
+    /// loading it if necessary.  The query's families are tried in order.  Faces from @font-face
+    /// rules take precedence over system fonts, and hide any system family of the same name.  If
+    /// none of the families are available, the system's sans-serif font is used instead.
+    ///
+    /// https://drafts.csswg.org/css-fonts-4/#font-style-matching
+    pub fn match_font_for_char(
+        &self,
+        query: &FontQuery,
+        ch: char,
+    ) -> Result<PostscriptName, FontError> {
+        let fallback = FamilyName::SansSerif;
+        for family in query.families.iter().chain(std::iter::once(&fallback)) {
+            let postscript_name = match family {
+                FamilyName::Title(name) if self.has_web_font_family(name) => {
+                    self.match_web_font(name, &query.properties, ch)
+                }
+                _ => self.match_system_font(family, &query.properties)?,
+            };
+            if let Some(postscript_name) = postscript_name {
+                return Ok(postscript_name);
+            }
+        }
+        Err(FontError::Selection(SelectionError::NotFound))
+    }
+
+    /// Returns a font this handle has already loaded, given the name it was matched by.
+    pub fn loaded_font(&self, postscript_name: &str) -> Option<Ref<'_, Font>> {
         let cached_fonts = self.cached_fonts.borrow();
-        let font = Ref::map(cached_fonts, |fonts| fonts.get(&key).unwrap());
-        Ok(font)
+        if !cached_fonts.contains_key(postscript_name) {
+            return None;
+        }
+        Some(Ref::map(cached_fonts, |fonts| {
+            fonts.get(postscript_name).unwrap()
+        }))
+    }
+
+    fn has_web_font_family(&self, family: &str) -> bool {
+        self.web_fonts
+            .iter()
+            .any(|web_font| web_font.rule.family.as_deref() == Some(family))
+    }
+
+    /// Matches `properties` against the faces of the given @font-face family that cover `ch`.
+    fn match_web_font(
+        &self,
+        family: &str,
+        properties: &Properties,
+        ch: char,
+    ) -> Option<PostscriptName> {
+        let rank = |web_font: &WebFont| {
+            (
+                web_font.style_rank(properties.style),
+                web_font.weight_rank(properties.weight.0),
+            )
+        };
+        self.web_fonts
+            .iter()
+            .filter(|web_font| {
+                web_font.rule.family.as_deref() == Some(family) && web_font.rule.covers(ch)
+            })
+            .min_by(|a, b| rank(a).partial_cmp(&rank(b)).unwrap())
+            .map(|web_font| web_font.postscript_name.clone())
+    }
+
+    /// Matches `properties` against the installed fonts of the given family, loading the best
+    /// match if it hasn't been already.
+    fn match_system_font(
+        &self,
+        family: &FamilyName,
+        properties: &Properties,
+    ) -> Result<Option<PostscriptName>, FontError> {
+        let matched = self
+            .matched_system_fonts
+            .borrow()
+            .iter()
+            .find(|(matched_family, matched_properties, _)| {
+                matched_family == family && matched_properties == properties
+            })
+            .map(|(_, _, postscript_name)| postscript_name.clone());
+        if let Some(postscript_name) = matched {
+            return Ok(postscript_name);
+        }
+        let postscript_name = match SystemSource::new()
+            .select_best_match(std::slice::from_ref(family), properties)
+        {
+            Ok(handle) => {
+                let font = handle.load()?;
+                let postscript_name = font.postscript_name().unwrap_or_else(|| font.full_name());
+                self.cached_fonts
+                    .borrow_mut()
+                    .entry(postscript_name.clone())
+                    .or_insert(font);
+                Some(postscript_name)
+            }
+            Err(SelectionError::NotFound) => None,
+            Err(err) => return Err(err.into()),
+        };
+        self.matched_system_fonts.borrow_mut().push((
+            family.clone(),
+            *properties,
+            postscript_name.clone(),
+        ));
+        Ok(postscript_name)
+    }
+}
+
+/// The font properties to select a font by: a prioritized list of families, and the style,
+/// weight and stretch to look for within the first available family.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontQuery {
+    pub families: Vec<FamilyName>,
+    pub properties: Properties,
+}
+
+impl FontQuery {
+    /// The query for the font computed values of an element.
+    pub fn new(computed_values: &ComputedValues) -> FontQuery {
+        let families = computed_values
+            .font_family
+            .families
+            .iter()
+            .map(|family| match family {
+                SingleFontFamily::FamilyName(name) => FamilyName::Title(name.clone()),
+                SingleFontFamily::Generic(generic) => generic_family_name(*generic),
+            })
+            .collect();
+        let mut properties = Properties::new();
+        properties
+            .style(font_kit_style(computed_values.font_style))
+            .weight(Weight(computed_values.font_weight.0))
+            .stretch(Stretch((computed_values.font_stretch.0).0));
+        FontQuery {
+            families,
+            properties,
+        }
+    }
+}
+
+fn font_kit_style(font_style: FontStyle) -> Style {
+    match font_style {
+        FontStyle::Normal => Style::Normal,
+        FontStyle::Italic => Style::Italic,
+        FontStyle::Oblique => Style::Oblique,
     }
 }
 
-fn load_font(postscript_name: &str) -> Result<Font, FontError> {
-    Ok(SystemSource::new()
-        .select_by_postscript_name(postscript_name)?
-        .load()?)
+/// font-kit has no `system-ui` family, so it's approximated by the sans-serif family, which is
+/// what most platforms' UI fonts are.
+fn generic_family_name(generic: GenericFontFamily) -> FamilyName {
+    match generic {
+        GenericFontFamily::Cursive => FamilyName::Cursive,
+        GenericFontFamily::Fantasy => FamilyName::Fantasy,
+        GenericFontFamily::Monospace => FamilyName::Monospace,
+        GenericFontFamily::SansSerif | GenericFontFamily::SystemUi => FamilyName::SansSerif,
+        GenericFontFamily::Serif => FamilyName::Serif,
+    }
+}
+
+/// Font metrics needed to resolve font-relative lengths and lay out lines of text, as fractions of
+/// the font's em size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FontMetrics {
+    /// The distance from the baseline to the top of the font's em box.
+    pub ascent: f32,
+    /// The distance from the baseline to the bottom of the font's em box, as a positive number.
+    pub descent: f32,
+    /// The extra space the font recommends between lines, used for `line-height: normal`.
+    pub line_gap: f32,
+    /// The height of a lowercase "x", used by the `ex` unit.  `None` if the font doesn't say.
+    pub x_height: Option<f32>,
+    /// The advance of the "0" glyph, used by the `ch` unit.  `None` if the font has no such glyph.
+    pub zero_advance: Option<f32>,
+}
+
+/// The advance of the glyph `font` uses for `ch`, as a fraction of the font's em size.  Characters
+/// the font has no glyph for are measured as its missing glyph.
+pub fn glyph_advance(font: &Font, ch: char) -> f32 {
+    let glyph_id = font.glyph_for_char(ch).unwrap_or(0);
+    font.advance(glyph_id)
+        .map(|advance| advance.x() / font.metrics().units_per_em as f32)
+        .unwrap_or(0.)
+}
+
+impl FontMetrics {
+    pub fn new(font: &Font) -> FontMetrics {
+        let metrics = font.metrics();
+        let units_per_em = metrics.units_per_em as f32;
+        let zero_advance = font
+            .glyph_for_char('0')
+            .and_then(|glyph_id| font.advance(glyph_id).ok())
+            .map(|advance| advance.x() / units_per_em);
+        FontMetrics {
+            ascent: metrics.ascent / units_per_em,
+            descent: -metrics.descent / units_per_em,
+            line_gap: metrics.line_gap / units_per_em,
+            x_height: Some(metrics.x_height / units_per_em).filter(|x_height| *x_height > 0.),
+            zero_advance,
+        }
+    }
+}
+
+/// Loads the font file or installed font an @font-face source refers to.  Files are read into
+/// memory and handed to the font loader as is, so they must be TrueType or OpenType fonts or
+/// collections.
+fn load_font_face_source(source: &FontFaceSource) -> Result<Font, FontError> {
+    match source {
+        FontFaceSource::Url(path) => {
+            let bytes = std::fs::read(path).map_err(FontLoadingError::Io)?;
+            Ok(Font::from_bytes(Arc::new(bytes), 0)?)
+        }
+        FontFaceSource::Local(name) => Ok(SystemSource::new()
+            .select_by_postscript_name(name)?
+            .load()?),
+    }
 }
 
 // TODO: This error type seems a bit too general.  May want to refactor as this module evolves.
@@ -59,3 +367,91 @@ impl From<SelectionError> for FontError {
         FontError::Selection(err)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cssparser::{SourceLocation, UnicodeRange};
+    use std::path::Path;
+
+    fn web_font(postscript_name: &str, weight: (f32, f32), style: FontStyle) -> WebFont {
+        WebFont {
+            rule: FontFaceRule {
+                family: Some("Brand".to_owned()),
+                sources: Vec::new(),
+                weight,
+                style,
+                unicode_range: Vec::new(),
+                source_location: SourceLocation { line: 0, column: 0 },
+            },
+            postscript_name: postscript_name.to_owned(),
+        }
+    }
+
+    #[test]
+    fn matches_web_fonts_by_style_then_weight() {
+        let font_handle = FontHandle {
+            web_fonts: vec![
+                web_font("Light", (300., 300.), FontStyle::Normal),
+                web_font("Regular", (400., 400.), FontStyle::Normal),
+                web_font("Semibold", (600., 600.), FontStyle::Normal),
+                web_font("Variable", (700., 900.), FontStyle::Normal),
+                web_font("Italic", (400., 400.), FontStyle::Italic),
+            ],
+            ..FontHandle::new()
+        };
+        let matched = |style, weight| {
+            let mut properties = Properties::new();
+            properties.style(style).weight(Weight(weight));
+            font_handle.match_web_font("Brand", &properties, 'a')
+        };
+        assert_eq!(matched(Style::Normal, 400.).as_deref(), Some("Regular"));
+        assert_eq!(matched(Style::Normal, 800.).as_deref(), Some("Variable"));
+        assert_eq!(matched(Style::Normal, 650.).as_deref(), Some("Variable"));
+        assert_eq!(matched(Style::Normal, 500.).as_deref(), Some("Regular"));
+        assert_eq!(matched(Style::Normal, 200.).as_deref(), Some("Light"));
+        assert_eq!(matched(Style::Normal, 350.).as_deref(), Some("Light"));
+        assert_eq!(matched(Style::Oblique, 700.).as_deref(), Some("Italic"));
+        assert_eq!(
+            font_handle.match_web_font("Other", &Properties::new(), 'a'),
+            None
+        );
+    }
+
+    #[test]
+    fn matches_faces_loaded_from_files_by_unicode_range() {
+        let font_face = |file: &str, unicode_range: Vec<UnicodeRange>| FontFaceRule {
+            family: Some("Kosmonaut Test".to_owned()),
+            sources: vec![FontFaceSource::Url(Path::new("tests/fonts").join(file))],
+            weight: (400., 400.),
+            style: FontStyle::Normal,
+            unicode_range,
+            source_location: SourceLocation { line: 0, column: 0 },
+        };
+        // The digits face only covers some of the digits it has glyphs for, so the rest fall
+        // through to the letters face, whose rule has no unicode-range.
+        let rules = vec![
+            font_face(
+                "kosmonaut-test-digits.ttf",
+                vec![UnicodeRange {
+                    start: 0x30,
+                    end: 0x34,
+                }],
+            ),
+            font_face("kosmonaut-test-letters.ttf", Vec::new()),
+        ];
+        let mut font_handle = FontHandle::new();
+        font_handle.load_font_faces(&rules);
+        assert_eq!(font_handle.web_fonts.len(), 2);
+
+        let query = FontQuery {
+            families: vec![FamilyName::Title("Kosmonaut Test".to_owned())],
+            properties: Properties::new(),
+        };
+        let matched = |ch| font_handle.match_font_for_char(&query, ch).unwrap();
+        assert_eq!(matched('0'), "KosmonautTest-Digits");
+        assert_eq!(matched('7'), "KosmonautTest-Letters");
+        assert_eq!(matched('a'), "KosmonautTest-Letters");
+        assert!(font_handle.loaded_font("KosmonautTest-Digits").is_some());
+    }
+}
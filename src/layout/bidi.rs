@@ -0,0 +1,871 @@
+//! Resolving the embedding levels of bidirectional text, and reordering it for display, following
+//! the Unicode Bidirectional Algorithm.
+//!
+//! The bidi classes of characters come from a condensed version of the Unicode Character
+//! Database's `DerivedBidiClass.txt`, which covers the right-to-left scripts, digits, punctuation
+//! and formatting characters.  Characters it doesn't list are treated as left-to-right.
+//!
+//! https://www.unicode.org/reports/tr9/
+
+use crate::style::values::computed::Direction;
+use std::ops::Range;
+
+/// An embedding level.  Text at even levels runs left-to-right, and text at odd levels runs
+/// right-to-left.
+pub type Level = u8;
+
+/// The deepest level explicit embeddings and isolates can reach (BD2).
+const MAX_DEPTH: Level = 125;
+
+/// The largest number of nested bracket pairs that are matched (BD16).
+const MAX_BRACKET_DEPTH: usize = 63;
+
+/// The embedding levels of some text, resolved paragraph by paragraph.
+#[derive(Clone, Debug)]
+pub struct BidiInfo {
+    /// The level of each character.  Characters the algorithm removes, such as embedding
+    /// controls, are given the level of the character before them.
+    pub levels: Vec<Level>,
+    pub paragraphs: Vec<Paragraph>,
+}
+
+/// A paragraph of bidirectional text, which is resolved independently of the others.
+#[derive(Clone, Debug)]
+pub struct Paragraph {
+    /// The characters in the paragraph, including the separator that ends it.
+    pub range: Range<usize>,
+    pub level: Level,
+}
+
+pub fn is_rtl(level: Level) -> bool {
+    level % 2 == 1
+}
+
+/// The base level of paragraphs with the given `direction`.
+pub fn direction_level(direction: Direction) -> Level {
+    match direction {
+        Direction::Ltr => 0,
+        Direction::Rtl => 1,
+    }
+}
+
+/// Resolves the embedding level of each character of `text`, which is split into paragraphs at
+/// paragraph separators.  The paragraphs' level comes from `direction`, unless `auto` is set, in
+/// which case it comes from the first strong character of each paragraph (P2, P3), with
+/// `direction` only used for paragraphs without any.
+///
+/// The levels of whitespace at the end of lines aren't reset to the paragraph level (L1), since
+/// that depends on where lines break.
+pub fn resolve_levels(text: &[char], direction: Direction, auto: bool) -> BidiInfo {
+    let classes = text.iter().map(|&ch| bidi_class(ch)).collect::<Vec<_>>();
+    let mut levels = vec![0; text.len()];
+    let mut paragraphs = Vec::new();
+    let mut start = 0;
+    // P1
+    while start < text.len() {
+        let end = classes[start..]
+            .iter()
+            .position(|&class| class == BidiClass::B)
+            .map_or(text.len(), |separator| start + separator + 1);
+        let level = if auto {
+            first_strong_level(&classes[start..end])
+        } else {
+            None
+        }
+        .unwrap_or_else(|| direction_level(direction));
+        let range = start..end;
+        let paragraph_levels =
+            resolve_paragraph(&text[range.clone()], &classes[range.clone()], level);
+        levels[range.clone()].copy_from_slice(&paragraph_levels);
+        paragraphs.push(Paragraph { range, level });
+        start = end;
+    }
+    BidiInfo { levels, paragraphs }
+}
+
+/// Finds the visual order of a line's characters, or of other units of text, from their levels.
+/// Each sequence at or above a level is reversed, from the highest level on the line down to the
+/// lowest odd one (L2).  Returns the indices of the units from left to right.
+pub fn visual_order(levels: &[Level]) -> Vec<usize> {
+    let mut order = (0..levels.len()).collect::<Vec<_>>();
+    let highest = levels.iter().copied().max().unwrap_or(0);
+    let lowest_odd = levels.iter().copied().min().unwrap_or(0) | 1;
+    for level in (lowest_odd..=highest).rev() {
+        let mut start = 0;
+        while start < order.len() {
+            if levels[order[start]] < level {
+                start += 1;
+                continue;
+            }
+            let end = order[start..]
+                .iter()
+                .position(|&index| levels[index] < level)
+                .map_or(order.len(), |length| start + length);
+            order[start..end].reverse();
+            start = end;
+        }
+    }
+    order
+}
+
+/// The character `ch` is displayed as when it's in right-to-left text, which is its mirror image
+/// for brackets and similar characters (L4).
+pub fn mirrored(ch: char) -> char {
+    MIRRORED_PAIRS
+        .iter()
+        .find_map(|&(first, second)| {
+            if ch == first {
+                Some(second)
+            } else if ch == second {
+                Some(first)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(ch)
+}
+
+/// The level given by the first strong character of `classes`, skipping over isolates, if there
+/// is one.
+fn first_strong_level(classes: &[BidiClass]) -> Option<Level> {
+    use BidiClass::*;
+    let mut isolates = 0;
+    for class in classes {
+        match class {
+            Lri | Rli | Fsi => isolates += 1,
+            Pdi if isolates > 0 => isolates -= 1,
+            L if isolates == 0 => return Some(0),
+            R | Al if isolates == 0 => return Some(1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// An entry of the directional status stack (X1).
+#[derive(Clone, Copy)]
+struct DirectionalStatus {
+    level: Level,
+    /// The class characters are overridden to, if any.
+    override_class: Option<BidiClass>,
+    isolate: bool,
+}
+
+/// The least level greater than `level` that's right-to-left if `rtl` is set, or left-to-right
+/// otherwise.
+fn next_level(level: Level, rtl: bool) -> Level {
+    if rtl {
+        (level + 1) | 1
+    } else {
+        (level + 2) & !1
+    }
+}
+
+/// Whether characters of `class` are removed by X9, and ignored by the rules that follow.
+fn is_removed(class: BidiClass) -> bool {
+    use BidiClass::*;
+    matches!(class, Rle | Lre | Rlo | Lro | Pdf | Bn)
+}
+
+fn is_isolate_initiator(class: BidiClass) -> bool {
+    matches!(class, BidiClass::Lri | BidiClass::Rli | BidiClass::Fsi)
+}
+
+/// Resolves the levels of the characters of a single paragraph, with the given classes and
+/// paragraph level.
+fn resolve_paragraph(text: &[char], original_classes: &[BidiClass], level: Level) -> Vec<Level> {
+    use BidiClass::*;
+    let length = text.len();
+    let mut classes = original_classes.to_vec();
+    let mut levels = vec![level; length];
+
+    // The PDI that matches each isolate initiator (BD9).
+    let mut matching_pdis = vec![None; length];
+    let mut open_isolates = Vec::new();
+    for (index, &class) in classes.iter().enumerate() {
+        match class {
+            Lri | Rli | Fsi => open_isolates.push(index),
+            Pdi => {
+                if let Some(initiator) = open_isolates.pop() {
+                    matching_pdis[initiator] = Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // X1-X8
+    let mut stack = vec![DirectionalStatus {
+        level,
+        override_class: None,
+        isolate: false,
+    }];
+    let mut overflow_isolates = 0;
+    let mut overflow_embeddings = 0;
+    let mut valid_isolates = 0;
+    for index in 0..length {
+        let current = *stack.last().unwrap();
+        let class = classes[index];
+        match class {
+            Rle | Lre | Rlo | Lro => {
+                levels[index] = current.level;
+                let new_level = next_level(current.level, matches!(class, Rle | Rlo));
+                if new_level <= MAX_DEPTH && overflow_isolates == 0 && overflow_embeddings == 0 {
+                    stack.push(DirectionalStatus {
+                        level: new_level,
+                        override_class: match class {
+                            Rlo => Some(R),
+                            Lro => Some(L),
+                            _ => None,
+                        },
+                        isolate: false,
+                    });
+                } else if overflow_isolates == 0 {
+                    overflow_embeddings += 1;
+                }
+            }
+            Rli | Lri | Fsi => {
+                levels[index] = current.level;
+                if let Some(override_class) = current.override_class {
+                    classes[index] = override_class;
+                }
+                let rtl = match class {
+                    Rli => true,
+                    Lri => false,
+                    _ => {
+                        let end = matching_pdis[index].unwrap_or(length);
+                        first_strong_level(&original_classes[index + 1..end]) == Some(1)
+                    }
+                };
+                let new_level = next_level(current.level, rtl);
+                if new_level <= MAX_DEPTH && overflow_isolates == 0 && overflow_embeddings == 0 {
+                    valid_isolates += 1;
+                    stack.push(DirectionalStatus {
+                        level: new_level,
+                        override_class: None,
+                        isolate: true,
+                    });
+                } else {
+                    overflow_isolates += 1;
+                }
+            }
+            Pdi => {
+                if overflow_isolates > 0 {
+                    overflow_isolates -= 1;
+                } else if valid_isolates > 0 {
+                    overflow_embeddings = 0;
+                    while !stack.last().unwrap().isolate {
+                        stack.pop();
+                    }
+                    stack.pop();
+                    valid_isolates -= 1;
+                }
+                let current = *stack.last().unwrap();
+                levels[index] = current.level;
+                if let Some(override_class) = current.override_class {
+                    classes[index] = override_class;
+                }
+            }
+            Pdf => {
+                levels[index] = current.level;
+                if overflow_isolates > 0 {
+                } else if overflow_embeddings > 0 {
+                    overflow_embeddings -= 1;
+                } else if !current.isolate && stack.len() >= 2 {
+                    stack.pop();
+                }
+            }
+            B => levels[index] = level,
+            Bn => levels[index] = current.level,
+            _ => {
+                levels[index] = current.level;
+                if let Some(override_class) = current.override_class {
+                    classes[index] = override_class;
+                }
+            }
+        }
+    }
+
+    // X9, X10
+    let kept = (0..length)
+        .filter(|&index| !is_removed(classes[index]))
+        .collect::<Vec<_>>();
+    let mut level_runs: Vec<Vec<usize>> = Vec::new();
+    for &index in &kept {
+        match level_runs.last_mut() {
+            Some(run) if levels[*run.last().unwrap()] == levels[index] => run.push(index),
+            _ => level_runs.push(vec![index]),
+        }
+    }
+    // The level run starting at each character, if any.
+    let mut run_starting_at = vec![None; length];
+    for (run_index, run) in level_runs.iter().enumerate() {
+        run_starting_at[run[0]] = Some(run_index);
+    }
+    let mut continues_sequence = vec![false; level_runs.len()];
+    let mut sequences = Vec::new();
+    for run_index in 0..level_runs.len() {
+        if continues_sequence[run_index] {
+            continue;
+        }
+        let mut sequence = level_runs[run_index].clone();
+        // Isolating run sequences continue from an isolate initiator to its matching PDI (BD13).
+        while let Some(next_run) = matching_pdis[*sequence.last().unwrap()]
+            .and_then(|pdi| run_starting_at[pdi])
+            .filter(|_| is_isolate_initiator(classes[*sequence.last().unwrap()]))
+        {
+            continues_sequence[next_run] = true;
+            sequence.extend(&level_runs[next_run]);
+        }
+        sequences.push(sequence);
+    }
+
+    let mut kept_position = vec![0; length];
+    for (position, &index) in kept.iter().enumerate() {
+        kept_position[index] = position;
+    }
+    for sequence in sequences {
+        let first = sequence[0];
+        let last = *sequence.last().unwrap();
+        let sequence_level = levels[first];
+        let level_before = kept_position[first]
+            .checked_sub(1)
+            .map_or(level, |position| levels[kept[position]]);
+        let level_after = if is_isolate_initiator(classes[last]) {
+            level
+        } else {
+            kept.get(kept_position[last] + 1)
+                .map_or(level, |&index| levels[index])
+        };
+        let sos = direction_class(sequence_level.max(level_before));
+        let eos = direction_class(sequence_level.max(level_after));
+        let types = resolve_sequence(text, &classes, &sequence, sequence_level, sos, eos);
+        // I1, I2
+        for (&index, class) in sequence.iter().zip(types) {
+            let level = levels[index];
+            levels[index] = match (is_rtl(level), class) {
+                (false, R) => level + 1,
+                (false, An) | (false, En) => level + 2,
+                (true, L) | (true, En) | (true, An) => level + 1,
+                _ => level,
+            };
+        }
+    }
+
+    // Removed characters take the level of the character before them.
+    for index in 0..length {
+        if is_removed(classes[index]) {
+            levels[index] = if index == 0 { level } else { levels[index - 1] };
+        }
+    }
+    // L1, apart from whitespace at the end of lines: separators are reset to the paragraph level,
+    // along with the whitespace before them and at the end of the paragraph.
+    let mut resets_whitespace = true;
+    for index in (0..length).rev() {
+        match original_classes[index] {
+            S | B => {
+                levels[index] = level;
+                resets_whitespace = true;
+            }
+            Ws | Lri | Rli | Fsi | Pdi | Rle | Lre | Rlo | Lro | Pdf | Bn if resets_whitespace => {
+                levels[index] = level;
+            }
+            _ => resets_whitespace = false,
+        }
+    }
+    levels
+}
+
+/// The strong class of text at `level`.
+fn direction_class(level: Level) -> BidiClass {
+    if is_rtl(level) {
+        BidiClass::R
+    } else {
+        BidiClass::L
+    }
+}
+
+/// Resolves the weak and neutral classes of an isolating run sequence, which is given as the
+/// indices of its characters.  Returns the resolved class of each, which is `L`, `R`, `En` or
+/// `An`.
+fn resolve_sequence(
+    text: &[char],
+    classes: &[BidiClass],
+    sequence: &[usize],
+    level: Level,
+    sos: BidiClass,
+    eos: BidiClass,
+) -> Vec<BidiClass> {
+    use BidiClass::*;
+    let original = sequence
+        .iter()
+        .map(|&index| classes[index])
+        .collect::<Vec<_>>();
+    let mut types = original.clone();
+    let length = types.len();
+
+    // W1
+    let mut previous = sos;
+    for class in types.iter_mut() {
+        if *class == Nsm {
+            *class = match previous {
+                Lri | Rli | Fsi | Pdi => On,
+                _ => previous,
+            };
+        }
+        previous = *class;
+    }
+    // W2, W3
+    let mut last_strong = sos;
+    for class in types.iter_mut() {
+        match *class {
+            L | R => last_strong = *class,
+            Al => {
+                last_strong = Al;
+                *class = R;
+            }
+            En if last_strong == Al => *class = An,
+            _ => {}
+        }
+    }
+    // W4
+    for position in 1..length.saturating_sub(1) {
+        let (before, after) = (types[position - 1], types[position + 1]);
+        match types[position] {
+            Es if before == En && after == En => types[position] = En,
+            Cs if before == after && matches!(before, En | An) => types[position] = before,
+            _ => {}
+        }
+    }
+    // W5
+    let mut position = 0;
+    while position < length {
+        if types[position] != Et {
+            position += 1;
+            continue;
+        }
+        let end = types[position..]
+            .iter()
+            .position(|&class| class != Et)
+            .map_or(length, |run_length| position + run_length);
+        let touches_number =
+            (position > 0 && types[position - 1] == En) || (end < length && types[end] == En);
+        if touches_number {
+            types[position..end]
+                .iter_mut()
+                .for_each(|class| *class = En);
+        }
+        position = end;
+    }
+    // W6, W7
+    let mut last_strong = sos;
+    for class in types.iter_mut() {
+        match *class {
+            Es | Et | Cs => *class = On,
+            L | R => last_strong = *class,
+            En if last_strong == L => *class = L,
+            _ => {}
+        }
+    }
+
+    let embedding_class = direction_class(level);
+    let strong_class = |class: BidiClass| match class {
+        L => Some(L),
+        R | En | An => Some(R),
+        _ => None,
+    };
+    // N0
+    for (open, close) in bracket_pairs(text, sequence, &types) {
+        let mut has_embedding_class = false;
+        let mut has_opposite_class = false;
+        for class in types[open + 1..close]
+            .iter()
+            .filter_map(|&class| strong_class(class))
+        {
+            if class == embedding_class {
+                has_embedding_class = true;
+            } else {
+                has_opposite_class = true;
+            }
+        }
+        let resolved = if has_embedding_class {
+            embedding_class
+        } else if has_opposite_class {
+            let class_before = types[..open]
+                .iter()
+                .rev()
+                .find_map(|&class| strong_class(class))
+                .unwrap_or(sos);
+            if class_before != embedding_class {
+                class_before
+            } else {
+                embedding_class
+            }
+        } else {
+            continue;
+        };
+        for bracket in [open, close].iter().copied() {
+            types[bracket] = resolved;
+            // Combining marks on the brackets change along with them.
+            for position in bracket + 1..length {
+                if original[position] != Nsm {
+                    break;
+                }
+                types[position] = resolved;
+            }
+        }
+    }
+    // N1, N2
+    let is_neutral = |class: BidiClass| matches!(class, B | S | Ws | On | Lri | Rli | Fsi | Pdi);
+    let mut position = 0;
+    while position < length {
+        if !is_neutral(types[position]) {
+            position += 1;
+            continue;
+        }
+        let end = types[position..]
+            .iter()
+            .position(|&class| !is_neutral(class))
+            .map_or(length, |run_length| position + run_length);
+        let before = position
+            .checked_sub(1)
+            .map_or(Some(sos), |before| strong_class(types[before]));
+        let after = if end < length {
+            strong_class(types[end])
+        } else {
+            Some(eos)
+        };
+        let resolved = if before == after {
+            before.unwrap_or(embedding_class)
+        } else {
+            embedding_class
+        };
+        types[position..end]
+            .iter_mut()
+            .for_each(|class| *class = resolved);
+        position = end;
+    }
+    types
+}
+
+/// Finds the bracket pairs of an isolating run sequence (BD16), as positions in the sequence,
+/// sorted by the positions of their opening brackets.
+fn bracket_pairs(text: &[char], sequence: &[usize], types: &[BidiClass]) -> Vec<(usize, usize)> {
+    let mut open_brackets: Vec<(char, usize)> = Vec::new();
+    let mut pairs = Vec::new();
+    for (position, &index) in sequence.iter().enumerate() {
+        if types[position] != BidiClass::On {
+            continue;
+        }
+        let ch = canonical_bracket(text[index]);
+        if let Some(&(_, close)) = BRACKET_PAIRS.iter().find(|(open, _)| *open == ch) {
+            if open_brackets.len() == MAX_BRACKET_DEPTH {
+                break;
+            }
+            open_brackets.push((close, position));
+        } else if BRACKET_PAIRS.iter().any(|(_, close)| *close == ch) {
+            if let Some(depth) = open_brackets.iter().rposition(|(close, _)| *close == ch) {
+                pairs.push((open_brackets[depth].1, position));
+                open_brackets.truncate(depth);
+            }
+        }
+    }
+    pairs.sort_unstable();
+    pairs
+}
+
+/// The angle brackets that are canonically equivalent to the CJK ones are matched as if they
+/// were them.
+fn canonical_bracket(ch: char) -> char {
+    match ch {
+        '\u{2329}' => '\u{3008}',
+        '\u{232a}' => '\u{3009}',
+        _ => ch,
+    }
+}
+
+/// Opening brackets and the closing brackets they pair with.
+const BRACKET_PAIRS: &[(char, char)] = &[
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('\u{2045}', '\u{2046}'),
+    ('\u{207d}', '\u{207e}'),
+    ('\u{208d}', '\u{208e}'),
+    ('\u{3008}', '\u{3009}'),
+    ('\u{300a}', '\u{300b}'),
+    ('\u{300c}', '\u{300d}'),
+    ('\u{300e}', '\u{300f}'),
+    ('\u{3010}', '\u{3011}'),
+    ('\u{3014}', '\u{3015}'),
+    ('\u{3016}', '\u{3017}'),
+    ('\u{3018}', '\u{3019}'),
+    ('\u{301a}', '\u{301b}'),
+    ('\u{ff08}', '\u{ff09}'),
+    ('\u{ff3b}', '\u{ff3d}'),
+    ('\u{ff5b}', '\u{ff5d}'),
+];
+
+/// Characters that are displayed as each other in right-to-left text.
+const MIRRORED_PAIRS: &[(char, char)] = &[
+    ('(', ')'),
+    ('<', '>'),
+    ('[', ']'),
+    ('{', '}'),
+    ('\u{ab}', '\u{bb}'),
+    ('\u{2039}', '\u{203a}'),
+    ('\u{2045}', '\u{2046}'),
+    ('\u{207d}', '\u{207e}'),
+    ('\u{208d}', '\u{208e}'),
+    ('\u{2264}', '\u{2265}'),
+    ('\u{2329}', '\u{232a}'),
+    ('\u{3008}', '\u{3009}'),
+    ('\u{300a}', '\u{300b}'),
+    ('\u{300c}', '\u{300d}'),
+    ('\u{300e}', '\u{300f}'),
+    ('\u{3010}', '\u{3011}'),
+    ('\u{3014}', '\u{3015}'),
+    ('\u{3016}', '\u{3017}'),
+    ('\u{3018}', '\u{3019}'),
+    ('\u{301a}', '\u{301b}'),
+    ('\u{ff08}', '\u{ff09}'),
+    ('\u{ff1c}', '\u{ff1e}'),
+    ('\u{ff3b}', '\u{ff3d}'),
+    ('\u{ff5b}', '\u{ff5d}'),
+];
+
+/// The bidirectional character types.
+///
+/// https://www.unicode.org/reports/tr9/#Table_Bidirectional_Character_Types
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum BidiClass {
+    /// Left-to-right
+    L,
+    /// Right-to-left
+    R,
+    /// Right-to-left Arabic
+    Al,
+    /// European number
+    En,
+    /// European number separator
+    Es,
+    /// European number terminator
+    Et,
+    /// Arabic number
+    An,
+    /// Common number separator
+    Cs,
+    /// Nonspacing mark
+    Nsm,
+    /// Boundary neutral
+    Bn,
+    /// Paragraph separator
+    B,
+    /// Segment separator
+    S,
+    /// Whitespace
+    Ws,
+    /// Other neutrals
+    On,
+    /// Left-to-right embedding
+    Lre,
+    /// Left-to-right override
+    Lro,
+    /// Right-to-left embedding
+    Rle,
+    /// Right-to-left override
+    Rlo,
+    /// Pop directional format
+    Pdf,
+    /// Left-to-right isolate
+    Lri,
+    /// Right-to-left isolate
+    Rli,
+    /// First strong isolate
+    Fsi,
+    /// Pop directional isolate
+    Pdi,
+}
+
+fn bidi_class(ch: char) -> BidiClass {
+    let code_point = ch as u32;
+    BIDI_CLASSES
+        .binary_search_by(|&(start, end, _)| {
+            if end < code_point {
+                std::cmp::Ordering::Less
+            } else if start > code_point {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .map_or(BidiClass::L, |index| BIDI_CLASSES[index].2)
+}
+
+/// Inclusive ranges of code points and their bidi class, in order.
+#[rustfmt::skip]
+const BIDI_CLASSES: &[(u32, u32, BidiClass)] = {
+    use BidiClass::*;
+    &[
+        // ASCII and Latin-1
+        (0x0000, 0x0008, Bn), (0x0009, 0x0009, S), (0x000a, 0x000a, B), (0x000b, 0x000b, S),
+        (0x000c, 0x000c, Ws), (0x000d, 0x000d, B), (0x000e, 0x001b, Bn), (0x001c, 0x001e, B),
+        (0x001f, 0x001f, S), (0x0020, 0x0020, Ws), (0x0021, 0x0022, On), (0x0023, 0x0025, Et),
+        (0x0026, 0x002a, On), (0x002b, 0x002b, Es), (0x002c, 0x002c, Cs), (0x002d, 0x002d, Es),
+        (0x002e, 0x002f, Cs), (0x0030, 0x0039, En), (0x003a, 0x003a, Cs), (0x003b, 0x0040, On),
+        (0x005b, 0x0060, On), (0x007b, 0x007e, On), (0x007f, 0x0084, Bn), (0x0085, 0x0085, B),
+        (0x0086, 0x009f, Bn), (0x00a0, 0x00a0, Cs), (0x00a1, 0x00a1, On), (0x00a2, 0x00a5, Et),
+        (0x00a6, 0x00a9, On), (0x00ab, 0x00ac, On), (0x00ad, 0x00ad, Bn), (0x00ae, 0x00af, On),
+        (0x00b0, 0x00b1, Et), (0x00b2, 0x00b3, En), (0x00b4, 0x00b4, On), (0x00b6, 0x00b8, On),
+        (0x00b9, 0x00b9, En), (0x00bb, 0x00bf, On), (0x00d7, 0x00d7, On), (0x00f7, 0x00f7, On),
+        // Combining diacritical marks
+        (0x0300, 0x036f, Nsm), (0x0483, 0x0489, Nsm),
+        // Hebrew
+        (0x0591, 0x05bd, Nsm), (0x05be, 0x05be, R), (0x05bf, 0x05bf, Nsm), (0x05c0, 0x05c0, R),
+        (0x05c1, 0x05c2, Nsm), (0x05c3, 0x05c3, R), (0x05c4, 0x05c5, Nsm), (0x05c6, 0x05c6, R),
+        (0x05c7, 0x05c7, Nsm), (0x05c8, 0x05ff, R),
+        // Arabic
+        (0x0600, 0x0605, An), (0x0606, 0x0607, On), (0x0608, 0x0608, Al), (0x0609, 0x060a, Et),
+        (0x060b, 0x060b, Al), (0x060c, 0x060c, Cs), (0x060d, 0x060d, Al), (0x060e, 0x060f, On),
+        (0x0610, 0x061a, Nsm), (0x061b, 0x064a, Al), (0x064b, 0x065f, Nsm), (0x0660, 0x0669, An),
+        (0x066a, 0x066a, Et), (0x066b, 0x066c, An), (0x066d, 0x066f, Al), (0x0670, 0x0670, Nsm),
+        (0x0671, 0x06d5, Al), (0x06d6, 0x06dc, Nsm), (0x06dd, 0x06dd, An), (0x06de, 0x06de, On),
+        (0x06df, 0x06e4, Nsm), (0x06e5, 0x06e6, Al), (0x06e7, 0x06e8, Nsm), (0x06e9, 0x06e9, On),
+        (0x06ea, 0x06ed, Nsm), (0x06ee, 0x06ef, Al), (0x06f0, 0x06f9, En), (0x06fa, 0x0710, Al),
+        (0x0711, 0x0711, Nsm), (0x0712, 0x072f, Al), (0x0730, 0x074a, Nsm), (0x074b, 0x07a5, Al),
+        (0x07a6, 0x07b0, Nsm), (0x07b1, 0x07bf, Al),
+        // NKo, Samaritan, Mandaic and Arabic extensions
+        (0x07c0, 0x07ea, R), (0x07eb, 0x07f3, Nsm), (0x07f4, 0x07f5, R), (0x07f6, 0x07f9, On),
+        (0x07fa, 0x0815, R), (0x0816, 0x0819, Nsm), (0x081a, 0x0858, R), (0x0859, 0x085b, Nsm),
+        (0x085c, 0x085f, R), (0x0860, 0x08d2, Al), (0x08d3, 0x08e1, Nsm), (0x08e2, 0x08e2, An),
+        (0x08e3, 0x08ff, Nsm),
+        (0x1ab0, 0x1aff, Nsm), (0x1dc0, 0x1dff, Nsm),
+        // General punctuation, super- and subscripts, currency symbols
+        (0x2000, 0x200a, Ws), (0x200b, 0x200d, Bn), (0x200e, 0x200e, L), (0x200f, 0x200f, R),
+        (0x2010, 0x2027, On), (0x2028, 0x2028, Ws), (0x2029, 0x2029, B), (0x202a, 0x202a, Lre),
+        (0x202b, 0x202b, Rle), (0x202c, 0x202c, Pdf), (0x202d, 0x202d, Lro), (0x202e, 0x202e, Rlo),
+        (0x202f, 0x202f, Cs), (0x2030, 0x2034, Et), (0x2035, 0x2043, On), (0x2044, 0x2044, Cs),
+        (0x2045, 0x205e, On), (0x205f, 0x205f, Ws), (0x2060, 0x2064, Bn), (0x2066, 0x2066, Lri),
+        (0x2067, 0x2067, Rli), (0x2068, 0x2068, Fsi), (0x2069, 0x2069, Pdi), (0x206a, 0x206f, Bn),
+        (0x2070, 0x2070, En), (0x2074, 0x2079, En), (0x207a, 0x207b, Es), (0x207c, 0x207e, On),
+        (0x2080, 0x2089, En), (0x208a, 0x208b, Es), (0x208c, 0x208e, On), (0x20a0, 0x20cf, Et),
+        (0x20d0, 0x20f0, Nsm),
+        // Arrows, mathematical operators, technical symbols, shapes and dingbats
+        (0x2190, 0x2211, On), (0x2212, 0x2212, Es), (0x2213, 0x2213, Et), (0x2214, 0x2335, On),
+        (0x237b, 0x2394, On), (0x2396, 0x2487, On), (0x2488, 0x249b, En), (0x24ea, 0x26ab, On),
+        (0x26ad, 0x27ff, On), (0x2900, 0x2bff, On), (0x2e00, 0x2e7f, On),
+        // CJK symbols and punctuation
+        (0x3000, 0x3000, Ws), (0x3001, 0x3004, On), (0x3008, 0x3020, On), (0x302a, 0x302d, Nsm),
+        (0x3030, 0x3030, On), (0x303d, 0x303f, On), (0x3099, 0x309a, Nsm), (0x309b, 0x309c, On),
+        (0x30a0, 0x30a0, On), (0x30fb, 0x30fb, On),
+        // Hebrew and Arabic presentation forms, variation selectors, small and fullwidth forms
+        (0xfb1d, 0xfb1d, R), (0xfb1e, 0xfb1e, Nsm), (0xfb1f, 0xfb28, R), (0xfb29, 0xfb29, Es),
+        (0xfb2a, 0xfb4f, R), (0xfb50, 0xfd3d, Al), (0xfd3e, 0xfd3f, On), (0xfd40, 0xfdcf, Al),
+        (0xfdf0, 0xfdfc, Al), (0xfdfd, 0xfdfd, On), (0xfe00, 0xfe0f, Nsm), (0xfe10, 0xfe19, On),
+        (0xfe20, 0xfe2f, Nsm), (0xfe30, 0xfe4f, On), (0xfe50, 0xfe50, Cs), (0xfe51, 0xfe51, On),
+        (0xfe52, 0xfe52, Cs), (0xfe54, 0xfe54, On), (0xfe55, 0xfe55, Cs), (0xfe56, 0xfe5e, On),
+        (0xfe5f, 0xfe5f, Et), (0xfe60, 0xfe61, On), (0xfe62, 0xfe63, Es), (0xfe64, 0xfe66, On),
+        (0xfe68, 0xfe68, On), (0xfe69, 0xfe6a, Et), (0xfe6b, 0xfe6b, On), (0xfe70, 0xfefe, Al),
+        (0xfeff, 0xfeff, Bn), (0xff01, 0xff02, On), (0xff03, 0xff05, Et), (0xff06, 0xff0a, On),
+        (0xff0b, 0xff0b, Es), (0xff0c, 0xff0c, Cs), (0xff0d, 0xff0d, Es), (0xff0e, 0xff0f, Cs),
+        (0xff10, 0xff19, En), (0xff1a, 0xff1a, Cs), (0xff1b, 0xff20, On), (0xff3b, 0xff40, On),
+        (0xff5b, 0xff65, On), (0xffe0, 0xffe1, Et), (0xffe2, 0xffe4, On), (0xffe5, 0xffe6, Et),
+        (0xffe8, 0xffee, On), (0xfff9, 0xfffd, On),
+        // Supplementary right-to-left scripts
+        (0x10800, 0x10cff, R), (0x10d00, 0x10d23, Al), (0x10d24, 0x10d27, Nsm),
+        (0x10d30, 0x10d39, An), (0x10d3a, 0x10e5f, R), (0x10e60, 0x10e7e, An),
+        (0x10e7f, 0x10f2f, R), (0x10f30, 0x10f45, Al), (0x10f46, 0x10f50, Nsm),
+        (0x10f51, 0x10f6f, Al), (0x10f70, 0x10fff, R),
+        (0x1e800, 0x1ec6f, R), (0x1ec70, 0x1ecbf, Al), (0x1ecc0, 0x1ecff, R),
+        (0x1ed00, 0x1ed4f, Al), (0x1ed50, 0x1edff, R), (0x1ee00, 0x1eeef, Al),
+        (0x1eef0, 0x1eef1, On), (0x1eef2, 0x1efff, R),
+        // Emoji and pictographs, tags, variation selectors supplement
+        (0x1f000, 0x1f0ff, On), (0x1f100, 0x1f10a, En), (0x1f300, 0x1faff, On),
+        (0xe0001, 0xe007f, Bn), (0xe0100, 0xe01ef, Nsm),
+    ]
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn levels(text: &str, direction: Direction) -> Vec<Level> {
+        let text = text.chars().collect::<Vec<_>>();
+        resolve_levels(&text, direction, false).levels
+    }
+
+    fn visual(text: &str, direction: Direction) -> String {
+        let chars = text.chars().collect::<Vec<_>>();
+        let levels = resolve_levels(&chars, direction, false).levels;
+        visual_order(&levels)
+            .into_iter()
+            .filter(|&index| !is_removed(bidi_class(chars[index])))
+            .map(|index| {
+                if is_rtl(levels[index]) {
+                    mirrored(chars[index])
+                } else {
+                    chars[index]
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bidi_classes_are_sorted() {
+        for window in BIDI_CLASSES.windows(2) {
+            assert!(window[0].0 <= window[0].1);
+            assert!(window[0].1 < window[1].0, "{:x?} overlaps", window);
+        }
+    }
+
+    #[test]
+    fn reverses_right_to_left_runs() {
+        assert_eq!(
+            levels("ab אב cd", Direction::Ltr),
+            vec![0, 0, 0, 1, 1, 0, 0, 0]
+        );
+        assert_eq!(visual("ab אבג cd", Direction::Ltr), "ab גבא cd");
+        // Numbers keep running left-to-right within right-to-left text.
+        assert_eq!(levels("א 12", Direction::Rtl), vec![1, 1, 2, 2]);
+        assert_eq!(visual("אב 12 גד", Direction::Rtl), "דג 12 בא");
+        // Trailing whitespace takes the paragraph level.
+        assert_eq!(levels("אב ", Direction::Ltr), vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn resolves_brackets_and_mirrors_them() {
+        // The brackets take the direction of the text inside them, rather than of what's around
+        // them, since it matches the paragraph's.
+        assert_eq!(visual("אב (cd) גד", Direction::Rtl), "דג (cd) בא");
+        assert_eq!(visual("ab (גד)", Direction::Ltr), "ab (דג)");
+        assert_eq!(visual("(אב)", Direction::Rtl), "(בא)");
+    }
+
+    #[test]
+    fn embeddings_isolates_and_overrides() {
+        // A right-to-left override reverses left-to-right letters.
+        assert_eq!(visual("a\u{202e}bc\u{202c}d", Direction::Ltr), "acbd");
+        // An isolate is reordered as a unit, and doesn't affect the text around it.
+        assert_eq!(
+            levels("a\u{2067}b\u{2069}c", Direction::Ltr),
+            vec![0, 0, 2, 0, 0]
+        );
+        // A first strong isolate takes its direction from its content.
+        assert_eq!(
+            levels("a\u{2068}אb\u{2069}", Direction::Ltr),
+            vec![0, 0, 1, 2, 0]
+        );
+    }
+
+    #[test]
+    fn paragraph_levels_from_first_strong_character() {
+        let text = "123 אב\nab".chars().collect::<Vec<_>>();
+        let info = resolve_levels(&text, Direction::Ltr, true);
+        let paragraph_levels = info
+            .paragraphs
+            .iter()
+            .map(|paragraph| (paragraph.range.clone(), paragraph.level))
+            .collect::<Vec<_>>();
+        assert_eq!(paragraph_levels, vec![(0..7, 1), (7..9, 0)]);
+    }
+}
@@ -25,6 +25,12 @@ impl LogicalDimensions {
         self.dimensions = new_dimensions;
     }
 
+    /// Moves the content area by `dx` along the x-axis and `dy` along the y-axis, along with the
+    /// edges surrounding it.
+    pub fn translate(&mut self, dx: CSSFloat, dy: CSSFloat) {
+        self.dimensions.content.translate(dx, dy);
+    }
+
     pub fn physical(&self) -> PhysicalDimensions {
         self.dimensions
     }
@@ -159,142 +165,14 @@ impl LogicalDimensions {
         }
     }
 
-    // Make sure the `get` and `set` tables stay in sync.  There's probably a better way to share
-    // the lookup logic between get and set operations, but copy-paste will do for now.
     pub fn get(&self, dir: LogicalDirection, box_component: BoxComponent) -> CSSPixelLength {
-        // Maps to this table: https://drafts.csswg.org/css-writing-modes-4/#logical-to-physical
-        match (self.writing_mode, self.direction, dir) {
-            (
-                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
-                Direction::Ltr,
-                LogicalDirection::InlineStart,
-            )
-            | (
-                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
-                Direction::Rtl,
-                LogicalDirection::InlineEnd,
-            )
-            | (WritingMode::SidewaysLr, Direction::Ltr, LogicalDirection::InlineEnd)
-            | (WritingMode::SidewaysLr, Direction::Rtl, LogicalDirection::InlineStart)
-            | (WritingMode::HorizontalTb, _, LogicalDirection::BlockStart) => match box_component {
-                BoxComponent::Border => self.dimensions.border.top,
-                BoxComponent::Margin => self.dimensions.margin.top,
-                BoxComponent::Padding => self.dimensions.padding.top,
-            },
-            (
-                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
-                Direction::Ltr,
-                LogicalDirection::InlineEnd,
-            )
-            | (
-                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
-                Direction::Rtl,
-                LogicalDirection::InlineStart,
-            )
-            | (WritingMode::SidewaysLr, Direction::Ltr, LogicalDirection::InlineStart)
-            | (WritingMode::SidewaysLr, Direction::Rtl, LogicalDirection::InlineEnd)
-            | (WritingMode::HorizontalTb, _, LogicalDirection::BlockEnd) => match box_component {
-                BoxComponent::Border => self.dimensions.border.bottom,
-                BoxComponent::Margin => self.dimensions.margin.bottom,
-                BoxComponent::Padding => self.dimensions.padding.bottom,
-            },
-            (WritingMode::VerticalRl | WritingMode::SidewaysRl, _, LogicalDirection::BlockEnd)
-            | (
-                WritingMode::VerticalLr | WritingMode::SidewaysLr,
-                _,
-                LogicalDirection::BlockStart,
-            )
-            | (WritingMode::HorizontalTb, Direction::Ltr, LogicalDirection::InlineStart)
-            | (WritingMode::HorizontalTb, Direction::Rtl, LogicalDirection::InlineEnd) => {
-                match box_component {
-                    BoxComponent::Border => self.dimensions.border.left,
-                    BoxComponent::Margin => self.dimensions.margin.left,
-                    BoxComponent::Padding => self.dimensions.padding.left,
-                }
-            }
-            (
-                WritingMode::VerticalRl | WritingMode::SidewaysRl,
-                _,
-                LogicalDirection::BlockStart,
-            )
-            | (WritingMode::VerticalLr | WritingMode::SidewaysLr, _, LogicalDirection::BlockEnd)
-            | (WritingMode::HorizontalTb, Direction::Ltr, LogicalDirection::InlineEnd)
-            | (WritingMode::HorizontalTb, Direction::Rtl, LogicalDirection::InlineStart) => {
-                match box_component {
-                    BoxComponent::Border => self.dimensions.border.right,
-                    BoxComponent::Margin => self.dimensions.margin.right,
-                    BoxComponent::Padding => self.dimensions.padding.right,
-                }
-            }
-        }
+        let side = dir.physical_side(self.writing_mode, self.direction);
+        self.dimensions.edges(box_component).get(side)
     }
 
     pub fn set(&mut self, dir: LogicalDirection, box_component: BoxComponent, val: CSSPixelLength) {
-        // Maps to this table: https://drafts.csswg.org/css-writing-modes-4/#logical-to-physical
-        match (self.writing_mode, self.direction, dir) {
-            (
-                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
-                Direction::Ltr,
-                LogicalDirection::InlineStart,
-            )
-            | (
-                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
-                Direction::Rtl,
-                LogicalDirection::InlineEnd,
-            )
-            | (WritingMode::SidewaysLr, Direction::Ltr, LogicalDirection::InlineEnd)
-            | (WritingMode::SidewaysLr, Direction::Rtl, LogicalDirection::InlineStart)
-            | (WritingMode::HorizontalTb, _, LogicalDirection::BlockStart) => match box_component {
-                BoxComponent::Border => self.dimensions.border.top = val,
-                BoxComponent::Margin => self.dimensions.margin.top = val,
-                BoxComponent::Padding => self.dimensions.padding.top = val,
-            },
-            (
-                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
-                Direction::Ltr,
-                LogicalDirection::InlineEnd,
-            )
-            | (
-                WritingMode::VerticalRl | WritingMode::SidewaysRl | WritingMode::VerticalLr,
-                Direction::Rtl,
-                LogicalDirection::InlineStart,
-            )
-            | (WritingMode::SidewaysLr, Direction::Ltr, LogicalDirection::InlineStart)
-            | (WritingMode::SidewaysLr, Direction::Rtl, LogicalDirection::InlineEnd)
-            | (WritingMode::HorizontalTb, _, LogicalDirection::BlockEnd) => match box_component {
-                BoxComponent::Border => self.dimensions.border.bottom = val,
-                BoxComponent::Margin => self.dimensions.margin.bottom = val,
-                BoxComponent::Padding => self.dimensions.padding.bottom = val,
-            },
-            (WritingMode::VerticalRl | WritingMode::SidewaysRl, _, LogicalDirection::BlockEnd)
-            | (
-                WritingMode::VerticalLr | WritingMode::SidewaysLr,
-                _,
-                LogicalDirection::BlockStart,
-            )
-            | (WritingMode::HorizontalTb, Direction::Ltr, LogicalDirection::InlineStart)
-            | (WritingMode::HorizontalTb, Direction::Rtl, LogicalDirection::InlineEnd) => {
-                match box_component {
-                    BoxComponent::Border => self.dimensions.border.left = val,
-                    BoxComponent::Margin => self.dimensions.margin.left = val,
-                    BoxComponent::Padding => self.dimensions.padding.left = val,
-                }
-            }
-            (
-                WritingMode::VerticalRl | WritingMode::SidewaysRl,
-                _,
-                LogicalDirection::BlockStart,
-            )
-            | (WritingMode::VerticalLr | WritingMode::SidewaysLr, _, LogicalDirection::BlockEnd)
-            | (WritingMode::HorizontalTb, Direction::Ltr, LogicalDirection::InlineEnd)
-            | (WritingMode::HorizontalTb, Direction::Rtl, LogicalDirection::InlineStart) => {
-                match box_component {
-                    BoxComponent::Border => self.dimensions.border.right = val,
-                    BoxComponent::Margin => self.dimensions.margin.right = val,
-                    BoxComponent::Padding => self.dimensions.padding.right = val,
-                }
-            }
-        }
+        let side = dir.physical_side(self.writing_mode, self.direction);
+        *self.dimensions.edges_mut(box_component).get_mut(side) = val;
     }
 }
 
@@ -311,6 +189,22 @@ pub struct PhysicalDimensions {
 }
 
 impl PhysicalDimensions {
+    fn edges(&self, box_component: BoxComponent) -> &EdgeSizes {
+        match box_component {
+            BoxComponent::Border => &self.border,
+            BoxComponent::Margin => &self.margin,
+            BoxComponent::Padding => &self.padding,
+        }
+    }
+
+    fn edges_mut(&mut self, box_component: BoxComponent) -> &mut EdgeSizes {
+        match box_component {
+            BoxComponent::Border => &mut self.border,
+            BoxComponent::Margin => &mut self.margin,
+            BoxComponent::Padding => &mut self.padding,
+        }
+    }
+
     pub fn border_size(self, side: Side) -> CSSPixelLength {
         match side {
             Side::Bottom => self.border.bottom,
@@ -330,8 +224,8 @@ impl PhysicalDimensions {
         self.padding_box().expanded_by(self.border)
     }
 
-    /// The area covered by the content area plus padding, borders, and margin.
-    // TODO: This will need to change when we implement margin collapsing: http://www.w3.org/TR/CSS2/box.html#collapsing-margins
+    /// The area covered by the content area plus padding, borders, and margin.  Margins are taken
+    /// as they are, before any collapsing: http://www.w3.org/TR/CSS2/box.html#collapsing-margins
     pub fn margin_box(self) -> Rect {
         self.border_box().expanded_by(self.margin)
     }
@@ -0,0 +1,1912 @@
+//! Layout of inline formatting contexts: the inline-level content of a block container, flowed
+//! into line boxes.
+//!
+//! https://drafts.csswg.org/css-inline-3/#model
+//! https://www.w3.org/TR/CSS2/visuren.html#inline-formatting
+
+use crate::gfx::font::{glyph_advance, FontHandle, FontMetrics, FontQuery, PostscriptName};
+use crate::layout::bidi::{direction_level, is_rtl, mirrored, resolve_levels, visual_order, Level};
+use crate::layout::layout_box::{BoxType, ContentSizes, FloatManager, FloatSide, LayoutBox};
+use crate::layout::line_break::{break_opportunities, continues_grapheme, Break};
+use crate::layout::rect::{EdgeSizes, Rect};
+use crate::layout::{DumpLayout, DumpLayoutFormat};
+use crate::style::values::computed::length::CSSPixelLength;
+use crate::style::values::computed::{
+    ComputedValues, Direction, Display, Hyphens, LineHeight, OverflowWrap, UnicodeBidi, WhiteSpace,
+    WordBreak, WritingMode,
+};
+use crate::style::values::used::ToPx;
+use crate::style::values::CSSFloat;
+use crate::Side;
+use std::io::Write;
+
+/// The part of an inline box, or of a run of text, that's placed on a single line.  Boxes and
+/// text that are split across lines have a fragment on each of those lines.
+#[derive(Clone, Debug)]
+pub struct Fragment {
+    /// The content area of the fragment, which is as tall (in the block axis) as the em box of the
+    /// first available font, no matter the `line-height`.
+    pub content: Rect,
+    pub kind: FragmentKind,
+}
+
+impl Fragment {
+    /// The area covered by the fragment's content and padding.
+    pub fn padding_box(&self) -> Rect {
+        match &self.kind {
+            FragmentKind::InlineBox { padding, .. } => self.content.expanded_by(*padding),
+            FragmentKind::Text(_) => self.content,
+        }
+    }
+
+    /// The area covered by the fragment's content, padding and borders.  Only the content area
+    /// takes part in the height of the line box.
+    pub fn border_box(&self) -> Rect {
+        match &self.kind {
+            FragmentKind::InlineBox { padding, border } => {
+                self.content.expanded_by(*padding).expanded_by(*border)
+            }
+            FragmentKind::Text(_) => self.content,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum FragmentKind {
+    /// A fragment of an inline box.  The edges of its inline-start side are only present on the
+    /// box's first fragment, and those of its inline-end side only on its last.
+    InlineBox {
+        padding: EdgeSizes,
+        border: EdgeSizes,
+    },
+    Text(TextFragment),
+}
+
+/// Text placed on a single line.
+#[derive(Clone, Debug)]
+pub struct TextFragment {
+    /// The text as placed, after whitespace collapsing.
+    pub text: String,
+    pub glyphs: Vec<Glyph>,
+    /// The computed font size, which the glyphs are rasterized at before the scale factor is
+    /// applied.
+    pub font_size: CSSPixelLength,
+}
+
+/// A glyph positioned by inline layout.
+#[derive(Clone, Debug)]
+pub struct Glyph {
+    pub ch: char,
+    /// The font the glyph was measured with, and should be rendered with.
+    pub font: PostscriptName,
+    /// The horizontal position of the glyph's origin.
+    pub origin_x: CSSFloat,
+    /// The vertical position of the glyph's origin, which lies on the baseline.
+    pub origin_y: CSSFloat,
+}
+
+/// Lays out the contents of the root inline box `root` of an inline formatting context.  `root`
+/// should already have been sized and positioned by its block container.  Its inline-level
+/// descendants are given fragments, atomic inline-level boxes are laid out and moved onto their
+/// lines, and its line boxes are recorded on it.  Floats among its contents are laid out and
+/// placed in `floats`, those of the block formatting context it's in, which the lines are then
+/// shortened around.  Absolutely positioned boxes among its contents are moved to their static
+/// position, for their containing block to lay them out from later.
+///
+/// Returns the block size of the laid out contents.
+pub fn layout_inline_formatting_context(
+    root: &mut LayoutBox,
+    floats: &mut FloatManager,
+    font_handle: &FontHandle,
+    scale_factor: f32,
+) -> CSSPixelLength {
+    root.layout_atomic_inlines(
+        root.physical_dimensions(),
+        floats,
+        font_handle,
+        scale_factor,
+    );
+    let content = root.physical_dimensions().content;
+    let mut context = InlineFormattingContext::new(root, font_handle, scale_factor);
+    let strut = context.baseline_extents(&root.computed_values());
+    context.collect_items(root, &mut Vec::new());
+    context.insert_soft_breaks();
+    let (direction, unicode_bidi) = {
+        let root_values = root.computed_values();
+        (root_values.direction, root_values.unicode_bidi)
+    };
+    context.resolve_bidi_levels(direction, unicode_bidi == UnicodeBidi::Plaintext);
+    let (lines, placed) = context.break_lines(floats, strut);
+    for (index, position) in placed {
+        if let Some(atomic) = &mut context.boxes[index].atomic {
+            atomic.placed = Some(position);
+        }
+    }
+
+    let mut line_boxes = Vec::new();
+    let mut block_size = CSSPixelLength::new(0.);
+    let mut last_baseline = None;
+    let mut open_boxes = Vec::new();
+    for line in lines {
+        let (line_block_size, baseline) = context.place_line(&line, &mut open_boxes, strut);
+        line_boxes.push(context.physical_rect(
+            line.inline_offset,
+            line.block_offset,
+            line.inline_size,
+            line_block_size,
+        ));
+        last_baseline = Some(context.physical_block_coord(baseline));
+        block_size = line.block_offset + line_block_size;
+    }
+
+    for inline_box in context.boxes {
+        let layout_box = root.descendant_mut(&inline_box.path);
+        match inline_box.atomic {
+            Some(AtomicInline {
+                origin,
+                placed: Some(placed),
+                ..
+            }) => layout_box.translate(placed.0 - origin.0, placed.1 - origin.1),
+            Some(_) => {}
+            None => {
+                layout_box.set_fragments(inline_box.fragments, (content.start_x, content.start_y))
+            }
+        }
+    }
+    root.set_line_boxes(line_boxes, last_baseline);
+    block_size
+}
+
+/// The min-content and max-content inline sizes of the contents of the root inline box `root`,
+/// with the scale factor applied.  The max-content size is that of the longest line when lines
+/// only break where they're forced to, and the min-content size is that of the widest content
+/// between two opportunities to break.
+///
+/// https://drafts.csswg.org/css-sizing-3/#intrinsic-sizes
+pub fn inline_content_sizes(
+    root: &LayoutBox,
+    font_handle: &FontHandle,
+    scale_factor: f32,
+) -> ContentSizes {
+    let mut context = InlineFormattingContext::new(root, font_handle, scale_factor);
+    context.collect_items(root, &mut Vec::new());
+    context.insert_soft_breaks();
+
+    let zero = CSSPixelLength::new(0.);
+    let mut sizes = ContentSizes::default();
+    let mut line_size = zero;
+    // Collapsible spaces that are removed if the line ends after them, and so only count once
+    // something follows them.
+    let mut pending_spaces = zero;
+    let mut unbreakable_size = zero;
+    for item in &context.items {
+        let item_sizes = match item {
+            Item::Atomic(index) | Item::Float(index) => root
+                .descendant(&context.boxes[*index].path)
+                .intrinsic_contributions(font_handle, scale_factor),
+            item => ContentSizes::new(context.item_inline_size(item)),
+        };
+        match item {
+            // Floats sit beside the lines around them, or on lines of their own.
+            Item::Float(_) => {
+                line_size += item_sizes.max_content;
+                sizes.min_content = sizes.min_content.max(item_sizes.min_content);
+                continue;
+            }
+            Item::ForcedBreak => {
+                sizes.max_content = sizes.max_content.max(line_size);
+                line_size = zero;
+                pending_spaces = zero;
+            }
+            Item::Space(index, _) if context.is_collapsible_space(*index) => {
+                pending_spaces += item_sizes.max_content
+            }
+            Item::Word(..) | Item::Atomic(_) | Item::Space(..) => {
+                line_size += pending_spaces + item_sizes.max_content;
+                pending_spaces = zero;
+            }
+            Item::BoxStart(_)
+            | Item::BoxEnd(_)
+            | Item::Absolute { .. }
+            | Item::SoftBreak { .. } => line_size += item_sizes.max_content,
+        }
+
+        let is_opportunity = context.is_break_opportunity(item)
+            || matches!(item, Item::SoftBreak { text, emergency: true, .. }
+                if context.boxes[*text].overflow_wrap == OverflowWrap::Anywhere);
+        if is_opportunity {
+            sizes.min_content = sizes.min_content.max(unbreakable_size);
+            unbreakable_size = zero;
+        } else {
+            unbreakable_size += item_sizes.min_content;
+        }
+    }
+    sizes.max_content = sizes.max_content.max(line_size);
+    sizes.min_content = sizes.min_content.max(unbreakable_size);
+    sizes
+}
+
+impl DumpLayout for Fragment {
+    fn dump_layout<W: Write>(&self, write_to: &mut W, indent_spaces: usize) {
+        let text = match &self.kind {
+            FragmentKind::InlineBox { .. } => "".to_owned(),
+            FragmentKind::Text(text_fragment) => format!(" {:?}", text_fragment.text),
+        };
+        writeln!(
+            write_to,
+            "{:indent_spaces$}Fragment at ({}, {}) size {}x{}{}",
+            "",
+            self.content.start_x.dump_layout_format(),
+            self.content.start_y.dump_layout_format(),
+            self.content.width.dump_layout_format(),
+            self.content.height.dump_layout_format(),
+            text,
+            indent_spaces = indent_spaces,
+        )
+        .expect("error writing layout dump");
+    }
+}
+
+struct InlineFormattingContext<'a> {
+    /// The inline-level boxes in the formatting context, in tree order.
+    boxes: Vec<InlineLevelBox>,
+    /// The contents of the boxes, in tree order.
+    items: Vec<Item>,
+    /// The bidi levels of the items.
+    levels: Vec<ItemLevel>,
+    font_handle: &'a FontHandle,
+    available_inline_size: CSSPixelLength,
+    scale_factor: f32,
+    writing_mode: WritingMode,
+    /// The physical position of the start of the formatting context's content area.
+    origin: (CSSFloat, CSSFloat),
+}
+
+/// An inline-level box in an inline formatting context, and what's needed to place it.
+struct InlineLevelBox {
+    /// The indices of the children leading from the root inline box to this box.
+    path: Vec<usize>,
+    /// How far the box's content, and the lines it sits on, extend from the baseline.
+    extents: BaselineExtents,
+    /// The box's margins, borders and padding, oriented as if the writing mode were horizontal and
+    /// left-to-right, so their left sides are at the inline start.
+    margin: EdgeSizes,
+    border: EdgeSizes,
+    padding: EdgeSizes,
+    direction: Direction,
+    unicode_bidi: UnicodeBidi,
+    font_query: FontQuery,
+    font_size: CSSPixelLength,
+    white_space: WhiteSpace,
+    word_break: WordBreak,
+    overflow_wrap: OverflowWrap,
+    hyphens: Hyphens,
+    /// The fragments placed so far.
+    fragments: Vec<Fragment>,
+    /// The size and position of the box, if it's an atomic inline-level box, a float or an
+    /// absolutely positioned box, which is placed as a whole rather than given fragments.
+    atomic: Option<AtomicInline>,
+    /// The side the box floats to, if it's a float.
+    float: Option<FloatSide>,
+    /// The sides of the earlier floats the box is placed below, if it's a float.
+    cleared: &'static [FloatSide],
+}
+
+/// An atomic inline-level box, such as an inline-block, or a float, which has already been laid
+/// out by itself.
+#[derive(Clone, Copy)]
+struct AtomicInline {
+    /// The size of the box's margin box.
+    inline_size: CSSPixelLength,
+    block_size: CSSPixelLength,
+    /// How far the box's baseline is from the block-start of its margin box.
+    baseline: CSSPixelLength,
+    /// The physical position of the box's margin box, as laid out.
+    origin: (CSSFloat, CSSFloat),
+    /// The physical position the box's margin box was placed at.
+    placed: Option<(CSSFloat, CSSFloat)>,
+}
+
+impl InlineLevelBox {
+    /// The bidi formatting characters the box's `unicode-bidi` is equivalent to, at its start and
+    /// at its end.
+    ///
+    /// https://drafts.csswg.org/css-writing-modes-4/#bidi-control-codes-injection-table
+    fn bidi_controls(&self) -> (&'static [char], &'static [char]) {
+        match (self.unicode_bidi, self.direction) {
+            (UnicodeBidi::Normal, _) => (&[], &[]),
+            (UnicodeBidi::Embed, Direction::Ltr) => (&[LRE], &[PDF]),
+            (UnicodeBidi::Embed, Direction::Rtl) => (&[RLE], &[PDF]),
+            (UnicodeBidi::Isolate, Direction::Ltr) => (&[LRI], &[PDI]),
+            (UnicodeBidi::Isolate, Direction::Rtl) => (&[RLI], &[PDI]),
+            (UnicodeBidi::BidiOverride, Direction::Ltr) => (&[LRO], &[PDF]),
+            (UnicodeBidi::BidiOverride, Direction::Rtl) => (&[RLO], &[PDF]),
+            (UnicodeBidi::IsolateOverride, Direction::Ltr) => (&[LRI, LRO], &[PDF, PDI]),
+            (UnicodeBidi::IsolateOverride, Direction::Rtl) => (&[RLI, RLO], &[PDF, PDI]),
+            (UnicodeBidi::Plaintext, _) => (&[FSI], &[PDI]),
+        }
+    }
+}
+
+/// A piece of the content of an inline formatting context, which line breaking places as a unit.
+enum Item {
+    /// The start of the box with the given index.
+    BoxStart(usize),
+    /// The end of the box with the given index.
+    BoxEnd(usize),
+    /// A word of the text node with the given index, which may not be broken across lines.
+    Word(usize, Vec<ShapedGlyph>),
+    /// A space or tab in the text node with the given index.  Collapsible whitespace has already
+    /// been collapsed into a single space.  Unless the text node's `white-space` prevents
+    /// wrapping, a line may break at it.
+    Space(usize, ShapedGlyph),
+    /// The atomic inline-level box with the given index.
+    Atomic(usize),
+    /// The float with the given index, which is placed beside the line it's on if it fits.
+    Float(usize),
+    /// The absolutely positioned box with the given index, which takes up no space, and only
+    /// marks its static position.  `inline_level` is whether the box was inline-level before it
+    /// was blockified, which decides whether that position is on the current line or below it.
+    Absolute { index: usize, inline_level: bool },
+    /// A preserved segment break, which ends the line.
+    ForcedBreak,
+    /// An opportunity to break the line within the text before it, found by the Unicode line
+    /// breaking algorithm.  `text` is the index of the text node before it.  If the line breaks
+    /// here, `hyphen` is placed at the end of the line.  Emergency opportunities are only used
+    /// when a word doesn't fit on a line by itself, which `overflow-wrap` allows.
+    SoftBreak {
+        text: usize,
+        hyphen: Option<ShapedGlyph>,
+        emergency: bool,
+    },
+}
+
+/// A character and the font and advance it was measured with.  Characters no font could be
+/// loaded for aren't given a font, and take up no space.
+#[derive(Clone)]
+struct ShapedGlyph {
+    ch: char,
+    font: Option<PostscriptName>,
+    advance: CSSPixelLength,
+}
+
+/// Text of a single text node and bidi level, placed on a line.
+struct TextRun {
+    index: usize,
+    level: Level,
+    /// The inline offsets the text starts and ends at.
+    start: CSSPixelLength,
+    end: CSSPixelLength,
+    glyphs: Vec<Glyph>,
+    /// The text of each item in the run, and its position on the line.
+    text: Vec<(usize, String)>,
+}
+
+/// The bidi embedding level of an item, and that of the paragraph it's in.
+#[derive(Clone, Copy)]
+struct ItemLevel {
+    level: Level,
+    paragraph: Level,
+}
+
+/// A line the items of an inline formatting context were broken into, and the space it was given
+/// beside the floats around it.
+struct Line {
+    /// The indices of the items on the line, which include at least one word or inline box edge.
+    items: Vec<usize>,
+    /// Where the line box starts, relative to the start of the formatting context.
+    inline_offset: CSSPixelLength,
+    block_offset: CSSPixelLength,
+    inline_size: CSSPixelLength,
+}
+
+/// The state of breaking the items of an inline formatting context into lines.
+struct LineBreaker<'c, 'a, 'f> {
+    context: &'c InlineFormattingContext<'a>,
+    /// The floats of the block formatting context the lines are in.
+    floats: &'f mut FloatManager,
+    /// How far the root inline box extends from the baseline, which every line makes room for.
+    strut: BaselineExtents,
+    lines: Vec<Line>,
+    /// The items on the current line.
+    line: Vec<usize>,
+    line_size: CSSPixelLength,
+    /// Where the current line starts, and the inline size it has beside the floats around it.
+    inline_offset: CSSPixelLength,
+    block_offset: CSSPixelLength,
+    available_inline_size: CSSPixelLength,
+    /// The boxes whose start was placed on a finished line, but whose end hasn't been placed yet.
+    open_boxes: Vec<usize>,
+    /// The floats that didn't fit beside the current line, which are placed below it.
+    pending_floats: Vec<usize>,
+    /// The block-level absolutely positioned boxes that came after content on the current line,
+    /// whose static position is at the start of the next one, and whether their paragraph is
+    /// right-to-left.
+    pending_absolutes: Vec<(usize, bool)>,
+    /// The floats and absolutely positioned boxes placed so far, and the physical positions of
+    /// their margin boxes.
+    placed: Vec<(usize, (CSSFloat, CSSFloat))>,
+    /// The items since the last opportunity to break the line, which have to be placed together
+    /// unless they contain emergency opportunities.
+    unbreakable: Vec<usize>,
+    unbreakable_size: CSSPixelLength,
+}
+
+impl<'c, 'a, 'f> LineBreaker<'c, 'a, 'f> {
+    /// Places the unbreakable items on the current line, or on a new one if they don't fit.
+    /// `break_size` is the size of any content added if the line breaks after them.  If they
+    /// don't fit on a line by themselves either, the line is moved down past the floats beside it
+    /// until they do, and if there are none, they're broken at emergency opportunities.
+    ///
+    /// https://www.w3.org/TR/CSS2/visuren.html#floats
+    fn place_unbreakable(&mut self, break_size: CSSPixelLength) {
+        if self.line_size + self.unbreakable_size + break_size > self.available_inline_size
+            && self.context.has_content(&self.line)
+        {
+            self.finish_line();
+        }
+        while self.line_size + self.unbreakable_size + break_size > self.available_inline_size
+            && !self.context.has_content(&self.line)
+            && self.move_past_floats()
+        {}
+        while self.line_size + self.unbreakable_size + break_size > self.available_inline_size {
+            let position = match self.emergency_break() {
+                Some(position) => position,
+                None => break,
+            };
+            let rest = self.unbreakable.split_off(position + 1);
+            let placed = std::mem::replace(&mut self.unbreakable, rest);
+            for index in placed {
+                self.unbreakable_size -= self.context.item_inline_size(&self.context.items[index]);
+                self.line.push(index);
+            }
+            self.finish_line();
+        }
+        self.line.append(&mut self.unbreakable);
+        self.line_size += self.unbreakable_size;
+        self.unbreakable_size = CSSPixelLength::new(0.);
+    }
+
+    /// Finds the position in the unbreakable items of the last emergency opportunity to break
+    /// that fits on the current line.  If none fit, the first one that places anything on the
+    /// line is used, so that the line overflows by as little as possible.
+    fn emergency_break(&self) -> Option<usize> {
+        let mut size = self.line_size;
+        let mut first = None;
+        let mut last_fitting = None;
+        for (position, &index) in self.unbreakable.iter().enumerate() {
+            let item = &self.context.items[index];
+            if let Item::SoftBreak {
+                emergency: true, ..
+            } = item
+            {
+                if size > self.line_size {
+                    if size <= self.available_inline_size {
+                        last_fitting = Some(position);
+                    } else if first.is_none() {
+                        first = Some(position);
+                    }
+                }
+            }
+            size += self.context.item_inline_size(item);
+        }
+        last_fitting.or(first)
+    }
+
+    /// Places the float with the given index beside the current line if it fits there along with
+    /// the content placed on the line so far, or leaves it to be placed below the line otherwise.
+    /// Floats that follow a float placed below the line are placed below it as well, to keep
+    /// them in order.
+    fn add_float(&mut self, index: usize) {
+        let inline_size = self.context.boxes[index]
+            .atomic
+            .map_or(CSSPixelLength::new(0.), |atomic| atomic.inline_size);
+        let fits = self.line_is_empty()
+            || self.line_size + self.unbreakable_size + inline_size <= self.available_inline_size;
+        if fits && self.pending_floats.is_empty() {
+            self.place_float(index);
+            self.fit_line();
+        } else {
+            self.pending_floats.push(index);
+        }
+    }
+
+    /// Places the float with the given index as high as it can go, starting at the current line.
+    fn place_float(&mut self, index: usize) {
+        let context = self.context;
+        let float = &context.boxes[index];
+        let (atomic, side) = match (float.atomic, float.float) {
+            (Some(atomic), Some(side)) => (atomic, side),
+            _ => return,
+        };
+        let (inline_origin, block_origin) = context.flow_origin();
+        let (inline_start, block_start) = self.floats.place(
+            side,
+            float.cleared,
+            atomic.inline_size,
+            atomic.block_size,
+            block_origin + self.block_offset.px(),
+            (
+                inline_origin,
+                inline_origin + context.available_inline_size.px(),
+            ),
+        );
+        let position = context.physical_point(
+            CSSPixelLength::new(inline_start - inline_origin),
+            CSSPixelLength::new(block_start - block_origin),
+        );
+        self.placed.push((index, position));
+    }
+
+    /// Places the absolutely positioned box with the given index at its static position, which
+    /// is where its margin box would have started in normal flow, or ended if its paragraph is
+    /// right-to-left (`rtl`), as lines start at their right end then.  An inline-level box would
+    /// have followed the content on the current line, while a block-level one would have started
+    /// a line of its own, after the current line if that has any content.
+    ///
+    /// https://www.w3.org/TR/CSS2/visudet.html#abs-non-replaced-width
+    fn add_absolute(&mut self, index: usize, inline_level: bool, rtl: bool) {
+        if inline_level {
+            let content_size = self.line_size + self.unbreakable_size;
+            let inline_offset = if rtl {
+                self.inline_offset + self.available_inline_size - content_size
+            } else {
+                self.inline_offset + content_size
+            };
+            self.place_absolute(index, inline_offset);
+        } else if self.line_is_empty() {
+            self.place_absolute(index, self.content_start(rtl));
+        } else {
+            self.pending_absolutes.push((index, rtl));
+        }
+    }
+
+    /// The inline offset that block-level content starts at in a paragraph that's right-to-left
+    /// (`rtl`) or not, which is an edge of the formatting context's content area, whatever floats
+    /// are beside it.
+    fn content_start(&self, rtl: bool) -> CSSPixelLength {
+        if rtl {
+            self.context.available_inline_size
+        } else {
+            CSSPixelLength::new(0.)
+        }
+    }
+
+    /// Places the absolutely positioned box with the given index at the given inline offset on
+    /// the current line.
+    fn place_absolute(&mut self, index: usize, inline_offset: CSSPixelLength) {
+        let position = self
+            .context
+            .physical_point(inline_offset, self.block_offset);
+        self.placed.push((index, position));
+    }
+
+    /// Whether nothing but collapsible spaces has been added to the current line yet.
+    fn line_is_empty(&self) -> bool {
+        !self.context.has_content(&self.line) && !self.context.has_content(&self.unbreakable)
+    }
+
+    /// Gives the current line the space beside the floats around it.  As the block size of the
+    /// line isn't known until its content is, only the floats beside the part of it taken up by
+    /// the strut are taken into account.
+    fn fit_line(&mut self) {
+        let (inline_origin, block_origin) = self.context.flow_origin();
+        let block_start = block_origin + self.block_offset.px();
+        let (line_left, line_right) = self.floats.available_space(
+            block_start,
+            block_start + (self.strut.above + self.strut.below).px(),
+            inline_origin,
+            inline_origin + self.context.available_inline_size.px(),
+        );
+        self.inline_offset = CSSPixelLength::new(line_left - inline_origin);
+        self.available_inline_size =
+            CSSPixelLength::new(line_right - line_left).max(CSSPixelLength::new(0.));
+    }
+
+    /// Moves the current line down to where the first of the floats beside it ends, and returns
+    /// whether there were any.
+    fn move_past_floats(&mut self) -> bool {
+        let block_origin = self.context.flow_origin().1;
+        let block_start = block_origin + self.block_offset.px();
+        let float_end = self.floats.next_float_end(
+            block_start,
+            block_start + (self.strut.above + self.strut.below).px(),
+        );
+        match float_end {
+            Some(float_end) => {
+                self.block_offset = CSSPixelLength::new(float_end - block_origin);
+                self.fit_line();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Finishes the current line, and starts the next one below it, after placing the floats that
+    /// didn't fit beside it.
+    fn finish_line(&mut self) {
+        let line = std::mem::take(&mut self.line);
+        if let Some(items) = self.context.finish_line(line) {
+            let (above, below) = self
+                .context
+                .line_extents(&items, &self.open_boxes, self.strut);
+            for &index in &items {
+                match self.context.items[index] {
+                    Item::BoxStart(box_index) => self.open_boxes.push(box_index),
+                    Item::BoxEnd(box_index) => self
+                        .open_boxes
+                        .retain(|open_index| *open_index != box_index),
+                    _ => {}
+                }
+            }
+            self.lines.push(Line {
+                items,
+                inline_offset: self.inline_offset,
+                block_offset: self.block_offset,
+                inline_size: self.available_inline_size,
+            });
+            self.block_offset += above + below;
+        }
+        self.line_size = CSSPixelLength::new(0.);
+        for index in std::mem::take(&mut self.pending_floats) {
+            self.place_float(index);
+        }
+        self.fit_line();
+        for (index, rtl) in std::mem::take(&mut self.pending_absolutes) {
+            self.place_absolute(index, self.content_start(rtl));
+        }
+    }
+}
+
+/// How far an inline box, or the text directly inside it, extends from the baseline.
+#[derive(Clone, Copy, Debug)]
+struct BaselineExtents {
+    /// The ascent of the first available font.
+    ascent: CSSPixelLength,
+    /// The descent of the first available font.
+    descent: CSSPixelLength,
+    /// The ascent plus half the leading, which is how far the box extends the line box upwards.
+    above: CSSPixelLength,
+    /// The descent plus half the leading.
+    below: CSSPixelLength,
+}
+
+impl<'a> InlineFormattingContext<'a> {
+    /// A formatting context for the contents of the root inline box `root`, with nothing collected
+    /// yet.
+    fn new(
+        root: &LayoutBox,
+        font_handle: &'a FontHandle,
+        scale_factor: f32,
+    ) -> InlineFormattingContext<'a> {
+        let content = root.physical_dimensions().content;
+        let writing_mode = root.writing_mode();
+        let available_inline_size = if writing_mode.is_horizontal() {
+            content.width
+        } else {
+            content.height
+        };
+        InlineFormattingContext {
+            boxes: Vec::new(),
+            items: Vec::new(),
+            levels: Vec::new(),
+            font_handle,
+            available_inline_size,
+            scale_factor,
+            writing_mode,
+            origin: (content.start_x, content.start_y),
+        }
+    }
+
+    /// Determines how far content with the given computed values extends from the baseline.
+    ///
+    /// https://www.w3.org/TR/CSS2/visudet.html#leading
+    fn baseline_extents(&self, cvs: &ComputedValues) -> BaselineExtents {
+        let metrics = self
+            .font_handle
+            .get_font(&FontQuery::new(cvs))
+            .map(|font| FontMetrics::new(&font))
+            .ok();
+        let (ascent, descent, line_gap) = metrics
+            .map(|metrics| (metrics.ascent, metrics.descent, metrics.line_gap))
+            .unwrap_or((0.8, 0.2, 0.));
+        let font_size = cvs.font_size.size * self.scale_factor;
+        let line_height = match cvs.line_height {
+            LineHeight::Normal => font_size * (ascent + descent + line_gap),
+            LineHeight::Number(number) => font_size * number,
+            LineHeight::Length(length) => length * self.scale_factor,
+        };
+        let ascent = font_size * ascent;
+        let descent = font_size * descent;
+        let half_leading = (line_height - ascent - descent) / 2.;
+        BaselineExtents {
+            ascent,
+            descent,
+            above: ascent + half_leading,
+            below: descent + half_leading,
+        }
+    }
+
+    /// Flattens the inline-level descendants of `parent` into items, in tree order.
+    fn collect_items(&mut self, parent: &LayoutBox, path: &mut Vec<usize>) {
+        for (child_index, child) in parent.children().iter().enumerate() {
+            path.push(child_index);
+            let index = self.boxes.len();
+            match child.box_type() {
+                // Inline boxes are split around their block-level descendants when the layout
+                // tree is built, so those never end up in an inline formatting context.
+                BoxType::Anonymous => {
+                    unreachable!("block-level box inside an inline formatting context")
+                }
+                // The only blocks left are floats and absolutely positioned boxes, which aren't
+                // block-level.
+                BoxType::Block if child.is_absolutely_positioned() => {
+                    let mut positioned = self.inline_level_box(child, path.clone());
+                    positioned.atomic = Some(self.atomic_inline(child));
+                    self.boxes.push(positioned);
+                    let inline_level = matches!(
+                        child.computed_values().display,
+                        Display::Inline | Display::InlineBlock
+                    );
+                    self.items.push(Item::Absolute {
+                        index,
+                        inline_level,
+                    });
+                }
+                BoxType::Block => {
+                    let mut float = self.inline_level_box(child, path.clone());
+                    float.atomic = Some(self.atomic_inline(child));
+                    float.float = child.float_side();
+                    float.cleared = child.cleared_sides();
+                    self.boxes.push(float);
+                    self.items.push(Item::Float(index));
+                }
+                BoxType::InlineBlock => {
+                    let mut atomic_box = self.inline_level_box(child, path.clone());
+                    atomic_box.atomic = Some(self.atomic_inline(child));
+                    self.boxes.push(atomic_box);
+                    self.items.push(Item::Atomic(index));
+                }
+                BoxType::AnonymousInline | BoxType::Inline => {
+                    self.boxes.push(self.inline_level_box(child, path.clone()));
+                    match child.node().as_text() {
+                        Some(text) => self.collect_text(index, &text.borrow()),
+                        None => {
+                            self.items.push(Item::BoxStart(index));
+                            self.collect_items(child, path);
+                            self.items.push(Item::BoxEnd(index));
+                        }
+                    }
+                }
+            }
+            path.pop();
+        }
+    }
+
+    fn inline_level_box(&self, layout_box: &LayoutBox, path: Vec<usize>) -> InlineLevelBox {
+        let cvs = layout_box.computed_values();
+        let mut margin = EdgeSizes::default();
+        let mut border = EdgeSizes::default();
+        let mut padding = EdgeSizes::default();
+        // Text nodes have no box of their own to give edges to, though they may inherit a
+        // `border-*-style` or similar.
+        if layout_box.node().as_text().is_none() {
+            let containing_size = self.available_inline_size;
+            for &(side, physical_side) in self.physical_sides(cvs.direction).iter() {
+                *margin.get_mut(side) =
+                    cvs.margin(physical_side).to_px(containing_size) * self.scale_factor;
+                *border.get_mut(side) = cvs.border_width(physical_side) * self.scale_factor;
+                *padding.get_mut(side) =
+                    cvs.padding(physical_side).to_px(containing_size) * self.scale_factor;
+            }
+            // The pieces of a box split around a block-level box have no edges where they're
+            // continued by another piece.
+            let split = layout_box.split();
+            for &(side, continued) in [
+                (Side::Left, split.continues_before),
+                (Side::Right, split.continues_after),
+            ]
+            .iter()
+            {
+                if continued {
+                    *margin.get_mut(side) = CSSPixelLength::new(0.);
+                    *border.get_mut(side) = CSSPixelLength::new(0.);
+                    *padding.get_mut(side) = CSSPixelLength::new(0.);
+                }
+            }
+        }
+        InlineLevelBox {
+            path,
+            extents: self.baseline_extents(&cvs),
+            margin,
+            border,
+            padding,
+            direction: cvs.direction,
+            unicode_bidi: cvs.unicode_bidi,
+            font_query: FontQuery::new(&cvs),
+            font_size: cvs.font_size.size,
+            white_space: cvs.white_space,
+            word_break: cvs.word_break,
+            overflow_wrap: cvs.overflow_wrap,
+            hyphens: cvs.hyphens,
+            fragments: Vec::new(),
+            atomic: None,
+            float: None,
+            cleared: &[],
+        }
+    }
+
+    /// Measures an atomic inline-level box that has already been laid out.  Its baseline is that
+    /// of its last line box, or the block-end edge of its margin box if it has none.
+    ///
+    /// https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align
+    fn atomic_inline(&self, layout_box: &LayoutBox) -> AtomicInline {
+        let margin_box = layout_box.physical_dimensions().margin_box();
+        let (inline_size, block_size, block_start) = if self.writing_mode.is_horizontal() {
+            (margin_box.width, margin_box.height, margin_box.start_y)
+        } else {
+            (margin_box.height, margin_box.width, margin_box.start_x)
+        };
+        AtomicInline {
+            inline_size,
+            block_size,
+            baseline: layout_box.last_baseline().map_or(block_size, |baseline| {
+                CSSPixelLength::new(baseline - block_start)
+            }),
+            origin: (margin_box.start_x, margin_box.start_y),
+            placed: None,
+        }
+    }
+
+    /// Pairs each side of a horizontal, left-to-right writing mode with the physical side it
+    /// corresponds to in this formatting context's writing mode and the given `direction`.
+    // FIXME: Like block layout, this treats all vertical writing modes as `vertical-lr`.
+    fn physical_sides(&self, direction: Direction) -> [(Side, Side); 4] {
+        let (line_left, line_right, over, under) = if self.writing_mode.is_horizontal() {
+            (Side::Left, Side::Right, Side::Top, Side::Bottom)
+        } else {
+            (Side::Top, Side::Bottom, Side::Left, Side::Right)
+        };
+        let (inline_start, inline_end) = match direction {
+            Direction::Ltr => (line_left, line_right),
+            Direction::Rtl => (line_right, line_left),
+        };
+        [
+            (Side::Left, inline_start),
+            (Side::Right, inline_end),
+            (Side::Top, over),
+            (Side::Bottom, under),
+        ]
+    }
+
+    /// Splits the text of the text node at `index` into words, spaces and forced breaks, collapsing
+    /// and transforming its whitespace as its `white-space` dictates.
+    ///
+    /// https://drafts.csswg.org/css-text-3/#white-space-phase-1
+    fn collect_text(&mut self, index: usize, text: &str) {
+        let query = self.boxes[index].font_query.clone();
+        let font_size = self.boxes[index].font_size * self.scale_factor;
+        let white_space = self.boxes[index].white_space;
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut word = Vec::new();
+        for (char_index, &ch) in chars.iter().enumerate() {
+            let is_segment_break = ch == '\n';
+            if ch == SOFT_HYPHEN {
+                // Soft hyphens are invisible unless a line breaks at them.
+                word.push(ShapedGlyph {
+                    ch,
+                    font: None,
+                    advance: CSSPixelLength::new(0.),
+                });
+                continue;
+            }
+            if !is_segment_break && !is_space(ch) {
+                word.push(self.shape(&query, font_size, ch));
+                continue;
+            }
+            if !word.is_empty() {
+                self.items
+                    .push(Item::Word(index, std::mem::take(&mut word)));
+            }
+            if is_segment_break && white_space.preserves_segment_breaks() {
+                if white_space.collapses_spaces() {
+                    // Collapsible spaces before a preserved segment break are removed, as are
+                    // those after it (by `follows_collapsible_space`).
+                    while matches!(self.items.last(), Some(Item::Space(space_index, _)) if *space_index == index)
+                    {
+                        self.items.pop();
+                    }
+                }
+                self.items.push(Item::ForcedBreak);
+            } else if white_space.collapses_spaces() {
+                if is_segment_break && is_removed_segment_break(&chars, char_index) {
+                    continue;
+                }
+                if !self.follows_collapsible_space() {
+                    let space = self.shape(&query, font_size, ' ');
+                    self.items.push(Item::Space(index, space));
+                }
+            } else {
+                let mut space = self.shape(&query, font_size, ' ');
+                if ch == '\t' {
+                    // FIXME: Tabs should advance to the next tab stop, rather than by a fixed
+                    // amount.  https://drafts.csswg.org/css-text-3/#tab-size-property
+                    space.ch = '\t';
+                    space.advance *= TAB_SIZE;
+                }
+                self.items.push(Item::Space(index, space));
+            }
+        }
+        if !word.is_empty() {
+            self.items.push(Item::Word(index, word));
+        }
+    }
+
+    /// Whether a collapsible space added now would collapse away, which it does at the start of
+    /// the formatting context, or after another collapsible space, a forced break or a block (even
+    /// across inline box boundaries).
+    fn follows_collapsible_space(&self) -> bool {
+        self.items
+            .iter()
+            .rev()
+            .find_map(|item| match item {
+                Item::Word(..) | Item::Atomic(_) => Some(false),
+                Item::Space(index, _) => Some(self.boxes[*index].white_space.collapses_spaces()),
+                Item::ForcedBreak => Some(true),
+                Item::BoxStart(_)
+                | Item::BoxEnd(_)
+                | Item::Float(_)
+                | Item::Absolute { .. }
+                | Item::SoftBreak { .. } => None,
+            })
+            .unwrap_or(true)
+    }
+
+    /// Splits words at the opportunities to break lines within them, and inserts opportunities
+    /// between words that aren't separated by spaces.  These are found by running the Unicode line
+    /// breaking algorithm over all the text in the formatting context, across inline box
+    /// boundaries.  Where `overflow-wrap` allows it, emergency opportunities are inserted between
+    /// the other grapheme clusters of words.
+    ///
+    /// Spaces, forced breaks and blocks remain opportunities of their own.
+    ///
+    /// https://drafts.csswg.org/css-text-3/#line-breaking
+    // FIXME: Spaces are always opportunities, even after characters the algorithm forbids a break
+    // after no matter the spaces that follow (such as opening quotation marks).
+    fn insert_soft_breaks(&mut self) {
+        let mut text = Vec::new();
+        for item in &self.items {
+            match item {
+                Item::Word(index, glyphs) => {
+                    let word_break = self.boxes[*index].word_break;
+                    text.extend(glyphs.iter().map(|glyph| (glyph.ch, word_break)));
+                }
+                Item::Space(index, _) => text.push((' ', self.boxes[*index].word_break)),
+                Item::Atomic(index) => {
+                    text.push((OBJECT_REPLACEMENT_CHARACTER, self.boxes[*index].word_break))
+                }
+                Item::ForcedBreak => text.push(('\n', WordBreak::Normal)),
+                Item::BoxStart(_)
+                | Item::BoxEnd(_)
+                | Item::Float(_)
+                | Item::Absolute { .. }
+                | Item::SoftBreak { .. } => {}
+            }
+        }
+        let breaks = break_opportunities(&text);
+
+        let mut items = Vec::with_capacity(self.items.len());
+        let mut char_index = 0;
+        // The last character if it was part of a word, with the index of its text node and where
+        // a break after it would be inserted: after any boxes that end after it, but before any
+        // that start.
+        let mut previous: Option<(usize, char, usize)> = None;
+        for item in std::mem::take(&mut self.items) {
+            match item {
+                Item::Word(index, glyphs) => {
+                    let mut word = Vec::new();
+                    for glyph in glyphs {
+                        if let Some((previous_index, previous_char, position)) = previous {
+                            let soft_break = self.soft_break(
+                                previous_index,
+                                previous_char,
+                                breaks[char_index],
+                                glyph.ch,
+                            );
+                            if let Some(soft_break) = soft_break {
+                                if word.is_empty() {
+                                    items.insert(position, soft_break);
+                                } else {
+                                    items.push(Item::Word(index, std::mem::take(&mut word)));
+                                    items.push(soft_break);
+                                }
+                            }
+                        }
+                        previous = Some((index, glyph.ch, items.len() + 1));
+                        word.push(glyph);
+                        char_index += 1;
+                    }
+                    items.push(Item::Word(index, word));
+                }
+                Item::Atomic(index) => {
+                    if let Some((previous_index, previous_char, position)) = previous {
+                        let soft_break = self.soft_break(
+                            previous_index,
+                            previous_char,
+                            breaks[char_index],
+                            OBJECT_REPLACEMENT_CHARACTER,
+                        );
+                        if let Some(soft_break) = soft_break {
+                            items.insert(position, soft_break);
+                        }
+                    }
+                    items.push(item);
+                    previous = Some((index, OBJECT_REPLACEMENT_CHARACTER, items.len()));
+                    char_index += 1;
+                }
+                Item::BoxEnd(_) => {
+                    items.push(item);
+                    if let Some((.., position)) = &mut previous {
+                        *position = items.len();
+                    }
+                }
+                Item::Space(..) | Item::ForcedBreak => {
+                    items.push(item);
+                    previous = None;
+                    char_index += 1;
+                }
+                Item::BoxStart(_)
+                | Item::Float(_)
+                | Item::Absolute { .. }
+                | Item::SoftBreak { .. } => items.push(item),
+            }
+        }
+        self.items = items;
+    }
+
+    /// The item to insert between two characters of words, given whether the line breaking
+    /// algorithm allows a break between them.  `text` is the index of the text node of the
+    /// character before.
+    fn soft_break(
+        &self,
+        text: usize,
+        before: char,
+        line_break: Break,
+        after: char,
+    ) -> Option<Item> {
+        let inline_box = &self.boxes[text];
+        match line_break {
+            Break::Mandatory => return Some(Item::ForcedBreak),
+            Break::Allowed if before != SOFT_HYPHEN => {
+                return Some(Item::SoftBreak {
+                    text,
+                    hyphen: None,
+                    emergency: false,
+                })
+            }
+            // With `hyphens: none`, soft hyphens aren't opportunities to break.
+            Break::Allowed if inline_box.hyphens != Hyphens::None => {
+                let font_size = inline_box.font_size * self.scale_factor;
+                return Some(Item::SoftBreak {
+                    text,
+                    hyphen: Some(self.shape(&inline_box.font_query, font_size, '-')),
+                    emergency: false,
+                });
+            }
+            Break::Allowed | Break::Prohibited => {}
+        }
+        if inline_box.overflow_wrap != OverflowWrap::Normal
+            && inline_box.white_space.wraps()
+            && !continues_grapheme(after)
+        {
+            Some(Item::SoftBreak {
+                text,
+                hyphen: None,
+                emergency: true,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Resolves the bidi embedding level of each item, splitting words where their level changes.
+    /// The levels are resolved over all the text in the formatting context, with the
+    /// `unicode-bidi` of each inline box standing in for the formatting characters it's
+    /// equivalent to.  Paragraphs take their direction from `direction`, or from their text if
+    /// `plaintext` is set.
+    ///
+    /// Items without text of their own, such as the edges of boxes, take the level of the text
+    /// inside the box they belong to, so they're reordered along with it.
+    ///
+    /// https://drafts.csswg.org/css-writing-modes-4/#text-direction
+    // FIXME: Arabic text should be shaped, so that its letters take their joining forms.
+    fn resolve_bidi_levels(&mut self, direction: Direction, plaintext: bool) {
+        let mut text = Vec::new();
+        for item in &self.items {
+            match item {
+                Item::Word(_, glyphs) => text.extend(glyphs.iter().map(|glyph| glyph.ch)),
+                Item::Space(_, space) => text.push(space.ch),
+                Item::Atomic(_) => text.push(OBJECT_REPLACEMENT_CHARACTER),
+                Item::ForcedBreak => text.push(PARAGRAPH_SEPARATOR),
+                Item::BoxStart(index) => text.extend(self.boxes[*index].bidi_controls().0),
+                Item::BoxEnd(index) => text.extend(self.boxes[*index].bidi_controls().1),
+                Item::Float(_) | Item::Absolute { .. } | Item::SoftBreak { .. } => {}
+            }
+        }
+        let info = resolve_levels(&text, direction, plaintext);
+        let paragraph_level = |char_index: usize| {
+            info.paragraphs
+                .iter()
+                .find(|paragraph| char_index < paragraph.range.end)
+                .or_else(|| info.paragraphs.last())
+                .map_or(direction_level(direction), |paragraph| paragraph.level)
+        };
+
+        let mut items = Vec::with_capacity(self.items.len());
+        // The levels of the items with text, and the paragraph levels of all items.
+        let mut text_levels = Vec::with_capacity(self.items.len());
+        let mut paragraph_levels = Vec::with_capacity(self.items.len());
+        let mut char_index = 0;
+        for item in std::mem::take(&mut self.items) {
+            let paragraph = paragraph_level(char_index);
+            match item {
+                Item::Word(index, glyphs) => {
+                    let mut word = Vec::new();
+                    let mut word_level = None;
+                    for glyph in glyphs {
+                        let level = info.levels[char_index];
+                        if !word.is_empty() && word_level != Some(level) {
+                            items.push(Item::Word(index, std::mem::take(&mut word)));
+                            text_levels.push(word_level);
+                            paragraph_levels.push(paragraph);
+                        }
+                        word_level = Some(level);
+                        word.push(glyph);
+                        char_index += 1;
+                    }
+                    items.push(Item::Word(index, word));
+                    text_levels.push(word_level);
+                }
+                Item::Space(..) | Item::Atomic(_) => {
+                    items.push(item);
+                    text_levels.push(Some(info.levels[char_index]));
+                    char_index += 1;
+                }
+                Item::ForcedBreak => {
+                    items.push(item);
+                    text_levels.push(Some(paragraph));
+                    char_index += 1;
+                }
+                Item::BoxStart(index) => {
+                    char_index += self.boxes[index].bidi_controls().0.len();
+                    items.push(item);
+                    text_levels.push(None);
+                }
+                Item::BoxEnd(index) => {
+                    char_index += self.boxes[index].bidi_controls().1.len();
+                    items.push(item);
+                    text_levels.push(None);
+                }
+                Item::Float(_) | Item::Absolute { .. } | Item::SoftBreak { .. } => {
+                    items.push(item);
+                    text_levels.push(None);
+                }
+            }
+            paragraph_levels.push(paragraph);
+        }
+
+        let mut levels: Vec<ItemLevel> = Vec::with_capacity(items.len());
+        for (position, item) in items.iter().enumerate() {
+            let level = match item {
+                Item::BoxStart(index) => items[position + 1..]
+                    .iter()
+                    .zip(&text_levels[position + 1..])
+                    .take_while(|(item, _)| !matches!(item, Item::BoxEnd(end_index) if end_index == index))
+                    .find_map(|(_, level)| *level),
+                Item::BoxEnd(index) => items[..position]
+                    .iter()
+                    .zip(&text_levels[..position])
+                    .rev()
+                    .take_while(|(item, _)| !matches!(item, Item::BoxStart(start_index) if start_index == index))
+                    .find_map(|(_, level)| *level),
+                _ => text_levels[position],
+            }
+            .or_else(|| levels.last().map(|level| level.level))
+            .unwrap_or(paragraph_levels[position]);
+            levels.push(ItemLevel {
+                level,
+                paragraph: paragraph_levels[position],
+            });
+        }
+        self.items = items;
+        self.levels = levels;
+    }
+
+    fn shape(&self, query: &FontQuery, font_size: CSSPixelLength, ch: char) -> ShapedGlyph {
+        match self.font_handle.match_font_for_char(query, ch) {
+            Ok(font_name) => {
+                let font = self.font_handle.loaded_font(&font_name).unwrap();
+                ShapedGlyph {
+                    ch,
+                    advance: font_size * glyph_advance(&font, ch),
+                    font: Some(font_name),
+                }
+            }
+            Err(_) => ShapedGlyph {
+                ch,
+                font: None,
+                advance: CSSPixelLength::new(0.),
+            },
+        }
+    }
+
+    /// The inline size an item takes up on a line.
+    fn item_inline_size(&self, item: &Item) -> CSSPixelLength {
+        match item {
+            Item::BoxStart(index) => {
+                let inline_box = &self.boxes[*index];
+                inline_box.margin.left + inline_box.border.left + inline_box.padding.left
+            }
+            Item::BoxEnd(index) => {
+                let inline_box = &self.boxes[*index];
+                inline_box.margin.right + inline_box.border.right + inline_box.padding.right
+            }
+            Item::Word(_, glyphs) => glyphs
+                .iter()
+                .fold(CSSPixelLength::new(0.), |size, glyph| size + glyph.advance),
+            Item::Space(_, space) => space.advance,
+            Item::Atomic(index) => self.boxes[*index]
+                .atomic
+                .map_or(CSSPixelLength::new(0.), |atomic| atomic.inline_size),
+            // A hyphen only takes up space at the end of a line, which `place_line` accounts for.
+            // Floats sit beside lines rather than on them, and absolutely positioned boxes are out
+            // of the flow altogether.
+            Item::Float(_) | Item::Absolute { .. } | Item::ForcedBreak | Item::SoftBreak { .. } => {
+                CSSPixelLength::new(0.)
+            }
+        }
+    }
+
+    /// Breaks the items into lines, greedily fitting as many words on each line as the available
+    /// inline size allows.  A word that doesn't fit on a line by itself overflows it, unless it has
+    /// emergency opportunities to break at.  Collapsible spaces at the start and end of a line are
+    /// removed, while preserved spaces at the end of a line hang (unless they're `break-spaces`).
+    ///
+    /// Lines are shortened around the floats in `floats`, and every line makes room for `strut`.
+    /// Floats among the items are placed as they're reached, and returned with their positions,
+    /// along with the static positions of absolutely positioned boxes.
+    ///
+    /// https://drafts.csswg.org/css-text-3/#white-space-phase-2
+    fn break_lines(
+        &self,
+        floats: &mut FloatManager,
+        strut: BaselineExtents,
+    ) -> (Vec<Line>, Vec<(usize, (CSSFloat, CSSFloat))>) {
+        let mut breaker = LineBreaker {
+            context: self,
+            floats,
+            strut,
+            lines: Vec::new(),
+            line: Vec::new(),
+            line_size: CSSPixelLength::new(0.),
+            inline_offset: CSSPixelLength::new(0.),
+            block_offset: CSSPixelLength::new(0.),
+            available_inline_size: self.available_inline_size,
+            open_boxes: Vec::new(),
+            pending_floats: Vec::new(),
+            pending_absolutes: Vec::new(),
+            placed: Vec::new(),
+            unbreakable: Vec::new(),
+            unbreakable_size: CSSPixelLength::new(0.),
+        };
+        breaker.fit_line();
+        for (index, item) in self.items.iter().enumerate() {
+            match item {
+                Item::Float(float_index) => {
+                    breaker.add_float(*float_index);
+                    continue;
+                }
+                Item::Absolute {
+                    index: box_index,
+                    inline_level,
+                } => {
+                    let rtl = is_rtl(self.levels[index].paragraph);
+                    breaker.add_absolute(*box_index, *inline_level, rtl);
+                    continue;
+                }
+                _ => {}
+            }
+            if !self.is_break_opportunity(item) {
+                breaker.unbreakable.push(index);
+                breaker.unbreakable_size += self.item_inline_size(item);
+                continue;
+            }
+            match item {
+                Item::ForcedBreak => {
+                    breaker.place_unbreakable(CSSPixelLength::new(0.));
+                    breaker.line.push(index);
+                    breaker.finish_line();
+                }
+                Item::SoftBreak { hyphen, .. } => {
+                    // The hyphen has to fit too, if the line breaks here.
+                    breaker.place_unbreakable(
+                        hyphen
+                            .as_ref()
+                            .map_or(CSSPixelLength::new(0.), |hyphen| hyphen.advance),
+                    );
+                    breaker.line.push(index);
+                }
+                Item::Space(text, space) => {
+                    breaker.place_unbreakable(CSSPixelLength::new(0.));
+                    let white_space = self.boxes[*text].white_space;
+                    if white_space == WhiteSpace::BreakSpaces
+                        && breaker.line_size + space.advance > breaker.available_inline_size
+                        && self.has_content(&breaker.line)
+                    {
+                        breaker.finish_line();
+                    }
+                    if !white_space.collapses_spaces() || self.has_content(&breaker.line) {
+                        breaker.line.push(index);
+                        breaker.line_size += space.advance;
+                    }
+                }
+                Item::BoxStart(_)
+                | Item::BoxEnd(_)
+                | Item::Word(..)
+                | Item::Atomic(_)
+                | Item::Float(_)
+                | Item::Absolute { .. } => unreachable!(),
+            }
+        }
+        breaker.place_unbreakable(CSSPixelLength::new(0.));
+        breaker.finish_line();
+        (breaker.lines, breaker.placed)
+    }
+
+    /// Whether a line may break at the given item, not counting emergency opportunities.
+    fn is_break_opportunity(&self, item: &Item) -> bool {
+        match item {
+            Item::Space(text, _)
+            | Item::SoftBreak {
+                text,
+                emergency: false,
+                ..
+            } => self.boxes[*text].white_space.wraps(),
+            Item::ForcedBreak => true,
+            _ => false,
+        }
+    }
+
+    /// Whether a line with the given items would have anything on it besides collapsible spaces.
+    fn has_content(&self, line: &[usize]) -> bool {
+        line.iter().any(|index| match &self.items[*index] {
+            Item::Word(..) | Item::Atomic(_) | Item::ForcedBreak => true,
+            Item::Space(space_index, _) => !self.is_collapsible_space(*space_index),
+            item @ Item::BoxStart(_) | item @ Item::BoxEnd(_) => {
+                self.item_inline_size(item) > CSSPixelLength::new(0.)
+            }
+            Item::Float(_) | Item::Absolute { .. } | Item::SoftBreak { .. } => false,
+        })
+    }
+
+    fn is_collapsible_space(&self, text_index: usize) -> bool {
+        self.boxes[text_index].white_space.collapses_spaces()
+    }
+
+    /// Removes the collapsible spaces at the start and end of a line, and returns it if anything
+    /// remains on it.  Lines with nothing on them aren't given a line box.
+    fn finish_line(&self, line: Vec<usize>) -> Option<Vec<usize>> {
+        let is_content = |index: &usize| match &self.items[*index] {
+            Item::Space(space_index, _) => !self.is_collapsible_space(*space_index),
+            Item::BoxStart(_)
+            | Item::BoxEnd(_)
+            | Item::Float(_)
+            | Item::Absolute { .. }
+            | Item::SoftBreak { .. } => false,
+            Item::Word(..) | Item::Atomic(_) | Item::ForcedBreak => true,
+        };
+        let first_content = line.iter().position(is_content).unwrap_or(line.len());
+        let last_content = line.iter().rposition(is_content).map_or(0, |last| last + 1);
+        let line = line
+            .into_iter()
+            .enumerate()
+            .filter(|(position, index)| {
+                (first_content..last_content).contains(position)
+                    || !matches!(self.items[*index], Item::Space(..))
+            })
+            .map(|(_, index)| index)
+            .collect::<Vec<_>>();
+        if self.has_content(&line) {
+            Some(line)
+        } else {
+            None
+        }
+    }
+
+    /// How far the content of a line extends above and below its baseline, which is as far as the
+    /// boxes with content on the line, and at least as far as `strut`.  Atomic inline-level boxes
+    /// are aligned by their own baseline.  `open_boxes` holds the boxes whose start was placed on
+    /// an earlier line, but whose end hasn't been placed yet.
+    ///
+    /// https://www.w3.org/TR/CSS2/visudet.html#line-height
+    fn line_extents(
+        &self,
+        items: &[usize],
+        open_boxes: &[usize],
+        strut: BaselineExtents,
+    ) -> (CSSPixelLength, CSSPixelLength) {
+        let mut above = strut.above;
+        let mut below = strut.below;
+        let box_starts = items.iter().filter_map(|&index| match self.items[index] {
+            Item::BoxStart(box_index) => Some(box_index),
+            _ => None,
+        });
+        let text_boxes = items.iter().enumerate().filter_map(|(position, &index)| {
+            self.line_glyphs(&self.items[index], position == items.len() - 1)
+                .map(|(text_index, _)| text_index)
+        });
+        for extents in open_boxes
+            .iter()
+            .copied()
+            .chain(box_starts)
+            .chain(text_boxes)
+            .map(|index| self.boxes[index].extents)
+        {
+            above = above.max(extents.above);
+            below = below.max(extents.below);
+        }
+        for &index in items {
+            if let Item::Atomic(atomic_index) = self.items[index] {
+                if let Some(atomic) = self.boxes[atomic_index].atomic {
+                    above = above.max(atomic.baseline);
+                    below = below.max(atomic.block_size - atomic.baseline);
+                }
+            }
+        }
+        (above, below)
+    }
+
+    /// Places the items of a line, giving fragments to the boxes they belong to, and returns the
+    /// block size of the line box and the offset of its baseline.  `open_boxes` holds the boxes
+    /// whose start was placed on an earlier line, but whose end hasn't been placed yet.
+    ///
+    /// Items are placed in visual order, which bidi reordering may make differ from their logical
+    /// order.  Lines start at the start edge of their paragraph's direction.
+    ///
+    /// https://www.w3.org/TR/CSS2/visudet.html#line-height
+    /// https://www.unicode.org/reports/tr9/#Reordering_Resolved_Levels
+    // FIXME: Lines should be aligned as `text-align` dictates.
+    fn place_line(
+        &mut self,
+        line: &Line,
+        open_boxes: &mut Vec<usize>,
+        strut: BaselineExtents,
+    ) -> (CSSPixelLength, CSSPixelLength) {
+        let items = &line.items[..];
+        let (above, below) = self.line_extents(items, open_boxes, strut);
+        let paragraph_level = self.levels[items[0]].paragraph;
+        let mut levels = items
+            .iter()
+            .map(|&index| self.levels[index].level)
+            .collect::<Vec<_>>();
+        // Preserved spaces at the end of the line take the paragraph's direction (L1).
+        for position in (0..items.len()).rev() {
+            match self.items[items[position]] {
+                Item::Space(..) | Item::ForcedBreak => levels[position] = paragraph_level,
+                _ => break,
+            }
+        }
+        let glyphs = items
+            .iter()
+            .enumerate()
+            .map(|(position, &index)| {
+                self.line_glyphs(&self.items[index], position == items.len() - 1)
+            })
+            .collect::<Vec<_>>();
+        let sizes = items
+            .iter()
+            .zip(&glyphs)
+            .map(|(&index, glyphs)| match glyphs {
+                Some((_, glyphs)) => glyphs
+                    .iter()
+                    .fold(CSSPixelLength::new(0.), |size, glyph| size + glyph.advance),
+                None => self.item_inline_size(&self.items[index]),
+            })
+            .collect::<Vec<_>>();
+        let line_size = sizes
+            .iter()
+            .fold(CSSPixelLength::new(0.), |line_size, &size| line_size + size);
+
+        // Where each item starts and ends on the line, in visual order.
+        let order = visual_order(&levels);
+        let mut spans = vec![(CSSPixelLength::new(0.), CSSPixelLength::new(0.)); items.len()];
+        let mut inline_offset = if is_rtl(paragraph_level) {
+            line.inline_offset + line.inline_size - line_size
+        } else {
+            line.inline_offset
+        };
+        for &position in &order {
+            spans[position] = (inline_offset, inline_offset + sizes[position]);
+            inline_offset += sizes[position];
+        }
+        let mut visual_positions = vec![0; items.len()];
+        for (visual_position, &position) in order.iter().enumerate() {
+            visual_positions[position] = visual_position;
+        }
+
+        // The boxes that have a fragment on this line, with the positions of the items inside them
+        // (including their own edges).
+        let mut box_items: Vec<(usize, Vec<usize>)> = open_boxes
+            .iter()
+            .map(|&index| (index, Vec::new()))
+            .collect();
+        for (position, &item_index) in items.iter().enumerate() {
+            if let Item::BoxStart(index) = self.items[item_index] {
+                open_boxes.push(index);
+                box_items.push((index, Vec::new()));
+            }
+            for open_index in open_boxes.iter() {
+                if let Some((_, positions)) = box_items
+                    .iter_mut()
+                    .rev()
+                    .find(|(index, _)| index == open_index)
+                {
+                    positions.push(position);
+                }
+            }
+            if let Item::BoxEnd(index) = self.items[item_index] {
+                open_boxes.retain(|open_index| *open_index != index);
+            }
+        }
+        // The fragments of those boxes, with the inline offsets of their content's left and right
+        // edges, and whether they're the box's first and last fragments.
+        let mut placed_boxes = Vec::new();
+        for (index, positions) in box_items {
+            let edge_side = |position: usize| match self.items[items[position]] {
+                Item::BoxStart(edge_index) if edge_index == index => {
+                    Some(if is_rtl(levels[position]) {
+                        Side::Right
+                    } else {
+                        Side::Left
+                    })
+                }
+                Item::BoxEnd(edge_index) if edge_index == index => {
+                    Some(if is_rtl(levels[position]) {
+                        Side::Left
+                    } else {
+                        Side::Right
+                    })
+                }
+                _ => None,
+            };
+            let leftmost = positions
+                .iter()
+                .copied()
+                .min_by_key(|&position| visual_positions[position]);
+            let rightmost = positions
+                .iter()
+                .copied()
+                .max_by_key(|&position| visual_positions[position]);
+            let (leftmost, rightmost) = match (leftmost, rightmost) {
+                (Some(leftmost), Some(rightmost)) => (leftmost, rightmost),
+                _ => continue,
+            };
+            // The content is inside the box's edges, where they're on this line.
+            let left = if edge_side(leftmost) == Some(Side::Left) {
+                spans[leftmost].1
+            } else {
+                spans[leftmost].0
+            };
+            let right = if edge_side(rightmost) == Some(Side::Right) {
+                spans[rightmost].0
+            } else {
+                spans[rightmost].1
+            };
+            let is_first = positions.iter().any(|&position| {
+                matches!(self.items[items[position]], Item::BoxStart(start_index) if start_index == index)
+            });
+            let is_last = positions.iter().any(|&position| {
+                matches!(self.items[items[position]], Item::BoxEnd(end_index) if end_index == index)
+            });
+            placed_boxes.push((index, left, right, is_first, is_last));
+        }
+
+        let atomics = items
+            .iter()
+            .enumerate()
+            .filter_map(|(position, &item_index)| match self.items[item_index] {
+                Item::Atomic(index) => Some((position, index)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let baseline = line.block_offset + above;
+
+        for (position, index) in atomics {
+            if let Some(atomic) = self.boxes[index].atomic {
+                let margin_box = self.physical_rect(
+                    spans[position].0,
+                    baseline - atomic.baseline,
+                    atomic.inline_size,
+                    atomic.block_size,
+                );
+                self.boxes[index].atomic = Some(AtomicInline {
+                    placed: Some((margin_box.start_x, margin_box.start_y)),
+                    ..atomic
+                });
+            }
+        }
+
+        for (index, left, right, is_first, is_last) in placed_boxes {
+            let inline_box = &self.boxes[index];
+            let mut padding = self.physical_edges(inline_box.padding, inline_box.direction);
+            let mut border = self.physical_edges(inline_box.border, inline_box.direction);
+            let [inline_start, inline_end, _, _] = self.physical_sides(inline_box.direction);
+            // Fragments that don't start or end the box have no edges on that side.
+            if !is_first {
+                *padding.get_mut(inline_start.1) = CSSPixelLength::new(0.);
+                *border.get_mut(inline_start.1) = CSSPixelLength::new(0.);
+            }
+            if !is_last {
+                *padding.get_mut(inline_end.1) = CSSPixelLength::new(0.);
+                *border.get_mut(inline_end.1) = CSSPixelLength::new(0.);
+            }
+            let extents = inline_box.extents;
+            let content = self.physical_rect(
+                left,
+                baseline - extents.ascent,
+                (right - left).max(CSSPixelLength::new(0.)),
+                extents.ascent + extents.descent,
+            );
+            self.push_fragment(
+                index,
+                Fragment {
+                    content,
+                    kind: FragmentKind::InlineBox { padding, border },
+                },
+            );
+        }
+
+        // Neighbouring items of the same text node and level are placed as a single fragment.
+        let mut runs: Vec<TextRun> = Vec::new();
+        for &position in &order {
+            let (index, item_glyphs) = match &glyphs[position] {
+                Some((index, item_glyphs)) => (*index, item_glyphs),
+                None => continue,
+            };
+            let level = levels[position];
+            let (start, end) = spans[position];
+            let text = item_glyphs
+                .iter()
+                .map(|glyph| glyph.ch)
+                .filter(|ch| *ch != SOFT_HYPHEN)
+                .collect::<String>();
+            let mut visual_glyphs = item_glyphs.iter().collect::<Vec<_>>();
+            if is_rtl(level) {
+                visual_glyphs.reverse();
+            }
+            let mut origin = start;
+            let mut placed_glyphs = Vec::new();
+            for glyph in visual_glyphs {
+                if let Some(font) = &glyph.font {
+                    let (origin_x, origin_y) = self.physical_point(origin, baseline);
+                    placed_glyphs.push(Glyph {
+                        ch: if is_rtl(level) {
+                            mirrored(glyph.ch)
+                        } else {
+                            glyph.ch
+                        },
+                        font: font.clone(),
+                        origin_x,
+                        origin_y,
+                    });
+                }
+                origin += glyph.advance;
+            }
+            match runs.last_mut() {
+                Some(run) if run.index == index && run.level == level => {
+                    run.end = end;
+                    run.glyphs.extend(placed_glyphs);
+                    run.text.push((position, text));
+                }
+                _ => runs.push(TextRun {
+                    index,
+                    level,
+                    start,
+                    end,
+                    glyphs: placed_glyphs,
+                    text: vec![(position, text)],
+                }),
+            }
+        }
+        for mut run in runs {
+            let extents = self.boxes[run.index].extents;
+            let content = self.physical_rect(
+                run.start,
+                baseline - extents.ascent,
+                run.end - run.start,
+                extents.ascent + extents.descent,
+            );
+            // The text is kept in logical order.
+            run.text.sort_by_key(|(position, _)| *position);
+            let font_size = self.boxes[run.index].font_size;
+            self.push_fragment(
+                run.index,
+                Fragment {
+                    content,
+                    kind: FragmentKind::Text(TextFragment {
+                        text: run.text.into_iter().map(|(_, text)| text).collect(),
+                        glyphs: run.glyphs,
+                        font_size,
+                    }),
+                },
+            );
+        }
+        (above + below, baseline)
+    }
+
+    /// The glyphs an item places on a line, and the text node they belong to.  Soft breaks only
+    /// place their hyphen, if they have one and the line ends at them.
+    fn line_glyphs(&self, item: &Item, ends_line: bool) -> Option<(usize, Vec<ShapedGlyph>)> {
+        match item {
+            Item::Word(index, glyphs) => Some((*index, glyphs.clone())),
+            Item::Space(index, space) => Some((*index, vec![space.clone()])),
+            Item::SoftBreak {
+                text,
+                hyphen: Some(hyphen),
+                ..
+            } if ends_line => Some((*text, vec![hyphen.clone()])),
+            _ => None,
+        }
+    }
+
+    fn push_fragment(&mut self, index: usize, fragment: Fragment) {
+        self.boxes[index].fragments.push(fragment);
+    }
+
+    /// Converts edges oriented as if the writing mode were horizontal and left-to-right into
+    /// physical edges.
+    fn physical_edges(&self, edges: EdgeSizes, direction: Direction) -> EdgeSizes {
+        let mut physical = EdgeSizes::default();
+        for &(side, physical_side) in self.physical_sides(direction).iter() {
+            *physical.get_mut(physical_side) = edges.get(side);
+        }
+        physical
+    }
+
+    /// Converts a rect given by offsets and sizes in the inline and block axes, relative to the
+    /// start of the formatting context, into a physical rect.
+    fn physical_rect(
+        &self,
+        inline_offset: CSSPixelLength,
+        block_offset: CSSPixelLength,
+        inline_size: CSSPixelLength,
+        block_size: CSSPixelLength,
+    ) -> Rect {
+        let (start_x, start_y) = self.physical_point(inline_offset, block_offset);
+        if self.writing_mode.is_horizontal() {
+            Rect {
+                start_x,
+                start_y,
+                width: inline_size,
+                height: block_size,
+            }
+        } else {
+            Rect {
+                start_x,
+                start_y,
+                width: block_size,
+                height: inline_size,
+            }
+        }
+    }
+
+    /// The physical position of the start of the formatting context's content area, as an
+    /// inline and a block coordinate, which is how the float manager measures positions.
+    fn flow_origin(&self) -> (CSSFloat, CSSFloat) {
+        let (origin_x, origin_y) = self.origin;
+        if self.writing_mode.is_horizontal() {
+            (origin_x, origin_y)
+        } else {
+            (origin_y, origin_x)
+        }
+    }
+
+    /// Converts an offset in the block axis, relative to the start of the formatting context, into
+    /// a physical coordinate along that axis.
+    fn physical_block_coord(&self, block_offset: CSSPixelLength) -> CSSFloat {
+        let (origin_x, origin_y) = self.origin;
+        if self.writing_mode.is_horizontal() {
+            origin_y + block_offset.px()
+        } else {
+            origin_x + block_offset.px()
+        }
+    }
+
+    fn physical_point(
+        &self,
+        inline_offset: CSSPixelLength,
+        block_offset: CSSPixelLength,
+    ) -> (CSSFloat, CSSFloat) {
+        let (origin_x, origin_y) = self.origin;
+        if self.writing_mode.is_horizontal() {
+            (origin_x + inline_offset.px(), origin_y + block_offset.px())
+        } else {
+            (origin_x + block_offset.px(), origin_y + inline_offset.px())
+        }
+    }
+}
+
+/// The number of spaces a preserved tab advances by, which is the initial value of `tab-size`.
+const TAB_SIZE: CSSFloat = 8.;
+
+/// Whether `ch` is white space other than a segment break.  Carriage returns and form feeds are
+/// treated as spaces, as HTML considers them white space.
+///
+/// https://drafts.csswg.org/css-text-3/#white-space-processing
+fn is_space(ch: char) -> bool {
+    matches!(ch, ' ' | '\t' | '\r' | '\u{c}')
+}
+
+/// Whether the collapsible segment break at `chars[index]` is removed rather than transformed into
+/// a space, which happens when the text on either side of it is a zero width space.
+///
+/// https://drafts.csswg.org/css-text-3/#line-break-transform
+// FIXME: Segment breaks between East Asian wide characters should be removed as well.
+fn is_removed_segment_break(chars: &[char], index: usize) -> bool {
+    let is_collapsible = |ch: &&char| is_space(**ch) || **ch == '\n';
+    let before = chars[..index].iter().rev().find(|ch| !is_collapsible(ch));
+    let after = chars[index + 1..].iter().find(|ch| !is_collapsible(ch));
+    before == Some(&ZERO_WIDTH_SPACE) || after == Some(&ZERO_WIDTH_SPACE)
+}
+
+const ZERO_WIDTH_SPACE: char = '\u{200b}';
+const SOFT_HYPHEN: char = '\u{ad}';
+const PARAGRAPH_SEPARATOR: char = '\u{2029}';
+/// Stands in for atomic inline-level boxes in the text that bidi and line breaking run over.
+const OBJECT_REPLACEMENT_CHARACTER: char = '\u{fffc}';
+
+// Bidi formatting characters.
+const LRE: char = '\u{202a}';
+const RLE: char = '\u{202b}';
+const PDF: char = '\u{202c}';
+const LRO: char = '\u{202d}';
+const RLO: char = '\u{202e}';
+const LRI: char = '\u{2066}';
+const RLI: char = '\u{2067}';
+const FSI: char = '\u{2068}';
+const PDI: char = '\u{2069}';
+
+/// The bounding rect of the given rects, or an empty rect at `origin` if there are none.
+pub(super) fn rects_bounds(
+    rects: impl IntoIterator<Item = Rect>,
+    origin: (CSSFloat, CSSFloat),
+) -> Rect {
+    let mut rects = rects.into_iter();
+    let first = match rects.next() {
+        Some(rect) => rect,
+        None => {
+            return Rect {
+                start_x: origin.0,
+                start_y: origin.1,
+                ..Rect::default()
+            }
+        }
+    };
+    let (mut start_x, mut start_y) = (first.start_x, first.start_y);
+    let (mut end_x, mut end_y) = (
+        first.start_x + first.width.px(),
+        first.start_y + first.height.px(),
+    );
+    for rect in rects {
+        start_x = start_x.min(rect.start_x);
+        start_y = start_y.min(rect.start_y);
+        end_x = end_x.max(rect.start_x + rect.width.px());
+        end_y = end_y.max(rect.start_y + rect.height.px());
+    }
+    Rect {
+        start_x,
+        start_y,
+        width: CSSPixelLength::new(end_x - start_x),
+        height: CSSPixelLength::new(end_y - start_y),
+    }
+}
@@ -1,11 +1,19 @@
 use crate::dom::tree::{NodeData, NodeRef};
+use crate::gfx::font::FontHandle;
 use crate::layout::dimensions::{LogicalDimensions, PhysicalDimensions};
+use crate::layout::inline::{
+    inline_content_sizes, layout_inline_formatting_context, rects_bounds, Fragment,
+};
+use crate::layout::rect::{EdgeSizes, Rect};
 use crate::layout::{BoxComponent, DumpLayout, DumpLayoutFormat, LogicalDirection};
 use crate::style::values::computed::length::{
-    CSSPixelLength, LengthPercentage, LengthPercentageOrAuto,
+    CSSPixelLength, LengthPercentage, LengthPercentageOrAuto, LengthPercentageOrNone,
+};
+use crate::style::values::computed::{
+    BoxSizing, Clear, ComputedValues, Direction, Display, Float, Position, WritingMode,
 };
-use crate::style::values::computed::{ComputedValues, Direction, WritingMode};
 use crate::style::values::used::ToPx;
+use crate::style::values::CSSFloat;
 use crate::Side;
 use std::cell::Ref;
 use std::io::Write;
@@ -13,15 +21,31 @@ use std::mem::discriminant;
 
 #[derive(Clone, Debug)]
 pub struct LayoutBox {
+    /// The position along the block axis of the baseline of the last line box, if this is the
+    /// root inline box of an inline formatting context with any lines.
+    baseline: Option<CSSFloat>,
+    /// The margins of this box in the block axis, once collapsed with those of its children.
+    block_margins: BlockMargins,
     box_type: BoxType,
     children: Vec<LayoutBox>,
     dimensions: LogicalDimensions,
     direction: Direction,
+    /// The formatting context this box establishes for its contents, if it doesn't lay them out in
+    /// the formatting context it's in itself.
+    formatting_context: Option<FormattingContext>,
+    /// The pieces of this box placed on each line, if it takes part in an inline formatting
+    /// context.
+    fragments: Vec<Fragment>,
+    /// The line boxes of the inline formatting context, if this is the root inline box of one.
+    line_boxes: Vec<Rect>,
     /// Reference to the closest non-anonymous node.  This distinction only matters for anonymous
     /// boxes, since anonymous boxes are by definition not associated with a node, but need access
     /// to a node to get computed values during layout.  If the box is a block, inline, or any other
     /// non-anonymous box, this field is simply the actual DOM node associated with this box.
     node: NodeRef,
+    /// The sides this box is continued on by other pieces of it, if it's an inline box that was
+    /// split around a block-level descendant.
+    split: InlineSplit,
     writing_mode: WritingMode,
 }
 
@@ -36,11 +60,17 @@ impl LayoutBox {
         writing_mode: WritingMode,
     ) -> LayoutBox {
         LayoutBox {
+            baseline: None,
+            block_margins: BlockMargins::default(),
             box_type,
             dimensions: LogicalDimensions::new(writing_mode, direction),
             direction,
+            formatting_context: established_formatting_context(box_type, &node, writing_mode),
             children: Vec::new(),
+            fragments: Vec::new(),
+            line_boxes: Vec::new(),
             node,
+            split: InlineSplit::default(),
             writing_mode,
         }
     }
@@ -57,6 +87,72 @@ impl LayoutBox {
         &self.children
     }
 
+    pub fn fragments(&self) -> &[Fragment] {
+        &self.fragments
+    }
+
+    pub fn line_boxes(&self) -> &[Rect] {
+        &self.line_boxes
+    }
+
+    pub fn node(&self) -> &NodeRef {
+        &self.node
+    }
+
+    pub fn split(&self) -> InlineSplit {
+        self.split
+    }
+
+    pub fn writing_mode(&self) -> WritingMode {
+        self.writing_mode
+    }
+
+    /// The descendant reached by following the given child indices down from this box.
+    pub(super) fn descendant(&self, path: &[usize]) -> &LayoutBox {
+        path.iter()
+            .fold(self, |layout_box, index| &layout_box.children[*index])
+    }
+
+    /// The descendant reached by following the given child indices down from this box.
+    pub(super) fn descendant_mut(&mut self, path: &[usize]) -> &mut LayoutBox {
+        path.iter()
+            .fold(self, |layout_box, index| &mut layout_box.children[*index])
+    }
+
+    /// Stores the fragments inline layout gave this box.  Its content area becomes their bounds,
+    /// or an empty rect at `origin` if it has none.
+    pub(super) fn set_fragments(&mut self, fragments: Vec<Fragment>, origin: (CSSFloat, CSSFloat)) {
+        self.dimensions.replace_inner_physical(PhysicalDimensions {
+            content: rects_bounds(fragments.iter().map(|fragment| fragment.content), origin),
+            ..PhysicalDimensions::default()
+        });
+        self.fragments = fragments;
+    }
+
+    /// Stores the line boxes inline layout placed the contents of this root inline box into, and
+    /// the position of the baseline of the last of them along the block axis.
+    pub(super) fn set_line_boxes(&mut self, line_boxes: Vec<Rect>, baseline: Option<CSSFloat>) {
+        self.line_boxes = line_boxes;
+        self.baseline = baseline;
+    }
+
+    /// The position along the block axis of the baseline of the last line box in this box's normal
+    /// flow, if there is one.  Lines inside atomic inline-level descendants don't count, as those
+    /// boxes are placed on lines of their own formatting context.
+    ///
+    /// https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align
+    pub fn last_baseline(&self) -> Option<CSSFloat> {
+        match self.box_type {
+            BoxType::AnonymousInline => self.baseline,
+            BoxType::Anonymous | BoxType::Block | BoxType::InlineBlock => self
+                .children
+                .iter()
+                .rev()
+                .find_map(|child| child.last_baseline()),
+            BoxType::Inline => None,
+        }
+    }
+
     /// Retrieve the computed values of the node associated with this layout box.
     pub fn computed_values(&self) -> Ref<ComputedValues> {
         self.node.computed_values()
@@ -70,6 +166,97 @@ impl LayoutBox {
         }
     }
 
+    /// Whether this box participates in a block formatting context as part of its normal flow.
+    /// Floats and absolutely positioned boxes are taken out of the flow, and stay with the
+    /// inline-level content around them.
+    pub fn is_block_level(&self) -> bool {
+        match self.box_type {
+            BoxType::Anonymous => true,
+            BoxType::Block => !self.is_floated() && !self.is_absolutely_positioned(),
+            BoxType::AnonymousInline | BoxType::Inline | BoxType::InlineBlock => false,
+        }
+    }
+
+    /// Whether this box is floated.  The root element and anonymous boxes never are.
+    pub fn is_floated(&self) -> bool {
+        self.float_side().is_some()
+    }
+
+    /// The side of its containing block this box floats to, if it's floated.  The flow-relative
+    /// values of `float` are resolved against the `direction` of the containing block.
+    ///
+    /// https://drafts.csswg.org/css-logical-1/#float-clear
+    pub(super) fn float_side(&self) -> Option<FloatSide> {
+        if matches!(self.box_type, BoxType::Anonymous | BoxType::AnonymousInline)
+            || self.is_root()
+            || self.is_absolutely_positioned()
+        {
+            return None;
+        }
+        match (self.computed_values().float, self.containing_direction()) {
+            (Float::None, _) => None,
+            (Float::Left, _)
+            | (Float::InlineStart, Direction::Ltr)
+            | (Float::InlineEnd, Direction::Rtl) => Some(FloatSide::LineLeft),
+            (Float::Right, _)
+            | (Float::InlineStart, Direction::Rtl)
+            | (Float::InlineEnd, Direction::Ltr) => Some(FloatSide::LineRight),
+        }
+    }
+
+    /// Whether this box is absolutely positioned, which boxes with `position: absolute` or
+    /// `position: fixed` are, other than the root element's.  They're blockified when the layout
+    /// tree is built.
+    pub(super) fn is_absolutely_positioned(&self) -> bool {
+        self.box_type == BoxType::Block
+            && !self.is_root()
+            && self.computed_values().position.is_absolutely_positioned()
+    }
+
+    /// Whether this box is the containing block of the absolutely positioned boxes among its
+    /// descendants, which positioned block containers and inline boxes are.
+    ///
+    /// https://www.w3.org/TR/CSS2/visudet.html#containing-block-details
+    fn establishes_absolute_containing_block(&self) -> bool {
+        matches!(
+            self.box_type,
+            BoxType::Block | BoxType::InlineBlock | BoxType::Inline
+        ) && self.computed_values().position != Position::Static
+    }
+
+    /// The sides of the earlier floats this box has to be placed below.  Only block-level boxes
+    /// and floats can clear floats.
+    ///
+    /// https://www.w3.org/TR/CSS2/visuren.html#flow-control
+    pub(super) fn cleared_sides(&self) -> &'static [FloatSide] {
+        if self.box_type != BoxType::Block {
+            return &[];
+        }
+        match (self.computed_values().clear, self.containing_direction()) {
+            (Clear::None, _) => &[],
+            (Clear::Left, _)
+            | (Clear::InlineStart, Direction::Ltr)
+            | (Clear::InlineEnd, Direction::Rtl) => &[FloatSide::LineLeft],
+            (Clear::Right, _)
+            | (Clear::InlineStart, Direction::Rtl)
+            | (Clear::InlineEnd, Direction::Ltr) => &[FloatSide::LineRight],
+            (Clear::Both, _) => &[FloatSide::LineLeft, FloatSide::LineRight],
+        }
+    }
+
+    /// The `direction` of this box's containing block, which is that of the parent node.
+    fn containing_direction(&self) -> Direction {
+        self.node
+            .parent()
+            .map_or(self.direction, |parent| parent.computed_values().direction)
+    }
+
+    /// Whether this box establishes a new block formatting context for its contents, whose margins
+    /// then don't collapse with its own.
+    pub fn establishes_block_formatting_context(&self) -> bool {
+        self.formatting_context == Some(FormattingContext::Block)
+    }
+
     /// Directly adds `new_child` to this layout box's children.
     pub fn add_child(&mut self, new_child: LayoutBox) {
         self.children.push(new_child)
@@ -85,36 +272,161 @@ impl LayoutBox {
     /// containers inline-level contents.
     ///
     /// If this box is already an inline or anonymous box, we can use ourselves to contain the
-    /// inline children.  Otherwise, find or create an anonymous box.
+    /// inline children.  Otherwise, use the anonymous box holding the inline children added since
+    /// the last block-level child, creating it if there is none, so that inline and block-level
+    /// children stay in tree order.
     ///
     /// https://drafts.csswg.org/css-inline-3/#model
     fn get_root_inline_box(&mut self) -> &mut LayoutBox {
         match self.box_type {
             BoxType::Anonymous | BoxType::AnonymousInline | BoxType::Inline => self,
-            BoxType::Block => {
-                let root_inline_box_idx_opt =
-                    self.children.iter().enumerate().find_map(|(idx, child)| {
-                        if discriminant(&child.box_type) == discriminant(&BoxType::AnonymousInline)
-                        {
-                            return Some(idx);
-                        }
-                        None
-                    });
-                let idx = match root_inline_box_idx_opt {
-                    Some(idx) => idx,
-                    None => {
-                        self.children.push(LayoutBox::new(
-                            BoxType::AnonymousInline,
-                            self.node.clone(),
-                            self.direction,
-                            self.writing_mode,
-                        ));
-                        self.children.len() - 1
+            BoxType::Block | BoxType::InlineBlock => {
+                let ends_with_root_inline_box = self.children.last().map_or(false, |child| {
+                    discriminant(&child.box_type) == discriminant(&BoxType::AnonymousInline)
+                });
+                if !ends_with_root_inline_box {
+                    self.children.push(LayoutBox::new(
+                        BoxType::AnonymousInline,
+                        self.node.clone(),
+                        self.direction,
+                        self.writing_mode,
+                    ));
+                }
+                self.children.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// Makes the children of this block container either all block-level or all inline-level.
+    /// Inline boxes are split around the block-level boxes inside them, which become children of
+    /// this box.  If this box then has any block-level children, each run of inline-level content
+    /// is wrapped in an anonymous block box, unless it's only white space that collapses away.
+    ///
+    /// https://www.w3.org/TR/CSS2/visuren.html#anonymous-block-level
+    pub fn generate_anonymous_block_boxes(&mut self) {
+        let mut children = Vec::with_capacity(self.children.len());
+        for child in std::mem::take(&mut self.children) {
+            if child.is_block_level() {
+                children.push(child);
+            } else {
+                children.extend(child.split_around_blocks());
+            }
+        }
+        if children.iter().any(LayoutBox::is_block_level) {
+            children = children
+                .into_iter()
+                .filter(|child| child.is_block_level() || !child.is_collapsible_white_space())
+                .map(|child| {
+                    if child.is_block_level() {
+                        child
+                    } else {
+                        child.wrap_in_anonymous_block()
                     }
-                };
-                self.children.get_mut(idx).unwrap()
+                })
+                .collect();
+        }
+        self.children = children;
+    }
+
+    /// Splits this inline-level box around the block-level boxes among its descendants.  Returns
+    /// the pieces of this box in order, with the block-level boxes between them.  A box without
+    /// block-level descendants is returned whole.
+    fn split_around_blocks(mut self) -> Vec<LayoutBox> {
+        if !self.has_block_level_descendant() {
+            return vec![self];
+        }
+        let mut boxes = Vec::new();
+        let mut piece = self.empty_piece();
+        for child in std::mem::take(&mut self.children) {
+            let child_boxes = if child.is_block_level() {
+                vec![child]
+            } else {
+                child.split_around_blocks()
+            };
+            for child_box in child_boxes {
+                if child_box.is_block_level() {
+                    boxes.push(std::mem::replace(&mut piece, self.empty_piece()));
+                    boxes.push(child_box);
+                } else {
+                    piece.children.push(child_box);
+                }
             }
         }
+        boxes.push(piece);
+
+        let piece_count = boxes
+            .iter()
+            .filter(|layout_box| !layout_box.is_block_level())
+            .count();
+        for (piece_index, piece) in boxes
+            .iter_mut()
+            .filter(|layout_box| !layout_box.is_block_level())
+            .enumerate()
+        {
+            piece.split = InlineSplit {
+                continues_before: piece_index > 0,
+                continues_after: piece_index < piece_count - 1,
+            };
+        }
+        boxes
+    }
+
+    /// Whether this inline-level box holds nothing but white space that collapses away, which
+    /// wouldn't give it any line boxes.
+    ///
+    /// https://drafts.csswg.org/css-text-3/#white-space-phase-1
+    fn is_collapsible_white_space(&self) -> bool {
+        match self.box_type {
+            BoxType::AnonymousInline => self
+                .children
+                .iter()
+                .all(LayoutBox::is_collapsible_white_space),
+            BoxType::Inline => match self.node.as_text() {
+                Some(text) => {
+                    let white_space = self.computed_values().white_space;
+                    let text = text.borrow();
+                    white_space.collapses_spaces()
+                        && text.chars().all(|ch| ch.is_ascii_whitespace())
+                        && !(white_space.preserves_segment_breaks() && text.contains('\n'))
+                }
+                None => false,
+            },
+            BoxType::Anonymous | BoxType::Block | BoxType::InlineBlock => false,
+        }
+    }
+
+    /// Whether this inline box has block-level descendants, which are only looked for in its
+    /// inline-level contents.  The contents of block containers, such as inline-blocks, stay
+    /// inside them.
+    fn has_block_level_descendant(&self) -> bool {
+        match self.box_type {
+            BoxType::AnonymousInline | BoxType::Inline => self
+                .children
+                .iter()
+                .any(|child| child.is_block_level() || child.has_block_level_descendant()),
+            BoxType::Anonymous | BoxType::Block | BoxType::InlineBlock => false,
+        }
+    }
+
+    /// A piece of this box, without any children yet.
+    fn empty_piece(&self) -> LayoutBox {
+        LayoutBox::new(
+            self.box_type,
+            self.node.clone(),
+            self.direction,
+            self.writing_mode,
+        )
+    }
+
+    fn wrap_in_anonymous_block(self) -> LayoutBox {
+        let mut anonymous_block = LayoutBox::new(
+            BoxType::Anonymous,
+            self.node.clone(),
+            self.direction,
+            self.writing_mode,
+        );
+        anonymous_block.children.push(self);
+        anonymous_block
     }
 
     /// Calculates the dimensions of this box, and any child boxes.
@@ -124,14 +436,27 @@ impl LayoutBox {
     /// in layout.
     ///
     /// In this step, we will be taking computed values and calculating actual, used values
-    /// based on the constraint of our environment.
-    pub fn layout(&mut self, containing_block: PhysicalDimensions, scale_factor: f32) {
+    /// based on the constraint of our environment.  `floats` holds the floats placed so far in
+    /// the block formatting context this box is in.
+    pub fn layout(
+        &mut self,
+        containing_block: PhysicalDimensions,
+        floats: &mut FloatManager,
+        font_handle: &FontHandle,
+        scale_factor: f32,
+    ) {
         match self.box_type {
-            BoxType::Anonymous | BoxType::AnonymousInline => {
-                //                println!("layout anonymous box types not implemented");
-                layout_non_block_because_only_block_is_impl(self, containing_block, scale_factor);
+            BoxType::Anonymous => {
+                self.layout_anonymous_block(containing_block, floats, font_handle, scale_factor)
+            }
+            BoxType::AnonymousInline => {
+                self.layout_root_inline_box(containing_block, floats, font_handle, scale_factor)
+            }
+            // Inline-blocks and floats are laid out like blocks, then placed as a whole by the
+            // inline formatting context they're in.
+            BoxType::Block | BoxType::InlineBlock => {
+                self.layout_block(containing_block, floats, font_handle, scale_factor)
             }
-            BoxType::Block => self.layout_block(containing_block, scale_factor),
             BoxType::Inline => {
                 // TODO: The root element is an inline box-type, so when we can actually layout
                 // inline boxes, make sure to handle the root element.  This current implementation
@@ -141,59 +466,141 @@ impl LayoutBox {
                     // The root element takes the dimensions of the containing block, which is the viewport.
                     self.dimensions.replace_inner_physical(containing_block);
                     for child in &mut self.children {
-                        child.layout(self.dimensions.physical(), scale_factor);
+                        child.layout(
+                            self.dimensions.physical(),
+                            floats,
+                            font_handle,
+                            scale_factor,
+                        );
+                        child.apply_relative_offset(self.dimensions.physical(), scale_factor);
                     }
                 } else {
-                    //                    println!("layout inline box types not implemented");
+                    // Inline boxes within an inline formatting context are laid out by its root
+                    // inline box, so this is only reached by those outside of one, such as the
+                    // document's.
                     layout_non_block_because_only_block_is_impl(
                         self,
                         containing_block,
+                        floats,
+                        font_handle,
                         scale_factor,
                     );
                 }
             }
         }
 
-        // TODO: Implement inline and anonymous layout and then remove this function
+        // TODO: Implement inline layout outside of an inline formatting context and then remove
+        // this function
         fn layout_non_block_because_only_block_is_impl(
             layout_box: &mut LayoutBox,
             containing_block: PhysicalDimensions,
+            floats: &mut FloatManager,
+            font_handle: &FontHandle,
             scale_factor: f32,
         ) {
-            layout_box.calculate_block_logical_width(containing_block, scale_factor);
-            layout_box.layout_block_children(scale_factor);
+            layout_box.calculate_block_logical_width(containing_block, font_handle, scale_factor);
+            layout_box.layout_block_children(floats, font_handle, scale_factor);
+        }
+    }
+
+    /// Lays out a root inline box, which fills the inline size of its block container and is
+    /// placed after the container's preceding children.  Its block size is that of the lines its
+    /// contents are laid out into.
+    fn layout_root_inline_box(
+        &mut self,
+        containing_block: PhysicalDimensions,
+        floats: &mut FloatManager,
+        font_handle: &FontHandle,
+        scale_factor: f32,
+    ) {
+        self.fill_containing_block(containing_block);
+        let block_size = layout_inline_formatting_context(self, floats, font_handle, scale_factor);
+        self.dimensions.set_block_size(block_size);
+        self.layout_inline_positioned_descendants(font_handle, scale_factor);
+        self.apply_inline_relative_offsets(containing_block, scale_factor);
+        // Without any line boxes, margins collapse through the contents as if they weren't there.
+        self.block_margins = BlockMargins {
+            collapses_through: self.line_boxes.is_empty(),
+            ..BlockMargins::default()
+        };
+    }
+
+    /// Lays out an anonymous block box.  Having no margins, borders or padding of its own, it
+    /// fills the inline size of its containing block and is placed after the containing block's
+    /// preceding children.  Its block size is that of its children.
+    fn layout_anonymous_block(
+        &mut self,
+        containing_block: PhysicalDimensions,
+        floats: &mut FloatManager,
+        font_handle: &FontHandle,
+        scale_factor: f32,
+    ) {
+        self.fill_containing_block(containing_block);
+        self.layout_block_children(floats, font_handle, scale_factor);
+    }
+
+    /// Gives this box the inline size of `containing_block`'s content area, and places it at the
+    /// end of the content laid out in it so far.
+    fn fill_containing_block(&mut self, containing_block: PhysicalDimensions) {
+        let content = containing_block.content;
+        let d = &mut self.dimensions;
+        if self.writing_mode.is_horizontal() {
+            d.set_inline_start_coord(content.start_x);
+            d.set_block_start_coord(content.start_y + content.height.px());
+            d.set_inline_size(content.width);
+        } else {
+            d.set_inline_start_coord(content.start_y);
+            d.set_block_start_coord(content.start_x + content.width.px());
+            d.set_inline_size(content.height);
         }
     }
 
     /// Assuming `self` is a block-box, calculate the dimensions of this box and any children.
-    fn layout_block(&mut self, containing_block: PhysicalDimensions, scale_factor: f32) {
+    fn layout_block(
+        &mut self,
+        containing_block: PhysicalDimensions,
+        floats: &mut FloatManager,
+        font_handle: &FontHandle,
+        scale_factor: f32,
+    ) {
         // Child logical width (inline size) can depend on parent logical width, so we need to
         // calculate it for this box before laying out its children.
-        self.calculate_block_logical_width(containing_block, scale_factor);
+        self.calculate_block_logical_width(containing_block, font_handle, scale_factor);
 
         // Determine where the box is located within its containing block.
         self.calculate_block_position(containing_block, scale_factor);
 
         // Recursively layout the children of this box, which also determines this block's logical
         // height (block size).
-        self.layout_block_children(scale_factor);
+        self.layout_block_children(floats, font_handle, scale_factor);
 
         // Now that we've performed a layout with logical properties, let's apply any physical
-        // properties explicitly given for this block (e.g. `width`, `height`, bottom/left/right/top
-        // properties).
+        // properties explicitly given for this block (e.g. `width`, `height`).
         self.apply_physical_properties(containing_block, scale_factor);
+
+        if self.establishes_absolute_containing_block() {
+            self.layout_positioned_descendants(
+                self.dimensions.padding_box(),
+                PositionedScope::Absolute,
+                font_handle,
+                scale_factor,
+            );
+        }
     }
 
     /// Calculate the logical width (inline size) of a block-level non-replaced element in normal
-    /// flow.
+    /// flow, or of an inline-block or floating non-replaced element.
     ///
     /// https://www.w3.org/TR/CSS2/visudet.html#blockwidth
+    /// https://www.w3.org/TR/CSS2/visudet.html#inlineblock-width
+    /// https://www.w3.org/TR/CSS2/visudet.html#float-width
     /// https://drafts.csswg.org/css-writing-modes-4/#vertical-layout
     ///
     /// Sets the inline margin/padding/border dimensions, and the inline size.
     fn calculate_block_logical_width(
         &mut self,
         containing_block: PhysicalDimensions,
+        font_handle: &FontHandle,
         scale_factor: f32,
     ) {
         // FIXME: In all of our abstract layout code, we use self.writing_mode to determine logical
@@ -206,134 +613,121 @@ impl LayoutBox {
         // work when I tried it, likely due to other bugs, such as the FIXME directly above.
         // https://drafts.csswg.org/css-writing-modes-4/#logical-direction-layout
         let containing_width = containing_block.content.width;
+        // Over-constrained margins are resolved according to the `direction` of the containing
+        // block.
+        let containing_direction = self.containing_direction();
+        let shrinks_to_fit = self.box_type == BoxType::InlineBlock || self.is_floated();
         let cvs = self.node.computed_values();
 
-        let mut logical_width = cvs.logical_width();
-        let mut logical_margin_left = cvs.logical_margin(Side::Left);
-        let mut logical_margin_right = cvs.logical_margin(Side::Right);
-
         let logical_border_left = cvs.logical_border_width(Side::Left);
         let logical_border_right = cvs.logical_border_width(Side::Right);
 
         let logical_padding_left = cvs.logical_padding(Side::Left);
         let logical_padding_right = cvs.logical_padding(Side::Right);
 
-        // Run block layout _with_ the device scale factor applied to ensure the proper values are
-        // computed.
-        let block_width = (logical_margin_left.to_px(containing_width)
-            + logical_margin_right.to_px(containing_width)
-            + logical_border_left
+        let inline_padding_and_border = logical_border_left
             + logical_border_right
             + logical_padding_left.to_px(containing_width)
-            + logical_padding_right.to_px(containing_width)
-            + logical_width.to_px(containing_width))
-            * scale_factor;
-
-        let auto = LengthPercentageOrAuto::Auto;
-        // If 'width' is not 'auto' and 'border-left-width' + 'padding-left' + 'width' +
-        // 'padding-right' + 'border-right-width' (plus any of 'margin-left' or 'margin-right'
-        // that are not 'auto') is larger than the width of the containing block, then any 'auto'
-        // values for 'margin-left' or 'margin-right' are, for the following rules, treated as zero.
-        if logical_width != auto && block_width > containing_width {
-            if logical_margin_left == auto {
-                logical_margin_left =
-                    LengthPercentageOrAuto::LengthPercentage(LengthPercentage::new_len(0.));
-            }
-            if logical_margin_right == auto {
-                logical_margin_right =
-                    LengthPercentageOrAuto::LengthPercentage(LengthPercentage::new_len(0.));
+            + logical_padding_right.to_px(containing_width);
+        let content_width = |size: &LengthPercentage| {
+            content_size(
+                size.to_px(containing_width),
+                cvs.box_sizing,
+                inline_padding_and_border,
+            )
+        };
+
+        // https://www.w3.org/TR/CSS2/visudet.html#min-max-widths
+        // 1. The tentative used width is calculated (without 'min-width' and 'max-width')
+        // following the rules under "Calculating widths and margins".
+        let solve = |logical_width: Option<CSSPixelLength>| {
+            if shrinks_to_fit {
+                solve_inline_block_inline_sizes(
+                    &cvs,
+                    logical_width.unwrap_or_else(|| CSSPixelLength::new(0.)),
+                    scale_factor,
+                )
+            } else {
+                solve_block_inline_sizes(
+                    &cvs,
+                    logical_width,
+                    containing_width,
+                    containing_direction,
+                    scale_factor,
+                )
             }
-        }
-        // This value can be negative, indicating an overflow or "overconstraint", if the width of
-        // this box is greater than that of the containing one.
-        let underflow = containing_width - block_width;
-        match (
-            logical_width == auto,
-            logical_margin_left == auto,
-            logical_margin_right == auto,
-        ) {
-            // If all of the above have a computed value other than 'auto', the values are said to be
-            // "over-constrained" and one of the used values will have to be different from its computed
-            // value. If the 'direction' property of the containing block has the value 'ltr', the
-            // specified value of 'margin-right' is ignored and the value is calculated so as to make
-            // the equality true. If the value of 'direction' is 'rtl', this happens to 'margin-left' instead.
-            (false, false, false) => {
-                // TODO: Support `direction: rtl` property/value
-                logical_margin_right = LengthPercentageOrAuto::new_len_px(
-                    logical_margin_right.to_px(containing_width) + underflow,
+        };
+        let logical_width = match cvs.logical_width() {
+            LengthPercentageOrAuto::LengthPercentage(width) => Some(content_width(&width)),
+            // An inline-block or float with an `auto` width shrinks to fit its contents, as far as
+            // the space available in its containing block allows.
+            // https://www.w3.org/TR/CSS2/visudet.html#shrink-to-fit-float
+            LengthPercentageOrAuto::Auto if shrinks_to_fit => {
+                let available = containing_width
+                    - (cvs.logical_margin(Side::Left).to_px(containing_width)
+                        + cvs.logical_margin(Side::Right).to_px(containing_width)
+                        + inline_padding_and_border)
+                        * scale_factor;
+                let sizes = self.content_sizes(font_handle, scale_factor);
+                Some(
+                    sizes
+                        .max_content
+                        .min(sizes.min_content.max(available))
+                        .clamp_to_non_negative()
+                        / scale_factor,
                 )
             }
-            // If there is exactly one margin value specified as 'auto', its used value follows
-            // from the equality.
-            (false, true, false) => {
-                logical_margin_left = LengthPercentageOrAuto::new_len_px(underflow)
-            }
-            (false, false, true) => {
-                logical_margin_right = LengthPercentageOrAuto::new_len_px(underflow)
-            }
-            // If both 'margin-left' and 'margin-right' are 'auto', their used values are equal.
-            // This centers the element with respect to the edges of the containing block.
-            (false, true, true) => {
-                logical_margin_left = LengthPercentageOrAuto::new_len_px(underflow / 2.);
-                logical_margin_right = LengthPercentageOrAuto::new_len_px(underflow / 2.);
-            }
-            // If 'width' is set to 'auto', any other 'auto' values become '0' and 'width' follows
-            // from the resulting equality.
-            (true, _, _) => {
-                if logical_margin_left == auto {
-                    logical_margin_left = LengthPercentageOrAuto::new_len(0.)
-                };
-                if logical_margin_right == auto {
-                    logical_margin_right = LengthPercentageOrAuto::new_len(0.)
-                };
-
-                if underflow >= CSSPixelLength::new(0.) {
-                    logical_width = LengthPercentageOrAuto::new_len_px(underflow)
-                } else {
-                    // Width cannot be negative, adjust `margin-right` instead
-                    // TODO: Support `direction: rtl` property/value
-                    logical_width = LengthPercentageOrAuto::new_len(0.);
-                    logical_margin_right = LengthPercentageOrAuto::new_len_px(
-                        logical_margin_right.to_px(containing_width) + underflow,
-                    );
-                }
+            LengthPercentageOrAuto::Auto => None,
+        };
+        let mut used = solve(logical_width);
+        // 2. If the tentative used width is greater than 'max-width', the rules above are applied
+        // again, but this time using the computed value of 'max-width' as the computed value for
+        // 'width'.
+        if let LengthPercentageOrNone::LengthPercentage(max_width) = cvs.logical_max_width() {
+            let max_width = content_width(&max_width);
+            if used.inline_size > max_width * scale_factor {
+                used = solve(Some(max_width));
+            }
+        }
+        // 3. If the resulting width is smaller than 'min-width', the rules above are applied again,
+        // but this time using the value of 'min-width' as the computed value for 'width'.
+        if let LengthPercentageOrAuto::LengthPercentage(min_width) = cvs.logical_min_width() {
+            let min_width = content_width(&min_width);
+            if used.inline_size < min_width * scale_factor {
+                used = solve(Some(min_width));
             }
         }
+
         // Now that we've calculated the inline used values, store them in this box's dimensions.
+        // The "left" and "right" sides above are line-left and line-right, which are the inline
+        // end and start of right-to-left boxes.
+        let (line_left, line_right) = line_sides(self.direction);
         let d = &mut self.dimensions;
-        d.set_inline_size(logical_width.to_px(containing_width));
+        d.set_inline_size(used.inline_size);
 
         d.set(
-            LogicalDirection::InlineStart,
+            line_left,
             BoxComponent::Padding,
             logical_padding_left.to_px(containing_width),
         );
         d.set(
-            LogicalDirection::InlineEnd,
+            line_right,
             BoxComponent::Padding,
             logical_padding_right.to_px(containing_width),
         );
 
-        d.set(
-            LogicalDirection::InlineStart,
-            BoxComponent::Border,
-            logical_border_left,
-        );
-        d.set(
-            LogicalDirection::InlineEnd,
-            BoxComponent::Border,
-            logical_border_right,
-        );
+        d.set(line_left, BoxComponent::Border, logical_border_left);
+        d.set(line_right, BoxComponent::Border, logical_border_right);
 
         d.set(
-            LogicalDirection::InlineStart,
+            line_left,
             BoxComponent::Margin,
-            logical_margin_left.to_px(containing_width),
+            used.margin_left.to_px(containing_width),
         );
         d.set(
-            LogicalDirection::InlineEnd,
+            line_right,
             BoxComponent::Margin,
-            logical_margin_right.to_px(containing_width),
+            used.margin_right.to_px(containing_width),
         );
     }
 
@@ -349,6 +743,7 @@ impl LayoutBox {
         } else {
             containing_block.content.height
         };
+        let (line_left, _) = line_sides(self.direction);
         let d = &mut self.dimensions;
 
         d.set(
@@ -400,9 +795,9 @@ impl LayoutBox {
         };
         d.set_inline_start_coord(
             (container_inline_start_coord
-                + d.get(LogicalDirection::InlineStart, BoxComponent::Margin)
-                + d.get(LogicalDirection::InlineStart, BoxComponent::Border)
-                + d.get(LogicalDirection::InlineStart, BoxComponent::Padding))
+                + d.get(line_left, BoxComponent::Margin)
+                + d.get(line_left, BoxComponent::Border)
+                + d.get(line_left, BoxComponent::Padding))
             .into(),
         );
         // TODO: Always adding containing_block.content.start_y is almost certainly wrong, but
@@ -421,45 +816,1180 @@ impl LayoutBox {
         );
     }
 
-    fn layout_block_children(&mut self, scale_factor: f32) {
-        let mut physical_dimensions = self.dimensions.physical();
+    /// Lays out the children of this box one after another in the block axis, collapsing the
+    /// margins that adjoin between them.  The margins of the first and last children may also
+    /// collapse with this box's own, in which case they're left for the parent to place this box
+    /// with.
+    ///
+    /// Children that clear floats are placed below them, and block formatting context roots are
+    /// placed beside them, or below them if there isn't room.  If this box is a block formatting context root itself, it places the
+    /// floats in its contents by itself, and grows to contain them.
+    ///
+    /// https://www.w3.org/TR/CSS2/box.html#collapsing-margins
+    /// https://www.w3.org/TR/CSS2/visuren.html#floats
+    fn layout_block_children(
+        &mut self,
+        floats: &mut FloatManager,
+        font_handle: &FontHandle,
+        scale_factor: f32,
+    ) {
+        let establishes_block_formatting_context = self.establishes_block_formatting_context();
+        let mut own_floats = FloatManager::default();
+        let floats = if establishes_block_formatting_context {
+            &mut own_floats
+        } else {
+            floats
+        };
+        let content = self.dimensions.physical().content;
+        let content_block_start = if self.writing_mode.is_horizontal() {
+            content.start_y
+        } else {
+            content.start_x
+        };
+        let collapses_with_start =
+            self.margin_collapses_with_children(LogicalDirection::BlockStart);
+        let collapses_with_end = self.margin_collapses_with_children(LogicalDirection::BlockEnd);
+        let mut start = CollapsedMargin::new(
+            self.dimensions
+                .get(LogicalDirection::BlockStart, BoxComponent::Margin),
+        );
+        // The margins collapsed since the last child with content, which are yet to be placed.
+        let mut pending = CollapsedMargin::default();
+        let mut has_content = false;
+        let mut block_size = CSSPixelLength::new(0.);
         for child in &mut self.children {
-            child.layout(physical_dimensions, scale_factor);
-            // Track the block size so each child is laid out after the previous one.
-            self.dimensions.set_block_size(
-                self.dimensions.get_content_block_size() + child.dimensions.margin_box_block_size(),
-            );
-            physical_dimensions = self.dimensions.physical();
+            // Lay each child out where it goes if its margins don't collapse with those of its own
+            // children, which is how far past the previous child's border box its own border box
+            // starts.  It's moved once its margins are known, along with the floats in it.
+            let margin =
+                child.uncollapsed_block_start_margin(self.dimensions.physical(), scale_factor);
+            let mut estimate = if !has_content && collapses_with_start {
+                CSSPixelLength::new(0.)
+            } else {
+                pending.adjoin(CollapsedMargin::new(margin)).resolve()
+            };
+            // A child whose border box would start above the bottom of the floats it clears is
+            // moved below them instead, and its margins no longer collapse with those before it.
+            // https://www.w3.org/TR/CSS2/visuren.html#clearance
+            let mut clearance = floats
+                .clearance(child.cleared_sides())
+                .map(|floats_end| {
+                    CSSPixelLength::new(floats_end - content_block_start) - block_size
+                })
+                .filter(|cleared| *cleared > estimate);
+            // The border box of a block formatting context root may not overlap the floats of the
+            // formatting context it's in, so it's laid out in the space beside them.  When it
+            // doesn't fit there, it's moved down past the floats in the way, the same as if it
+            // cleared them, until it does or there are no floats left beside it.
+            // https://www.w3.org/TR/CSS2/visuren.html#bfc-next-to-float
+            // FIXME: Vertical writing modes, where block positions are derived from the containing
+            // block's `start_y`, aren't handled yet.
+            let avoids_floats = child.box_type == BoxType::Block
+                && child.establishes_block_formatting_context()
+                && self.writing_mode.is_horizontal()
+                && child.writing_mode.is_horizontal();
+            let (tentative_block_size, float_count) = loop {
+                if let Some(cleared) = clearance {
+                    estimate = cleared;
+                }
+                let tentative_block_size = block_size + estimate - margin;
+                self.dimensions.set_block_size(tentative_block_size);
+
+                let mut containing_block = self.dimensions.physical();
+                let border_box_start = content_block_start + (block_size + estimate).px();
+                if avoids_floats {
+                    let (line_left, line_right) = floats.available_space(
+                        border_box_start,
+                        border_box_start,
+                        content.start_x,
+                        content.start_x + content.width.px(),
+                    );
+                    containing_block.content.start_x = line_left;
+                    containing_block.content.width =
+                        CSSPixelLength::new(line_right - line_left).max(CSSPixelLength::new(0.));
+                }
+
+                let float_count = floats.count();
+                child.layout(containing_block, floats, font_handle, scale_factor);
+                if !avoids_floats {
+                    break (tentative_block_size, float_count);
+                }
+                let border_box = child.dimensions.physical().border_box();
+                let border_box_end = border_box_start + border_box.height.px();
+                let (left, right) = floats.available_space(
+                    border_box_start,
+                    border_box_end,
+                    content.start_x,
+                    content.start_x + content.width.px(),
+                );
+                // Boxes that fill the space they're given may overshoot it by a rounding error.
+                let fits = border_box.start_x > left - FIT_TOLERANCE
+                    && border_box.start_x + border_box.width.px() < right + FIT_TOLERANCE;
+                match floats.next_float_end(border_box_start, border_box_end) {
+                    Some(float_end) if !fits => {
+                        clearance =
+                            Some(CSSPixelLength::new(float_end - content_block_start) - block_size);
+                    }
+                    _ => break (tentative_block_size, float_count),
+                }
+            };
+            let margins = child.block_margins;
+            let offset = match clearance {
+                Some(cleared) => cleared,
+                None if !has_content && collapses_with_start => CSSPixelLength::new(0.),
+                None => pending.adjoin(margins.start).resolve(),
+            };
+            let translation = (block_size + offset
+                - tentative_block_size
+                - child
+                    .dimensions
+                    .get(LogicalDirection::BlockStart, BoxComponent::Margin))
+            .px();
+            child.translate_block(translation);
+            floats.translate_block_since(float_count, translation);
+
+            if margins.collapses_through && clearance.is_none() {
+                pending = pending.adjoin(margins.start).adjoin(margins.end);
+            } else {
+                if !has_content && collapses_with_start {
+                    start = start.adjoin(pending);
+                    if clearance.is_none() {
+                        start = start.adjoin(margins.start);
+                    }
+                }
+                block_size = block_size + offset + child.dimensions.border_box_block_size();
+                pending = margins.end;
+                has_content = true;
+            }
+        }
+        if !has_content && collapses_with_start {
+            start = start.adjoin(pending);
+            pending = CollapsedMargin::default();
+        }
+
+        let mut end = CollapsedMargin::new(
+            self.dimensions
+                .get(LogicalDirection::BlockEnd, BoxComponent::Margin),
+        );
+        if collapses_with_end {
+            end = end.adjoin(pending);
+        } else {
+            block_size += pending.resolve();
+        }
+        // https://www.w3.org/TR/CSS2/visudet.html#root-height
+        if let Some(floats_end) = floats
+            .lowest_end()
+            .filter(|_| establishes_block_formatting_context)
+        {
+            block_size = block_size.max(CSSPixelLength::new(floats_end - content_block_start));
+        }
+        self.dimensions.set_block_size(block_size);
+        self.block_margins = BlockMargins {
+            start,
+            end,
+            collapses_through: !has_content && collapses_with_start && collapses_with_end,
+        };
+
+        // Relatively positioned children are only moved once they've all been placed, as the
+        // space they take up in the flow stays where it was.
+        let containing_block = self.dimensions.physical();
+        for child in &mut self.children {
+            child.apply_relative_offset(containing_block, scale_factor);
+        }
+    }
+
+    /// The block-start margin `calculate_block_position` gives this box when it's laid out in
+    /// `containing_block`, before it's collapsed with any others.  Anonymous boxes have none.
+    fn uncollapsed_block_start_margin(
+        &self,
+        containing_block: PhysicalDimensions,
+        scale_factor: f32,
+    ) -> CSSPixelLength {
+        match self.box_type {
+            BoxType::Block | BoxType::InlineBlock => {
+                let containing_width = if self.writing_mode.is_horizontal() {
+                    containing_block.content.width
+                } else {
+                    containing_block.content.height
+                };
+                self.computed_values()
+                    .margin_top
+                    .size
+                    .to_px(containing_width)
+                    * scale_factor
+            }
+            BoxType::Anonymous | BoxType::AnonymousInline | BoxType::Inline => {
+                CSSPixelLength::new(0.)
+            }
+        }
+    }
+
+    /// Whether the margin on the given block-axis side of this box adjoins that of its first or
+    /// last in-flow child.  It doesn't if this box establishes a block formatting context or has
+    /// border or padding on that side, and the block-end margin only adjoins the last child's if
+    /// this box's block size depends on its content.
+    fn margin_collapses_with_children(&self, side: LogicalDirection) -> bool {
+        if self.establishes_block_formatting_context()
+            || self.dimensions.get(side, BoxComponent::Border) != 0.
+            || self.dimensions.get(side, BoxComponent::Padding) != 0.
+        {
+            return false;
+        }
+        match (self.box_type, side) {
+            (BoxType::Block, LogicalDirection::BlockEnd) => {
+                let cvs = self.computed_values();
+                cvs.logical_height() == LengthPercentageOrAuto::Auto
+                    && cvs.logical_min_height().to_px(CSSPixelLength::new(0.)) == 0.
+            }
+            _ => true,
+        }
+    }
+
+    /// Moves this box and everything in it by `offset` along its block axis.
+    fn translate_block(&mut self, offset: CSSFloat) {
+        if self.writing_mode.is_horizontal() {
+            self.translate(0., offset);
+        } else {
+            self.translate(offset, 0.);
+        }
+    }
+
+    /// Moves this box and everything in it by `dx` along the x-axis and `dy` along the y-axis.
+    pub(super) fn translate(&mut self, dx: CSSFloat, dy: CSSFloat) {
+        self.dimensions.translate(dx, dy);
+        if let Some(baseline) = &mut self.baseline {
+            *baseline += if self.writing_mode.is_horizontal() {
+                dy
+            } else {
+                dx
+            };
+        }
+        self.line_boxes
+            .iter_mut()
+            .for_each(|line_box| line_box.translate(dx, dy));
+        self.fragments
+            .iter_mut()
+            .for_each(|fragment| fragment.content.translate(dx, dy));
+        self.children
+            .iter_mut()
+            .for_each(|child| child.translate(dx, dy));
+    }
+
+    /// Lays out the atomic inline-level boxes and floats among the inline-level contents of this
+    /// box, so that the inline formatting context they're in can place them as a whole.
+    /// `containing_block` is the block container of that inline formatting context.  Absolutely
+    /// positioned boxes are left for their containing block to lay out, once the inline
+    /// formatting context has found their static position.
+    pub(super) fn layout_atomic_inlines(
+        &mut self,
+        containing_block: PhysicalDimensions,
+        floats: &mut FloatManager,
+        font_handle: &FontHandle,
+        scale_factor: f32,
+    ) {
+        for child in &mut self.children {
+            match child.box_type {
+                BoxType::AnonymousInline | BoxType::Inline => {
+                    child.layout_atomic_inlines(containing_block, floats, font_handle, scale_factor)
+                }
+                // The only other blocks among inline-level content are floats.
+                BoxType::Block if child.is_absolutely_positioned() => {}
+                BoxType::Block | BoxType::InlineBlock => {
+                    child.layout(containing_block, floats, font_handle, scale_factor)
+                }
+                BoxType::Anonymous => {}
+            }
         }
     }
 
+    /// The min-content and max-content inline sizes of the contents of this block container, with
+    /// the scale factor applied.
+    ///
+    /// https://drafts.csswg.org/css-sizing-3/#intrinsic-sizes
+    pub(super) fn content_sizes(
+        &self,
+        font_handle: &FontHandle,
+        scale_factor: f32,
+    ) -> ContentSizes {
+        self.children
+            .iter()
+            .fold(ContentSizes::default(), |sizes, child| {
+                sizes.max(match child.box_type {
+                    BoxType::AnonymousInline => {
+                        inline_content_sizes(child, font_handle, scale_factor)
+                    }
+                    BoxType::Anonymous | BoxType::Inline => {
+                        child.content_sizes(font_handle, scale_factor)
+                    }
+                    BoxType::Block | BoxType::InlineBlock => {
+                        child.intrinsic_contributions(font_handle, scale_factor)
+                    }
+                })
+            })
+    }
+
+    /// The min-content and max-content contributions of this box to the inline size of its
+    /// container, which are the size of its margin box when sized under those constraints.  A
+    /// definite `width` is used as is, and percentages, which can't be resolved against a
+    /// container that is still being sized, are treated as zero.
+    ///
+    /// https://drafts.csswg.org/css-sizing-3/#contributions
+    pub(super) fn intrinsic_contributions(
+        &self,
+        font_handle: &FontHandle,
+        scale_factor: f32,
+    ) -> ContentSizes {
+        let cvs = self.computed_values();
+        let zero = CSSPixelLength::new(0.);
+        let inline_padding_and_border = cvs.logical_border_width(Side::Left)
+            + cvs.logical_border_width(Side::Right)
+            + cvs.logical_padding(Side::Left).to_px(zero)
+            + cvs.logical_padding(Side::Right).to_px(zero);
+        let sizes = match cvs.logical_width() {
+            LengthPercentageOrAuto::LengthPercentage(LengthPercentage::Length(width)) => {
+                ContentSizes::new(
+                    content_size(width, cvs.box_sizing, inline_padding_and_border) * scale_factor,
+                )
+            }
+            _ => self.content_sizes(font_handle, scale_factor),
+        };
+        sizes.expanded_by(
+            (cvs.logical_margin(Side::Left).to_px(zero)
+                + cvs.logical_margin(Side::Right).to_px(zero)
+                + inline_padding_and_border)
+                * scale_factor,
+        )
+    }
+
     /// If this block has any explicitly set values (e.g. lenght or percentage values, NOT auto) for
-    /// physical properties (e.g. `width`, `height`, left/bottom/right/top properties), this
-    /// function will set them.  Otherwise, the used values will be those given by other layout
-    /// equations.
+    /// physical properties (e.g. `width`, `height`), this function will set them.  Otherwise, the
+    /// used values will be those given by other layout equations.  The insets are applied
+    /// separately, once a box has been placed in flow.
     fn apply_physical_properties(
         &mut self,
         containing_block: PhysicalDimensions,
         scale_factor: f32,
     ) {
-        let width = self.node.computed_values().width.size;
-        if let LengthPercentageOrAuto::LengthPercentage(lp) = width {
-            self.dimensions
-                .set_phys_width(lp.to_px(containing_block.content.width) * scale_factor);
-        }
+        let cvs = self.node.computed_values();
+        let containing_width = containing_block.content.width;
+        let containing_height = containing_block.content.height;
+        // Padding and borders have already been scaled, so sizes are converted to device pixels
+        // before `box-sizing` is taken into account.
+        let physical = self.dimensions.physical();
+        let content_width = |size: &LengthPercentage| {
+            content_size(
+                size.to_px(containing_width) * scale_factor,
+                cvs.box_sizing,
+                physical.padding.left
+                    + physical.padding.right
+                    + physical.border.left
+                    + physical.border.right,
+            )
+        };
+        let content_height = |size: &LengthPercentage| {
+            content_size(
+                size.to_px(containing_height) * scale_factor,
+                cvs.box_sizing,
+                physical.padding.top
+                    + physical.padding.bottom
+                    + physical.border.top
+                    + physical.border.bottom,
+            )
+        };
 
-        let height = self.node.computed_values().height.size;
-        if let LengthPercentageOrAuto::LengthPercentage(lp) = height {
-            self.dimensions
-                .set_phys_height(lp.to_px(containing_block.content.height) * scale_factor);
+        if let LengthPercentageOrAuto::LengthPercentage(width) = &cvs.width.size {
+            self.dimensions.set_phys_width(content_width(width));
+        }
+        if let LengthPercentageOrAuto::LengthPercentage(height) = &cvs.height.size {
+            self.dimensions.set_phys_height(content_height(height));
         }
 
+        // Whether the sizes above were explicitly given or came from the layout equations, they're
+        // constrained by the min/max properties.  For the inline size this has already happened in
+        // `calculate_block_logical_width`, so that margins could be resolved against the clamped
+        // value, and clamping again here is a no-op.
+        // https://www.w3.org/TR/CSS2/visudet.html#min-max-widths
+        // https://www.w3.org/TR/CSS2/visudet.html#min-max-heights
+        let content = self.dimensions.physical().content;
+        self.dimensions.set_phys_width(clamp_size(
+            content.width,
+            cvs.min_width.size.as_length_percentage().map(content_width),
+            cvs.max_width.size.as_length_percentage().map(content_width),
+        ));
+        self.dimensions.set_phys_height(clamp_size(
+            content.height,
+            cvs.min_height
+                .size
+                .as_length_percentage()
+                .map(content_height),
+            cvs.max_height
+                .size
+                .as_length_percentage()
+                .map(content_height),
+        ));
+
         // FIXME: The physical bottom/left/right/top properties for margin, border, and padding
         // are broken in non-horizontal writing modes because they are applied logically, when
         // these properties should instead be applied physically.  E.g., margin-left should always affect
         // the page-relative left margin of the box, but instead reflects the flow relative margin
         // left, which physically ends up being the top margin.
     }
+
+    /// Lays out the absolutely positioned boxes among the descendants of this box that `scope`
+    /// covers, in `containing_block`.  Positioned descendants are the containing block of the
+    /// absolutely positioned boxes inside them, and lay those out themselves, so only the fixed
+    /// positioned boxes inside them are left to look for.
+    pub(super) fn layout_positioned_descendants(
+        &mut self,
+        containing_block: Rect,
+        scope: PositionedScope,
+        font_handle: &FontHandle,
+        scale_factor: f32,
+    ) {
+        for child in &mut self.children {
+            let descendant_scope = if child.is_absolutely_positioned() {
+                if scope.covers(child.computed_values().position) {
+                    child.layout_absolute(containing_block, font_handle, scale_factor);
+                }
+                scope.inside_containing_block()
+            } else if child.establishes_absolute_containing_block() {
+                scope.inside_containing_block()
+            } else {
+                Some(scope)
+            };
+            if let Some(descendant_scope) = descendant_scope {
+                child.layout_positioned_descendants(
+                    containing_block,
+                    descendant_scope,
+                    font_handle,
+                    scale_factor,
+                );
+            }
+        }
+    }
+
+    /// Lays out the absolutely positioned boxes inside the positioned inline boxes among the
+    /// inline-level descendants of this box, once their inline formatting context has given
+    /// those inline boxes their fragments.
+    fn layout_inline_positioned_descendants(
+        &mut self,
+        font_handle: &FontHandle,
+        scale_factor: f32,
+    ) {
+        for child in &mut self.children {
+            if child.box_type != BoxType::Inline {
+                continue;
+            }
+            if child.establishes_absolute_containing_block() {
+                child.layout_positioned_descendants(
+                    child.inline_containing_block(),
+                    PositionedScope::Absolute,
+                    font_handle,
+                    scale_factor,
+                );
+            }
+            child.layout_inline_positioned_descendants(font_handle, scale_factor);
+        }
+    }
+
+    /// The containing block this positioned inline box is for the absolutely positioned boxes
+    /// inside it, which bounds the padding boxes of its first and last fragments.
+    ///
+    /// https://www.w3.org/TR/CSS2/visudet.html#containing-block-details
+    fn inline_containing_block(&self) -> Rect {
+        let content = self.dimensions.physical().content;
+        let first_and_last = self
+            .fragments
+            .first()
+            .into_iter()
+            .chain(self.fragments.last());
+        rects_bounds(
+            first_and_last.map(Fragment::padding_box),
+            (content.start_x, content.start_y),
+        )
+    }
+
+    /// Lays out this absolutely positioned box in `containing_block`, which is the padding box of
+    /// its nearest positioned ancestor, the initial containing block, or the viewport if the box
+    /// is fixed positioned.  Where its insets are `auto`, the box is placed at its static
+    /// position, which is where the inline formatting context it was found in left it.  In a
+    /// right-to-left containing block, the box ends there rather than starting there.
+    ///
+    /// https://www.w3.org/TR/CSS2/visudet.html#abs-non-replaced-width
+    /// https://www.w3.org/TR/CSS2/visudet.html#abs-non-replaced-height
+    // FIXME: Absolutely positioned boxes are laid out in physical terms, so their contents are
+    // laid out as if their writing mode were horizontal.
+    fn layout_absolute(
+        &mut self,
+        containing_block: Rect,
+        font_handle: &FontHandle,
+        scale_factor: f32,
+    ) {
+        let static_position = self.dimensions.physical().margin_box();
+        let containing_direction = self.containing_direction();
+        let (padding, border, horizontal, vertical) = {
+            let cvs = self.computed_values();
+            // Percentages of margins and padding refer to the width of the containing block along
+            // both axes, while those of insets refer to its size along their own axis.
+            let containing_width = containing_block.width;
+            let resolve = |value: &LengthPercentageOrAuto, basis: CSSPixelLength| {
+                value
+                    .as_length_percentage()
+                    .map(|value| value.to_px(basis) * scale_factor)
+            };
+            let mut padding = EdgeSizes::default();
+            let mut border = EdgeSizes::default();
+            for &side in [Side::Left, Side::Right, Side::Top, Side::Bottom].iter() {
+                *padding.get_mut(side) = cvs.padding(side).to_px(containing_width) * scale_factor;
+                *border.get_mut(side) = cvs.border_width(side) * scale_factor;
+            }
+            let horizontal = AbsoluteAxis {
+                containing_size: containing_width,
+                inset_start: resolve(cvs.inset(Side::Left), containing_width),
+                inset_end: resolve(cvs.inset(Side::Right), containing_width),
+                margin_start: resolve(cvs.margin(Side::Left), containing_width),
+                margin_end: resolve(cvs.margin(Side::Right), containing_width),
+                padding_and_border: padding.left + padding.right + border.left + border.right,
+                static_start: CSSPixelLength::new(
+                    static_position.start_x - containing_block.start_x,
+                ),
+                static_end: CSSPixelLength::new(
+                    containing_block.start_x + containing_width.px() - static_position.start_x,
+                ),
+                direction: Some(containing_direction),
+            };
+            let vertical = AbsoluteAxis {
+                containing_size: containing_block.height,
+                inset_start: resolve(cvs.inset(Side::Top), containing_block.height),
+                inset_end: resolve(cvs.inset(Side::Bottom), containing_block.height),
+                margin_start: resolve(cvs.margin(Side::Top), containing_width),
+                margin_end: resolve(cvs.margin(Side::Bottom), containing_width),
+                padding_and_border: padding.top + padding.bottom + border.top + border.bottom,
+                static_start: CSSPixelLength::new(
+                    static_position.start_y - containing_block.start_y,
+                ),
+                static_end: CSSPixelLength::new(
+                    containing_block.start_y + containing_block.height.px()
+                        - static_position.start_y,
+                ),
+                direction: None,
+            };
+            (padding, border, horizontal, vertical)
+        };
+
+        // An `auto` width shrinks to fit the contents, as far as the available space allows.
+        // https://www.w3.org/TR/CSS2/visudet.html#shrink-to-fit-float
+        let width = {
+            let cvs = self.computed_values();
+            let content_width = |size: &LengthPercentage| {
+                content_size(
+                    size.to_px(containing_block.width) * scale_factor,
+                    cvs.box_sizing,
+                    horizontal.padding_and_border,
+                )
+            };
+            horizontal.solve_clamped(
+                cvs.width.size.as_length_percentage().map(content_width),
+                cvs.min_width.size.as_length_percentage().map(content_width),
+                cvs.max_width.size.as_length_percentage().map(content_width),
+                &|available| {
+                    let sizes = self.content_sizes(font_handle, scale_factor);
+                    sizes
+                        .max_content
+                        .min(sizes.min_content.max(available))
+                        .clamp_to_non_negative()
+                },
+            )
+        };
+
+        // The contents are laid out with the box at the top of the containing block, and moved
+        // along with it once its height is known.
+        self.dimensions.replace_inner_physical(PhysicalDimensions {
+            content: Rect {
+                start_x: containing_block.start_x
+                    + (width.inset_start + width.margin_start + border.left + padding.left).px(),
+                start_y: containing_block.start_y + (border.top + padding.top).px(),
+                width: width.size,
+                height: CSSPixelLength::new(0.),
+            },
+            padding,
+            border,
+            margin: EdgeSizes {
+                left: width.margin_start,
+                right: width.margin_end,
+                ..EdgeSizes::default()
+            },
+        });
+        self.layout_block_children(&mut FloatManager::default(), font_handle, scale_factor);
+
+        let contents_height = self.dimensions.physical().content.height;
+        let height = {
+            let cvs = self.computed_values();
+            let content_height = |size: &LengthPercentage| {
+                content_size(
+                    size.to_px(containing_block.height) * scale_factor,
+                    cvs.box_sizing,
+                    vertical.padding_and_border,
+                )
+            };
+            vertical.solve_clamped(
+                cvs.height.size.as_length_percentage().map(content_height),
+                cvs.min_height
+                    .size
+                    .as_length_percentage()
+                    .map(content_height),
+                cvs.max_height
+                    .size
+                    .as_length_percentage()
+                    .map(content_height),
+                &|_| contents_height,
+            )
+        };
+        self.translate(0., (height.inset_start + height.margin_start).px());
+        let mut dimensions = self.dimensions.physical();
+        dimensions.content.height = height.size;
+        dimensions.margin.top = height.margin_start;
+        dimensions.margin.bottom = height.margin_end;
+        self.dimensions.replace_inner_physical(dimensions);
+
+        self.layout_positioned_descendants(
+            self.dimensions.padding_box(),
+            PositionedScope::Absolute,
+            font_handle,
+            scale_factor,
+        );
+    }
+
+    /// Moves this box, along with everything in it, by the offsets its insets give it if it's
+    /// relatively positioned.  This happens once it has been laid out in flow, which isn't
+    /// affected by the move.  Where opposing insets are both given, `top` wins, as does whichever
+    /// of `left` and `right` the `direction` of the containing block starts at.
+    ///
+    /// https://www.w3.org/TR/CSS2/visuren.html#relative-positioning
+    // FIXME: Percentages of the containing block's height should only resolve if that height
+    // doesn't depend on its contents.
+    fn apply_relative_offset(&mut self, containing_block: PhysicalDimensions, scale_factor: f32) {
+        if matches!(self.box_type, BoxType::Anonymous | BoxType::AnonymousInline) {
+            return;
+        }
+        let (dx, dy) = {
+            let cvs = self.computed_values();
+            if cvs.position != Position::Relative {
+                return;
+            }
+            let content = containing_block.content;
+            let inset = |side: Side, basis: CSSPixelLength| {
+                cvs.inset(side)
+                    .as_length_percentage()
+                    .map(|inset| inset.to_px(basis) * scale_factor)
+            };
+            let dx = match (
+                inset(Side::Left, content.width),
+                inset(Side::Right, content.width),
+                self.containing_direction(),
+            ) {
+                (Some(left), None, _) | (Some(left), Some(_), Direction::Ltr) => left,
+                (_, Some(right), _) => -right,
+                (None, None, _) => CSSPixelLength::new(0.),
+            };
+            let dy = match (
+                inset(Side::Top, content.height),
+                inset(Side::Bottom, content.height),
+            ) {
+                (Some(top), _) => top,
+                (None, Some(bottom)) => -bottom,
+                (None, None) => CSSPixelLength::new(0.),
+            };
+            (dx.px(), dy.px())
+        };
+        self.translate(dx, dy);
+    }
+
+    /// Applies the relative offsets of the inline-level boxes in this inline box, and of the boxes
+    /// nested in those, once their inline formatting context has been laid out.  The contents of
+    /// atomic inline-level boxes and floats have already been offset when they were laid out.
+    fn apply_inline_relative_offsets(
+        &mut self,
+        containing_block: PhysicalDimensions,
+        scale_factor: f32,
+    ) {
+        for child in &mut self.children {
+            child.apply_relative_offset(containing_block, scale_factor);
+            if let BoxType::AnonymousInline | BoxType::Inline = child.box_type {
+                child.apply_inline_relative_offsets(containing_block, scale_factor);
+            }
+        }
+    }
+
+    /// Applies the sticky offsets of the stickily positioned boxes among the descendants of this
+    /// box, once everything has been laid out and the `scrollport` is known.  `containing_block`
+    /// is the content box of the block container this box is in, which the inline-level children
+    /// of inline boxes stick within as well.
+    pub(super) fn apply_sticky_offsets(
+        &mut self,
+        containing_block: Rect,
+        scrollport: Rect,
+        scale_factor: f32,
+    ) {
+        let containing_block = match self.box_type {
+            BoxType::Inline | BoxType::AnonymousInline => containing_block,
+            BoxType::Anonymous | BoxType::Block | BoxType::InlineBlock => {
+                self.dimensions.physical().content
+            }
+        };
+        for child in &mut self.children {
+            child.apply_sticky_offset(containing_block, scrollport, scale_factor);
+            child.apply_sticky_offsets(containing_block, scrollport, scale_factor);
+        }
+    }
+
+    /// Moves this box, along with everything in it other than fixed positioned boxes, by the
+    /// offsets that keep it inside its sticky view rectangle if it's stickily positioned.  That's
+    /// the `scrollport` shrunk by the box's insets, and the box only moves as far as it can
+    /// without leaving `containing_block`.  Where opposing insets can't both be honored, `top`
+    /// wins, as does whichever of `left` and `right` the `direction` of the containing block
+    /// starts at.
+    ///
+    /// https://drafts.csswg.org/css-position-3/#stickypos-insets
+    fn apply_sticky_offset(&mut self, containing_block: Rect, scrollport: Rect, scale_factor: f32) {
+        if matches!(self.box_type, BoxType::Anonymous | BoxType::AnonymousInline) {
+            return;
+        }
+        let (dx, dy) = {
+            let cvs = self.computed_values();
+            if cvs.position != Position::Sticky {
+                return;
+            }
+            // Percentages of the insets refer to the size of the scrollport.
+            let inset = |side: Side, basis: CSSPixelLength| {
+                cvs.inset(side)
+                    .as_length_percentage()
+                    .map(|inset| (inset.to_px(basis) * scale_factor).px())
+            };
+            let margin_box = self.dimensions.physical().margin_box();
+            let dx = sticky_offset(
+                (
+                    margin_box.start_x,
+                    margin_box.start_x + margin_box.width.px(),
+                ),
+                (
+                    containing_block.start_x,
+                    containing_block.start_x + containing_block.width.px(),
+                ),
+                inset(Side::Left, scrollport.width).map(|left| scrollport.start_x + left),
+                inset(Side::Right, scrollport.width)
+                    .map(|right| scrollport.start_x + scrollport.width.px() - right),
+                self.containing_direction(),
+            );
+            let dy = sticky_offset(
+                (
+                    margin_box.start_y,
+                    margin_box.start_y + margin_box.height.px(),
+                ),
+                (
+                    containing_block.start_y,
+                    containing_block.start_y + containing_block.height.px(),
+                ),
+                inset(Side::Top, scrollport.height).map(|top| scrollport.start_y + top),
+                inset(Side::Bottom, scrollport.height)
+                    .map(|bottom| scrollport.start_y + scrollport.height.px() - bottom),
+                Direction::Ltr,
+            );
+            (dx, dy)
+        };
+        self.translate(dx, dy);
+        self.translate_fixed_descendants(-dx, -dy);
+    }
+
+    /// Moves the fixed positioned boxes among the descendants of this box, which stay where they
+    /// are in the viewport when their ancestors move.
+    fn translate_fixed_descendants(&mut self, dx: CSSFloat, dy: CSSFloat) {
+        for child in &mut self.children {
+            if child.is_absolutely_positioned()
+                && child.computed_values().position == Position::Fixed
+            {
+                child.translate(dx, dy);
+            } else {
+                child.translate_fixed_descendants(dx, dy);
+            }
+        }
+    }
+}
+
+/// How far a stickily positioned box whose margin box spans `(start, end)` along one physical
+/// axis moves to stay after `view_start` and before `view_end`, the edges of its sticky view
+/// rectangle given by its insets.  It doesn't move past the edges of its containing block,
+/// `containing`, and if it can't honor both view edges, the one at the physical start of the axis
+/// wins if `direction` is left-to-right, and the other one otherwise.
+///
+/// https://drafts.csswg.org/css-position-3/#stickypos-insets
+fn sticky_offset(
+    (start, end): (CSSFloat, CSSFloat),
+    (containing_start, containing_end): (CSSFloat, CSSFloat),
+    view_start: Option<CSSFloat>,
+    view_end: Option<CSSFloat>,
+    direction: Direction,
+) -> CSSFloat {
+    let after_view_start = view_start
+        .filter(|view_start| start < *view_start)
+        .map(|view_start| (view_start - start).min(containing_end - end).max(0.));
+    let before_view_end = view_end
+        .filter(|view_end| end > *view_end)
+        .map(|view_end| (view_end - end).max(containing_start - start).min(0.));
+    match (after_view_start, before_view_end, direction) {
+        (Some(offset), None, _) | (Some(offset), Some(_), Direction::Ltr) => offset,
+        (_, Some(offset), _) => offset,
+        (None, None, _) => 0.,
+    }
+}
+
+/// Constrains `size` to the given min and max sizes, with the min taking precedence if the two
+/// conflict.  A missing min size (`auto`) is treated as zero, which is correct for block-level
+/// boxes, and a missing max size (`none`) imposes no limit.
+fn clamp_size(
+    size: CSSPixelLength,
+    min_size: Option<CSSPixelLength>,
+    max_size: Option<CSSPixelLength>,
+) -> CSSPixelLength {
+    let size = match max_size {
+        Some(max_size) => size.min(max_size),
+        None => size,
+    };
+    match min_size {
+        Some(min_size) => size.max(min_size),
+        None => size,
+    }
+}
+
+/// Converts a size given by `width`, `height` or their min/max counterparts into the size of the
+/// content box, which is what layout works with.  Under `box-sizing: border-box` the size includes
+/// the box's padding and border along the same axis, which may leave no room for content.
+///
+/// https://drafts.csswg.org/css-sizing-3/#box-sizing
+fn content_size(
+    size: CSSPixelLength,
+    box_sizing: BoxSizing,
+    padding_and_border: CSSPixelLength,
+) -> CSSPixelLength {
+    match box_sizing {
+        BoxSizing::ContentBox => size,
+        BoxSizing::BorderBox => (size - padding_and_border).max(CSSPixelLength::new(0.)),
+    }
+}
+
+/// The flow-relative directions of the line-left and line-right sides of a box with the given
+/// `direction`.
+///
+/// https://drafts.csswg.org/css-writing-modes-4/#line-directions
+fn line_sides(direction: Direction) -> (LogicalDirection, LogicalDirection) {
+    match direction {
+        Direction::Ltr => (LogicalDirection::InlineStart, LogicalDirection::InlineEnd),
+        Direction::Rtl => (LogicalDirection::InlineEnd, LogicalDirection::InlineStart),
+    }
+}
+
+/// The used inline size and inline margins of a block-level non-replaced element in normal flow.
+/// The inline size has the scale factor applied, while the margins have yet to be scaled.
+struct BlockInlineSizes {
+    inline_size: CSSPixelLength,
+    margin_left: LengthPercentageOrAuto,
+    margin_right: LengthPercentageOrAuto,
+}
+
+/// Solves the CSS2 §10.3.3 constraint equation for the given logical content width, where `None`
+/// stands for `auto`.  Split out of `calculate_block_logical_width` so it can be re-run with
+/// `min-width` and `max-width` as described in §10.4.
+///
+/// https://www.w3.org/TR/CSS2/visudet.html#blockwidth
+fn solve_block_inline_sizes(
+    cvs: &ComputedValues,
+    logical_width: Option<CSSPixelLength>,
+    containing_width: CSSPixelLength,
+    containing_direction: Direction,
+    scale_factor: f32,
+) -> BlockInlineSizes {
+    let mut logical_margin_left = cvs.logical_margin(Side::Left);
+    let mut logical_margin_right = cvs.logical_margin(Side::Right);
+
+    let logical_border_left = cvs.logical_border_width(Side::Left);
+    let logical_border_right = cvs.logical_border_width(Side::Right);
+
+    let logical_padding_left = cvs.logical_padding(Side::Left);
+    let logical_padding_right = cvs.logical_padding(Side::Right);
+
+    // Run block layout _with_ the device scale factor applied to ensure the proper values are
+    // computed.
+    let block_width = (logical_margin_left.to_px(containing_width)
+        + logical_margin_right.to_px(containing_width)
+        + logical_border_left
+        + logical_border_right
+        + logical_padding_left.to_px(containing_width)
+        + logical_padding_right.to_px(containing_width)
+        + logical_width.unwrap_or_else(|| CSSPixelLength::new(0.)))
+        * scale_factor;
+
+    let auto = LengthPercentageOrAuto::Auto;
+    // If 'width' is not 'auto' and 'border-left-width' + 'padding-left' + 'width' +
+    // 'padding-right' + 'border-right-width' (plus any of 'margin-left' or 'margin-right'
+    // that are not 'auto') is larger than the width of the containing block, then any 'auto'
+    // values for 'margin-left' or 'margin-right' are, for the following rules, treated as zero.
+    if logical_width.is_some() && block_width > containing_width {
+        if logical_margin_left == auto {
+            logical_margin_left =
+                LengthPercentageOrAuto::LengthPercentage(LengthPercentage::new_len(0.));
+        }
+        if logical_margin_right == auto {
+            logical_margin_right =
+                LengthPercentageOrAuto::LengthPercentage(LengthPercentage::new_len(0.));
+        }
+    }
+    // This value can be negative, indicating an overflow or "overconstraint", if the width of
+    // this box is greater than that of the containing one.
+    let underflow = containing_width - block_width;
+    let mut inline_size = logical_width.unwrap_or_else(|| CSSPixelLength::new(0.)) * scale_factor;
+    match (
+        logical_width.is_none(),
+        logical_margin_left == auto,
+        logical_margin_right == auto,
+    ) {
+        // If all of the above have a computed value other than 'auto', the values are said to be
+        // "over-constrained" and one of the used values will have to be different from its computed
+        // value. If the 'direction' property of the containing block has the value 'ltr', the
+        // specified value of 'margin-right' is ignored and the value is calculated so as to make
+        // the equality true. If the value of 'direction' is 'rtl', this happens to 'margin-left' instead.
+        (false, false, false) => match containing_direction {
+            Direction::Ltr => {
+                logical_margin_right = LengthPercentageOrAuto::new_len_px(
+                    logical_margin_right.to_px(containing_width) + underflow,
+                )
+            }
+            Direction::Rtl => {
+                logical_margin_left = LengthPercentageOrAuto::new_len_px(
+                    logical_margin_left.to_px(containing_width) + underflow,
+                )
+            }
+        },
+        // If there is exactly one margin value specified as 'auto', its used value follows
+        // from the equality.
+        (false, true, false) => logical_margin_left = LengthPercentageOrAuto::new_len_px(underflow),
+        (false, false, true) => {
+            logical_margin_right = LengthPercentageOrAuto::new_len_px(underflow)
+        }
+        // If both 'margin-left' and 'margin-right' are 'auto', their used values are equal.
+        // This centers the element with respect to the edges of the containing block.
+        (false, true, true) => {
+            logical_margin_left = LengthPercentageOrAuto::new_len_px(underflow / 2.);
+            logical_margin_right = LengthPercentageOrAuto::new_len_px(underflow / 2.);
+        }
+        // If 'width' is set to 'auto', any other 'auto' values become '0' and 'width' follows
+        // from the resulting equality.
+        (true, _, _) => {
+            if logical_margin_left == auto {
+                logical_margin_left = LengthPercentageOrAuto::new_len(0.)
+            };
+            if logical_margin_right == auto {
+                logical_margin_right = LengthPercentageOrAuto::new_len(0.)
+            };
+
+            if underflow >= CSSPixelLength::new(0.) {
+                inline_size = underflow
+            } else {
+                // Width cannot be negative, adjust the margin at the inline end instead.
+                inline_size = CSSPixelLength::new(0.);
+                match containing_direction {
+                    Direction::Ltr => {
+                        logical_margin_right = LengthPercentageOrAuto::new_len_px(
+                            logical_margin_right.to_px(containing_width) + underflow,
+                        )
+                    }
+                    Direction::Rtl => {
+                        logical_margin_left = LengthPercentageOrAuto::new_len_px(
+                            logical_margin_left.to_px(containing_width) + underflow,
+                        )
+                    }
+                }
+            }
+        }
+    }
+    BlockInlineSizes {
+        inline_size,
+        margin_left: logical_margin_left,
+        margin_right: logical_margin_right,
+    }
+}
+
+/// The used inline size and inline margins of an inline-block or floating non-replaced element,
+/// whose `auto` margins become zero.  Any `auto` width must already have been resolved to the
+/// shrink-to-fit width.
+///
+/// https://www.w3.org/TR/CSS2/visudet.html#inlineblock-width
+/// https://www.w3.org/TR/CSS2/visudet.html#float-width
+fn solve_inline_block_inline_sizes(
+    cvs: &ComputedValues,
+    logical_width: CSSPixelLength,
+    scale_factor: f32,
+) -> BlockInlineSizes {
+    let zero_if_auto = |margin: LengthPercentageOrAuto| match margin {
+        LengthPercentageOrAuto::Auto => LengthPercentageOrAuto::new_len(0.),
+        margin => margin,
+    };
+    BlockInlineSizes {
+        inline_size: logical_width * scale_factor,
+        margin_left: zero_if_auto(cvs.logical_margin(Side::Left)),
+        margin_right: zero_if_auto(cvs.logical_margin(Side::Right)),
+    }
+}
+
+/// The sizes along one physical axis of an absolutely positioned box, from which its used position
+/// and size along that axis are solved.  They have the scale factor applied, and `None` stands for
+/// `auto`.
+struct AbsoluteAxis {
+    containing_size: CSSPixelLength,
+    inset_start: Option<CSSPixelLength>,
+    inset_end: Option<CSSPixelLength>,
+    margin_start: Option<CSSPixelLength>,
+    margin_end: Option<CSSPixelLength>,
+    padding_and_border: CSSPixelLength,
+    /// How far past the start of the containing block the box's margin box would start if it
+    /// were in normal flow.
+    static_start: CSSPixelLength,
+    /// How far before the end of the containing block the box's static position is, which is
+    /// where its margin box would have ended in normal flow if the containing block is
+    /// right-to-left.
+    static_end: CSSPixelLength,
+    /// The `direction` of the containing block along the horizontal axis, which over-constrained
+    /// equations are resolved by.  The vertical axis has none.
+    direction: Option<Direction>,
+}
+
+/// The used start inset, margins and content size of an absolutely positioned box along one axis.
+#[derive(Clone, Copy)]
+struct AbsoluteSizes {
+    inset_start: CSSPixelLength,
+    margin_start: CSSPixelLength,
+    size: CSSPixelLength,
+    margin_end: CSSPixelLength,
+}
+
+impl AbsoluteAxis {
+    /// Solves the CSS2 §10.3.7 (or §10.6.4) constraint equation for the given content size, then
+    /// solves it again with the max and min sizes if the size breaks them, as described in §10.4
+    /// (or §10.7).  Where the size is `auto` and isn't given by the insets, it's given by
+    /// `auto_size` from the space available to the content.
+    ///
+    /// https://www.w3.org/TR/CSS2/visudet.html#abs-non-replaced-width
+    /// https://www.w3.org/TR/CSS2/visudet.html#abs-non-replaced-height
+    fn solve_clamped(
+        &self,
+        size: Option<CSSPixelLength>,
+        min_size: Option<CSSPixelLength>,
+        max_size: Option<CSSPixelLength>,
+        auto_size: &dyn Fn(CSSPixelLength) -> CSSPixelLength,
+    ) -> AbsoluteSizes {
+        let mut used = self.solve(size, auto_size);
+        if let Some(max_size) = max_size.filter(|max_size| used.size > *max_size) {
+            used = self.solve(Some(max_size), auto_size);
+        }
+        if let Some(min_size) = min_size.filter(|min_size| used.size < *min_size) {
+            used = self.solve(Some(min_size), auto_size);
+        }
+        used
+    }
+
+    fn solve(
+        &self,
+        size: Option<CSSPixelLength>,
+        auto_size: &dyn Fn(CSSPixelLength) -> CSSPixelLength,
+    ) -> AbsoluteSizes {
+        let zero = CSSPixelLength::new(0.);
+        // Unless the insets and size are all given, `auto` margins are treated as zero.
+        let margin_start = self.margin_start.unwrap_or(zero);
+        let margin_end = self.margin_end.unwrap_or(zero);
+        // The space left for the content between the given insets.
+        let available = |inset_start: CSSPixelLength, inset_end: CSSPixelLength| {
+            self.containing_size
+                - inset_start
+                - inset_end
+                - margin_start
+                - margin_end
+                - self.padding_and_border
+        };
+        let (inset_start, size) = match (self.inset_start, size, self.inset_end) {
+            (Some(inset_start), Some(size), Some(inset_end)) => {
+                return self.solve_margins(inset_start, size, inset_end)
+            }
+            // With both insets given, the size follows from them.
+            (Some(inset_start), None, Some(inset_end)) => (
+                inset_start,
+                available(inset_start, inset_end).clamp_to_non_negative(),
+            ),
+            (Some(inset_start), size, None) => (
+                inset_start,
+                size.unwrap_or_else(|| auto_size(available(inset_start, zero))),
+            ),
+            // With only the end inset given, the start inset follows from it and the size.
+            (None, size, Some(inset_end)) => {
+                let size = size.unwrap_or_else(|| auto_size(available(zero, inset_end)));
+                (available(zero, inset_end) - size, size)
+            }
+            // With neither given, the box stays at its static position, which the end inset is set
+            // from in a right-to-left containing block, and the start inset otherwise.
+            (None, size, None) if self.direction == Some(Direction::Rtl) => {
+                let size = size.unwrap_or_else(|| auto_size(available(zero, self.static_end)));
+                (available(zero, self.static_end) - size, size)
+            }
+            (None, size, None) => (
+                self.static_start,
+                size.unwrap_or_else(|| auto_size(available(self.static_start, zero))),
+            ),
+        };
+        AbsoluteSizes {
+            inset_start,
+            margin_start,
+            size,
+            margin_end,
+        }
+    }
+
+    /// Solves for the margins of a box whose insets and size are all given.  `auto` margins share
+    /// the space left over equally, and if there are none, the equation is over-constrained and
+    /// the end inset is ignored.  Along the horizontal axis, `auto` margins that would be negative
+    /// and the ignored inset follow the `direction` of the containing block.
+    fn solve_margins(
+        &self,
+        inset_start: CSSPixelLength,
+        size: CSSPixelLength,
+        inset_end: CSSPixelLength,
+    ) -> AbsoluteSizes {
+        let zero = CSSPixelLength::new(0.);
+        let remaining =
+            self.containing_size - inset_start - inset_end - size - self.padding_and_border;
+        let sizes = |inset_start, margin_start, margin_end| AbsoluteSizes {
+            inset_start,
+            margin_start,
+            size,
+            margin_end,
+        };
+        match (self.margin_start, self.margin_end) {
+            (None, None) => match self.direction {
+                Some(Direction::Ltr) if remaining < zero => sizes(inset_start, zero, remaining),
+                Some(Direction::Rtl) if remaining < zero => sizes(inset_start, remaining, zero),
+                _ => sizes(inset_start, remaining / 2., remaining / 2.),
+            },
+            (None, Some(margin_end)) => sizes(inset_start, remaining - margin_end, margin_end),
+            (Some(margin_start), None) => {
+                sizes(inset_start, margin_start, remaining - margin_start)
+            }
+            (Some(margin_start), Some(margin_end)) => match self.direction {
+                Some(Direction::Rtl) => sizes(
+                    inset_start + remaining - margin_start - margin_end,
+                    margin_start,
+                    margin_end,
+                ),
+                _ => sizes(inset_start, margin_start, margin_end),
+            },
+        }
+    }
 }
 
 /// Writes a textual representation of the layout tree starting with the `self` LayoutBox.  Built
@@ -476,7 +2006,9 @@ impl DumpLayout for LayoutBox {
     fn dump_layout<W: Write>(&self, write_to: &mut W, indent_spaces: usize) {
         let node_name = match self.box_type {
             BoxType::Anonymous | BoxType::AnonymousInline => "".to_owned(),
-            BoxType::Block | BoxType::Inline => self.node.data().dump_layout_format(),
+            BoxType::Block | BoxType::Inline | BoxType::InlineBlock => {
+                self.node.data().dump_layout_format()
+            }
         };
         let physical_dimensions = self.dimensions.physical();
         writeln!(
@@ -493,12 +2025,364 @@ impl DumpLayout for LayoutBox {
         )
         .expect("error writing layout dump");
 
+        self.line_boxes.iter().for_each(|line_box| {
+            writeln!(
+                write_to,
+                "{:indent_spaces$}LineBox at ({}, {}) size {}x{}",
+                "",
+                line_box.start_x.dump_layout_format(),
+                line_box.start_y.dump_layout_format(),
+                line_box.width.dump_layout_format(),
+                line_box.height.dump_layout_format(),
+                indent_spaces = indent_spaces + 2,
+            )
+            .expect("error writing layout dump");
+        });
+        self.fragments.iter().for_each(|fragment| {
+            fragment.dump_layout(write_to, indent_spaces + 2);
+        });
         self.children.iter().for_each(|child| {
             child.dump_layout(write_to, indent_spaces + 2);
         })
     }
 }
 
+/// The kinds of formatting context a box can establish for its contents.
+///
+/// https://drafts.csswg.org/css-display-3/#formatting-context
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FormattingContext {
+    Block,
+    Inline,
+}
+
+/// Which of the absolutely positioned boxes among the descendants of a box are laid out in the
+/// containing block they're given.
+///
+/// https://www.w3.org/TR/CSS2/visudet.html#containing-block-details
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PositionedScope {
+    /// Boxes with `position: absolute`, whose containing block is the nearest positioned ancestor.
+    Absolute,
+    /// Boxes with `position: fixed`, whose containing block is the viewport.
+    Fixed,
+}
+
+impl PositionedScope {
+    /// Whether boxes with the given `position` are laid out in this scope.
+    fn covers(self, position: Position) -> bool {
+        match self {
+            PositionedScope::Absolute => position == Position::Absolute,
+            PositionedScope::Fixed => position == Position::Fixed,
+        }
+    }
+
+    /// The scope that's left inside a box that is the containing block of its absolutely
+    /// positioned descendants, if any.
+    fn inside_containing_block(self) -> Option<PositionedScope> {
+        match self {
+            PositionedScope::Absolute => None,
+            PositionedScope::Fixed => Some(PositionedScope::Fixed),
+        }
+    }
+}
+
+/// The formatting context a box of the given type establishes for its contents, if any.  Anonymous
+/// block boxes and block boxes in normal flow continue the block formatting context they're in,
+/// while root inline boxes establish an inline formatting context.  The document, the root
+/// element, inline-blocks, floats, absolutely positioned boxes, `display: flow-root` boxes, and
+/// boxes whose writing mode is orthogonal to that of their parent establish a new block
+/// formatting context.
+///
+/// https://www.w3.org/TR/CSS2/visuren.html#block-formatting
+/// https://drafts.csswg.org/css-display-3/#valdef-display-flow-root
+/// https://drafts.csswg.org/css-writing-modes-4/#orthogonal-flows
+fn established_formatting_context(
+    box_type: BoxType,
+    node: &NodeRef,
+    writing_mode: WritingMode,
+) -> Option<FormattingContext> {
+    let parent = node.parent();
+    let is_root_or_document = parent.as_ref().map_or(true, |parent| {
+        matches!(*parent.data(), NodeData::Document(_))
+    });
+    let is_orthogonal = || {
+        parent.as_ref().map_or(false, |parent| {
+            parent.computed_values().writing_mode.is_horizontal() != writing_mode.is_horizontal()
+        })
+    };
+    match box_type {
+        BoxType::Anonymous => None,
+        BoxType::AnonymousInline => Some(FormattingContext::Inline),
+        BoxType::InlineBlock => Some(FormattingContext::Block),
+        BoxType::Block
+            if is_root_or_document
+                || node.computed_values().display == Display::FlowRoot
+                || node.computed_values().float != Float::None
+                || node.computed_values().position.is_absolutely_positioned()
+                || is_orthogonal() =>
+        {
+            Some(FormattingContext::Block)
+        }
+        BoxType::Inline if is_root_or_document => Some(FormattingContext::Block),
+        BoxType::Block | BoxType::Inline => None,
+    }
+}
+
+/// The min-content and max-content inline sizes of a box or its contents.
+///
+/// https://drafts.csswg.org/css-sizing-3/#intrinsic-sizes
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ContentSizes {
+    pub min_content: CSSPixelLength,
+    pub max_content: CSSPixelLength,
+}
+
+impl ContentSizes {
+    /// Content sizes for a box whose inline size doesn't depend on the available space.
+    pub fn new(size: CSSPixelLength) -> ContentSizes {
+        ContentSizes {
+            min_content: size,
+            max_content: size,
+        }
+    }
+
+    pub fn max(self, other: ContentSizes) -> ContentSizes {
+        ContentSizes {
+            min_content: self.min_content.max(other.min_content),
+            max_content: self.max_content.max(other.max_content),
+        }
+    }
+
+    /// These sizes with `size`, such as that of the margins, borders and padding around the
+    /// content, added to both.
+    pub fn expanded_by(self, size: CSSPixelLength) -> ContentSizes {
+        ContentSizes {
+            min_content: self.min_content + size,
+            max_content: self.max_content + size,
+        }
+    }
+}
+
+/// How far a box may stick out of the space beside floats, in pixels, and still be considered to
+/// fit there.
+const FIT_TOLERANCE: CSSFloat = 0.01;
+
+/// The floats placed so far in a block formatting context.  The line boxes in the formatting
+/// context are shortened to flow around them, and the border boxes of the block formatting context
+/// roots in it are placed beside them.  Positions are physical coordinates along the inline and
+/// block axes of the formatting context.
+///
+/// https://www.w3.org/TR/CSS2/visuren.html#floats
+#[derive(Clone, Debug, Default)]
+pub struct FloatManager {
+    /// The margin boxes of the floats, in the order they were placed.
+    floats: Vec<PlacedFloat>,
+}
+
+impl FloatManager {
+    /// The number of floats placed so far.
+    pub(super) fn count(&self) -> usize {
+        self.floats.len()
+    }
+
+    /// Moves the floats placed after the first `count` by `offset` along the block axis, following
+    /// the box they were laid out in.
+    pub(super) fn translate_block_since(&mut self, count: usize, offset: CSSFloat) {
+        for float in &mut self.floats[count..] {
+            float.block_start += offset;
+            float.block_end += offset;
+        }
+    }
+
+    /// The part of the inline range from `line_left` to `line_right` that isn't taken up by the
+    /// floats beside the band from `block_start` to `block_end`.
+    pub(super) fn available_space(
+        &self,
+        block_start: CSSFloat,
+        block_end: CSSFloat,
+        line_left: CSSFloat,
+        line_right: CSSFloat,
+    ) -> (CSSFloat, CSSFloat) {
+        self.floats
+            .iter()
+            .filter(|float| float.is_beside(block_start, block_end))
+            .fold(
+                (line_left, line_right),
+                |(left, right), float| match float.side {
+                    FloatSide::LineLeft => (left.max(float.inline_end), right),
+                    FloatSide::LineRight => (left, right.min(float.inline_start)),
+                },
+            )
+    }
+
+    /// Where the first of the floats beside the band from `block_start` to `block_end` ends, which
+    /// is how far content in the band has to move down to find more space.
+    pub(super) fn next_float_end(
+        &self,
+        block_start: CSSFloat,
+        block_end: CSSFloat,
+    ) -> Option<CSSFloat> {
+        self.floats
+            .iter()
+            .filter(|float| float.is_beside(block_start, block_end))
+            .map(|float| float.block_end)
+            .fold(None, |end: Option<CSSFloat>, float_end| {
+                Some(end.map_or(float_end, |end| end.min(float_end)))
+            })
+    }
+
+    /// Where the lowest of the floats on the given sides ends, which a box that clears them has
+    /// to be placed below.
+    pub(super) fn clearance(&self, sides: &[FloatSide]) -> Option<CSSFloat> {
+        self.floats
+            .iter()
+            .filter(|float| sides.contains(&float.side))
+            .map(|float| float.block_end)
+            .fold(None, |end: Option<CSSFloat>, float_end| {
+                Some(end.map_or(float_end, |end| end.max(float_end)))
+            })
+    }
+
+    /// Where the lowest float ends.
+    pub(super) fn lowest_end(&self) -> Option<CSSFloat> {
+        self.clearance(&[FloatSide::LineLeft, FloatSide::LineRight])
+    }
+
+    /// Places a float with a margin box of the given size within the inline range from
+    /// `line_left` to `line_right`, and returns where its margin box starts along the inline and
+    /// block axes.  The float is placed as high as possible, though no higher than `block_start`,
+    /// the top of any earlier float, or the bottom of the floats it clears.  It's placed as far
+    /// towards `side` as the floats beside it allow, and below them if it doesn't fit beside them.
+    ///
+    /// https://www.w3.org/TR/CSS2/visuren.html#float-rules
+    pub(super) fn place(
+        &mut self,
+        side: FloatSide,
+        cleared: &[FloatSide],
+        inline_size: CSSPixelLength,
+        block_size: CSSPixelLength,
+        block_start: CSSFloat,
+        (line_left, line_right): (CSSFloat, CSSFloat),
+    ) -> (CSSFloat, CSSFloat) {
+        let mut block_start = self
+            .floats
+            .last()
+            .map_or(block_start, |last| block_start.max(last.block_start));
+        if let Some(clearance) = self.clearance(cleared) {
+            block_start = block_start.max(clearance);
+        }
+        loop {
+            let block_end = block_start + block_size.px();
+            let (left, right) = self.available_space(block_start, block_end, line_left, line_right);
+            match self.next_float_end(block_start, block_end) {
+                Some(float_end) if right - left < inline_size.px() => block_start = float_end,
+                // Without floats beside it, a float too wide for the inline range overflows it.
+                _ => {
+                    let inline_start = match side {
+                        FloatSide::LineLeft => left,
+                        FloatSide::LineRight => right - inline_size.px(),
+                    };
+                    self.floats.push(PlacedFloat {
+                        side,
+                        inline_start,
+                        inline_end: inline_start + inline_size.px(),
+                        block_start,
+                        block_end,
+                    });
+                    return (inline_start, block_start);
+                }
+            }
+        }
+    }
+}
+
+/// The margin box of a float placed in a block formatting context.
+#[derive(Clone, Copy, Debug)]
+struct PlacedFloat {
+    side: FloatSide,
+    inline_start: CSSFloat,
+    inline_end: CSSFloat,
+    block_start: CSSFloat,
+    block_end: CSSFloat,
+}
+
+impl PlacedFloat {
+    /// Whether the float is beside any part of the band from `block_start` to `block_end`, or
+    /// beside the line at `block_start` if the band is empty.
+    fn is_beside(&self, block_start: CSSFloat, block_end: CSSFloat) -> bool {
+        self.block_end > block_start
+            && (self.block_start < block_end || self.block_start <= block_start)
+    }
+}
+
+/// The side of its containing block a box floats to.  Line-left is the left side of horizontal
+/// lines, and the top of vertical ones.
+///
+/// https://drafts.csswg.org/css-writing-modes-4/#line-directions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FloatSide {
+    LineLeft,
+    LineRight,
+}
+
+/// The margins at the block-start and block-end of a box, once collapsed with the margins of its
+/// children that adjoin them.  These may collapse further with the margins around the box.
+#[derive(Clone, Copy, Debug, Default)]
+struct BlockMargins {
+    start: CollapsedMargin,
+    end: CollapsedMargin,
+    /// Whether the box is empty and its block-start and block-end margins adjoin, so that margins
+    /// collapse through it.
+    collapses_through: bool,
+}
+
+/// A set of adjoining margins collapsed into one, which is as wide as the most positive of them
+/// minus the absolute value of the most negative.
+///
+/// https://www.w3.org/TR/CSS2/box.html#collapsing-margins
+#[derive(Clone, Copy, Debug, Default)]
+struct CollapsedMargin {
+    max_positive: CSSPixelLength,
+    min_negative: CSSPixelLength,
+}
+
+impl CollapsedMargin {
+    fn new(margin: CSSPixelLength) -> CollapsedMargin {
+        let zero = CSSPixelLength::new(0.);
+        CollapsedMargin {
+            max_positive: margin.max(zero),
+            min_negative: margin.min(zero),
+        }
+    }
+
+    /// Collapses these margins with the `other` margins adjoining them.
+    fn adjoin(self, other: CollapsedMargin) -> CollapsedMargin {
+        CollapsedMargin {
+            max_positive: self.max_positive.max(other.max_positive),
+            min_negative: self.min_negative.min(other.min_negative),
+        }
+    }
+
+    /// The width of the collapsed margin.
+    fn resolve(self) -> CSSPixelLength {
+        self.max_positive + self.min_negative
+    }
+}
+
+/// The sides of an inline box that are continued by other pieces of it, once it's been split
+/// around a block-level descendant.  A piece has no margin, border or padding on the sides it's
+/// continued on.
+///
+/// https://www.w3.org/TR/CSS2/visuren.html#anonymous-block-level
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InlineSplit {
+    /// Whether an earlier piece continues the box before this one, at its inline start.
+    pub continues_before: bool,
+    /// Whether a later piece continues the box after this one, at its inline end.
+    pub continues_after: bool,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum BoxType {
     Anonymous,
@@ -509,4 +2393,7 @@ pub enum BoxType {
     AnonymousInline,
     Block,
     Inline,
+    /// An inline-level box that is laid out as a block container, then placed on a line as a
+    /// single atomic box.
+    InlineBlock,
 }
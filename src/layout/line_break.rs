@@ -0,0 +1,526 @@
+//! Finding the opportunities to break text across lines, following the Unicode line breaking
+//! algorithm.
+//!
+//! The break classes of characters come from a condensed version of the Unicode Character
+//! Database's `LineBreak.txt`, which covers the scripts and punctuation that matter most for
+//! breaking.  Characters it doesn't list are treated as alphabetic, so lines never break between
+//! them.
+//!
+//! https://www.unicode.org/reports/tr14/
+
+use crate::style::values::computed::WordBreak;
+
+/// Whether a line may break between two characters.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Break {
+    Prohibited,
+    Allowed,
+    Mandatory,
+}
+
+/// Finds whether a line may break before each character of `text`, given the `word-break` of
+/// each character.  A line never breaks before the first character.
+pub fn break_opportunities(text: &[(char, WordBreak)]) -> Vec<Break> {
+    let classes = text
+        .iter()
+        .map(|(ch, word_break)| resolve_class(break_class(*ch), *word_break))
+        .collect::<Vec<_>>();
+    // The classes characters are treated as once combining marks have taken on the class of the
+    // character they attach to (LB9), and those that can't attach to anything are treated as
+    // alphabetic (LB10).
+    let mut combined = Vec::with_capacity(classes.len());
+    for (index, &class) in classes.iter().enumerate() {
+        let combined_class = match class {
+            BreakClass::Cm | BreakClass::Zwj => match index.checked_sub(1) {
+                Some(previous) if !is_unattachable(classes[previous]) => combined[previous],
+                _ => BreakClass::Al,
+            },
+            _ => class,
+        };
+        combined.push(combined_class);
+    }
+
+    let mut breaks = Vec::with_capacity(text.len());
+    if !text.is_empty() {
+        // LB2
+        breaks.push(Break::Prohibited);
+    }
+    for index in 1..text.len() {
+        let mut line_break = break_before(&classes, &combined, index);
+        let both_keep_all =
+            text[index - 1].1 == WordBreak::KeepAll && text[index].1 == WordBreak::KeepAll;
+        if line_break == Break::Allowed
+            && both_keep_all
+            && is_letter(combined[index - 1])
+            && is_letter(combined[index])
+        {
+            line_break = Break::Prohibited;
+        }
+        breaks.push(line_break);
+    }
+    breaks
+}
+
+/// Whether `ch` continues the grapheme cluster before it, rather than starting a new one.  This
+/// only accounts for combining marks and joiners.
+pub fn continues_grapheme(ch: char) -> bool {
+    matches!(break_class(ch), BreakClass::Cm | BreakClass::Zwj)
+}
+
+/// Applies the rules of the algorithm, in order, to find whether a line may break before the
+/// character at `index`.
+fn break_before(classes: &[BreakClass], combined: &[BreakClass], index: usize) -> Break {
+    use BreakClass::*;
+    let (before, after) = (classes[index - 1], classes[index]);
+    // LB4, LB5
+    match (before, after) {
+        (Cr, Lf) => return Break::Prohibited,
+        (Bk, _) | (Cr, _) | (Lf, _) | (Nl, _) => return Break::Mandatory,
+        _ => {}
+    }
+    // LB6, LB7
+    if matches!(after, Bk | Cr | Lf | Nl | Sp | Zw) {
+        return Break::Prohibited;
+    }
+    // The class of the last character before any spaces that precede `index`.
+    let before_spaces = combined[..index]
+        .iter()
+        .rev()
+        .find(|class| **class != Sp)
+        .copied();
+    // LB8
+    if before_spaces == Some(Zw) {
+        return Break::Allowed;
+    }
+    // LB8a, LB9
+    if before == Zwj || (matches!(after, Cm | Zwj) && !is_unattachable(before)) {
+        return Break::Prohibited;
+    }
+
+    let (before, after) = (combined[index - 1], combined[index]);
+    let prohibited = match (before, after) {
+        // LB11
+        (Wj, _) | (_, Wj) => true,
+        // LB12
+        (Gl, _) => true,
+        // LB12a
+        (Sp, Gl) | (Ba, Gl) | (Hy, Gl) => false,
+        (_, Gl) => true,
+        // LB13
+        (_, Cl) | (_, Cp) | (_, Ex) | (_, Is) | (_, Sy) => true,
+        _ => false,
+    };
+    if prohibited {
+        return Break::Prohibited;
+    }
+    // LB14 - LB17
+    match (before_spaces, after) {
+        (Some(Op), _) | (Some(Qu), Op) | (Some(Cl), Ns) | (Some(Cp), Ns) | (Some(B2), B2) => {
+            return Break::Prohibited
+        }
+        _ => {}
+    }
+    // LB18
+    if before == Sp {
+        return Break::Allowed;
+    }
+    // LB19, LB20
+    match (before, after) {
+        (Qu, _) | (_, Qu) => return Break::Prohibited,
+        (Cb, _) | (_, Cb) => return Break::Allowed,
+        _ => {}
+    }
+    let before_before = index.checked_sub(2).map(|index| combined[index]);
+    let prohibited = match (before, after) {
+        // LB21
+        (_, Ba) | (_, Hy) | (_, Ns) | (Bb, _) => true,
+        // LB21a
+        (Hy, _) | (Ba, _) if before_before == Some(Hl) => true,
+        // LB21b
+        (Sy, Hl) => true,
+        // LB22
+        (_, In) => true,
+        // LB23
+        (Al, Nu) | (Hl, Nu) | (Nu, Al) | (Nu, Hl) => true,
+        // LB23a
+        (Pr, Id) | (Pr, Em) | (Id, Po) | (Em, Po) => true,
+        // LB24
+        (Pr, Al) | (Pr, Hl) | (Po, Al) | (Po, Hl) | (Al, Pr) | (Al, Po) | (Hl, Pr) | (Hl, Po) => {
+            true
+        }
+        // LB25, in the simplified form the algorithm suggests for implementations that don't
+        // tailor it.
+        (Cl, Po)
+        | (Cp, Po)
+        | (Cl, Pr)
+        | (Cp, Pr)
+        | (Nu, Po)
+        | (Nu, Pr)
+        | (Po, Op)
+        | (Po, Nu)
+        | (Pr, Op)
+        | (Pr, Nu)
+        | (Hy, Nu)
+        | (Is, Nu)
+        | (Nu, Nu)
+        | (Sy, Nu) => true,
+        // LB26
+        (Jl, Jl)
+        | (Jl, Jv)
+        | (Jl, H2)
+        | (Jl, H3)
+        | (Jv, Jv)
+        | (Jv, Jt)
+        | (H2, Jv)
+        | (H2, Jt)
+        | (Jt, Jt)
+        | (H3, Jt) => true,
+        // LB27
+        (Jl, Po) | (Jv, Po) | (Jt, Po) | (H2, Po) | (H3, Po) => true,
+        (Pr, Jl) | (Pr, Jv) | (Pr, Jt) | (Pr, H2) | (Pr, H3) => true,
+        // LB28
+        (Al, Al) | (Al, Hl) | (Hl, Al) | (Hl, Hl) => true,
+        // LB29
+        (Is, Al) | (Is, Hl) => true,
+        // LB30
+        (Al, Op) | (Hl, Op) | (Nu, Op) | (Cp, Al) | (Cp, Hl) | (Cp, Nu) => true,
+        // LB30a: regional indicators pair up into flags.
+        (Ri, Ri) => {
+            combined[..index]
+                .iter()
+                .rev()
+                .take_while(|class| **class == Ri)
+                .count()
+                % 2
+                == 1
+        }
+        // LB30b, with emoji bases treated as ideographs.
+        (Id, Em) => true,
+        _ => false,
+    };
+    if prohibited {
+        Break::Prohibited
+    } else {
+        // LB31
+        Break::Allowed
+    }
+}
+
+/// The classes of characters that combining marks can't attach to (LB9).
+fn is_unattachable(class: BreakClass) -> bool {
+    use BreakClass::*;
+    matches!(class, Bk | Cr | Lf | Nl | Sp | Zw)
+}
+
+/// Whether `class` is made up of typographic letter units, which `word-break` applies to.
+fn is_letter(class: BreakClass) -> bool {
+    use BreakClass::*;
+    matches!(class, Al | Hl | Nu | Id | H2 | H3 | Jl | Jv | Jt)
+}
+
+/// Resolves the classes the algorithm leaves up to the implementation (LB1), and applies
+/// `word-break: break-all`, which lets lines break between any letters.
+fn resolve_class(class: BreakClass, word_break: WordBreak) -> BreakClass {
+    use BreakClass::*;
+    let class = match class {
+        // Without a dictionary to find the words of South East Asian scripts, their text can only
+        // break at spaces.
+        Ai | Sa => Al,
+        // Small kana may start a line, as they do under `line-break: normal`.
+        Cj => Id,
+        _ => class,
+    };
+    match (class, word_break) {
+        (Al, WordBreak::BreakAll) | (Hl, WordBreak::BreakAll) | (Nu, WordBreak::BreakAll) => Id,
+        _ => class,
+    }
+}
+
+/// The line breaking classes of characters.
+///
+/// https://www.unicode.org/reports/tr14/#Table1
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum BreakClass {
+    /// Mandatory break
+    Bk,
+    /// Carriage return
+    Cr,
+    /// Line feed
+    Lf,
+    /// Combining mark
+    Cm,
+    /// Next line
+    Nl,
+    /// Word joiner
+    Wj,
+    /// Zero width space
+    Zw,
+    /// Non-breaking ("glue")
+    Gl,
+    /// Space
+    Sp,
+    /// Zero width joiner
+    Zwj,
+    /// Break opportunity before and after
+    B2,
+    /// Break after
+    Ba,
+    /// Break before
+    Bb,
+    /// Hyphen
+    Hy,
+    /// Contingent break opportunity
+    Cb,
+    /// Close punctuation
+    Cl,
+    /// Close parenthesis
+    Cp,
+    /// Exclamation or interrogation
+    Ex,
+    /// Inseparable
+    In,
+    /// Nonstarter
+    Ns,
+    /// Open punctuation
+    Op,
+    /// Quotation
+    Qu,
+    /// Infix numeric separator
+    Is,
+    /// Numeric
+    Nu,
+    /// Postfix numeric
+    Po,
+    /// Prefix numeric
+    Pr,
+    /// Symbols allowing break after
+    Sy,
+    /// Ambiguous (alphabetic or ideographic)
+    Ai,
+    /// Alphabetic
+    Al,
+    /// Conditional Japanese starter
+    Cj,
+    /// Emoji modifier
+    Em,
+    /// Hangul LV syllable
+    H2,
+    /// Hangul LVT syllable
+    H3,
+    /// Hebrew letter
+    Hl,
+    /// Ideographic
+    Id,
+    /// Hangul L jamo
+    Jl,
+    /// Hangul V jamo
+    Jv,
+    /// Hangul T jamo
+    Jt,
+    /// Regional indicator
+    Ri,
+    /// Complex context dependent (South East Asian)
+    Sa,
+}
+
+fn break_class(ch: char) -> BreakClass {
+    let code_point = ch as u32;
+    if (HANGUL_SYLLABLES_START..=HANGUL_SYLLABLES_END).contains(&code_point) {
+        // Syllables without a trailing consonant are spaced every 28 code points.
+        return if (code_point - HANGUL_SYLLABLES_START).is_multiple_of(28) {
+            BreakClass::H2
+        } else {
+            BreakClass::H3
+        };
+    }
+    BREAK_CLASSES
+        .binary_search_by(|&(start, end, _)| {
+            if end < code_point {
+                std::cmp::Ordering::Less
+            } else if start > code_point {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .map_or(BreakClass::Al, |index| BREAK_CLASSES[index].2)
+}
+
+const HANGUL_SYLLABLES_START: u32 = 0xac00;
+const HANGUL_SYLLABLES_END: u32 = 0xd7a3;
+
+/// Inclusive ranges of code points and their break class, in order.
+#[rustfmt::skip]
+const BREAK_CLASSES: &[(u32, u32, BreakClass)] = {
+    use BreakClass::*;
+    &[
+        (0x0000, 0x0008, Cm), (0x0009, 0x0009, Ba), (0x000a, 0x000a, Lf), (0x000b, 0x000c, Bk),
+        (0x000d, 0x000d, Cr), (0x000e, 0x001f, Cm), (0x0020, 0x0020, Sp), (0x0021, 0x0021, Ex),
+        (0x0022, 0x0022, Qu), (0x0023, 0x0023, Al), (0x0024, 0x0024, Pr), (0x0025, 0x0025, Po),
+        (0x0026, 0x0026, Al), (0x0027, 0x0027, Qu), (0x0028, 0x0028, Op), (0x0029, 0x0029, Cp),
+        (0x002a, 0x002a, Al), (0x002b, 0x002b, Pr), (0x002c, 0x002c, Is), (0x002d, 0x002d, Hy),
+        (0x002e, 0x002e, Is), (0x002f, 0x002f, Sy), (0x0030, 0x0039, Nu), (0x003a, 0x003b, Is),
+        (0x003c, 0x003e, Al), (0x003f, 0x003f, Ex), (0x0040, 0x005a, Al), (0x005b, 0x005b, Op),
+        (0x005c, 0x005c, Pr), (0x005d, 0x005d, Cp), (0x005e, 0x007a, Al), (0x007b, 0x007b, Op),
+        (0x007c, 0x007c, Ba), (0x007d, 0x007d, Cl), (0x007e, 0x007e, Al), (0x007f, 0x0084, Cm),
+        (0x0085, 0x0085, Nl), (0x0086, 0x009f, Cm), (0x00a0, 0x00a0, Gl), (0x00a1, 0x00a1, Op),
+        (0x00a2, 0x00a2, Po), (0x00a3, 0x00a5, Pr), (0x00a6, 0x00a6, Al), (0x00a7, 0x00a8, Ai),
+        (0x00a9, 0x00a9, Al), (0x00aa, 0x00aa, Ai), (0x00ab, 0x00ab, Qu), (0x00ac, 0x00ac, Al),
+        (0x00ad, 0x00ad, Ba), (0x00ae, 0x00af, Al), (0x00b0, 0x00b0, Po), (0x00b1, 0x00b1, Pr),
+        (0x00b2, 0x00b3, Ai), (0x00b4, 0x00b4, Bb), (0x00b5, 0x00b5, Al), (0x00b6, 0x00ba, Ai),
+        (0x00bb, 0x00bb, Qu), (0x00bc, 0x00be, Ai), (0x00bf, 0x00bf, Op), (0x00c0, 0x00d6, Al),
+        (0x00d7, 0x00d7, Ai), (0x00d8, 0x00f6, Al), (0x00f7, 0x00f7, Ai), (0x00f8, 0x02ff, Al),
+        // Combining diacritical marks
+        (0x0300, 0x034e, Cm), (0x034f, 0x034f, Gl), (0x0350, 0x035b, Cm), (0x035c, 0x0362, Gl),
+        (0x0363, 0x036f, Cm),
+        // Greek, Cyrillic, Armenian
+        (0x0370, 0x0482, Al), (0x0483, 0x0489, Cm), (0x048a, 0x0588, Al), (0x0589, 0x0589, Is),
+        (0x058a, 0x058a, Ba), (0x058b, 0x0590, Al),
+        // Hebrew
+        (0x0591, 0x05bd, Cm), (0x05be, 0x05be, Ba), (0x05bf, 0x05c7, Cm), (0x05d0, 0x05ea, Hl),
+        (0x05ef, 0x05f2, Hl), (0x05f3, 0x05ff, Al),
+        // Arabic
+        (0x0600, 0x060f, Al), (0x0610, 0x061a, Cm), (0x061b, 0x064a, Al), (0x064b, 0x065f, Cm),
+        (0x0660, 0x0669, Nu), (0x066a, 0x06ef, Al), (0x06f0, 0x06f9, Nu), (0x06fa, 0x0dff, Al),
+        // Thai, Lao
+        (0x0e00, 0x0e3e, Sa), (0x0e3f, 0x0e3f, Pr), (0x0e40, 0x0e4e, Sa), (0x0e4f, 0x0e4f, Al),
+        (0x0e50, 0x0e59, Nu), (0x0e5a, 0x0e5b, Ba), (0x0e80, 0x0eff, Sa), (0x0f00, 0x0fff, Al),
+        // Myanmar
+        (0x1000, 0x109f, Sa), (0x10a0, 0x10ff, Al),
+        // Hangul jamo
+        (0x1100, 0x115f, Jl), (0x1160, 0x11a7, Jv), (0x11a8, 0x11ff, Jt),
+        // Ethiopic, Ogham, Khmer
+        (0x1200, 0x135c, Al), (0x135d, 0x135f, Cm), (0x1360, 0x1360, Al), (0x1361, 0x1361, Ba),
+        (0x1362, 0x167f, Al), (0x1680, 0x1680, Ba), (0x1681, 0x177f, Al), (0x1780, 0x17ff, Sa),
+        (0x1800, 0x1aaf, Al), (0x1ab0, 0x1aff, Cm), (0x1b00, 0x1dbf, Al), (0x1dc0, 0x1dff, Cm),
+        (0x1e00, 0x1fff, Al),
+        // General punctuation
+        (0x2000, 0x2006, Ba), (0x2007, 0x2007, Gl), (0x2008, 0x200a, Ba), (0x200b, 0x200b, Zw),
+        (0x200c, 0x200c, Cm), (0x200d, 0x200d, Zwj), (0x200e, 0x200f, Cm), (0x2010, 0x2010, Ba),
+        (0x2011, 0x2011, Gl), (0x2012, 0x2013, Ba), (0x2014, 0x2014, B2), (0x2015, 0x2016, Ai),
+        (0x2017, 0x2017, Al), (0x2018, 0x2019, Qu), (0x201a, 0x201a, Op), (0x201b, 0x201d, Qu),
+        (0x201e, 0x201e, Op), (0x201f, 0x201f, Qu), (0x2020, 0x2021, Ai), (0x2022, 0x2023, Al),
+        (0x2024, 0x2026, In), (0x2027, 0x2027, Ba), (0x2028, 0x2029, Bk), (0x202a, 0x202e, Cm),
+        (0x202f, 0x202f, Gl), (0x2030, 0x2037, Po), (0x2038, 0x2038, Al), (0x2039, 0x203a, Qu),
+        (0x203b, 0x203b, Ai), (0x203c, 0x203d, Ns), (0x203e, 0x2043, Al), (0x2044, 0x2044, Is),
+        (0x2045, 0x2045, Op), (0x2046, 0x2046, Cl), (0x2047, 0x2049, Ns), (0x204a, 0x2055, Al),
+        (0x2056, 0x2056, Ba), (0x2057, 0x2057, Al), (0x2058, 0x205b, Ba), (0x205c, 0x205c, Al),
+        (0x205d, 0x205f, Ba), (0x2060, 0x2060, Wj), (0x2061, 0x2065, Al), (0x2066, 0x206f, Cm),
+        (0x2070, 0x209f, Al), (0x20a0, 0x20cf, Pr), (0x20d0, 0x20f0, Cm), (0x20f1, 0x2e7f, Al),
+        // CJK symbols and punctuation
+        (0x2e80, 0x2fff, Id), (0x3000, 0x3000, Ba), (0x3001, 0x3002, Cl), (0x3003, 0x3004, Id),
+        (0x3005, 0x3005, Ns), (0x3006, 0x3007, Id), (0x3008, 0x3008, Op), (0x3009, 0x3009, Cl),
+        (0x300a, 0x300a, Op), (0x300b, 0x300b, Cl), (0x300c, 0x300c, Op), (0x300d, 0x300d, Cl),
+        (0x300e, 0x300e, Op), (0x300f, 0x300f, Cl), (0x3010, 0x3010, Op), (0x3011, 0x3011, Cl),
+        (0x3012, 0x3013, Id), (0x3014, 0x3014, Op), (0x3015, 0x3015, Cl), (0x3016, 0x3016, Op),
+        (0x3017, 0x3017, Cl), (0x3018, 0x3018, Op), (0x3019, 0x3019, Cl), (0x301a, 0x301a, Op),
+        (0x301b, 0x301b, Cl), (0x301c, 0x301c, Ns), (0x301d, 0x301d, Op), (0x301e, 0x301f, Cl),
+        (0x3020, 0x3029, Id), (0x302a, 0x302f, Cm), (0x3030, 0x303a, Id), (0x303b, 0x303c, Ns),
+        (0x303d, 0x3040, Id),
+        // Hiragana
+        (0x3041, 0x3041, Cj), (0x3042, 0x3042, Id), (0x3043, 0x3043, Cj), (0x3044, 0x3044, Id),
+        (0x3045, 0x3045, Cj), (0x3046, 0x3046, Id), (0x3047, 0x3047, Cj), (0x3048, 0x3048, Id),
+        (0x3049, 0x3049, Cj), (0x304a, 0x3062, Id), (0x3063, 0x3063, Cj), (0x3064, 0x3082, Id),
+        (0x3083, 0x3083, Cj), (0x3084, 0x3084, Id), (0x3085, 0x3085, Cj), (0x3086, 0x3086, Id),
+        (0x3087, 0x3087, Cj), (0x3088, 0x308d, Id), (0x308e, 0x308e, Cj), (0x308f, 0x3094, Id),
+        (0x3095, 0x3096, Cj), (0x3097, 0x3098, Id), (0x3099, 0x309a, Cm), (0x309b, 0x309e, Ns),
+        (0x309f, 0x309f, Id), (0x30a0, 0x30a0, Ns),
+        // Katakana
+        (0x30a1, 0x30a1, Cj), (0x30a2, 0x30a2, Id), (0x30a3, 0x30a3, Cj), (0x30a4, 0x30a4, Id),
+        (0x30a5, 0x30a5, Cj), (0x30a6, 0x30a6, Id), (0x30a7, 0x30a7, Cj), (0x30a8, 0x30a8, Id),
+        (0x30a9, 0x30a9, Cj), (0x30aa, 0x30c2, Id), (0x30c3, 0x30c3, Cj), (0x30c4, 0x30e2, Id),
+        (0x30e3, 0x30e3, Cj), (0x30e4, 0x30e4, Id), (0x30e5, 0x30e5, Cj), (0x30e6, 0x30e6, Id),
+        (0x30e7, 0x30e7, Cj), (0x30e8, 0x30ed, Id), (0x30ee, 0x30ee, Cj), (0x30ef, 0x30f4, Id),
+        (0x30f5, 0x30f6, Cj), (0x30f7, 0x30fa, Id), (0x30fb, 0x30fb, Ns), (0x30fc, 0x30fc, Cj),
+        (0x30fd, 0x30fe, Ns), (0x30ff, 0x31ef, Id), (0x31f0, 0x31ff, Cj),
+        // CJK ideographs, Yi
+        (0x3200, 0x4dbf, Id), (0x4dc0, 0x4dff, Al), (0x4e00, 0xa4cf, Id), (0xa4d0, 0xabff, Al),
+        (0xd7b0, 0xd7c6, Jv), (0xd7cb, 0xd7fb, Jt), (0xf900, 0xfaff, Id), (0xfb00, 0xfb1c, Al),
+        (0xfb1d, 0xfb4f, Hl), (0xfb50, 0xfdff, Al),
+        // Variation selectors, vertical and small forms
+        (0xfe00, 0xfe0f, Cm), (0xfe10, 0xfe10, Is), (0xfe11, 0xfe12, Cl), (0xfe13, 0xfe14, Is),
+        (0xfe15, 0xfe16, Ex), (0xfe17, 0xfe17, Op), (0xfe18, 0xfe18, Cl), (0xfe19, 0xfe19, In),
+        (0xfe20, 0xfe2f, Cm), (0xfe30, 0xfe6f, Id), (0xfeff, 0xfeff, Wj),
+        // Halfwidth and fullwidth forms
+        (0xff01, 0xff01, Ex), (0xff02, 0xff03, Id), (0xff04, 0xff04, Pr), (0xff05, 0xff05, Po),
+        (0xff06, 0xff07, Id), (0xff08, 0xff08, Op), (0xff09, 0xff09, Cl), (0xff0a, 0xff0b, Id),
+        (0xff0c, 0xff0c, Cl), (0xff0d, 0xff0d, Id), (0xff0e, 0xff0e, Cl), (0xff0f, 0xff19, Id),
+        (0xff1a, 0xff1b, Ns), (0xff1c, 0xff1e, Id), (0xff1f, 0xff1f, Ex), (0xff20, 0xff3a, Id),
+        (0xff3b, 0xff3b, Op), (0xff3c, 0xff3c, Id), (0xff3d, 0xff3d, Cl), (0xff3e, 0xff5a, Id),
+        (0xff5b, 0xff5b, Op), (0xff5c, 0xff5c, Id), (0xff5d, 0xff5d, Cl), (0xff5e, 0xff5e, Id),
+        (0xff5f, 0xff5f, Op), (0xff60, 0xff61, Cl), (0xff62, 0xff62, Op), (0xff63, 0xff64, Cl),
+        (0xff65, 0xff65, Ns), (0xff66, 0xff66, Al), (0xff67, 0xff70, Cj), (0xff71, 0xff9d, Al),
+        (0xff9e, 0xff9f, Ns), (0xffa0, 0xffdf, Al), (0xffe0, 0xffe0, Po), (0xffe1, 0xffe1, Pr),
+        (0xffe2, 0xffe4, Id), (0xffe5, 0xffe6, Pr), (0xfffc, 0xfffc, Cb),
+        // Emoji and pictographs
+        (0x1f000, 0x1f1e5, Id), (0x1f1e6, 0x1f1ff, Ri), (0x1f200, 0x1f3fa, Id),
+        (0x1f3fb, 0x1f3ff, Em), (0x1f400, 0x1faff, Id),
+        // Supplementary ideographs, tags, variation selectors supplement
+        (0x20000, 0x3fffd, Id), (0xe0001, 0xe007f, Cm), (0xe0100, 0xe01ef, Cm),
+    ]
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowed_breaks(text: &str, word_break: WordBreak) -> Vec<usize> {
+        let text = text.chars().map(|ch| (ch, word_break)).collect::<Vec<_>>();
+        break_opportunities(&text)
+            .iter()
+            .enumerate()
+            .filter(|(_, line_break)| **line_break == Break::Allowed)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    #[test]
+    fn break_classes_are_sorted() {
+        for window in BREAK_CLASSES.windows(2) {
+            assert!(window[0].0 <= window[0].1);
+            assert!(window[0].1 < window[1].0, "{:x?} overlaps", window);
+        }
+    }
+
+    #[test]
+    fn breaks_after_spaces_and_hyphens() {
+        assert_eq!(
+            allowed_breaks("a well-known (fact).", WordBreak::Normal),
+            vec![2, 7, 13]
+        );
+        // Numbers stay together with their prefixes, suffixes and separators.
+        assert_eq!(
+            allowed_breaks("costs $1,000.50 or 10%", WordBreak::Normal),
+            vec![6, 16, 19]
+        );
+        // Zero width spaces allow breaks, and non-breaking spaces prevent them.
+        assert_eq!(
+            allowed_breaks("a\u{200b}b c\u{a0}d", WordBreak::Normal),
+            vec![2, 4]
+        );
+    }
+
+    #[test]
+    fn breaks_between_ideographs() {
+        // Not before closing punctuation, but before small kana.
+        assert_eq!(
+            allowed_breaks("日本語。ちょっと", WordBreak::Normal),
+            vec![1, 2, 4, 5, 6, 7]
+        );
+        assert_eq!(
+            allowed_breaks("日本語。ちょっと", WordBreak::KeepAll),
+            vec![4]
+        );
+        assert_eq!(allowed_breaks("한국어 텍스트", WordBreak::KeepAll), vec![4]);
+    }
+
+    #[test]
+    fn break_all_breaks_between_letters() {
+        assert_eq!(
+            allowed_breaks("abc de", WordBreak::BreakAll),
+            vec![1, 2, 4, 5]
+        );
+        // Punctuation still can't start a line.
+        assert_eq!(allowed_breaks("ab.", WordBreak::BreakAll), vec![1]);
+    }
+}
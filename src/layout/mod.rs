@@ -1,27 +1,53 @@
 // Useful links:
 //  * https://www.w3.org/TR/css-display-3/#css-box
 //  * https://www.w3.org/TR/2018/WD-css-box-3-20181218/#intro
+pub mod bidi;
 pub mod dimensions;
+pub mod inline;
 pub mod layout_box;
+pub mod line_break;
 pub mod rect;
 
 use crate::dom::tree::{NodeData, NodeRef};
+use crate::gfx::font::FontHandle;
 use crate::layout::dimensions::PhysicalDimensions;
-use crate::layout::layout_box::{BoxType, LayoutBox};
+use crate::layout::layout_box::{BoxType, FloatManager, LayoutBox, PositionedScope};
 use crate::layout::rect::Rect;
 use crate::style::values::computed::length::CSSPixelLength;
-use crate::style::values::computed::Display;
+use crate::style::values::computed::{Display, Float};
 use crate::style::values::CSSFloat;
 use std::io::Write;
 
+pub use crate::common::LogicalDirection;
+
 /// Takes a DOM node and builds the corresponding layout tree of it and its children.  Returns
 /// `None` if `node` is a `Display::None`.
 pub fn build_layout_tree(node: NodeRef) -> Option<LayoutBox> {
     let computed_values = &*node.computed_values();
+    let is_root = node.parent().map_or(false, |parent| {
+        matches!(*parent.data(), NodeData::Document(_))
+    });
+    // Floats and absolutely positioned boxes are block containers, whatever their display type.
+    // The root element is neither, and an absolutely positioned box doesn't float.
+    // https://drafts.csswg.org/css-display-3/#blockify
+    // https://www.w3.org/TR/CSS2/visuren.html#dis-pos-flo
+    let is_absolutely_positioned = computed_values.position.is_absolutely_positioned() && !is_root;
+    let is_floated = computed_values.float != Float::None && !is_absolutely_positioned && !is_root;
     // TODO: We need to think about the validity of making strong-ref clones to nodes here (and elsewhere).
     // Will things get properly dropped?  Maybe LayoutBox should store a `Weak` ref?
     let mut layout_box = match computed_values.display {
-        Display::Block => LayoutBox::new(
+        Display::None => {
+            return None;
+        }
+        // A flow-root is a block box that establishes a new block formatting context, which the
+        // layout box models on its own.
+        Display::Block | Display::FlowRoot => LayoutBox::new(
+            BoxType::Block,
+            node.clone(),
+            computed_values.direction,
+            computed_values.writing_mode,
+        ),
+        _ if is_floated || is_absolutely_positioned => LayoutBox::new(
             BoxType::Block,
             node.clone(),
             computed_values.direction,
@@ -33,66 +59,88 @@ pub fn build_layout_tree(node: NodeRef) -> Option<LayoutBox> {
             computed_values.direction,
             computed_values.writing_mode,
         ),
-        Display::None => {
-            return None;
-        }
+        Display::InlineBlock => LayoutBox::new(
+            BoxType::InlineBlock,
+            node.clone(),
+            computed_values.direction,
+            computed_values.writing_mode,
+        ),
     };
 
     for child in node.children() {
-        let child_computed_values = &*child.computed_values();
-        match child_computed_values.display {
-            Display::Block => {
-                if let Some(child_box) = build_layout_tree(child.clone()) {
-                    // TODO: We don't handle the case where a block-flow child box is added to an inline box.
-                    // This current behavior is wrong — we should be checking if `node` is an `Display::Inline` and
-                    // doing something different here.  To fix, see: https://www.w3.org/TR/CSS2/visuren.html#box-gen
-                    // Namely, the paragraph that begins with "When an inline box contains an in-flow block-level box"
-                    // This concept _might_ be called "fragmenting".
-                    layout_box.add_child(child_box)
-                }
+        if let Some(child_box) = build_layout_tree(child.clone()) {
+            if child_box.is_block_level() {
+                // Block-level children of inline boxes are moved out of them once the enclosing
+                // block container has all its children.
+                layout_box.add_child(child_box)
+            } else {
+                // Floats stay with the inline-level content around them, which is laid out
+                // around them.  Absolutely positioned boxes stay there too, as that's where
+                // their static position is found.
+                layout_box.add_child_inline(child_box)
             }
-            Display::Inline => {
-                if let Some(child_box) = build_layout_tree(child.clone()) {
-                    layout_box.add_child_inline(child_box)
-                }
-            }
-            Display::None => {}
         }
     }
+    if let BoxType::Block | BoxType::InlineBlock = layout_box.box_type() {
+        layout_box.generate_anonymous_block_boxes();
+    }
     Some(layout_box)
 }
 
 /// Given a `window` and what probably should be the root of a `layout_tree`, perform a layout
-/// with the dimensions of the `window`.
+/// with the dimensions of the `window`, with the document scrolled down by `scroll_y`.  The
+/// scroll offset is clamped to how far the document overflows the window, and the used offset is
+/// returned.  Fixed positioned boxes are laid out in the viewport, the part of the document that
+/// `scroll_y` brings into view, so they stay in place as the document scrolls, while stickily
+/// positioned boxes are kept within it.
 pub fn global_layout(
     layout_tree: &mut LayoutBox,
     inner_window_width: f32,
     inner_window_height: f32,
+    scroll_y: f32,
+    font_handle: &FontHandle,
     scale_factor: f32,
-) {
+) -> f32 {
+    let initial_containing_block = Rect {
+        start_x: 0.0,
+        start_y: 0.0,
+        width: CSSPixelLength::new(inner_window_width),
+        height: CSSPixelLength::new(inner_window_height),
+    };
     layout_tree.layout(
         PhysicalDimensions {
-            content: Rect {
-                start_x: 0.0,
-                start_y: 0.0,
-                width: CSSPixelLength::new(inner_window_width),
-                height: CSSPixelLength::new(inner_window_height),
-            },
+            content: initial_containing_block,
             padding: Default::default(),
             border: Default::default(),
             margin: Default::default(),
         },
+        &mut FloatManager::default(),
+        font_handle,
         scale_factor,
     );
-}
-
-/// https://drafts.csswg.org/css-writing-modes-4/#logical-directions
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum LogicalDirection {
-    BlockStart,
-    BlockEnd,
-    InlineStart,
-    InlineEnd,
+    // https://www.w3.org/TR/CSS2/visudet.html#containing-block-details
+    layout_tree.layout_positioned_descendants(
+        initial_containing_block,
+        PositionedScope::Absolute,
+        font_handle,
+        scale_factor,
+    );
+    let document_height = layout_tree.physical_dimensions().margin_box().height.px();
+    let scroll_y = scroll_y.min(document_height - inner_window_height).max(0.);
+    let viewport = Rect {
+        start_y: scroll_y,
+        ..initial_containing_block
+    };
+    layout_tree.layout_positioned_descendants(
+        viewport,
+        PositionedScope::Fixed,
+        font_handle,
+        scale_factor,
+    );
+    layout_tree.apply_sticky_offsets(initial_containing_block, viewport, scale_factor);
+    // Everything is painted relative to the viewport.
+    layout_tree.translate(0., -scroll_y);
+    scroll_y
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
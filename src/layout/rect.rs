@@ -1,5 +1,6 @@
 use crate::style::values::computed::length::CSSPixelLength;
 use crate::style::values::CSSFloat;
+use crate::Side;
 
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Rect {
@@ -20,6 +21,12 @@ impl Rect {
             height: self.height + edge.top + edge.bottom,
         }
     }
+
+    /// Moves the rectangle by `dx` along the x-axis and `dy` along the y-axis.
+    pub fn translate(&mut self, dx: CSSFloat, dy: CSSFloat) {
+        self.start_x += dx;
+        self.start_y += dy;
+    }
 }
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -31,6 +38,24 @@ pub struct EdgeSizes {
 }
 
 impl EdgeSizes {
+    pub fn get(&self, side: Side) -> CSSPixelLength {
+        match side {
+            Side::Bottom => self.bottom,
+            Side::Left => self.left,
+            Side::Right => self.right,
+            Side::Top => self.top,
+        }
+    }
+
+    pub fn get_mut(&mut self, side: Side) -> &mut CSSPixelLength {
+        match side {
+            Side::Bottom => &mut self.bottom,
+            Side::Left => &mut self.left,
+            Side::Right => &mut self.right,
+            Side::Top => &mut self.top,
+        }
+    }
+
     pub fn scale_by(&mut self, scale_factor: f32) {
         self.left *= scale_factor;
         self.right *= scale_factor;
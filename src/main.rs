@@ -12,15 +12,18 @@ extern crate strum_macros;
 extern crate derive_builder;
 
 use std::fs::File;
+use std::path::Path;
 
 use crate::dom::parser::parse_html;
 use crate::dom::traits::TendrilSink;
-use glutin::event::{Event, WindowEvent};
+use glutin::event::{Event, MouseScrollDelta, WindowEvent};
 use glutin::event_loop::EventLoop;
 
 use crate::dom::tree::NodeRef;
 use crate::layout::{build_layout_tree, global_layout, DumpLayout};
-use crate::style::apply_styles;
+use crate::style::media_queries::Device;
+use crate::style::stylesheet::ImportContext;
+use crate::style::{apply_styles, StylesheetSet};
 
 pub mod cli;
 pub mod common;
@@ -31,14 +34,15 @@ pub mod style;
 
 use crate::cli::{
     dump_layout_tree, html_file_path_from_files, inner_window_height, inner_window_width,
-    scale_factor, setup_and_get_cli_args, stylesheets_from_files,
+    scale_factor, scroll_y, setup_and_get_cli_args, stylesheets_from_files,
 };
 use crate::gfx::char::CharHandle;
 use crate::gfx::display::build_display_list;
+use crate::gfx::font::FontHandle;
 use crate::gfx::paint::MasterPainter;
 use crate::gfx::{init_main_window_and_gl, print_gl_info, resize_window};
 use crate::layout::layout_box::LayoutBox;
-pub use common::Side;
+pub use common::{LogicalDirection, Side};
 use gl::Gl;
 use glutin::event_loop::ControlFlow;
 use glutin::{PossiblyCurrent, WindowedContext};
@@ -60,72 +64,122 @@ fn main() {
     let ua_sheet = style::stylesheet::parse_css_to_stylesheet(
         Some("browser.css".to_owned()),
         &mut std::fs::read_to_string("web/browser.css").expect("file fail"),
-    )
-    .expect("parse stylesheet fail");
+        ImportContext::for_file(Path::new("web/browser.css")),
+    );
     let author_sheets = stylesheets_from_files(&arg_matches).unwrap_or_else(|| {
         vec![style::stylesheet::parse_css_to_stylesheet(
             Some("rainbow-divs.css".to_owned()),
             &mut std::fs::read_to_string("tests/websrc/rainbow-divs.css").expect("file fail"),
-        )
-        .expect("parse stylesheet fail")]
+            ImportContext::for_file(Path::new("tests/websrc/rainbow-divs.css")),
+        )]
     });
-    apply_styles(dom.clone(), &[ua_sheet], &[], &author_sheets);
+    let html_dir = Path::new(html_file)
+        .parent()
+        .unwrap_or_else(|| Path::new(""));
+    let sheets = StylesheetSet {
+        ua_sheets: vec![ua_sheet],
+        user_sheets: vec![],
+        author_sheets,
+        document_sheets: style::stylesheet::document_stylesheets(&dom, html_dir),
+    };
     let (inner_width_opt, inner_height_opt) = (
         inner_window_width(&arg_matches),
         inner_window_height(&arg_matches),
     );
 
     let scale_factor_opt = scale_factor(&arg_matches);
+    let scroll_y = scroll_y(&arg_matches).unwrap_or(0.);
     if dump_layout_tree(&arg_matches) {
         let scale_factor = scale_factor_opt
             .expect("scale factor must be explicitly specified when running layout dump");
-        run_layout_dump(dom, inner_width_opt, inner_height_opt, scale_factor);
+        run_layout_dump(
+            dom,
+            &sheets,
+            inner_width_opt,
+            inner_height_opt,
+            scroll_y,
+            scale_factor,
+        );
         return;
     }
     let (windowed_context, event_loop, gl) =
         init_main_window_and_gl(inner_width_opt, inner_height_opt);
     print_gl_info(&windowed_context, &gl);
-    run_event_loop(event_loop, gl, dom, windowed_context, scale_factor_opt);
+    run_event_loop(
+        event_loop,
+        gl,
+        dom,
+        sheets,
+        windowed_context,
+        scroll_y,
+        scale_factor_opt,
+    );
 }
 
 fn run_layout_dump(
-    styled_dom: NodeRef,
+    dom: NodeRef,
+    sheets: &StylesheetSet,
     inner_width_opt: Option<f32>,
     inner_height_opt: Option<f32>,
+    scroll_y: f32,
     scale_factor: f32,
 ) {
-    let mut layout_tree = build_layout_tree(styled_dom).unwrap();
+    let inner_width = inner_width_opt
+        .expect("Inner window width CLI arg 'width' must be specified for dump-layout.");
+    let inner_height = inner_height_opt
+        .expect("Inner window height CLI arg 'height' must be specified for dump-layout.");
+    let device = Device::new(inner_width, inner_height, scale_factor);
+    let font_handle = load_font_handle(sheets, &device);
+    apply_styles(dom.clone(), sheets, &device, &font_handle);
+    let mut layout_tree = build_layout_tree(dom).unwrap();
     global_layout(
         &mut layout_tree,
-        inner_width_opt
-            .expect("Inner window width CLI arg 'width' must be specified for dump-layout."),
-        inner_height_opt
-            .expect("Inner window height CLI arg 'height' must be specified for dump-layout."),
+        inner_width,
+        inner_height,
+        scroll_y,
+        &font_handle,
         scale_factor,
     );
     layout_tree.dump_layout(&mut std::io::stdout(), 0);
 }
 
+/// Creates a font handle with the faces of the @font-face rules that apply to `device` loaded.
+fn load_font_handle(sheets: &StylesheetSet, device: &Device) -> FontHandle {
+    let mut font_handle = FontHandle::new();
+    font_handle.load_font_faces(sheets.font_face_rules(device));
+    font_handle
+}
+
+/// How far the document scrolls for each line a mouse wheel is turned by, in CSS pixels.
+const LINE_SCROLL_DISTANCE: f32 = 40.;
+
 pub fn run_event_loop(
     event_loop: EventLoop<()>,
     gl: Gl,
-    styled_dom: NodeRef,
+    dom: NodeRef,
+    sheets: StylesheetSet,
     windowed_context: WindowedContext<PossiblyCurrent>,
+    mut scroll_y: f32,
     cli_specified_scale_factor: Option<f32>,
 ) {
     let mut master_painter = MasterPainter::new(&gl).unwrap();
     let char_handle = CharHandle::new(&gl);
-    // An un-laid-out tree of boxes, to be cloned from whenever a global layout is required.
-    // This saves us from having to rebuild the entire layout tree from the DOM when necessary,
-    // instead only needing a clone.
-    let clean_layout_tree = build_layout_tree(styled_dom).unwrap();
     let mut scale =
         cli_specified_scale_factor.unwrap_or(windowed_context.window().scale_factor() as f32);
-    paint(
+    let mut device = window_device(&windowed_context, scale);
+    let font_handle = load_font_handle(&sheets, &device);
+    apply_styles(dom.clone(), &sheets, &device, &font_handle);
+    // An un-laid-out tree of boxes, to be cloned from whenever a global layout is required.
+    // This saves us from having to rebuild the entire layout tree from the DOM when necessary,
+    // instead only needing a clone.  It only needs to be rebuilt when styles are re-applied.
+    let mut clean_layout_tree = build_layout_tree(dom.clone()).unwrap();
+    scroll_y = paint(
         clean_layout_tree.clone(),
         &windowed_context,
         &char_handle,
+        &font_handle,
         &mut master_painter,
+        scroll_y,
         scale,
     );
     event_loop.run(move |event, _, control_flow| {
@@ -136,11 +190,21 @@ pub fn run_event_loop(
             Event::WindowEvent { ref event, .. } => match event {
                 WindowEvent::Resized(physical_size) => {
                     resize_window(&gl, &windowed_context, physical_size);
-                    paint(
+                    restyle_if_device_changed(
+                        &dom,
+                        &sheets,
+                        &mut device,
+                        window_device(&windowed_context, scale),
+                        &font_handle,
+                        &mut clean_layout_tree,
+                    );
+                    scroll_y = paint(
                         clean_layout_tree.clone(),
                         &windowed_context,
                         &char_handle,
+                        &font_handle,
                         &mut master_painter,
+                        scroll_y,
                         scale,
                     )
                 }
@@ -150,11 +214,40 @@ pub fn run_event_loop(
                 } => {
                     scale = *scale_factor as f32;
                     resize_window(&gl, &windowed_context, new_inner_size);
-                    paint(
+                    restyle_if_device_changed(
+                        &dom,
+                        &sheets,
+                        &mut device,
+                        window_device(&windowed_context, scale),
+                        &font_handle,
+                        &mut clean_layout_tree,
+                    );
+                    scroll_y = paint(
+                        clean_layout_tree.clone(),
+                        &windowed_context,
+                        &char_handle,
+                        &font_handle,
+                        &mut master_painter,
+                        scroll_y,
+                        scale,
+                    )
+                }
+                WindowEvent::MouseWheel { delta, .. } => {
+                    // The delta is positive when the wheel is turned away from the user, which
+                    // scrolls towards the top of the document.
+                    scroll_y -= match delta {
+                        MouseScrollDelta::LineDelta(_, lines) => {
+                            lines * LINE_SCROLL_DISTANCE * scale
+                        }
+                        MouseScrollDelta::PixelDelta(position) => position.y as f32 * scale,
+                    };
+                    scroll_y = paint(
                         clean_layout_tree.clone(),
                         &windowed_context,
                         &char_handle,
+                        &font_handle,
                         &mut master_painter,
+                        scroll_y,
                         scale,
                     )
                 }
@@ -165,21 +258,56 @@ pub fn run_event_loop(
         }
     });
 
+    fn window_device(windowed_context: &WindowedContext<PossiblyCurrent>, scale: f32) -> Device {
+        let inner_window_size = windowed_context.window().inner_size();
+        Device::new(
+            inner_window_size.width as f32,
+            inner_window_size.height as f32,
+            scale,
+        )
+    }
+
+    /// Media queries and viewport-percentage lengths are evaluated against the device, so when it
+    /// changes (e.g. on resize), styles and the boxes generated from them need to be rebuilt.
+    fn restyle_if_device_changed(
+        dom: &NodeRef,
+        sheets: &StylesheetSet,
+        device: &mut Device,
+        new_device: Device,
+        font_handle: &FontHandle,
+        clean_layout_tree: &mut LayoutBox,
+    ) {
+        if *device == new_device {
+            return;
+        }
+        *device = new_device;
+        apply_styles(dom.clone(), sheets, device, font_handle);
+        *clean_layout_tree = build_layout_tree(dom.clone()).unwrap();
+    }
+
+    /// Lays out and paints the document scrolled down by `scroll_y`, returning the scroll offset
+    /// that was used, which stays within the document.
     fn paint(
         mut layout_tree: LayoutBox,
         windowed_context: &WindowedContext<PossiblyCurrent>,
         char_handle: &CharHandle,
+        font_handle: &FontHandle,
         painter: &mut MasterPainter,
+        scroll_y: f32,
         scale_factor: f32,
-    ) {
+    ) -> f32 {
         let inner_window_size = windowed_context.window().inner_size();
-        global_layout(
+        let scroll_y = global_layout(
             &mut layout_tree,
             inner_window_size.width as f32,
-            inner_window_size.width as f32,
+            inner_window_size.height as f32,
+            scroll_y,
+            font_handle,
             scale_factor,
         );
-        let display_list = build_display_list(&layout_tree, &char_handle, scale_factor);
+        let display_list =
+            build_display_list(&layout_tree, &char_handle, font_handle, scale_factor);
         painter.paint(&windowed_context, &display_list);
+        scroll_y
     }
 }
@@ -0,0 +1,402 @@
+//! Custom properties (`--foo: ...`) and `var()` substitution.
+//!
+//! https://www.w3.org/TR/css-variables-1/
+
+use std::collections::{HashMap, HashSet};
+
+use cssparser::{ParseError, Parser, ParserInput, Token};
+
+use crate::style::{CssWideKeywords, StyleParseErrorKind};
+
+/// The name of a custom property, without the leading `--`.
+pub type Name = String;
+
+/// The computed custom properties of an element, keyed by name.  Values in this map never contain
+/// `var()` references, as they have already been substituted.
+pub type CustomPropertiesMap = HashMap<Name, VariableValue>;
+
+/// Parses the name of a custom property, returning `None` if `prop_name` isn't one.
+pub fn parse_name(prop_name: &str) -> Option<&str> {
+    prop_name.strip_prefix("--")
+}
+
+/// A custom property declaration, e.g. `--gap: 8px`.
+#[derive(Clone, Debug)]
+pub struct CustomDeclaration {
+    pub name: Name,
+    pub value: CustomDeclarationValue,
+}
+
+#[derive(Clone, Debug)]
+pub enum CustomDeclarationValue {
+    Value(VariableValue),
+    CssWideKeyword(CssWideKeywords),
+}
+
+/// The value of a custom property, or of a regular property containing `var()` references.
+///
+/// https://www.w3.org/TR/css-variables-1/#syntax
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariableValue {
+    /// The CSS text of the value, with leading and trailing whitespace removed.
+    css: String,
+    /// The names of the custom properties referenced via `var()`, including from within fallbacks.
+    references: HashSet<Name>,
+}
+
+impl VariableValue {
+    /// Parses a `<declaration-value>`, which is any sequence of tokens so long as it's balanced and
+    /// doesn't contain any bad strings or URLs.  Any `var()` functions within must be well-formed.
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let start = input.position();
+        let mut references = HashSet::new();
+        parse_declaration_value(input, &mut references)?;
+        Ok(VariableValue {
+            css: input.slice_from(start).trim().to_owned(),
+            references,
+        })
+    }
+
+    pub fn css(&self) -> &str {
+        &self.css
+    }
+
+    pub fn has_references(&self) -> bool {
+        !self.references.is_empty()
+    }
+}
+
+fn parse_declaration_value<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    references: &mut HashSet<Name>,
+) -> Result<(), ParseError<'i, StyleParseErrorKind<'i>>> {
+    loop {
+        let token = match input.next_including_whitespace_and_comments() {
+            Ok(token) => token.clone(),
+            Err(_) => return Ok(()),
+        };
+        match token {
+            Token::BadUrl(url) => {
+                return Err(
+                    input.new_custom_error(StyleParseErrorKind::BadUrlInDeclarationValueBlock(url))
+                )
+            }
+            Token::BadString(string) => {
+                return Err(input.new_custom_error(
+                    StyleParseErrorKind::BadStringInDeclarationValueBlock(string),
+                ))
+            }
+            Token::CloseParenthesis => {
+                return Err(input.new_custom_error(
+                    StyleParseErrorKind::UnbalancedCloseParenthesisInDeclarationValueBlock,
+                ))
+            }
+            Token::CloseSquareBracket => {
+                return Err(input.new_custom_error(
+                    StyleParseErrorKind::UnbalancedCloseSquareBracketInDeclarationValueBlock,
+                ))
+            }
+            Token::CloseCurlyBracket => {
+                return Err(input.new_custom_error(
+                    StyleParseErrorKind::UnbalancedCloseCurlyBracketInDeclarationValueBlock,
+                ))
+            }
+            Token::Function(ref name) if name.eq_ignore_ascii_case("var") => {
+                input.parse_nested_block(|input| parse_var_function(input, references))?;
+            }
+            Token::Function(_)
+            | Token::ParenthesisBlock
+            | Token::SquareBracketBlock
+            | Token::CurlyBracketBlock => {
+                input.parse_nested_block(|input| parse_declaration_value(input, references))?;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Parses the arguments of a `var()` function: `var( <custom-property-name> [, <declaration-value>? ]? )`
+fn parse_var_function<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    references: &mut HashSet<Name>,
+) -> Result<(), ParseError<'i, StyleParseErrorKind<'i>>> {
+    let location = input.current_source_location();
+    let ident = input.expect_ident_cloned()?;
+    let name = match parse_name(&ident) {
+        Some(name) => name,
+        None => return Err(location.new_unexpected_token_error(Token::Ident(ident.clone()))),
+    };
+    references.insert(name.to_owned());
+    if input.try_parse(|input| input.expect_comma()).is_ok() {
+        parse_declaration_value(input, references)?;
+    }
+    Ok(())
+}
+
+/// Computes the custom properties of an element from the ones it inherits and the custom
+/// property declarations that won the cascade for it.
+///
+/// `var()` references are substituted.  Properties that reference themselves, directly or through
+/// other properties, are invalid at computed-value time, as are properties referencing an invalid
+/// property without a fallback.  Invalid properties are left out of the map, making them the
+/// guaranteed-invalid value.
+///
+/// https://www.w3.org/TR/css-variables-1/#cycles
+pub fn compute_custom_properties<'a>(
+    inherited: &CustomPropertiesMap,
+    declarations: impl Iterator<Item = &'a CustomDeclaration>,
+) -> CustomPropertiesMap {
+    let mut specified = inherited.clone();
+    for declaration in declarations {
+        match &declaration.value {
+            CustomDeclarationValue::Value(value) => {
+                specified.insert(declaration.name.clone(), value.clone());
+            }
+            CustomDeclarationValue::CssWideKeyword(CssWideKeywords::Initial) => {
+                specified.remove(&declaration.name);
+            }
+            // Custom properties are inherited, so the remaining keywords take the parent's value.
+            CustomDeclarationValue::CssWideKeyword(_) => match inherited.get(&declaration.name) {
+                Some(value) => {
+                    specified.insert(declaration.name.clone(), value.clone());
+                }
+                None => {
+                    specified.remove(&declaration.name);
+                }
+            },
+        }
+    }
+
+    let mut resolver = Resolver {
+        specified: &specified,
+        computed: HashMap::new(),
+        invalid: HashSet::new(),
+        stack: Vec::new(),
+    };
+    for name in specified.keys() {
+        resolver.resolve(name);
+    }
+    resolver.computed
+}
+
+/// Substitutes the custom properties referenced by `var()` functions in `css`.  Returns `None` if
+/// a reference can't be resolved and has no fallback, which makes the declaration `css` came from
+/// invalid at computed-value time.
+pub fn substitute(css: &str, custom_properties: &CustomPropertiesMap) -> Option<String> {
+    substitute_with(css, &mut |name| {
+        custom_properties
+            .get(name)
+            .map(|value| value.css().to_owned())
+    })
+}
+
+/// Resolves `var()` references between the custom properties of a single element.
+struct Resolver<'a> {
+    specified: &'a CustomPropertiesMap,
+    computed: CustomPropertiesMap,
+    /// Properties that are invalid at computed-value time.
+    invalid: HashSet<Name>,
+    /// The properties currently being resolved, in the order their resolution began.  Finding a
+    /// property that's already on the stack means every property from it upwards is in a cycle.
+    stack: Vec<Name>,
+}
+
+impl Resolver<'_> {
+    fn resolve(&mut self, name: &str) -> Option<String> {
+        if let Some(value) = self.computed.get(name) {
+            return Some(value.css().to_owned());
+        }
+        if self.invalid.contains(name) {
+            return None;
+        }
+        if let Some(cycle_start) = self.stack.iter().position(|on_stack| on_stack == name) {
+            self.invalid
+                .extend(self.stack[cycle_start..].iter().cloned());
+            return None;
+        }
+        let value = self.specified.get(name)?;
+        if !value.has_references() {
+            self.computed.insert(name.to_owned(), value.clone());
+            return Some(value.css().to_owned());
+        }
+
+        self.stack.push(name.to_owned());
+        let substituted = substitute_with(value.css(), &mut |reference| self.resolve(reference));
+        self.stack.pop();
+        // Being part of a cycle makes the property invalid, even if fallbacks allowed the
+        // substitution itself to succeed.
+        match substituted {
+            Some(css) if !self.invalid.contains(name) => {
+                self.computed.insert(
+                    name.to_owned(),
+                    VariableValue {
+                        css: css.clone(),
+                        references: HashSet::new(),
+                    },
+                );
+                Some(css)
+            }
+            _ => {
+                self.invalid.insert(name.to_owned());
+                None
+            }
+        }
+    }
+}
+
+fn substitute_with(css: &str, lookup: &mut dyn FnMut(&str) -> Option<String>) -> Option<String> {
+    let mut parser_input = ParserInput::new(css);
+    let mut input = Parser::new(&mut parser_input);
+    let mut substituted = String::new();
+    substitute_block(&mut input, &mut substituted, lookup).ok()?;
+    Some(substituted.trim().to_owned())
+}
+
+fn substitute_block<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    substituted: &mut String,
+    lookup: &mut dyn FnMut(&str) -> Option<String>,
+) -> Result<(), ParseError<'i, ()>> {
+    let mut unwritten_start = input.position();
+    loop {
+        let token_start = input.position();
+        let token = match input.next_including_whitespace_and_comments() {
+            Ok(token) => token.clone(),
+            Err(_) => break,
+        };
+        let closing = match token {
+            Token::Function(ref name) if name.eq_ignore_ascii_case("var") => {
+                substituted.push_str(input.slice(unwritten_start..token_start));
+                input.parse_nested_block(|input| {
+                    substitute_var_function(input, substituted, lookup)
+                })?;
+                unwritten_start = input.position();
+                continue;
+            }
+            Token::Function(_) | Token::ParenthesisBlock => ")",
+            Token::SquareBracketBlock => "]",
+            Token::CurlyBracketBlock => "}",
+            _ => continue,
+        };
+        // Write everything up to and including the opening of the block, then its (possibly
+        // substituted) contents, then close it.
+        substituted.push_str(input.slice_from(unwritten_start));
+        input.parse_nested_block(|input| substitute_block(input, substituted, lookup))?;
+        substituted.push_str(closing);
+        unwritten_start = input.position();
+    }
+    substituted.push_str(input.slice_from(unwritten_start));
+    Ok(())
+}
+
+fn substitute_var_function<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    substituted: &mut String,
+    lookup: &mut dyn FnMut(&str) -> Option<String>,
+) -> Result<(), ParseError<'i, ()>> {
+    let ident = input.expect_ident_cloned()?;
+    let name = parse_name(&ident).ok_or_else(|| input.new_custom_error(()))?;
+    if let Some(value) = lookup(name) {
+        substituted.push_str(&value);
+        // The fallback, if any, is unused.
+        while input.next_including_whitespace_and_comments().is_ok() {}
+        return Ok(());
+    }
+    input.expect_comma()?;
+    let mut fallback = String::new();
+    substitute_block(input, &mut fallback, lookup)?;
+    substituted.push_str(fallback.trim());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_value(css: &str) -> VariableValue {
+        let mut input = ParserInput::new(css);
+        VariableValue::parse(&mut Parser::new(&mut input)).expect("failed parsing variable value")
+    }
+
+    fn custom_declaration(name: &str, css: &str) -> CustomDeclaration {
+        CustomDeclaration {
+            name: name.to_owned(),
+            value: CustomDeclarationValue::Value(parse_value(css)),
+        }
+    }
+
+    fn computed_css<'a>(map: &'a CustomPropertiesMap, name: &str) -> Option<&'a str> {
+        map.get(name).map(|value| value.css())
+    }
+
+    #[test]
+    fn parses_declaration_values() {
+        let value = parse_value("  calc(var(--a) + var(--b, var(--c)))  ");
+        assert_eq!(value.css(), "calc(var(--a) + var(--b, var(--c)))");
+        let expected_references = ["a", "b", "c"].iter().map(|name| name.to_string());
+        assert_eq!(value.references, expected_references.collect());
+
+        let mut input = ParserInput::new("var(a)");
+        assert!(VariableValue::parse(&mut Parser::new(&mut input)).is_err());
+    }
+
+    #[test]
+    fn substitutes_references_and_fallbacks() {
+        let mut map = CustomPropertiesMap::new();
+        map.insert("gap".to_owned(), parse_value("8px"));
+        assert_eq!(
+            substitute("var(--gap) calc(var(--gap) * 2)", &map),
+            Some("8px calc(8px * 2)".to_owned())
+        );
+        assert_eq!(
+            substitute("var(--missing, var(--gap))", &map),
+            Some("8px".to_owned())
+        );
+        assert_eq!(substitute("var(--missing)", &map), None);
+    }
+
+    #[test]
+    fn inherits_and_overrides() {
+        let parent = compute_custom_properties(
+            &CustomPropertiesMap::new(),
+            [
+                custom_declaration("color", "red"),
+                custom_declaration("size", "10px"),
+            ]
+            .iter(),
+        );
+        let declarations = [
+            custom_declaration("size", "var(--color) 20px"),
+            CustomDeclaration {
+                name: "color".to_owned(),
+                value: CustomDeclarationValue::CssWideKeyword(CssWideKeywords::Initial),
+            },
+        ];
+        let child = compute_custom_properties(&parent, declarations.iter());
+        assert_eq!(computed_css(&child, "color"), None);
+        // `--color` is the guaranteed-invalid value, so `--size` is too.
+        assert_eq!(computed_css(&child, "size"), None);
+
+        let child = compute_custom_properties(&parent, [].iter());
+        assert_eq!(computed_css(&child, "color"), Some("red"));
+    }
+
+    #[test]
+    fn cycles_are_invalid() {
+        let declarations = [
+            custom_declaration("a", "var(--b)"),
+            custom_declaration("b", "var(--a, 1px)"),
+            custom_declaration("c", "var(--a, 2px)"),
+            custom_declaration("d", "var(--d)"),
+        ];
+        let computed = compute_custom_properties(&CustomPropertiesMap::new(), declarations.iter());
+        assert_eq!(computed_css(&computed, "a"), None);
+        // Despite its fallback, `--b` is part of the cycle.
+        assert_eq!(computed_css(&computed, "b"), None);
+        // `--c` merely references the cycle, so its fallback is used.
+        assert_eq!(computed_css(&computed, "c"), Some("2px"));
+        assert_eq!(computed_css(&computed, "d"), None);
+    }
+}
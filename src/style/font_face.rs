@@ -0,0 +1,248 @@
+//! `@font-face` rules, which make font files available to `font-family` matching under a family
+//! name of the author's choosing.
+//!
+//! https://drafts.csswg.org/css-fonts-4/#font-face-rule
+
+use std::path::{Path, PathBuf};
+
+use cssparser::{
+    AtRuleParser, CowRcStr, DeclarationListParser, DeclarationParser, ParseError, Parser,
+    SourceLocation, UnicodeRange,
+};
+
+use crate::style::values::computed::font::{FontStyle, SingleFontFamily};
+use crate::style::values::CSSFloat;
+use crate::style::StyleParseErrorKind;
+
+/// An @font-face rule.  Descriptors that are missing or invalid take their initial values, except
+/// for `font-family` and `src`, without which the rule doesn't define a usable face.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontFaceRule {
+    /// The family name the face is made available under.
+    pub family: Option<String>,
+    /// Where to load the face from, in order of preference.
+    pub sources: Vec<FontFaceSource>,
+    /// The range of weights the face is used for, as `(min, max)`.
+    pub weight: (CSSFloat, CSSFloat),
+    /// The style the face is used for.
+    pub style: FontStyle,
+    /// The characters the face may be used for.  Empty if it may be used for any character.
+    pub unicode_range: Vec<UnicodeRange>,
+    /// The location in the sheet where it was found.
+    pub source_location: SourceLocation,
+}
+
+impl FontFaceRule {
+    /// Parses the descriptor block of an @font-face rule.  Relative `url()` sources are resolved
+    /// against `base_dir`.
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+        base_dir: &Path,
+        source_location: SourceLocation,
+    ) -> FontFaceRule {
+        let rule = FontFaceRule {
+            family: None,
+            sources: Vec::new(),
+            weight: (400., 400.),
+            style: FontStyle::Normal,
+            unicode_range: Vec::new(),
+            source_location,
+        };
+        let mut descriptor_iter =
+            DeclarationListParser::new(input, FontFaceDescriptorParser { rule, base_dir });
+        for descriptor in &mut descriptor_iter {
+            if let Err(parse_err) = descriptor {
+                dbg!(parse_err);
+            }
+        }
+        descriptor_iter.parser.rule
+    }
+
+    /// Whether the face may be used to render `ch`.
+    pub fn covers(&self, ch: char) -> bool {
+        self.unicode_range.is_empty()
+            || self
+                .unicode_range
+                .iter()
+                .any(|range| (range.start..=range.end).contains(&(ch as u32)))
+    }
+}
+
+/// A source of an @font-face rule's `src` descriptor.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontFaceSource {
+    /// A font file on the local filesystem, given by `url()`.
+    Url(PathBuf),
+    /// A font installed on the system, given by `local()` with its full or PostScript name.
+    Local(String),
+}
+
+/// The `format()` hints of font files that can be loaded.  WOFF and WOFF2 files aren't decompressed,
+/// so sources hinted to be in those formats are skipped in favor of the next one.
+const SUPPORTED_FORMATS: [&str; 3] = ["truetype", "opentype", "collection"];
+
+/// The file extensions of compressed fonts, used to skip sources that have no `format()` hint.
+const UNSUPPORTED_EXTENSIONS: [&str; 2] = ["woff", "woff2"];
+
+struct FontFaceDescriptorParser<'a> {
+    rule: FontFaceRule,
+    base_dir: &'a Path,
+}
+
+impl<'a> FontFaceDescriptorParser<'a> {
+    /// Parses one source of the `src` descriptor.  Returns `None` for sources that are valid but
+    /// can't be loaded, such as a `url()` hinted to be in an unsupported format.
+    fn parse_source<'i, 't>(
+        &self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Option<FontFaceSource>, ParseError<'i, StyleParseErrorKind<'i>>> {
+        if input
+            .try_parse(|i| i.expect_function_matching("local"))
+            .is_ok()
+        {
+            return input.parse_nested_block(|input| match SingleFontFamily::parse(input)? {
+                SingleFontFamily::FamilyName(name) => Ok(Some(FontFaceSource::Local(name))),
+                SingleFontFamily::Generic(_) => Err(input.new_error_for_next_token()),
+            });
+        }
+        let url = input.expect_url_or_string()?.as_ref().to_owned();
+        let mut supported = Path::new(&url).extension().map_or(true, |ext| {
+            !UNSUPPORTED_EXTENSIONS.contains(&ext.to_string_lossy().to_ascii_lowercase().as_str())
+        });
+        // A `format()` hint takes precedence over the extension.
+        if input
+            .try_parse(|i| i.expect_function_matching("format"))
+            .is_ok()
+        {
+            supported = input.parse_nested_block(|input| {
+                let hints = input.parse_comma_separated(|i| {
+                    Ok(i.expect_string()?.as_ref().to_ascii_lowercase())
+                })?;
+                Ok(hints
+                    .iter()
+                    .any(|hint| SUPPORTED_FORMATS.contains(&hint.as_str())))
+            })?;
+        }
+        if !supported || url.contains("://") {
+            return Ok(None);
+        }
+        Ok(Some(FontFaceSource::Url(self.base_dir.join(url))))
+    }
+}
+
+/// Parses a `font-weight` descriptor value other than `auto`: a `normal`, `bold` or numeric
+/// weight.
+fn parse_absolute_weight<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<CSSFloat, ParseError<'i, StyleParseErrorKind<'i>>> {
+    let location = input.current_source_location();
+    if let Ok(weight) = input.try_parse(|i| i.expect_number()) {
+        if (1. ..=1000.).contains(&weight) {
+            return Ok(weight);
+        }
+        return Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+    }
+    try_match_ident_ignore_ascii_case! { input,
+        "normal" => Ok(400.),
+        "bold" => Ok(700.),
+    }
+}
+
+impl<'a, 'i> DeclarationParser<'i> for FontFaceDescriptorParser<'a> {
+    type Declaration = ();
+    type Error = StyleParseErrorKind<'i>;
+
+    fn parse_value<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<(), ParseError<'i, Self::Error>> {
+        match_ignore_ascii_case! { &*name,
+            "font-family" => {
+                match SingleFontFamily::parse(input)? {
+                    SingleFontFamily::FamilyName(family) => self.rule.family = Some(family),
+                    SingleFontFamily::Generic(_) => return Err(input.new_error_for_next_token()),
+                }
+            },
+            "src" => {
+                let sources = input.parse_comma_separated(|i| self.parse_source(i))?;
+                self.rule.sources = sources.into_iter().flatten().collect();
+            },
+            "font-weight" => {
+                let min = parse_absolute_weight(input)?;
+                let max = input.try_parse(parse_absolute_weight).unwrap_or(min);
+                self.rule.weight = (min.min(max), min.max(max));
+            },
+            "font-style" => self.rule.style = FontStyle::parse(input)?,
+            "unicode-range" => {
+                self.rule.unicode_range = input.parse_comma_separated(|i| Ok(UnicodeRange::parse(i)?))?;
+            },
+            _ => {
+                return Err(input.new_custom_error(StyleParseErrorKind::UnknownProperty(name)));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, 'i> AtRuleParser<'i> for FontFaceDescriptorParser<'a> {
+    type PreludeNoBlock = ();
+    type PreludeBlock = ();
+    type AtRule = ();
+    type Error = StyleParseErrorKind<'i>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cssparser::ParserInput;
+
+    fn parse(css: &str) -> FontFaceRule {
+        let mut input = ParserInput::new(css);
+        FontFaceRule::parse(
+            &mut Parser::new(&mut input),
+            Path::new("fonts"),
+            SourceLocation { line: 0, column: 0 },
+        )
+    }
+
+    #[test]
+    fn parses_descriptors() {
+        let rule = parse(
+            r#"font-family: "Brand Sans";
+               src: local(Brand Sans Bold), url(brand.eot) format("embedded-opentype"),
+                    url(brand.woff2) format("woff2"), url(brand.WOFF), url(brand.woff2),
+                    url(brand.ttf) format("woff", "truetype"), url(https://example.com/a.ttf);
+               font-weight: bold 900;
+               font-style: italic;
+               unicode-range: U+0-7F, U+4??"#,
+        );
+        assert_eq!(rule.family.as_deref(), Some("Brand Sans"));
+        assert_eq!(
+            rule.sources,
+            vec![
+                FontFaceSource::Local("Brand Sans Bold".to_owned()),
+                FontFaceSource::Url(Path::new("fonts").join("brand.ttf")),
+            ]
+        );
+        assert_eq!(rule.weight, (700., 900.));
+        assert_eq!(rule.style, FontStyle::Italic);
+        assert!(rule.covers('a'));
+        assert!(rule.covers('\u{4FF}'));
+        assert!(!rule.covers('é'));
+    }
+
+    #[test]
+    fn invalid_descriptors_keep_initial_values() {
+        let rule =
+            parse("font-family: serif; font-weight: 1001; font-style: slanted; src: url(a.otf)");
+        assert_eq!(rule.family, None);
+        assert_eq!(rule.weight, (400., 400.));
+        assert_eq!(rule.style, FontStyle::Normal);
+        assert_eq!(
+            rule.sources,
+            vec![FontFaceSource::Url(Path::new("fonts").join("a.otf"))]
+        );
+        assert!(rule.covers('é'));
+    }
+}
@@ -0,0 +1,866 @@
+/// Media queries, as used by `@media` rules.
+///
+/// Much of the structure here was taken from Servo: https://github.com/servo/servo
+/// Kosmonaut complies with Servo's license, the Mozilla Public License 2.0.
+///
+/// https://drafts.csswg.org/mediaqueries-4/
+use cssparser::{Delimiter, ParseError, Parser, Token};
+
+use crate::style::values::specified::{NoCalcLength, FONT_MEDIUM_PX};
+use crate::style::values::CSSFloat;
+use crate::style::StyleParseErrorKind;
+
+/// The environment media queries are evaluated against, and that some values (such as
+/// font-relative and viewport-percentage lengths) are computed against.
+///
+/// Kosmonaut always renders to a screen, so the interesting parts of the device are the size of
+/// the viewport and the scale factor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Device {
+    /// The width of the viewport, in CSS pixels.
+    viewport_width: CSSFloat,
+    /// The height of the viewport, in CSS pixels.
+    viewport_height: CSSFloat,
+    /// The number of device pixels per CSS pixel.
+    scale_factor: f32,
+    /// The color scheme preferred by the user.
+    color_scheme: ColorScheme,
+}
+
+impl Device {
+    /// Creates a new device from the inner window dimensions, which are given in device pixels.
+    pub fn new(inner_window_width: f32, inner_window_height: f32, scale_factor: f32) -> Self {
+        Device {
+            viewport_width: inner_window_width / scale_factor,
+            viewport_height: inner_window_height / scale_factor,
+            scale_factor,
+            color_scheme: ColorScheme::Light,
+        }
+    }
+
+    pub fn viewport_width(&self) -> CSSFloat {
+        self.viewport_width
+    }
+
+    pub fn viewport_height(&self) -> CSSFloat {
+        self.viewport_height
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    fn orientation(&self) -> Orientation {
+        // https://drafts.csswg.org/mediaqueries-4/#orientation
+        if self.viewport_height >= self.viewport_width {
+            Orientation::Portrait
+        } else {
+            Orientation::Landscape
+        }
+    }
+}
+
+/// A comma-separated list of media queries.  The list matches if any of its queries do, and an
+/// empty list always matches.
+///
+/// https://drafts.csswg.org/mediaqueries-4/#media-query-list
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaList {
+    pub media_queries: Vec<MediaQuery>,
+}
+
+impl MediaList {
+    /// Parses a media query list.  Per spec, a query that fails to parse doesn't invalidate the
+    /// whole list.  Instead, it is replaced by `not all`, which never matches.
+    ///
+    /// https://drafts.csswg.org/mediaqueries-4/#error-handling
+    pub fn parse(input: &mut Parser) -> Self {
+        let mut media_queries = vec![];
+        if input.is_exhausted() {
+            return MediaList { media_queries };
+        }
+        loop {
+            match input.parse_until_before(Delimiter::Comma, MediaQuery::parse) {
+                Ok(media_query) => media_queries.push(media_query),
+                Err(parse_err) => {
+                    dbg!(parse_err);
+                    media_queries.push(MediaQuery::never_matching());
+                }
+            }
+            match input.next() {
+                Ok(&Token::Comma) => {}
+                Ok(_) => unreachable!(),
+                Err(_) => break,
+            }
+        }
+        MediaList { media_queries }
+    }
+
+    pub fn evaluate(&self, device: &Device) -> bool {
+        self.media_queries.is_empty()
+            || self
+                .media_queries
+                .iter()
+                .any(|media_query| media_query.evaluate(device))
+    }
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#mq-prefix
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Qualifier {
+    /// Hide a media query from legacy user agents.
+    Only,
+    /// Negate a media query.
+    Not,
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#media-types
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MediaType {
+    All,
+    Screen,
+    Print,
+    /// Any other media type.  Unknown media types never match.
+    Unknown,
+}
+
+impl MediaType {
+    fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let location = input.current_source_location();
+        let ident = input.expect_ident()?;
+        Ok(match_ignore_ascii_case! { &ident,
+            "all" => MediaType::All,
+            "screen" => MediaType::Screen,
+            "print" => MediaType::Print,
+            // These can't be used as media types.
+            "only" | "not" | "and" | "or" => {
+                return Err(location.new_unexpected_token_error(Token::Ident(ident.clone())))
+            },
+            _ => MediaType::Unknown,
+        })
+    }
+
+    fn matches(self) -> bool {
+        match self {
+            MediaType::All | MediaType::Screen => true,
+            MediaType::Print | MediaType::Unknown => false,
+        }
+    }
+}
+
+/// A single media query, e.g. `screen and (min-width: 600px)`.
+///
+/// https://drafts.csswg.org/mediaqueries-4/#typedef-media-query
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaQuery {
+    pub qualifier: Option<Qualifier>,
+    pub media_type: MediaType,
+    pub condition: Option<MediaCondition>,
+}
+
+impl MediaQuery {
+    fn never_matching() -> Self {
+        MediaQuery {
+            qualifier: Some(Qualifier::Not),
+            media_type: MediaType::All,
+            condition: None,
+        }
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        // A media query can be a bare condition, e.g. `(min-width: 600px) and (max-width: 900px)`.
+        if let Ok(condition) =
+            input.try_parse(|input| -> Result<_, ParseError<StyleParseErrorKind>> {
+                let condition = MediaCondition::parse(input, true)?;
+                input.expect_exhausted()?;
+                Ok(condition)
+            })
+        {
+            return Ok(MediaQuery {
+                qualifier: None,
+                media_type: MediaType::All,
+                condition: Some(condition),
+            });
+        }
+
+        let qualifier = input
+            .try_parse(|input| -> Result<_, ParseError<StyleParseErrorKind>> {
+                let location = input.current_source_location();
+                let ident = input.expect_ident()?;
+                Ok(match_ignore_ascii_case! { &ident,
+                    "only" => Qualifier::Only,
+                    "not" => Qualifier::Not,
+                    _ => return Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError)),
+                })
+            })
+            .ok();
+        let media_type = MediaType::parse(input)?;
+        let condition = if input.try_parse(|i| i.expect_ident_matching("and")).is_ok() {
+            // Only `and` may follow a media type, so `or` isn't allowed at the top level here.
+            Some(MediaCondition::parse(input, false)?)
+        } else {
+            None
+        };
+        input.expect_exhausted()?;
+        Ok(MediaQuery {
+            qualifier,
+            media_type,
+            condition,
+        })
+    }
+
+    pub fn evaluate(&self, device: &Device) -> bool {
+        let matches = self.media_type.matches()
+            && match &self.condition {
+                Some(condition) => condition.evaluate(device),
+                None => true,
+            };
+        match self.qualifier {
+            Some(Qualifier::Not) => !matches,
+            Some(Qualifier::Only) | None => matches,
+        }
+    }
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#typedef-media-condition
+#[derive(Clone, Debug, PartialEq)]
+pub enum MediaCondition {
+    /// A single media feature, e.g. `(width >= 600px)`.
+    Feature(MediaFeatureExpression),
+    /// A negated condition, e.g. `not (orientation: portrait)`.
+    Not(Box<MediaCondition>),
+    /// A set of conditions joined by a single kind of operator.
+    Operation(Vec<MediaCondition>, Operator),
+    /// A condition wrapped in parentheses.
+    InParens(Box<MediaCondition>),
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#media-condition
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Operator {
+    And,
+    Or,
+}
+
+impl MediaCondition {
+    /// Parses a media condition.  `allow_or` is false when parsing a
+    /// `<media-condition-without-or>`, which is what follows `<media-type> and`.
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+        allow_or: bool,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        if input.try_parse(|i| i.expect_ident_matching("not")).is_ok() {
+            let inner_condition = MediaCondition::parse_in_parens(input)?;
+            return Ok(MediaCondition::Not(Box::new(inner_condition)));
+        }
+
+        let first_condition = MediaCondition::parse_in_parens(input)?;
+        let operator = match input.try_parse(Operator::parse) {
+            Ok(operator) => operator,
+            Err(_) => return Ok(first_condition),
+        };
+        if !allow_or && operator == Operator::Or {
+            return Err(input.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+        }
+
+        let mut conditions = vec![first_condition];
+        conditions.push(MediaCondition::parse_in_parens(input)?);
+        // Mixing `and` and `or` without parentheses isn't allowed, so every subsequent operator
+        // must match the first.
+        let operator_name = match operator {
+            Operator::And => "and",
+            Operator::Or => "or",
+        };
+        while input
+            .try_parse(|i| i.expect_ident_matching(operator_name))
+            .is_ok()
+        {
+            conditions.push(MediaCondition::parse_in_parens(input)?);
+        }
+        Ok(MediaCondition::Operation(conditions, operator))
+    }
+
+    fn parse_in_parens<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        input.expect_parenthesis_block()?;
+        input.parse_nested_block(|input| {
+            if let Ok(inner_condition) = input.try_parse(|input| MediaCondition::parse(input, true))
+            {
+                return Ok(MediaCondition::InParens(Box::new(inner_condition)));
+            }
+            MediaFeatureExpression::parse_in_parens_block(input).map(MediaCondition::Feature)
+        })
+    }
+
+    pub fn evaluate(&self, device: &Device) -> bool {
+        match self {
+            MediaCondition::Feature(expression) => expression.evaluate(device),
+            MediaCondition::Not(condition) => !condition.evaluate(device),
+            MediaCondition::InParens(condition) => condition.evaluate(device),
+            MediaCondition::Operation(conditions, Operator::And) => conditions
+                .iter()
+                .all(|condition| condition.evaluate(device)),
+            MediaCondition::Operation(conditions, Operator::Or) => conditions
+                .iter()
+                .any(|condition| condition.evaluate(device)),
+        }
+    }
+}
+
+impl Operator {
+    fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let location = input.current_source_location();
+        let ident = input.expect_ident()?;
+        Ok(match_ignore_ascii_case! { &ident,
+            "and" => Operator::And,
+            "or" => Operator::Or,
+            _ => return Err(location.new_unexpected_token_error(Token::Ident(ident.clone()))),
+        })
+    }
+}
+
+/// The media features Kosmonaut knows how to evaluate.
+///
+/// https://drafts.csswg.org/mediaqueries-4/#mq-features
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MediaFeature {
+    Width,
+    Height,
+    Orientation,
+    Resolution,
+    PrefersColorScheme,
+}
+
+impl MediaFeature {
+    /// Whether this is a "range" type feature, which can be used with `min-`/`max-` prefixes and
+    /// range syntax.  All other features are "discrete".
+    ///
+    /// https://drafts.csswg.org/mediaqueries-4/#mq-range-context
+    fn is_range(self) -> bool {
+        match self {
+            MediaFeature::Width | MediaFeature::Height | MediaFeature::Resolution => true,
+            MediaFeature::Orientation | MediaFeature::PrefersColorScheme => false,
+        }
+    }
+
+    fn parse_name(name: &str) -> Option<(Self, Option<RangeOperator>)> {
+        let (prefix, unprefixed_name) = if starts_with_ignore_ascii_case(name, "min-") {
+            (Some(RangeOperator::GreaterThanEqual), &name[4..])
+        } else if starts_with_ignore_ascii_case(name, "max-") {
+            (Some(RangeOperator::LessThanEqual), &name[4..])
+        } else {
+            (None, name)
+        };
+        let feature = match_ignore_ascii_case! { unprefixed_name,
+            "width" => MediaFeature::Width,
+            "height" => MediaFeature::Height,
+            "orientation" => MediaFeature::Orientation,
+            "resolution" => MediaFeature::Resolution,
+            "prefers-color-scheme" => MediaFeature::PrefersColorScheme,
+            _ => return None,
+        };
+        Some((feature, prefix))
+    }
+
+    fn parse_value<'i, 't>(
+        self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<MediaFeatureValue, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let value = match self {
+            MediaFeature::Width | MediaFeature::Height => {
+                input.try_parse(parse_length).map(MediaFeatureValue::Length)
+            }
+            MediaFeature::Resolution => input
+                .try_parse(parse_resolution)
+                .map(MediaFeatureValue::Resolution),
+            MediaFeature::Orientation => input.try_parse(|input| {
+                try_match_ident_ignore_ascii_case! { input,
+                    "portrait" => Ok(MediaFeatureValue::Orientation(Orientation::Portrait)),
+                    "landscape" => Ok(MediaFeatureValue::Orientation(Orientation::Landscape)),
+                }
+            }),
+            MediaFeature::PrefersColorScheme => input.try_parse(|input| {
+                try_match_ident_ignore_ascii_case! { input,
+                    "light" => Ok(MediaFeatureValue::ColorScheme(ColorScheme::Light)),
+                    "dark" => Ok(MediaFeatureValue::ColorScheme(ColorScheme::Dark)),
+                }
+            }),
+        };
+        value.map_err(|_: ParseError<StyleParseErrorKind>| {
+            input.new_custom_error(StyleParseErrorKind::MediaQueryExpectedFeatureValue)
+        })
+    }
+
+    fn value(self, device: &Device) -> MediaFeatureValue {
+        match self {
+            MediaFeature::Width => MediaFeatureValue::Length(device.viewport_width),
+            MediaFeature::Height => MediaFeatureValue::Length(device.viewport_height),
+            MediaFeature::Orientation => MediaFeatureValue::Orientation(device.orientation()),
+            MediaFeature::Resolution => MediaFeatureValue::Resolution(device.scale_factor),
+            MediaFeature::PrefersColorScheme => MediaFeatureValue::ColorScheme(device.color_scheme),
+        }
+    }
+}
+
+fn starts_with_ignore_ascii_case(string: &str, prefix: &str) -> bool {
+    string.len() >= prefix.len()
+        && string.as_bytes()[0..prefix.len()].eq_ignore_ascii_case(prefix.as_bytes())
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#orientation
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// https://drafts.csswg.org/mediaqueries-5/#prefers-color-scheme
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+/// The value of a media feature, either as specified in a query or as provided by the `Device`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MediaFeatureValue {
+    /// A length, in CSS pixels.
+    Length(CSSFloat),
+    /// A resolution, in dots per CSS pixel (`dppx`).
+    Resolution(CSSFloat),
+    Orientation(Orientation),
+    ColorScheme(ColorScheme),
+}
+
+impl MediaFeatureValue {
+    fn as_number(self) -> Option<CSSFloat> {
+        match self {
+            MediaFeatureValue::Length(number) | MediaFeatureValue::Resolution(number) => {
+                Some(number)
+            }
+            MediaFeatureValue::Orientation(_) | MediaFeatureValue::ColorScheme(_) => None,
+        }
+    }
+}
+
+fn parse_length<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<CSSFloat, ParseError<'i, StyleParseErrorKind<'i>>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+    match *token {
+        Token::Dimension {
+            value, ref unit, ..
+        } => match NoCalcLength::parse_dimension(value, unit) {
+            Ok(NoCalcLength::Absolute(absolute_length)) => Ok(absolute_length.to_px()),
+            // Relative units in media queries are based on the initial font.
+            // https://drafts.csswg.org/mediaqueries-4/#units
+            Ok(NoCalcLength::FontRelative(font_relative_length)) => {
+                let initial_font_size = FONT_MEDIUM_PX as CSSFloat;
+                Ok(font_relative_length.to_px(initial_font_size, initial_font_size, None))
+            }
+            // Media queries are parsed before they're matched against a device, so there's no
+            // viewport to resolve these against yet.
+            Ok(NoCalcLength::ViewportPercentage(_)) | Err(()) => {
+                Err(location.new_unexpected_token_error(token.clone()))
+            }
+        },
+        // Unitless zero is a valid length.
+        Token::Number { value, .. } => {
+            if value == 0. {
+                Ok(0.)
+            } else {
+                Err(location.new_unexpected_token_error(token.clone()))
+            }
+        }
+        _ => Err(location.new_unexpected_token_error(token.clone())),
+    }
+}
+
+/// Parses a `<resolution>` into `dppx`.
+///
+/// https://drafts.csswg.org/css-values-4/#resolution
+fn parse_resolution<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<CSSFloat, ParseError<'i, StyleParseErrorKind<'i>>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+    match *token {
+        Token::Dimension {
+            value, ref unit, ..
+        } => Ok(match_ignore_ascii_case! { unit,
+            "dppx" | "x" => value,
+            "dpi" => value / 96.,
+            "dpcm" => value * 2.54 / 96.,
+            _ => return Err(location.new_unexpected_token_error(token.clone())),
+        }),
+        _ => Err(location.new_unexpected_token_error(token.clone())),
+    }
+}
+
+/// https://drafts.csswg.org/mediaqueries-4/#typedef-mf-comparison
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RangeOperator {
+    LessThan,
+    LessThanEqual,
+    Equal,
+    GreaterThan,
+    GreaterThanEqual,
+}
+
+impl RangeOperator {
+    fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let location = input.current_source_location();
+        let operator = match *input.next()? {
+            Token::Delim('=') => return Ok(RangeOperator::Equal),
+            Token::Delim('<') => RangeOperator::LessThan,
+            Token::Delim('>') => RangeOperator::GreaterThan,
+            ref token => return Err(location.new_unexpected_token_error(token.clone())),
+        };
+        // `<=` and `>=` can't contain whitespace between the two delimiters.
+        let with_equal = input
+            .try_parse(|input| -> Result<_, ParseError<StyleParseErrorKind>> {
+                let location = input.current_source_location();
+                match *input.next_including_whitespace()? {
+                    Token::Delim('=') => Ok(()),
+                    ref token => Err(location.new_unexpected_token_error(token.clone())),
+                }
+            })
+            .is_ok();
+        Ok(match (operator, with_equal) {
+            (RangeOperator::LessThan, true) => RangeOperator::LessThanEqual,
+            (RangeOperator::GreaterThan, true) => RangeOperator::GreaterThanEqual,
+            (operator, _) => operator,
+        })
+    }
+
+    /// The operator to use when the feature name and value switch sides, e.g. turning
+    /// `600px < width` into `width > 600px`.
+    fn flip(self) -> Self {
+        match self {
+            RangeOperator::LessThan => RangeOperator::GreaterThan,
+            RangeOperator::LessThanEqual => RangeOperator::GreaterThanEqual,
+            RangeOperator::Equal => RangeOperator::Equal,
+            RangeOperator::GreaterThan => RangeOperator::LessThan,
+            RangeOperator::GreaterThanEqual => RangeOperator::LessThanEqual,
+        }
+    }
+
+    fn is_less_than(self) -> bool {
+        matches!(self, RangeOperator::LessThan | RangeOperator::LessThanEqual)
+    }
+
+    fn is_greater_than(self) -> bool {
+        matches!(
+            self,
+            RangeOperator::GreaterThan | RangeOperator::GreaterThanEqual
+        )
+    }
+
+    fn evaluate(self, actual: CSSFloat, query_value: CSSFloat) -> bool {
+        match self {
+            RangeOperator::LessThan => actual < query_value,
+            RangeOperator::LessThanEqual => actual <= query_value,
+            RangeOperator::Equal => (actual - query_value).abs() < CSSFloat::EPSILON,
+            RangeOperator::GreaterThan => actual > query_value,
+            RangeOperator::GreaterThanEqual => actual >= query_value,
+        }
+    }
+}
+
+/// A media feature, and how it is being tested.
+///
+/// https://drafts.csswg.org/mediaqueries-4/#typedef-media-feature
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaFeatureExpression {
+    pub feature: MediaFeature,
+    pub kind: MediaFeatureExpressionKind,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MediaFeatureExpressionKind {
+    /// The feature is evaluated in a boolean context, e.g. `(orientation)`.
+    Boolean,
+    /// The feature must equal the given value, e.g. `(orientation: portrait)` or `(width: 600px)`.
+    Plain(MediaFeatureValue),
+    /// The feature must satisfy every comparison, each written as `<feature> <op> <value>`.
+    /// `min-`/`max-` prefixed features are stored as a single comparison.
+    Range(Vec<(RangeOperator, MediaFeatureValue)>),
+}
+
+impl MediaFeatureExpression {
+    /// Parses the contents of a parenthesized media feature, e.g. `width >= 600px`.
+    fn parse_in_parens_block<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        if let Ok(expression) = input.try_parse(MediaFeatureExpression::parse_name_first) {
+            return Ok(expression);
+        }
+        MediaFeatureExpression::parse_value_first(input)
+    }
+
+    /// Parses `<mf-plain>`, `<mf-boolean>`, and `<mf-name> <mf-comparison> <mf-value>`.
+    fn parse_name_first<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let (feature, prefix) = parse_feature_name(input)?;
+
+        if input.is_exhausted() {
+            if prefix.is_some() {
+                return Err(
+                    input.new_custom_error(StyleParseErrorKind::RangedExpressionWithNoValue)
+                );
+            }
+            return Ok(MediaFeatureExpression {
+                feature,
+                kind: MediaFeatureExpressionKind::Boolean,
+            });
+        }
+
+        if input.try_parse(|i| i.expect_colon()).is_ok() {
+            let value = feature.parse_value(input)?;
+            input.expect_exhausted()?;
+            let kind = match prefix {
+                Some(operator) => MediaFeatureExpressionKind::Range(vec![(operator, value)]),
+                None => MediaFeatureExpressionKind::Plain(value),
+            };
+            return Ok(MediaFeatureExpression { feature, kind });
+        }
+
+        // Range syntax can't be combined with prefixes, and only applies to range features.
+        let operator = RangeOperator::parse(input)?;
+        if prefix.is_some() || !feature.is_range() {
+            return Err(input.new_custom_error(StyleParseErrorKind::MediaQueryUnexpectedOperator));
+        }
+        let value = feature.parse_value(input)?;
+        input.expect_exhausted()?;
+        Ok(MediaFeatureExpression {
+            feature,
+            kind: MediaFeatureExpressionKind::Range(vec![(operator, value)]),
+        })
+    }
+
+    /// Parses `<mf-value> <mf-comparison> <mf-name>` and the interval forms,
+    /// `<mf-value> <mf-lt> <mf-name> <mf-lt> <mf-value>` and the `<mf-gt>` equivalent.
+    fn parse_value_first<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        // Lengths and resolutions are the only values range features use.  We don't know which
+        // feature we're dealing with until after its value, but a value's type tells us which
+        // features it could apply to.
+        let first_value = input
+            .try_parse(parse_length)
+            .map(MediaFeatureValue::Length)
+            .or_else(|_| {
+                input
+                    .try_parse(parse_resolution)
+                    .map(MediaFeatureValue::Resolution)
+            })
+            .map_err(|_: ParseError<StyleParseErrorKind>| {
+                input.new_custom_error(StyleParseErrorKind::MediaQueryExpectedFeatureValue)
+            })?;
+        let operator = RangeOperator::parse(input)?;
+        let (feature, prefix) = parse_feature_name(input)?;
+        if prefix.is_some() || !feature.is_range() {
+            return Err(input.new_custom_error(StyleParseErrorKind::MediaQueryUnexpectedOperator));
+        }
+        if !value_matches_feature(first_value, feature) {
+            return Err(input.new_custom_error(StyleParseErrorKind::MediaQueryExpectedFeatureValue));
+        }
+
+        let mut comparisons = vec![(operator.flip(), first_value)];
+        if !input.is_exhausted() {
+            // This is the interval form, which requires both operators to point the same way.
+            let second_operator = RangeOperator::parse(input)?;
+            let same_direction = (operator.is_less_than() && second_operator.is_less_than())
+                || (operator.is_greater_than() && second_operator.is_greater_than());
+            if !same_direction {
+                return Err(
+                    input.new_custom_error(StyleParseErrorKind::MediaQueryUnexpectedOperator)
+                );
+            }
+            let second_value = feature.parse_value(input)?;
+            comparisons.push((second_operator, second_value));
+        }
+        input.expect_exhausted()?;
+        Ok(MediaFeatureExpression {
+            feature,
+            kind: MediaFeatureExpressionKind::Range(comparisons),
+        })
+    }
+
+    pub fn evaluate(&self, device: &Device) -> bool {
+        let actual = self.feature.value(device);
+        match &self.kind {
+            MediaFeatureExpressionKind::Boolean => match actual.as_number() {
+                Some(number) => number != 0.,
+                None => true,
+            },
+            MediaFeatureExpressionKind::Plain(query_value) => {
+                match (actual.as_number(), query_value.as_number()) {
+                    (Some(actual), Some(query_value)) => {
+                        RangeOperator::Equal.evaluate(actual, query_value)
+                    }
+                    _ => actual == *query_value,
+                }
+            }
+            MediaFeatureExpressionKind::Range(comparisons) => {
+                comparisons.iter().all(|(operator, query_value)| {
+                    match (actual.as_number(), query_value.as_number()) {
+                        (Some(actual), Some(query_value)) => operator.evaluate(actual, query_value),
+                        _ => false,
+                    }
+                })
+            }
+        }
+    }
+}
+
+fn parse_feature_name<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<(MediaFeature, Option<RangeOperator>), ParseError<'i, StyleParseErrorKind<'i>>> {
+    let location = input.current_source_location();
+    let name = input.expect_ident()?.clone();
+    match MediaFeature::parse_name(&name) {
+        Some((feature, Some(_))) if !feature.is_range() => {
+            Err(location.new_custom_error(StyleParseErrorKind::MediaQueryExpectedFeatureName(name)))
+        }
+        Some(feature_and_prefix) => Ok(feature_and_prefix),
+        None => {
+            Err(location.new_custom_error(StyleParseErrorKind::MediaQueryExpectedFeatureName(name)))
+        }
+    }
+}
+
+fn value_matches_feature(value: MediaFeatureValue, feature: MediaFeature) -> bool {
+    matches!(
+        (value, feature),
+        (MediaFeatureValue::Length(_), MediaFeature::Width)
+            | (MediaFeatureValue::Length(_), MediaFeature::Height)
+            | (MediaFeatureValue::Resolution(_), MediaFeature::Resolution)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cssparser::ParserInput;
+
+    fn media_list(css: &str) -> MediaList {
+        let mut input = ParserInput::new(css);
+        MediaList::parse(&mut Parser::new(&mut input))
+    }
+
+    fn matches(css: &str, device: &Device) -> bool {
+        media_list(css).evaluate(device)
+    }
+
+    #[test]
+    fn media_types() {
+        let device = Device::new(1920., 1080., 1.);
+        assert!(matches("", &device));
+        assert!(matches("all", &device));
+        assert!(matches("screen", &device));
+        assert!(matches("only screen", &device));
+        assert!(!matches("print", &device));
+        assert!(!matches("tv", &device));
+        assert!(matches("not print", &device));
+        assert!(matches("print, screen", &device));
+    }
+
+    #[test]
+    fn min_max_prefixes() {
+        let device = Device::new(800., 600., 1.);
+        assert!(matches("(min-width: 800px)", &device));
+        assert!(!matches("(min-width: 801px)", &device));
+        assert!(!matches("screen and (max-width: 799px)", &device));
+        assert!(matches("screen and (max-height: 600px)", &device));
+        assert!(matches("(width: 800px)", &device));
+        assert!(!matches("(height: 800px)", &device));
+        // `min-`/`max-` require a value.
+        assert_eq!(
+            media_list("(min-width)").media_queries[0],
+            MediaQuery::never_matching()
+        );
+    }
+
+    #[test]
+    fn range_syntax() {
+        let device = Device::new(800., 600., 1.);
+        assert!(matches("(width >= 800px)", &device));
+        assert!(!matches("(width > 800px)", &device));
+        assert!(matches("(width<900px)", &device));
+        assert!(matches("(700px < width)", &device));
+        assert!(!matches("(800px < width)", &device));
+        assert!(matches("(400px <= width <= 800px)", &device));
+        assert!(!matches("(400px <= height < 600px)", &device));
+        assert!(matches("(1000px > width > 600px)", &device));
+        // Operators in an interval must point the same direction.
+        assert!(!matches("(400px < width > 600px)", &device));
+        // `<=` can't contain whitespace.
+        assert!(!matches("(width < = 900px)", &device));
+        // Range syntax only applies to range features.
+        assert!(!matches("(orientation > landscape)", &device));
+    }
+
+    #[test]
+    fn discrete_features() {
+        let landscape = Device::new(800., 600., 1.);
+        let portrait = Device::new(600., 800., 1.);
+        assert!(matches("(orientation: landscape)", &landscape));
+        assert!(matches("(orientation: portrait)", &portrait));
+        assert!(matches("(orientation)", &portrait));
+        assert!(!matches("(min-orientation: portrait)", &portrait));
+        assert!(matches("(prefers-color-scheme: light)", &portrait));
+        assert!(!matches("(prefers-color-scheme: dark)", &portrait));
+    }
+
+    #[test]
+    fn resolution_and_scale_factor() {
+        let device = Device::new(1600., 1200., 2.);
+        // Width and height are in CSS pixels, not device pixels.
+        assert!(matches("(width: 800px)", &device));
+        assert!(matches("(resolution: 2dppx)", &device));
+        assert!(matches("(min-resolution: 192dpi)", &device));
+        assert!(matches("(resolution > 1x)", &device));
+        assert!(!matches("(max-resolution: 1.5dppx)", &device));
+    }
+
+    #[test]
+    fn conditions() {
+        let device = Device::new(800., 600., 1.);
+        assert!(matches(
+            "(min-width: 600px) and (orientation: landscape)",
+            &device
+        ));
+        assert!(matches(
+            "(max-width: 600px) or (min-height: 600px)",
+            &device
+        ));
+        assert!(matches("not (max-width: 600px)", &device));
+        assert!(matches(
+            "screen and ((max-width: 600px) or (min-height: 600px))",
+            &device
+        ));
+        // `or` can't follow a media type, and `and`/`or` can't be mixed without parentheses.
+        assert!(!matches(
+            "screen and (min-width: 0px) or (min-height: 0px)",
+            &device
+        ));
+        assert!(!matches(
+            "(min-width: 0px) and (min-height: 0px) or (color)",
+            &device
+        ));
+        // An unknown feature only invalidates the query it's in.
+        assert!(matches("(unknown-feature: 1), (width: 800px)", &device));
+    }
+}
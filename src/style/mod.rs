@@ -1,57 +1,124 @@
 use std::convert::From;
+use std::path::PathBuf;
 
 use cssparser::{
-    AtRuleParser, CowRcStr, ParseError, Parser, QualifiedRuleParser, SourceLocation, Token,
+    AtRuleParser, AtRuleType, CowRcStr, ParseError, Parser, ParserInput, QualifiedRuleParser,
+    RuleListParser, SourceLocation, Token,
 };
 use selectors::parser::SelectorParseErrorKind;
 
 use crate::dom::tree::{NodeData, NodeRef};
-use crate::style::properties::{parse_property_declaration_list, PropertyDeclarationBlock};
-use crate::style::select::Selectors;
-use crate::style::stylesheet::{apply_stylesheet_to_node, Stylesheet};
+use crate::gfx::font::FontHandle;
+use crate::style::font_face::FontFaceRule;
+use crate::style::media_queries::{Device, MediaList};
+use crate::style::properties::{
+    parse_property_declaration_list, ContextualPropertyDeclaration, PropertyDeclarationBlock,
+};
+use crate::style::select::{Selectors, Specificity};
+use crate::style::stylesheet::{
+    apply_embedded_stylesheet_to_node, apply_stylesheet_to_node, collect_font_face_rules,
+    load_imported_stylesheet, DocumentStylesheet, ImportContext, Stylesheet,
+};
 use crate::style::values::computed::compute_values;
 
 #[macro_use]
 mod macros;
 
+pub mod custom_properties;
+pub mod font_face;
+pub mod media_queries;
 pub mod properties;
 pub mod select;
 pub mod stylesheet;
 pub mod test_utils;
 pub mod values;
 
+/// All the stylesheets that apply to a document.  These are kept around after styles are first
+/// applied, since a change in the environment (e.g. resizing the window) can change which rules
+/// match, requiring styles to be applied again.
+#[derive(Clone, Debug, Default)]
+pub struct StylesheetSet {
+    pub ua_sheets: Vec<Stylesheet>,
+    pub user_sheets: Vec<Stylesheet>,
+    pub author_sheets: Vec<Stylesheet>,
+    /// Sheets found within the document itself, in tree order.  These are cascaded as author
+    /// sheets, after `author_sheets`.
+    pub document_sheets: Vec<DocumentStylesheet>,
+}
+
+impl StylesheetSet {
+    /// The @font-face rules of all the sheets that apply to `device`, in cascade order.
+    pub fn font_face_rules(&self, device: &Device) -> Vec<&FontFaceRule> {
+        let mut font_face_rules = Vec::new();
+        self.ua_sheets
+            .iter()
+            .chain(&self.user_sheets)
+            .chain(&self.author_sheets)
+            .chain(
+                self.document_sheets
+                    .iter()
+                    .map(|document_sheet| match document_sheet {
+                        DocumentStylesheet::Embedded(stylesheet)
+                        | DocumentStylesheet::Linked(stylesheet) => stylesheet,
+                    }),
+            )
+            .for_each(|stylesheet| {
+                collect_font_face_rules(stylesheet.rules(), device, &mut font_face_rules)
+            });
+        font_face_rules
+    }
+}
+
+/// Applies the given stylesheets to the DOM, evaluating any media queries against `device`.  Fonts
+/// that font-relative lengths depend on are loaded through `font_handle`.
+///
+/// This can be called multiple times for the same DOM, e.g. when the device changes.  Any
+/// previously applied styles are discarded first.
 pub fn apply_styles(
     dom: NodeRef,
-    ua_sheets: &[Stylesheet],
-    user_sheets: &[Stylesheet],
-    author_sheets: &[Stylesheet],
+    sheets: &StylesheetSet,
+    device: &Device,
+    font_handle: &FontHandle,
 ) {
+    dom.inclusive_descendants()
+        .for_each(|node| node.contextual_decls_mut().clear());
+
     // https://www.w3.org/TR/css-cascade-3/#value-stages
     // The final value of a CSS property for a given element or box is the result of a multi-step calculation:
 
     // 1. First, all the declared values applied to an element are collected, for each property on each element. There may be zero or many declared values applied to the element.
-    // TODO: Need to collect embedded styles (<style></style>)
-    ua_sheets.iter().for_each(|stylesheet| {
-        apply_stylesheet_to_node(&dom, stylesheet, CascadeOrigin::UserAgent);
+    sheets.ua_sheets.iter().for_each(|stylesheet| {
+        apply_stylesheet_to_node(&dom, stylesheet, CascadeOrigin::UserAgent, device);
     });
 
-    user_sheets.iter().for_each(|stylesheet| {
-        apply_stylesheet_to_node(&dom, stylesheet, CascadeOrigin::User);
+    sheets.user_sheets.iter().for_each(|stylesheet| {
+        apply_stylesheet_to_node(&dom, stylesheet, CascadeOrigin::User, device);
     });
 
-    author_sheets.iter().for_each(|stylesheet| {
-        apply_stylesheet_to_node(&dom, stylesheet, CascadeOrigin::Author);
+    sheets.author_sheets.iter().for_each(|stylesheet| {
+        apply_stylesheet_to_node(&dom, stylesheet, CascadeOrigin::Author, device);
     });
 
-    // collect all inline styles
+    sheets
+        .document_sheets
+        .iter()
+        .for_each(|document_sheet| match document_sheet {
+            DocumentStylesheet::Embedded(stylesheet) => {
+                apply_embedded_stylesheet_to_node(&dom, stylesheet, device);
+            }
+            DocumentStylesheet::Linked(stylesheet) => {
+                apply_stylesheet_to_node(&dom, stylesheet, CascadeOrigin::Author, device);
+            }
+        });
+
+    // Collect all inline styles.  These are added last, since declarations from style attributes
+    // are placed after any stylesheets in the cascade.
     dom.inclusive_descendants().for_each(|node| {
         if let NodeData::Element(element_data) = node.data() {
             match element_data.attributes.try_borrow() {
                 Ok(attrs) => {
                     if let Some(style_str) = attrs.get("style") {
-                        // TODO: Parse inline style and apply it to node.  Make sure these styles have a greater specificity than anything else
-                        // cssparser::ParserInput::new(style_str)
-                        dbg!("found inline style but did not collect it: {:?}", style_str);
+                        apply_inline_style_to_node(&node, style_str);
                     }
                 }
                 Err(_e) => {
@@ -60,7 +127,35 @@ pub fn apply_styles(
             }
         }
     });
-    cascade_and_compute(&dom);
+    cascade_and_compute(&dom, device, font_handle);
+}
+
+/// Parses the contents of a `style` attribute as a declaration list and adds the resulting
+/// declarations to the given node.
+///
+/// https://www.w3.org/TR/css-style-attr/#interpret
+fn apply_inline_style_to_node(node: &NodeRef, style_str: &str) {
+    let mut input = ParserInput::new(style_str);
+    let mut parser = Parser::new(&mut input);
+    let block = parse_property_declaration_list(&mut parser);
+    block
+        .declarations()
+        .iter()
+        .enumerate()
+        .for_each(|(index, decl)| {
+            node.add_decl(ContextualPropertyDeclaration {
+                inner_decl: decl.clone(),
+                important: block
+                    .declarations_importance()
+                    .get(index)
+                    .expect("important bit not set for declaration"),
+                origin: CssOrigin::Inline,
+                source_location: None,
+                // Inline declarations don't come from a selector.  The cascade ranks them above
+                // any selector specificity by virtue of their origin instead.
+                specificity: Specificity::new(0),
+            });
+        });
 }
 
 /// Performs steps 2-4 of https://www.w3.org/TR/2018/CR-css-cascade-3-20180828/#value-stages.
@@ -70,12 +165,12 @@ pub fn apply_styles(
 /// 2) Cascading — https://www.w3.org/TR/2018/CR-css-cascade-3-20180828/#cascade
 /// 3) Defaulting to specified values — https://www.w3.org/TR/2018/CR-css-cascade-3-20180828/#specified-value
 /// 4) Resolving specified values to computed values — https://www.w3.org/TR/2018/CR-css-cascade-3-20180828/#computed
-pub fn cascade_and_compute(start_node: &NodeRef) {
+pub fn cascade_and_compute(start_node: &NodeRef, device: &Device, font_handle: &FontHandle) {
     start_node.inclusive_descendants().for_each(|node| {
         // Step 2
         node.contextual_decls_mut().cascade_sort();
         // Step 3 and 4
-        compute_values(node);
+        compute_values(node, device, font_handle);
     });
 }
 
@@ -83,6 +178,9 @@ pub fn cascade_and_compute(start_node: &NodeRef) {
 #[derive(Clone, Debug)]
 pub enum CssRule {
     Style(StyleRule),
+    Media(MediaRule),
+    Import(ImportRule),
+    FontFace(FontFaceRule),
     None,
 }
 
@@ -97,6 +195,36 @@ pub struct StyleRule {
     pub source_location: SourceLocation,
 }
 
+/// A @media rule, whose nested rules only apply when its media query list matches the device.
+///
+/// https://drafts.csswg.org/css-conditional-3/#at-media
+#[derive(Clone, Debug)]
+pub struct MediaRule {
+    /// The media queries that determine whether the nested rules apply.
+    pub media_queries: MediaList,
+    /// The rules nested within the @media block.
+    pub rules: Vec<CssRule>,
+    /// The location in the sheet where it was found.
+    pub source_location: SourceLocation,
+}
+
+/// An @import rule, which pulls the rules of another stylesheet into the importing one at the
+/// position of the @import.
+///
+/// https://drafts.csswg.org/css-cascade-3/#at-import
+#[derive(Clone, Debug)]
+pub struct ImportRule {
+    /// The URL of the imported sheet, as written in the rule.
+    pub url: String,
+    /// The media queries that must match the device for the imported sheet to apply.
+    pub media_queries: MediaList,
+    /// The imported sheet, or `None` if it could not be loaded (e.g. it doesn't exist, fails to
+    /// parse, or would create an import cycle).
+    pub stylesheet: Option<Stylesheet>,
+    /// The location in the sheet where it was found.
+    pub source_location: SourceLocation,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum CssOrigin {
     /// CSS found within `style` attribute on node
@@ -111,11 +239,13 @@ pub enum CssOrigin {
 }
 
 /// https://www.w3.org/TR/2018/CR-css-cascade-3-20180828/#cascading-origins
-#[derive(Clone, Debug, PartialEq)]
+///
+/// Ordered from lowest to highest precedence amongst normal (non-important) declarations.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub enum CascadeOrigin {
-    Author,
-    User,
     UserAgent,
+    User,
+    Author,
 }
 
 #[derive(Clone, Debug)]
@@ -139,19 +269,110 @@ pub struct Color {
 }
 
 /// Parser for top-level CSS rules.
-pub struct TopLevelRuleParser {}
+pub struct TopLevelRuleParser {
+    /// Used to find and load the sheets referenced by @import rules.
+    import_context: ImportContext,
+    /// How far into the stylesheet the parser is, used to reject misplaced @import rules.
+    state: State,
+}
+
+impl TopLevelRuleParser {
+    pub fn new(import_context: ImportContext) -> Self {
+        TopLevelRuleParser {
+            import_context,
+            state: State::Start,
+        }
+    }
+
+    fn nested_rule_parser(&self) -> NestedRuleParser {
+        NestedRuleParser {
+            base_dir: self.import_context.base_dir().to_path_buf(),
+        }
+    }
+}
+
+/// The kinds of rules a `TopLevelRuleParser` has seen so far.  Some rules, like @import, are only
+/// valid before certain others.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum State {
+    Start,
+    Imports,
+    Body,
+}
 
-// TODO: Support @ rules
-pub enum AtRuleNonBlockPrelude {}
+pub enum AtRuleNonBlockPrelude {
+    /// An @import rule prelude, with the URL of the imported sheet and its media query list.
+    Import(String, MediaList),
+}
 
-pub enum AtRuleBlockPrelude {}
+pub enum AtRuleBlockPrelude {
+    /// A @media rule prelude, with its media query list.
+    Media(MediaList),
+    /// An @font-face rule prelude, which is empty.
+    FontFace,
+}
 
-/// Kosmonaut currently does not support @rules, so fall back to the default @rule error impl.
 impl<'i> AtRuleParser<'i> for TopLevelRuleParser {
     type PreludeNoBlock = AtRuleNonBlockPrelude;
     type PreludeBlock = AtRuleBlockPrelude;
     type AtRule = CssRule;
     type Error = StyleParseErrorKind<'i>;
+
+    #[inline]
+    fn parse_prelude<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<AtRuleType<AtRuleNonBlockPrelude, AtRuleBlockPrelude>, ParseError<'i, Self::Error>>
+    {
+        if name.eq_ignore_ascii_case("import") {
+            if self.state > State::Imports {
+                return Err(input.new_custom_error(StyleParseErrorKind::UnexpectedImportRule));
+            }
+            let url = input.expect_url_or_string()?.as_ref().to_owned();
+            let media_queries = MediaList::parse(input);
+            return Ok(AtRuleType::WithoutBlock(AtRuleNonBlockPrelude::Import(
+                url,
+                media_queries,
+            )));
+        }
+        AtRuleParser::parse_prelude(&mut self.nested_rule_parser(), name, input)
+    }
+
+    #[inline]
+    fn parse_block<'t>(
+        &mut self,
+        prelude: AtRuleBlockPrelude,
+        source_location: SourceLocation,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<CssRule, ParseError<'i, Self::Error>> {
+        let rule = AtRuleParser::parse_block(
+            &mut self.nested_rule_parser(),
+            prelude,
+            source_location,
+            input,
+        )?;
+        self.state = State::Body;
+        Ok(rule)
+    }
+
+    fn rule_without_block(
+        &mut self,
+        prelude: AtRuleNonBlockPrelude,
+        source_location: SourceLocation,
+    ) -> CssRule {
+        match prelude {
+            AtRuleNonBlockPrelude::Import(url, media_queries) => {
+                self.state = State::Imports;
+                CssRule::Import(ImportRule {
+                    stylesheet: load_imported_stylesheet(&url, &self.import_context),
+                    url,
+                    media_queries,
+                    source_location,
+                })
+            }
+        }
+    }
 }
 
 impl<'i> QualifiedRuleParser<'i> for TopLevelRuleParser {
@@ -159,6 +380,113 @@ impl<'i> QualifiedRuleParser<'i> for TopLevelRuleParser {
     type QualifiedRule = CssRule;
     type Error = StyleParseErrorKind<'i>;
 
+    #[inline]
+    fn parse_prelude<'t>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
+        QualifiedRuleParser::parse_prelude(&mut self.nested_rule_parser(), input)
+    }
+
+    #[inline]
+    fn parse_block<'t>(
+        &mut self,
+        selectors: Self::Prelude,
+        source_location: SourceLocation,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<CssRule, ParseError<'i, Self::Error>> {
+        let rule = QualifiedRuleParser::parse_block(
+            &mut self.nested_rule_parser(),
+            selectors,
+            source_location,
+            input,
+        )?;
+        self.state = State::Body;
+        Ok(rule)
+    }
+}
+
+/// Parser for rules nested within the block of a conditional group rule, such as @media.  This
+/// accepts everything the `TopLevelRuleParser` does, except for rules that are only valid at the
+/// top-level of a stylesheet.
+pub struct NestedRuleParser {
+    /// The directory relative URLs within the rules, e.g. those of @font-face sources, are
+    /// resolved against.
+    base_dir: PathBuf,
+}
+
+impl NestedRuleParser {
+    /// Parses the rules in the block of a conditional group rule.  Invalid rules, and rules that
+    /// aren't allowed there, are skipped without affecting the rules around them.
+    fn parse_nested_rules(&self, input: &mut Parser) -> Vec<CssRule> {
+        let rule_parser = RuleListParser::new_for_nested_rule(
+            input,
+            NestedRuleParser {
+                base_dir: self.base_dir.clone(),
+            },
+        );
+        let mut rules = Vec::new();
+        for rule in rule_parser {
+            match rule {
+                Ok(rule) => rules.push(rule),
+                Err((parse_err, _)) => {
+                    dbg!(parse_err);
+                }
+            }
+        }
+        rules
+    }
+}
+
+impl<'i> AtRuleParser<'i> for NestedRuleParser {
+    type PreludeNoBlock = AtRuleNonBlockPrelude;
+    type PreludeBlock = AtRuleBlockPrelude;
+    type AtRule = CssRule;
+    type Error = StyleParseErrorKind<'i>;
+
+    fn parse_prelude<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<AtRuleType<AtRuleNonBlockPrelude, AtRuleBlockPrelude>, ParseError<'i, Self::Error>>
+    {
+        match_ignore_ascii_case! { &*name,
+            "media" => Ok(AtRuleType::WithBlock(AtRuleBlockPrelude::Media(MediaList::parse(input)))),
+            "font-face" => {
+                input.expect_exhausted()?;
+                Ok(AtRuleType::WithBlock(AtRuleBlockPrelude::FontFace))
+            },
+            "import" => Err(input.new_custom_error(StyleParseErrorKind::UnexpectedImportRule)),
+            _ => Err(input.new_custom_error(StyleParseErrorKind::UnsupportedAtRule(name.clone()))),
+        }
+    }
+
+    fn parse_block<'t>(
+        &mut self,
+        prelude: AtRuleBlockPrelude,
+        source_location: SourceLocation,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<CssRule, ParseError<'i, Self::Error>> {
+        match prelude {
+            AtRuleBlockPrelude::Media(media_queries) => Ok(CssRule::Media(MediaRule {
+                media_queries,
+                rules: self.parse_nested_rules(input),
+                source_location,
+            })),
+            AtRuleBlockPrelude::FontFace => Ok(CssRule::FontFace(FontFaceRule::parse(
+                input,
+                &self.base_dir,
+                source_location,
+            ))),
+        }
+    }
+}
+
+impl<'i> QualifiedRuleParser<'i> for NestedRuleParser {
+    type Prelude = Selectors;
+    type QualifiedRule = CssRule;
+    type Error = StyleParseErrorKind<'i>;
+
     #[inline]
     fn parse_prelude<'t>(
         &mut self,
@@ -268,6 +596,7 @@ pub enum ValueParseErrorKind<'i> {
 
 /// Value computations common to all CSS properties.
 /// https://www.w3.org/TR/css3-values/#common-keywords
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum CssWideKeywords {
     /// Represents the value specified as the property’s initial value.
     Initial,
@@ -275,4 +604,21 @@ pub enum CssWideKeywords {
     Inherit,
     /// Acts as either inherit or initial, depending on whether the property is inherited or not.
     Unset,
+    /// Rolls the cascaded value back to the one the property would have had if no declarations
+    /// in the current cascade origin existed.
+    /// https://www.w3.org/TR/css-cascade-4/#default
+    Revert,
+}
+
+impl CssWideKeywords {
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        try_match_ident_ignore_ascii_case! { input,
+            "initial" => Ok(CssWideKeywords::Initial),
+            "inherit" => Ok(CssWideKeywords::Inherit),
+            "unset" => Ok(CssWideKeywords::Unset),
+            "revert" => Ok(CssWideKeywords::Revert),
+        }
+    }
 }
@@ -1,8 +1,12 @@
-use crate::style::properties::{ContextualPropertyDeclaration, PropertyDeclaration};
+use crate::style::custom_properties::{self, Name};
+use crate::style::properties::PropertyDeclaration;
 use crate::style::values::computed::{
-    ComputeContext, ComputedValuesBuilder, LineStyle, ValueDefault,
+    ComputeContext, ComputedValuesBuilder, Direction, LineStyle, ValueDefault, WritingMode,
 };
 use crate::style::values::{computed, specified};
+use crate::style::CssWideKeywords;
+use crate::{LogicalDirection, Side};
+use strum::IntoEnumIterator;
 
 /// Representation of a CSS property, that is, either a longhand, a
 /// shorthand, or a custom property.
@@ -14,57 +18,134 @@ pub enum PropertyId {
     Longhand(LonghandId),
     /// A shorthand property.
     Shorthand(ShorthandId),
-    // TODO: Support custom properties
-    //    /// A custom property.
-    //    Custom(crate::custom_properties::Name),
+    /// A custom property.
+    Custom(Name),
 }
 
 impl PropertyId {
     pub fn parse(prop_name: &str) -> Option<PropertyId> {
+        if let Some(name) = custom_properties::parse_name(prop_name) {
+            return Some(PropertyId::Custom(name.to_owned()));
+        }
         let id = match prop_name {
             // Longhands
             "background-color" => PropertyId::Longhand(LonghandId::BackgroundColor),
+            "border-block-end-color" => PropertyId::Longhand(LonghandId::BorderBlockEndColor),
+            "border-block-start-color" => PropertyId::Longhand(LonghandId::BorderBlockStartColor),
+            "border-inline-end-color" => PropertyId::Longhand(LonghandId::BorderInlineEndColor),
+            "border-inline-start-color" => PropertyId::Longhand(LonghandId::BorderInlineStartColor),
             "border-bottom-color" => PropertyId::Longhand(LonghandId::BorderBottomColor),
             "border-left-color" => PropertyId::Longhand(LonghandId::BorderLeftColor),
             "border-right-color" => PropertyId::Longhand(LonghandId::BorderRightColor),
             "border-top-color" => PropertyId::Longhand(LonghandId::BorderTopColor),
+            "border-block-end-style" => PropertyId::Longhand(LonghandId::BorderBlockEndStyle),
+            "border-block-start-style" => PropertyId::Longhand(LonghandId::BorderBlockStartStyle),
+            "border-inline-end-style" => PropertyId::Longhand(LonghandId::BorderInlineEndStyle),
+            "border-inline-start-style" => PropertyId::Longhand(LonghandId::BorderInlineStartStyle),
+            "border-bottom-left-radius" => PropertyId::Longhand(LonghandId::BorderBottomLeftRadius),
+            "border-bottom-right-radius" => {
+                PropertyId::Longhand(LonghandId::BorderBottomRightRadius)
+            }
+            "border-top-left-radius" => PropertyId::Longhand(LonghandId::BorderTopLeftRadius),
+            "border-top-right-radius" => PropertyId::Longhand(LonghandId::BorderTopRightRadius),
             "border-bottom-style" => PropertyId::Longhand(LonghandId::BorderBottomStyle),
             "border-left-style" => PropertyId::Longhand(LonghandId::BorderLeftStyle),
             "border-right-style" => PropertyId::Longhand(LonghandId::BorderRightStyle),
             "border-top-style" => PropertyId::Longhand(LonghandId::BorderTopStyle),
+            "border-block-end-width" => PropertyId::Longhand(LonghandId::BorderBlockEndWidth),
+            "border-block-start-width" => PropertyId::Longhand(LonghandId::BorderBlockStartWidth),
+            "border-inline-end-width" => PropertyId::Longhand(LonghandId::BorderInlineEndWidth),
+            "border-inline-start-width" => PropertyId::Longhand(LonghandId::BorderInlineStartWidth),
             "border-bottom-width" => PropertyId::Longhand(LonghandId::BorderBottomWidth),
             "border-left-width" => PropertyId::Longhand(LonghandId::BorderLeftWidth),
             "border-right-width" => PropertyId::Longhand(LonghandId::BorderRightWidth),
             "border-top-width" => PropertyId::Longhand(LonghandId::BorderTopWidth),
+            "bottom" => PropertyId::Longhand(LonghandId::Bottom),
+            "box-sizing" => PropertyId::Longhand(LonghandId::BoxSizing),
+            "clear" => PropertyId::Longhand(LonghandId::Clear),
             "color" => PropertyId::Longhand(LonghandId::Color),
             "direction" => PropertyId::Longhand(LonghandId::Direction),
             "display" => PropertyId::Longhand(LonghandId::Display),
-            //            "float" => PropertyId::Longhand(LonghandId::Float),
-            //            "font-style" => PropertyId::Longhand(LonghandId::FontStyle),
-            //            "font-weight" => PropertyId::Longhand(LonghandId::FontWeight),
+            "float" => PropertyId::Longhand(LonghandId::Float),
             //            "visibility" => PropertyId::Longhand(LonghandId::Visibility),
+            "font-family" => PropertyId::Longhand(LonghandId::FontFamily),
             "font-size" => PropertyId::Longhand(LonghandId::FontSize),
+            "font-stretch" => PropertyId::Longhand(LonghandId::FontStretch),
+            "font-style" => PropertyId::Longhand(LonghandId::FontStyle),
+            "font-weight" => PropertyId::Longhand(LonghandId::FontWeight),
             "height" => PropertyId::Longhand(LonghandId::Height),
+            "hyphens" => PropertyId::Longhand(LonghandId::Hyphens),
+            "inset-block-end" => PropertyId::Longhand(LonghandId::InsetBlockEnd),
+            "inset-block-start" => PropertyId::Longhand(LonghandId::InsetBlockStart),
+            "inset-inline-end" => PropertyId::Longhand(LonghandId::InsetInlineEnd),
+            "inset-inline-start" => PropertyId::Longhand(LonghandId::InsetInlineStart),
+            "left" => PropertyId::Longhand(LonghandId::Left),
+            "line-height" => PropertyId::Longhand(LonghandId::LineHeight),
+            "margin-block-end" => PropertyId::Longhand(LonghandId::MarginBlockEnd),
+            "margin-block-start" => PropertyId::Longhand(LonghandId::MarginBlockStart),
             "margin-bottom" => PropertyId::Longhand(LonghandId::MarginBottom),
+            "margin-inline-end" => PropertyId::Longhand(LonghandId::MarginInlineEnd),
+            "margin-inline-start" => PropertyId::Longhand(LonghandId::MarginInlineStart),
             "margin-left" => PropertyId::Longhand(LonghandId::MarginLeft),
             "margin-right" => PropertyId::Longhand(LonghandId::MarginRight),
             "margin-top" => PropertyId::Longhand(LonghandId::MarginTop),
+            "max-height" => PropertyId::Longhand(LonghandId::MaxHeight),
+            "max-width" => PropertyId::Longhand(LonghandId::MaxWidth),
+            "min-height" => PropertyId::Longhand(LonghandId::MinHeight),
+            "min-width" => PropertyId::Longhand(LonghandId::MinWidth),
+            "overflow-wrap" => PropertyId::Longhand(LonghandId::OverflowWrap),
+            "padding-block-end" => PropertyId::Longhand(LonghandId::PaddingBlockEnd),
+            "padding-block-start" => PropertyId::Longhand(LonghandId::PaddingBlockStart),
             "padding-bottom" => PropertyId::Longhand(LonghandId::PaddingBottom),
+            "padding-inline-end" => PropertyId::Longhand(LonghandId::PaddingInlineEnd),
+            "padding-inline-start" => PropertyId::Longhand(LonghandId::PaddingInlineStart),
             "padding-left" => PropertyId::Longhand(LonghandId::PaddingLeft),
             "padding-right" => PropertyId::Longhand(LonghandId::PaddingRight),
             "padding-top" => PropertyId::Longhand(LonghandId::PaddingTop),
+            "position" => PropertyId::Longhand(LonghandId::Position),
+            "right" => PropertyId::Longhand(LonghandId::Right),
+            "top" => PropertyId::Longhand(LonghandId::Top),
+            "unicode-bidi" => PropertyId::Longhand(LonghandId::UnicodeBidi),
+            "white-space" => PropertyId::Longhand(LonghandId::WhiteSpace),
             "width" => PropertyId::Longhand(LonghandId::Width),
+            "word-break" => PropertyId::Longhand(LonghandId::WordBreak),
+            // A legacy alias.  https://drafts.csswg.org/css-text-3/#propdef-word-wrap
+            "word-wrap" => PropertyId::Longhand(LonghandId::OverflowWrap),
             "writing-mode" => PropertyId::Longhand(LonghandId::WritingMode),
             // Shorthands
+            "all" => PropertyId::Shorthand(ShorthandId::All),
             "background" => PropertyId::Shorthand(ShorthandId::Background),
+            "border-color" => PropertyId::Shorthand(ShorthandId::BorderColor),
+            "border-style" => PropertyId::Shorthand(ShorthandId::BorderStyle),
             "border-width" => PropertyId::Shorthand(ShorthandId::BorderWidth),
             "border-top" => PropertyId::Shorthand(ShorthandId::BorderTop),
             "border-right" => PropertyId::Shorthand(ShorthandId::BorderRight),
             "border-bottom" => PropertyId::Shorthand(ShorthandId::BorderBottom),
             "border-left" => PropertyId::Shorthand(ShorthandId::BorderLeft),
+            "border-block-start" => PropertyId::Shorthand(ShorthandId::BorderBlockStart),
+            "border-block-end" => PropertyId::Shorthand(ShorthandId::BorderBlockEnd),
+            "border-inline-start" => PropertyId::Shorthand(ShorthandId::BorderInlineStart),
+            "border-inline-end" => PropertyId::Shorthand(ShorthandId::BorderInlineEnd),
             "border" => PropertyId::Shorthand(ShorthandId::Border),
+            "border-radius" => PropertyId::Shorthand(ShorthandId::BorderRadius),
+            "border-block-width" => PropertyId::Shorthand(ShorthandId::BorderBlockWidth),
+            "border-block-style" => PropertyId::Shorthand(ShorthandId::BorderBlockStyle),
+            "border-block-color" => PropertyId::Shorthand(ShorthandId::BorderBlockColor),
+            "border-inline-width" => PropertyId::Shorthand(ShorthandId::BorderInlineWidth),
+            "border-inline-style" => PropertyId::Shorthand(ShorthandId::BorderInlineStyle),
+            "border-inline-color" => PropertyId::Shorthand(ShorthandId::BorderInlineColor),
+            "border-block" => PropertyId::Shorthand(ShorthandId::BorderBlock),
+            "border-inline" => PropertyId::Shorthand(ShorthandId::BorderInline),
+            "font" => PropertyId::Shorthand(ShorthandId::Font),
             "margin" => PropertyId::Shorthand(ShorthandId::Margin),
+            "margin-block" => PropertyId::Shorthand(ShorthandId::MarginBlock),
+            "margin-inline" => PropertyId::Shorthand(ShorthandId::MarginInline),
             "padding" => PropertyId::Shorthand(ShorthandId::Padding),
+            "padding-block" => PropertyId::Shorthand(ShorthandId::PaddingBlock),
+            "padding-inline" => PropertyId::Shorthand(ShorthandId::PaddingInline),
+            "inset" => PropertyId::Shorthand(ShorthandId::Inset),
+            "inset-block" => PropertyId::Shorthand(ShorthandId::InsetBlock),
+            "inset-inline" => PropertyId::Shorthand(ShorthandId::InsetInline),
             _ => return None,
         };
         Some(id)
@@ -73,7 +154,7 @@ impl PropertyId {
 
 /// An identifier for a given longhand property.
 ///  TODO: Uncomment as properties are implemented.
-#[derive(Clone, Copy, Debug, EnumIter, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, EnumIter, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[repr(u16)]
 pub enum LonghandId {
     //    /// align-content
@@ -90,12 +171,12 @@ pub enum LonghandId {
     //    BorderCollapse = 5,
     //    /// border-image-repeat
     //    BorderImageRepeat = 6,
-    //    /// box-sizing
-    //    BoxSizing = 7,
+    /// box-sizing
+    BoxSizing = 7,
     //    /// caption-side
     //    CaptionSide = 8,
-    //    /// clear
-    //    Clear = 9,
+    /// clear
+    Clear = 9,
     //    /// column-count
     //    ColumnCount = 10,
     /// direction
@@ -108,16 +189,16 @@ pub enum LonghandId {
     //    FlexDirection = 14,
     //    /// flex-wrap
     //    FlexWrap = 15,
-    //    /// float
-    //    Float = 16,
-    //    /// font-stretch
-    //    FontStretch = 17,
-    //    /// font-style
-    //    FontStyle = 18,
+    /// float
+    Float = 16,
+    /// font-stretch
+    FontStretch = 17,
+    /// font-style
+    FontStyle = 18,
     //    /// font-variant-caps
     //    FontVariantCaps = 19,
-    //    /// font-weight
-    //    FontWeight = 20,
+    /// font-weight
+    FontWeight = 20,
     //    /// image-rendering
     //    ImageRendering = 21,
     //    /// justify-content
@@ -134,12 +215,12 @@ pub enum LonghandId {
     //    Order = 27,
     //    /// outline-style
     //    OutlineStyle = 28,
-    //    /// overflow-wrap
-    //    OverflowWrap = 29,
+    /// overflow-wrap
+    OverflowWrap = 29,
     //    /// pointer-events
     //    PointerEvents = 30,
-    //    /// position
-    //    Position = 31,
+    /// position
+    Position = 31,
     //    /// table-layout
     //    TableLayout = 32,
     //    /// text-align
@@ -154,14 +235,14 @@ pub enum LonghandId {
     //    TextTransform = 37,
     //    /// transform-style
     //    TransformStyle = 38,
-    //    /// unicode-bidi
-    //    UnicodeBidi = 39,
+    /// unicode-bidi
+    UnicodeBidi = 39,
     //    /// visibility
     //    Visibility = 40,
-    //    /// white-space
-    //    WhiteSpace = 41,
-    //    /// word-break
-    //    WordBreak = 42,
+    /// white-space
+    WhiteSpace = 41,
+    /// word-break
+    WordBreak = 42,
     /// writing-mode
     WritingMode = 43,
     //    /// z-index
@@ -178,16 +259,16 @@ pub enum LonghandId {
     //    OverflowX = 49,
     //    /// overflow-y
     //    OverflowY = 50,
-    //    /// border-block-end-style
-    //    BorderBlockEndStyle = 51,
-    //    /// border-block-start-style
-    //    BorderBlockStartStyle = 52,
+    /// border-block-end-style
+    BorderBlockEndStyle = 51,
+    /// border-block-start-style
+    BorderBlockStartStyle = 52,
     /// border-bottom-style
     BorderBottomStyle = 53,
-    //    /// border-inline-end-style
-    //    BorderInlineEndStyle = 54,
-    //    /// border-inline-start-style
-    //    BorderInlineStartStyle = 55,
+    /// border-inline-end-style
+    BorderInlineEndStyle = 54,
+    /// border-inline-start-style
+    BorderInlineStartStyle = 55,
     /// border-left-style
     BorderLeftStyle = 56,
     /// border-right-style
@@ -258,14 +339,14 @@ pub enum LonghandId {
     //    Filter = 89,
     //    /// flex-basis
     //    FlexBasis = 90,
-    //    /// font-family
-    //    FontFamily = 91,
+    /// font-family
+    FontFamily = 91,
     /// font-size
     FontSize = 92,
     //    /// letter-spacing
     //    LetterSpacing = 93,
-    //    /// line-height
-    //    LineHeight = 94,
+    /// line-height
+    LineHeight = 94,
     //    /// list-style-image
     //    ListStyleImage = 95,
     //    /// outline-offset
@@ -306,16 +387,16 @@ pub enum LonghandId {
     //    WordSpacing = 113,
     //    /// max-block-size
     //    MaxBlockSize = 114,
-    //    /// max-height
-    //    MaxHeight = 115,
+    /// max-height
+    MaxHeight = 115,
     //    /// max-inline-size
     //    MaxInlineSize = 116,
-    //    /// max-width
-    //    MaxWidth = 117,
-    //    /// border-bottom-left-radius
-    //    BorderBottomLeftRadius = 118,
-    //    /// border-bottom-right-radius
-    //    BorderBottomRightRadius = 119,
+    /// max-width
+    MaxWidth = 117,
+    /// border-bottom-left-radius
+    BorderBottomLeftRadius = 118,
+    /// border-bottom-right-radius
+    BorderBottomRightRadius = 119,
     //    /// border-end-end-radius
     //    BorderEndEndRadius = 120,
     //    /// border-end-start-radius
@@ -324,21 +405,21 @@ pub enum LonghandId {
     //    BorderStartEndRadius = 122,
     //    /// border-start-start-radius
     //    BorderStartStartRadius = 123,
-    //    /// border-top-left-radius
-    //    BorderTopLeftRadius = 124,
-    //    /// border-top-right-radius
-    //    BorderTopRightRadius = 125,
-    //    /// padding-block-end
-    //    PaddingBlockEnd = 126,
-    //    /// padding-block-start
-    //    PaddingBlockStart = 127,
+    /// border-top-left-radius
+    BorderTopLeftRadius = 124,
+    /// border-top-right-radius
+    BorderTopRightRadius = 125,
+    /// padding-block-end
+    PaddingBlockEnd = 126,
+    /// padding-block-start
+    PaddingBlockStart = 127,
     /// padding-bottom
     PaddingBottom = 128,
     /// padding-inline-end
-    //    PaddingInlineEnd = 129,
-    //    /// padding-inline-start
-    //    PaddingInlineStart = 130,
-    //    /// padding-left
+    PaddingInlineEnd = 129,
+    /// padding-inline-start
+    PaddingInlineStart = 130,
+    /// padding-left
     PaddingLeft = 131,
     /// padding-right
     PaddingRight = 132,
@@ -352,24 +433,24 @@ pub enum LonghandId {
     //    InlineSize = 136,
     //    /// min-block-size
     //    MinBlockSize = 137,
-    //    /// min-height
-    //    MinHeight = 138,
+    /// min-height
+    MinHeight = 138,
     //    /// min-inline-size
     //    MinInlineSize = 139,
-    //    /// min-width
-    //    MinWidth = 140,
+    /// min-width
+    MinWidth = 140,
     /// width
     Width = 141,
-    //    /// border-block-end-width
-    //    BorderBlockEndWidth = 142,
-    //    /// border-block-start-width
-    //    BorderBlockStartWidth = 143,
+    /// border-block-end-width
+    BorderBlockEndWidth = 142,
+    /// border-block-start-width
+    BorderBlockStartWidth = 143,
     /// border-bottom-width
     BorderBottomWidth = 144,
-    //    /// border-inline-end-width
-    //    BorderInlineEndWidth = 145,
-    //    /// border-inline-start-width
-    //    BorderInlineStartWidth = 146,
+    /// border-inline-end-width
+    BorderInlineEndWidth = 145,
+    /// border-inline-start-width
+    BorderInlineStartWidth = 146,
     /// border-left-width
     BorderLeftWidth = 147,
     /// border-right-width
@@ -380,16 +461,16 @@ pub enum LonghandId {
     //    OutlineWidth = 150,
     /// background-color
     BackgroundColor = 151,
-    //    /// border-block-end-color
-    //    BorderBlockEndColor = 152,
-    //    /// border-block-start-color
-    //    BorderBlockStartColor = 153,
+    /// border-block-end-color
+    BorderBlockEndColor = 152,
+    /// border-block-start-color
+    BorderBlockStartColor = 153,
     /// border-bottom-color
     BorderBottomColor = 154,
-    //    /// border-inline-end-color
-    //    BorderInlineEndColor = 155,
-    //    /// border-inline-start-color
-    //    BorderInlineStartColor = 156,
+    /// border-inline-end-color
+    BorderInlineEndColor = 155,
+    /// border-inline-start-color
+    BorderInlineStartColor = 156,
     /// border-left-color
     BorderLeftColor = 157,
     /// border-right-color
@@ -398,38 +479,40 @@ pub enum LonghandId {
     BorderTopColor = 159,
     //    /// outline-color
     //    OutlineColor = 160,
-    //    /// bottom
-    //    Bottom = 161,
-    //    /// inset-block-end
-    //    InsetBlockEnd = 162,
-    //    /// inset-block-start
-    //    InsetBlockStart = 163,
-    //    /// inset-inline-end
-    //    InsetInlineEnd = 164,
-    //    /// inset-inline-start
-    //    InsetInlineStart = 165,
-    //    /// left
-    //    Left = 166,
-    //    /// margin-block-end
-    //    MarginBlockEnd = 167,
-    //    /// margin-block-start
-    //    MarginBlockStart = 168,
+    /// bottom
+    Bottom = 161,
+    /// inset-block-end
+    InsetBlockEnd = 162,
+    /// inset-block-start
+    InsetBlockStart = 163,
+    /// inset-inline-end
+    InsetInlineEnd = 164,
+    /// inset-inline-start
+    InsetInlineStart = 165,
+    /// left
+    Left = 166,
+    /// margin-block-end
+    MarginBlockEnd = 167,
+    /// margin-block-start
+    MarginBlockStart = 168,
     /// margin-bottom
     MarginBottom = 169,
-    //    /// margin-inline-end
-    //    MarginInlineEnd = 170,
-    //    /// margin-inline-start
-    //    MarginInlineStart = 171,
+    /// margin-inline-end
+    MarginInlineEnd = 170,
+    /// margin-inline-start
+    MarginInlineStart = 171,
     /// margin-left
     MarginLeft = 172,
     /// margin-right
     MarginRight = 173,
     /// margin-top
     MarginTop = 174,
-    //    /// right
-    //    Right = 175,
-    //    /// top
-    //    Top = 176,
+    /// right
+    Right = 175,
+    /// top
+    Top = 176,
+    /// hyphens
+    Hyphens = 177,
 }
 
 impl LonghandId {
@@ -451,6 +534,22 @@ impl LonghandId {
             LonghandId::BorderTopColor => {
                 cv_builder.border_top_color(specified::BorderTopColor::value_default(ctx));
             }
+            LonghandId::BorderBottomLeftRadius => {
+                cv_builder
+                    .border_bottom_left_radius(specified::BorderCornerRadius::value_default(ctx));
+            }
+            LonghandId::BorderBottomRightRadius => {
+                cv_builder
+                    .border_bottom_right_radius(specified::BorderCornerRadius::value_default(ctx));
+            }
+            LonghandId::BorderTopLeftRadius => {
+                cv_builder
+                    .border_top_left_radius(specified::BorderCornerRadius::value_default(ctx));
+            }
+            LonghandId::BorderTopRightRadius => {
+                cv_builder
+                    .border_top_right_radius(specified::BorderCornerRadius::value_default(ctx));
+            }
             LonghandId::BorderBottomStyle => {
                 cv_builder.border_bottom_style(LineStyle::None);
             }
@@ -475,6 +574,15 @@ impl LonghandId {
             LonghandId::BorderTopWidth => {
                 cv_builder.border_top_width(specified::BorderTopWidth::value_default(ctx));
             }
+            LonghandId::Bottom => {
+                cv_builder.bottom(specified::Bottom::value_default(ctx));
+            }
+            LonghandId::BoxSizing => {
+                cv_builder.box_sizing(computed::BoxSizing::value_default(ctx));
+            }
+            LonghandId::Clear => {
+                cv_builder.clear(computed::Clear::value_default(ctx));
+            }
             LonghandId::Color => {
                 cv_builder.color(specified::Color::value_default(ctx));
             }
@@ -484,12 +592,36 @@ impl LonghandId {
             LonghandId::Display => {
                 cv_builder.display(computed::Display::value_default(ctx));
             }
+            LonghandId::Float => {
+                cv_builder.float(computed::Float::value_default(ctx));
+            }
+            LonghandId::FontFamily => {
+                cv_builder.font_family(computed::FontFamily::value_default(ctx));
+            }
             LonghandId::FontSize => {
                 cv_builder.font_size(specified::FontSize::value_default(ctx));
             }
+            LonghandId::FontStretch => {
+                cv_builder.font_stretch(computed::FontStretch::value_default(ctx));
+            }
+            LonghandId::FontStyle => {
+                cv_builder.font_style(computed::FontStyle::value_default(ctx));
+            }
+            LonghandId::FontWeight => {
+                cv_builder.font_weight(specified::FontWeight::value_default(ctx));
+            }
             LonghandId::Height => {
                 cv_builder.height(specified::Height::value_default(ctx));
             }
+            LonghandId::Hyphens => {
+                cv_builder.hyphens(computed::Hyphens::value_default(ctx));
+            }
+            LonghandId::Left => {
+                cv_builder.left(specified::Left::value_default(ctx));
+            }
+            LonghandId::LineHeight => {
+                cv_builder.line_height(specified::LineHeight::value_default(ctx));
+            }
             LonghandId::MarginBottom => {
                 cv_builder.margin_bottom(specified::MarginBottom::value_default(ctx));
             }
@@ -502,6 +634,21 @@ impl LonghandId {
             LonghandId::MarginTop => {
                 cv_builder.margin_top(specified::MarginTop::value_default(ctx));
             }
+            LonghandId::MaxHeight => {
+                cv_builder.max_height(specified::MaxHeight::value_default(ctx));
+            }
+            LonghandId::MaxWidth => {
+                cv_builder.max_width(specified::MaxWidth::value_default(ctx));
+            }
+            LonghandId::MinHeight => {
+                cv_builder.min_height(specified::MinHeight::value_default(ctx));
+            }
+            LonghandId::MinWidth => {
+                cv_builder.min_width(specified::MinWidth::value_default(ctx));
+            }
+            LonghandId::OverflowWrap => {
+                cv_builder.overflow_wrap(computed::OverflowWrap::value_default(ctx));
+            }
             LonghandId::PaddingBottom => {
                 cv_builder.padding_bottom(specified::PaddingBottom::value_default(ctx));
             }
@@ -514,9 +661,27 @@ impl LonghandId {
             LonghandId::PaddingTop => {
                 cv_builder.padding_top(specified::PaddingTop::value_default(ctx));
             }
+            LonghandId::Position => {
+                cv_builder.position(computed::Position::value_default(ctx));
+            }
+            LonghandId::Right => {
+                cv_builder.right(specified::Right::value_default(ctx));
+            }
+            LonghandId::Top => {
+                cv_builder.top(specified::Top::value_default(ctx));
+            }
+            LonghandId::UnicodeBidi => {
+                cv_builder.unicode_bidi(computed::UnicodeBidi::value_default(ctx));
+            }
+            LonghandId::WhiteSpace => {
+                cv_builder.white_space(computed::WhiteSpace::value_default(ctx));
+            }
             LonghandId::Width => {
                 cv_builder.width(specified::Width::value_default(ctx));
             }
+            LonghandId::WordBreak => {
+                cv_builder.word_break(computed::WordBreak::value_default(ctx));
+            }
             LonghandId::WritingMode => {
                 cv_builder.writing_mode(computed::WritingMode::value_default(ctx));
             }
@@ -526,58 +691,604 @@ impl LonghandId {
             ),
         }
     }
-}
 
-impl From<&PropertyDeclaration> for LonghandId {
-    fn from(prop_decl: &PropertyDeclaration) -> Self {
-        match prop_decl {
-            PropertyDeclaration::BackgroundColor(_) => LonghandId::BackgroundColor,
-            PropertyDeclaration::BorderBottomColor(_) => LonghandId::BorderBottomColor,
-            PropertyDeclaration::BorderLeftColor(_) => LonghandId::BorderLeftColor,
-            PropertyDeclaration::BorderRightColor(_) => LonghandId::BorderRightColor,
-            PropertyDeclaration::BorderTopColor(_) => LonghandId::BorderTopColor,
-            PropertyDeclaration::BorderBottomStyle(_) => LonghandId::BorderBottomStyle,
-            PropertyDeclaration::BorderLeftStyle(_) => LonghandId::BorderLeftStyle,
-            PropertyDeclaration::BorderRightStyle(_) => LonghandId::BorderRightStyle,
-            PropertyDeclaration::BorderTopStyle(_) => LonghandId::BorderTopStyle,
-            PropertyDeclaration::BorderBottomWidth(_) => LonghandId::BorderBottomWidth,
-            PropertyDeclaration::BorderLeftWidth(_) => LonghandId::BorderLeftWidth,
-            PropertyDeclaration::BorderRightWidth(_) => LonghandId::BorderRightWidth,
-            PropertyDeclaration::BorderTopWidth(_) => LonghandId::BorderTopWidth,
-            PropertyDeclaration::Color(_) => LonghandId::Color,
-            PropertyDeclaration::Direction(_) => LonghandId::Direction,
-            PropertyDeclaration::Display(_) => LonghandId::Display,
-            PropertyDeclaration::FontSize(_) => LonghandId::FontSize,
-            PropertyDeclaration::Height(_) => LonghandId::Height,
-            PropertyDeclaration::MarginBottom(_) => LonghandId::MarginBottom,
-            PropertyDeclaration::MarginLeft(_) => LonghandId::MarginLeft,
-            PropertyDeclaration::MarginRight(_) => LonghandId::MarginRight,
-            PropertyDeclaration::MarginTop(_) => LonghandId::MarginTop,
-            PropertyDeclaration::PaddingBottom(_) => LonghandId::PaddingBottom,
-            PropertyDeclaration::PaddingLeft(_) => LonghandId::PaddingLeft,
-            PropertyDeclaration::PaddingRight(_) => LonghandId::PaddingRight,
-            PropertyDeclaration::PaddingTop(_) => LonghandId::PaddingTop,
-            PropertyDeclaration::Width(_) => LonghandId::Width,
-            PropertyDeclaration::WritingMode(_) => LonghandId::WritingMode,
+    /// Whether this property is inherited by default, i.e. whether its value defaults to the
+    /// parent's computed value rather than its initial value.
+    pub fn is_inherited(self) -> bool {
+        matches!(
+            self,
+            LonghandId::Color
+                | LonghandId::Direction
+                | LonghandId::FontFamily
+                | LonghandId::FontSize
+                | LonghandId::FontStretch
+                | LonghandId::FontStyle
+                | LonghandId::FontWeight
+                | LonghandId::Hyphens
+                | LonghandId::LineHeight
+                | LonghandId::OverflowWrap
+                | LonghandId::WhiteSpace
+                | LonghandId::WordBreak
+                | LonghandId::WritingMode
+        )
+    }
+
+    /// Computes the value of this property when it is specified as a CSS-wide keyword.
+    ///
+    /// `revert` should already have been resolved by the cascade.  If it reaches this point,
+    /// there was no lower-origin declaration to roll back to, which behaves like `unset`.
+    pub fn compute_css_wide_keyword(
+        self,
+        keyword: CssWideKeywords,
+        cv_builder: &mut ComputedValuesBuilder,
+        ctx: &ComputeContext,
+    ) {
+        match keyword {
+            CssWideKeywords::Initial => self.initial_value(cv_builder, ctx),
+            CssWideKeywords::Inherit => self.inherit(cv_builder, ctx),
+            CssWideKeywords::Unset | CssWideKeywords::Revert => self.value_default(cv_builder, ctx),
         }
     }
-}
 
-impl From<PropertyDeclaration> for LonghandId {
-    fn from(prop_decl: PropertyDeclaration) -> Self {
-        LonghandId::from(&prop_decl)
+    /// Sets this property to its initial value.
+    fn initial_value(self, cv_builder: &mut ComputedValuesBuilder, ctx: &ComputeContext) {
+        match self {
+            LonghandId::Color => {
+                cv_builder.color(computed::Color::initial_value());
+            }
+            LonghandId::Direction => {
+                cv_builder.direction(computed::Direction::initial_value());
+            }
+            LonghandId::FontFamily => {
+                cv_builder.font_family(computed::FontFamily::initial_value());
+            }
+            LonghandId::FontSize => {
+                cv_builder.font_size(computed::FontSize::initial_value());
+            }
+            LonghandId::FontStretch => {
+                cv_builder.font_stretch(computed::FontStretch::initial_value());
+            }
+            LonghandId::FontStyle => {
+                cv_builder.font_style(computed::FontStyle::initial_value());
+            }
+            LonghandId::FontWeight => {
+                cv_builder.font_weight(computed::FontWeight::initial_value());
+            }
+            LonghandId::Hyphens => {
+                cv_builder.hyphens(computed::Hyphens::initial_value());
+            }
+            LonghandId::LineHeight => {
+                cv_builder.line_height(computed::LineHeight::initial_value());
+            }
+            LonghandId::OverflowWrap => {
+                cv_builder.overflow_wrap(computed::OverflowWrap::initial_value());
+            }
+            LonghandId::WhiteSpace => {
+                cv_builder.white_space(computed::WhiteSpace::initial_value());
+            }
+            LonghandId::WordBreak => {
+                cv_builder.word_break(computed::WordBreak::initial_value());
+            }
+            LonghandId::WritingMode => {
+                cv_builder.writing_mode(computed::WritingMode::initial_value());
+            }
+            // Properties that aren't inherited default to their initial value anyways.
+            _ => self.value_default(cv_builder, ctx),
+        }
+    }
+
+    /// Sets this property to the parent's computed value.
+    fn inherit(self, cv_builder: &mut ComputedValuesBuilder, ctx: &ComputeContext) {
+        let parent = ctx.parent_computed_values;
+        match self {
+            LonghandId::BackgroundColor => {
+                cv_builder.background_color(parent.background_color);
+            }
+            LonghandId::BorderBottomColor => {
+                cv_builder.border_bottom_color(parent.border_bottom_color);
+            }
+            LonghandId::BorderLeftColor => {
+                cv_builder.border_left_color(parent.border_left_color);
+            }
+            LonghandId::BorderRightColor => {
+                cv_builder.border_right_color(parent.border_right_color);
+            }
+            LonghandId::BorderTopColor => {
+                cv_builder.border_top_color(parent.border_top_color);
+            }
+            LonghandId::BorderBottomLeftRadius => {
+                cv_builder.border_bottom_left_radius(parent.border_bottom_left_radius.clone());
+            }
+            LonghandId::BorderBottomRightRadius => {
+                cv_builder.border_bottom_right_radius(parent.border_bottom_right_radius.clone());
+            }
+            LonghandId::BorderTopLeftRadius => {
+                cv_builder.border_top_left_radius(parent.border_top_left_radius.clone());
+            }
+            LonghandId::BorderTopRightRadius => {
+                cv_builder.border_top_right_radius(parent.border_top_right_radius.clone());
+            }
+            LonghandId::BorderBottomStyle => {
+                cv_builder.border_bottom_style(parent.border_bottom_style);
+            }
+            LonghandId::BorderLeftStyle => {
+                cv_builder.border_left_style(parent.border_left_style);
+            }
+            LonghandId::BorderRightStyle => {
+                cv_builder.border_right_style(parent.border_right_style);
+            }
+            LonghandId::BorderTopStyle => {
+                cv_builder.border_top_style(parent.border_top_style);
+            }
+            LonghandId::BorderBottomWidth => {
+                cv_builder.border_bottom_width(parent.border_bottom_width);
+            }
+            LonghandId::BorderLeftWidth => {
+                cv_builder.border_left_width(parent.border_left_width);
+            }
+            LonghandId::BorderRightWidth => {
+                cv_builder.border_right_width(parent.border_right_width);
+            }
+            LonghandId::BorderTopWidth => {
+                cv_builder.border_top_width(parent.border_top_width);
+            }
+            LonghandId::Bottom => {
+                cv_builder.bottom(parent.bottom.clone());
+            }
+            LonghandId::BoxSizing => {
+                cv_builder.box_sizing(parent.box_sizing);
+            }
+            LonghandId::Clear => {
+                cv_builder.clear(parent.clear);
+            }
+            LonghandId::Display => {
+                cv_builder.display(parent.display);
+            }
+            LonghandId::Float => {
+                cv_builder.float(parent.float);
+            }
+            LonghandId::Height => {
+                cv_builder.height(parent.height.clone());
+            }
+            LonghandId::Left => {
+                cv_builder.left(parent.left.clone());
+            }
+            LonghandId::MarginBottom => {
+                cv_builder.margin_bottom(parent.margin_bottom.clone());
+            }
+            LonghandId::MarginLeft => {
+                cv_builder.margin_left(parent.margin_left.clone());
+            }
+            LonghandId::MarginRight => {
+                cv_builder.margin_right(parent.margin_right.clone());
+            }
+            LonghandId::MarginTop => {
+                cv_builder.margin_top(parent.margin_top.clone());
+            }
+            LonghandId::MaxHeight => {
+                cv_builder.max_height(parent.max_height.clone());
+            }
+            LonghandId::MaxWidth => {
+                cv_builder.max_width(parent.max_width.clone());
+            }
+            LonghandId::MinHeight => {
+                cv_builder.min_height(parent.min_height.clone());
+            }
+            LonghandId::MinWidth => {
+                cv_builder.min_width(parent.min_width.clone());
+            }
+            LonghandId::PaddingBottom => {
+                cv_builder.padding_bottom(parent.padding_bottom.clone());
+            }
+            LonghandId::PaddingLeft => {
+                cv_builder.padding_left(parent.padding_left.clone());
+            }
+            LonghandId::PaddingRight => {
+                cv_builder.padding_right(parent.padding_right.clone());
+            }
+            LonghandId::PaddingTop => {
+                cv_builder.padding_top(parent.padding_top.clone());
+            }
+            LonghandId::Position => {
+                cv_builder.position(parent.position);
+            }
+            LonghandId::Right => {
+                cv_builder.right(parent.right.clone());
+            }
+            LonghandId::Top => {
+                cv_builder.top(parent.top.clone());
+            }
+            LonghandId::UnicodeBidi => {
+                cv_builder.unicode_bidi(parent.unicode_bidi);
+            }
+            LonghandId::Width => {
+                cv_builder.width(parent.width.clone());
+            }
+            // Inherited properties default to the parent's value anyways.
+            LonghandId::Color
+            | LonghandId::Direction
+            | LonghandId::FontFamily
+            | LonghandId::FontSize
+            | LonghandId::FontStretch
+            | LonghandId::FontStyle
+            | LonghandId::FontWeight
+            | LonghandId::Hyphens
+            | LonghandId::LineHeight
+            | LonghandId::OverflowWrap
+            | LonghandId::WhiteSpace
+            | LonghandId::WordBreak
+            | LonghandId::WritingMode => self.value_default(cv_builder, ctx),
+            // Flow-relative longhands are cascaded as their physical counterparts.
+            _ => unreachable!("{:?} has no computed value of its own", self),
+        }
+    }
+
+    /// The group of box-side properties this longhand belongs to, if any.
+    pub fn box_side_group(self) -> Option<BoxSideGroup> {
+        self.box_side().map(|(group, _)| group)
+    }
+
+    /// Whether this is a flow-relative longhand, such as `margin-inline-start`.  These have no
+    /// computed values of their own — instead, they cascade together with the physical longhand
+    /// they map to given the element's `writing-mode` and `direction`.
+    ///
+    /// https://drafts.csswg.org/css-logical/#box
+    pub fn is_logical(self) -> bool {
+        matches!(self.box_side(), Some((_, Some(_))))
+    }
+
+    /// The physical longhand this longhand maps to.  Longhands that aren't flow-relative map to
+    /// themselves.
+    pub fn to_physical(self, writing_mode: WritingMode, direction: Direction) -> LonghandId {
+        match self.box_side() {
+            Some((group, Some(logical_direction))) => {
+                group.longhand(logical_direction.physical_side(writing_mode, direction))
+            }
+            _ => self,
+        }
     }
+
+    /// The group this longhand belongs to, and for flow-relative longhands, the side it sets.
+    fn box_side(self) -> Option<(BoxSideGroup, Option<LogicalDirection>)> {
+        use LogicalDirection::{BlockEnd, BlockStart, InlineEnd, InlineStart};
+        Some(match self {
+            LonghandId::BorderBlockEndColor => (BoxSideGroup::BorderColor, Some(BlockEnd)),
+            LonghandId::BorderBlockStartColor => (BoxSideGroup::BorderColor, Some(BlockStart)),
+            LonghandId::BorderInlineEndColor => (BoxSideGroup::BorderColor, Some(InlineEnd)),
+            LonghandId::BorderInlineStartColor => (BoxSideGroup::BorderColor, Some(InlineStart)),
+            LonghandId::BorderBottomColor => (BoxSideGroup::BorderColor, None),
+            LonghandId::BorderLeftColor => (BoxSideGroup::BorderColor, None),
+            LonghandId::BorderRightColor => (BoxSideGroup::BorderColor, None),
+            LonghandId::BorderTopColor => (BoxSideGroup::BorderColor, None),
+            LonghandId::BorderBlockEndStyle => (BoxSideGroup::BorderStyle, Some(BlockEnd)),
+            LonghandId::BorderBlockStartStyle => (BoxSideGroup::BorderStyle, Some(BlockStart)),
+            LonghandId::BorderInlineEndStyle => (BoxSideGroup::BorderStyle, Some(InlineEnd)),
+            LonghandId::BorderInlineStartStyle => (BoxSideGroup::BorderStyle, Some(InlineStart)),
+            LonghandId::BorderBottomStyle => (BoxSideGroup::BorderStyle, None),
+            LonghandId::BorderLeftStyle => (BoxSideGroup::BorderStyle, None),
+            LonghandId::BorderRightStyle => (BoxSideGroup::BorderStyle, None),
+            LonghandId::BorderTopStyle => (BoxSideGroup::BorderStyle, None),
+            LonghandId::BorderBlockEndWidth => (BoxSideGroup::BorderWidth, Some(BlockEnd)),
+            LonghandId::BorderBlockStartWidth => (BoxSideGroup::BorderWidth, Some(BlockStart)),
+            LonghandId::BorderInlineEndWidth => (BoxSideGroup::BorderWidth, Some(InlineEnd)),
+            LonghandId::BorderInlineStartWidth => (BoxSideGroup::BorderWidth, Some(InlineStart)),
+            LonghandId::BorderBottomWidth => (BoxSideGroup::BorderWidth, None),
+            LonghandId::BorderLeftWidth => (BoxSideGroup::BorderWidth, None),
+            LonghandId::BorderRightWidth => (BoxSideGroup::BorderWidth, None),
+            LonghandId::BorderTopWidth => (BoxSideGroup::BorderWidth, None),
+            LonghandId::InsetBlockEnd => (BoxSideGroup::Inset, Some(BlockEnd)),
+            LonghandId::InsetBlockStart => (BoxSideGroup::Inset, Some(BlockStart)),
+            LonghandId::InsetInlineEnd => (BoxSideGroup::Inset, Some(InlineEnd)),
+            LonghandId::InsetInlineStart => (BoxSideGroup::Inset, Some(InlineStart)),
+            LonghandId::Bottom => (BoxSideGroup::Inset, None),
+            LonghandId::Left => (BoxSideGroup::Inset, None),
+            LonghandId::Right => (BoxSideGroup::Inset, None),
+            LonghandId::Top => (BoxSideGroup::Inset, None),
+            LonghandId::MarginBlockEnd => (BoxSideGroup::Margin, Some(BlockEnd)),
+            LonghandId::MarginBlockStart => (BoxSideGroup::Margin, Some(BlockStart)),
+            LonghandId::MarginInlineEnd => (BoxSideGroup::Margin, Some(InlineEnd)),
+            LonghandId::MarginInlineStart => (BoxSideGroup::Margin, Some(InlineStart)),
+            LonghandId::MarginBottom => (BoxSideGroup::Margin, None),
+            LonghandId::MarginLeft => (BoxSideGroup::Margin, None),
+            LonghandId::MarginRight => (BoxSideGroup::Margin, None),
+            LonghandId::MarginTop => (BoxSideGroup::Margin, None),
+            LonghandId::PaddingBlockEnd => (BoxSideGroup::Padding, Some(BlockEnd)),
+            LonghandId::PaddingBlockStart => (BoxSideGroup::Padding, Some(BlockStart)),
+            LonghandId::PaddingInlineEnd => (BoxSideGroup::Padding, Some(InlineEnd)),
+            LonghandId::PaddingInlineStart => (BoxSideGroup::Padding, Some(InlineStart)),
+            LonghandId::PaddingBottom => (BoxSideGroup::Padding, None),
+            LonghandId::PaddingLeft => (BoxSideGroup::Padding, None),
+            LonghandId::PaddingRight => (BoxSideGroup::Padding, None),
+            LonghandId::PaddingTop => (BoxSideGroup::Padding, None),
+            _ => return None,
+        })
+    }
+}
+
+/// A group of properties with one longhand per side of a box, each side having both a physical
+/// longhand (e.g. `margin-top`) and a flow-relative one (e.g. `margin-block-start`).
+///
+/// https://drafts.csswg.org/css-logical/#box
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BoxSideGroup {
+    BorderColor,
+    BorderStyle,
+    BorderWidth,
+    Inset,
+    Margin,
+    Padding,
 }
 
-impl From<&ContextualPropertyDeclaration> for LonghandId {
-    fn from(contextual_decl: &ContextualPropertyDeclaration) -> Self {
-        LonghandId::from(&contextual_decl.inner_decl)
+impl BoxSideGroup {
+    /// The physical and flow-relative longhands of this group.
+    pub fn longhands(self) -> impl Iterator<Item = LonghandId> {
+        LonghandId::iter().filter(move |longhand| longhand.box_side_group() == Some(self))
+    }
+
+    /// The physical longhand of this group for the given side.
+    fn longhand(self, side: Side) -> LonghandId {
+        match (self, side) {
+            (BoxSideGroup::BorderColor, Side::Bottom) => LonghandId::BorderBottomColor,
+            (BoxSideGroup::BorderColor, Side::Left) => LonghandId::BorderLeftColor,
+            (BoxSideGroup::BorderColor, Side::Right) => LonghandId::BorderRightColor,
+            (BoxSideGroup::BorderColor, Side::Top) => LonghandId::BorderTopColor,
+            (BoxSideGroup::BorderStyle, Side::Bottom) => LonghandId::BorderBottomStyle,
+            (BoxSideGroup::BorderStyle, Side::Left) => LonghandId::BorderLeftStyle,
+            (BoxSideGroup::BorderStyle, Side::Right) => LonghandId::BorderRightStyle,
+            (BoxSideGroup::BorderStyle, Side::Top) => LonghandId::BorderTopStyle,
+            (BoxSideGroup::BorderWidth, Side::Bottom) => LonghandId::BorderBottomWidth,
+            (BoxSideGroup::BorderWidth, Side::Left) => LonghandId::BorderLeftWidth,
+            (BoxSideGroup::BorderWidth, Side::Right) => LonghandId::BorderRightWidth,
+            (BoxSideGroup::BorderWidth, Side::Top) => LonghandId::BorderTopWidth,
+            (BoxSideGroup::Inset, Side::Bottom) => LonghandId::Bottom,
+            (BoxSideGroup::Inset, Side::Left) => LonghandId::Left,
+            (BoxSideGroup::Inset, Side::Right) => LonghandId::Right,
+            (BoxSideGroup::Inset, Side::Top) => LonghandId::Top,
+            (BoxSideGroup::Margin, Side::Bottom) => LonghandId::MarginBottom,
+            (BoxSideGroup::Margin, Side::Left) => LonghandId::MarginLeft,
+            (BoxSideGroup::Margin, Side::Right) => LonghandId::MarginRight,
+            (BoxSideGroup::Margin, Side::Top) => LonghandId::MarginTop,
+            (BoxSideGroup::Padding, Side::Bottom) => LonghandId::PaddingBottom,
+            (BoxSideGroup::Padding, Side::Left) => LonghandId::PaddingLeft,
+            (BoxSideGroup::Padding, Side::Right) => LonghandId::PaddingRight,
+            (BoxSideGroup::Padding, Side::Top) => LonghandId::PaddingTop,
+        }
     }
 }
 
-impl From<ContextualPropertyDeclaration> for LonghandId {
-    fn from(contextual_decl: ContextualPropertyDeclaration) -> Self {
-        LonghandId::from(&contextual_decl)
+/// An identifier for the property a `PropertyDeclaration` declares.  Unlike `PropertyId`, this is
+/// never a shorthand, since shorthands are expanded into their longhands when parsed.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PropertyDeclarationId<'a> {
+    Longhand(LonghandId),
+    Custom(&'a Name),
+}
+
+impl PropertyDeclaration {
+    pub fn id(&self) -> PropertyDeclarationId<'_> {
+        match self {
+            PropertyDeclaration::BackgroundColor(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BackgroundColor)
+            }
+            PropertyDeclaration::BorderBottomColor(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderBottomColor)
+            }
+            PropertyDeclaration::BorderLeftColor(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderLeftColor)
+            }
+            PropertyDeclaration::BorderRightColor(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderRightColor)
+            }
+            PropertyDeclaration::BorderTopColor(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderTopColor)
+            }
+            PropertyDeclaration::BorderBottomLeftRadius(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderBottomLeftRadius)
+            }
+            PropertyDeclaration::BorderBottomRightRadius(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderBottomRightRadius)
+            }
+            PropertyDeclaration::BorderTopLeftRadius(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderTopLeftRadius)
+            }
+            PropertyDeclaration::BorderTopRightRadius(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderTopRightRadius)
+            }
+            PropertyDeclaration::BorderBottomStyle(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderBottomStyle)
+            }
+            PropertyDeclaration::BorderLeftStyle(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderLeftStyle)
+            }
+            PropertyDeclaration::BorderRightStyle(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderRightStyle)
+            }
+            PropertyDeclaration::BorderTopStyle(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderTopStyle)
+            }
+            PropertyDeclaration::BorderBottomWidth(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderBottomWidth)
+            }
+            PropertyDeclaration::BorderLeftWidth(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderLeftWidth)
+            }
+            PropertyDeclaration::BorderRightWidth(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderRightWidth)
+            }
+            PropertyDeclaration::BorderTopWidth(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderTopWidth)
+            }
+            PropertyDeclaration::BoxSizing(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BoxSizing)
+            }
+            PropertyDeclaration::Clear(_) => PropertyDeclarationId::Longhand(LonghandId::Clear),
+            PropertyDeclaration::Color(_) => PropertyDeclarationId::Longhand(LonghandId::Color),
+            PropertyDeclaration::Direction(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::Direction)
+            }
+            PropertyDeclaration::Display(_) => PropertyDeclarationId::Longhand(LonghandId::Display),
+            PropertyDeclaration::Float(_) => PropertyDeclarationId::Longhand(LonghandId::Float),
+            PropertyDeclaration::FontFamily(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::FontFamily)
+            }
+            PropertyDeclaration::FontSize(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::FontSize)
+            }
+            PropertyDeclaration::FontStretch(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::FontStretch)
+            }
+            PropertyDeclaration::FontStyle(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::FontStyle)
+            }
+            PropertyDeclaration::FontWeight(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::FontWeight)
+            }
+            PropertyDeclaration::Height(_) => PropertyDeclarationId::Longhand(LonghandId::Height),
+            PropertyDeclaration::LineHeight(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::LineHeight)
+            }
+            PropertyDeclaration::MarginBottom(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MarginBottom)
+            }
+            PropertyDeclaration::MarginLeft(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MarginLeft)
+            }
+            PropertyDeclaration::MarginRight(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MarginRight)
+            }
+            PropertyDeclaration::MarginTop(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MarginTop)
+            }
+            PropertyDeclaration::MaxHeight(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MaxHeight)
+            }
+            PropertyDeclaration::MaxWidth(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MaxWidth)
+            }
+            PropertyDeclaration::MinHeight(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MinHeight)
+            }
+            PropertyDeclaration::MinWidth(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MinWidth)
+            }
+            PropertyDeclaration::PaddingBottom(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::PaddingBottom)
+            }
+            PropertyDeclaration::PaddingLeft(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::PaddingLeft)
+            }
+            PropertyDeclaration::PaddingRight(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::PaddingRight)
+            }
+            PropertyDeclaration::PaddingTop(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::PaddingTop)
+            }
+            PropertyDeclaration::Position(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::Position)
+            }
+            PropertyDeclaration::Hyphens(_) => PropertyDeclarationId::Longhand(LonghandId::Hyphens),
+            PropertyDeclaration::OverflowWrap(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::OverflowWrap)
+            }
+            PropertyDeclaration::UnicodeBidi(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::UnicodeBidi)
+            }
+            PropertyDeclaration::WhiteSpace(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::WhiteSpace)
+            }
+            PropertyDeclaration::Width(_) => PropertyDeclarationId::Longhand(LonghandId::Width),
+            PropertyDeclaration::WordBreak(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::WordBreak)
+            }
+            PropertyDeclaration::WritingMode(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::WritingMode)
+            }
+            PropertyDeclaration::BorderBlockEndColor(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderBlockEndColor)
+            }
+            PropertyDeclaration::BorderBlockStartColor(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderBlockStartColor)
+            }
+            PropertyDeclaration::BorderInlineEndColor(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderInlineEndColor)
+            }
+            PropertyDeclaration::BorderInlineStartColor(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderInlineStartColor)
+            }
+            PropertyDeclaration::BorderBlockEndStyle(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderBlockEndStyle)
+            }
+            PropertyDeclaration::BorderBlockStartStyle(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderBlockStartStyle)
+            }
+            PropertyDeclaration::BorderInlineEndStyle(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderInlineEndStyle)
+            }
+            PropertyDeclaration::BorderInlineStartStyle(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderInlineStartStyle)
+            }
+            PropertyDeclaration::BorderBlockEndWidth(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderBlockEndWidth)
+            }
+            PropertyDeclaration::BorderBlockStartWidth(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderBlockStartWidth)
+            }
+            PropertyDeclaration::BorderInlineEndWidth(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderInlineEndWidth)
+            }
+            PropertyDeclaration::BorderInlineStartWidth(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::BorderInlineStartWidth)
+            }
+            PropertyDeclaration::Bottom(_) => PropertyDeclarationId::Longhand(LonghandId::Bottom),
+            PropertyDeclaration::InsetBlockEnd(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::InsetBlockEnd)
+            }
+            PropertyDeclaration::InsetBlockStart(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::InsetBlockStart)
+            }
+            PropertyDeclaration::InsetInlineEnd(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::InsetInlineEnd)
+            }
+            PropertyDeclaration::InsetInlineStart(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::InsetInlineStart)
+            }
+            PropertyDeclaration::Left(_) => PropertyDeclarationId::Longhand(LonghandId::Left),
+            PropertyDeclaration::MarginBlockEnd(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MarginBlockEnd)
+            }
+            PropertyDeclaration::MarginBlockStart(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MarginBlockStart)
+            }
+            PropertyDeclaration::MarginInlineEnd(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MarginInlineEnd)
+            }
+            PropertyDeclaration::MarginInlineStart(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::MarginInlineStart)
+            }
+            PropertyDeclaration::PaddingBlockEnd(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::PaddingBlockEnd)
+            }
+            PropertyDeclaration::PaddingBlockStart(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::PaddingBlockStart)
+            }
+            PropertyDeclaration::PaddingInlineEnd(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::PaddingInlineEnd)
+            }
+            PropertyDeclaration::PaddingInlineStart(_) => {
+                PropertyDeclarationId::Longhand(LonghandId::PaddingInlineStart)
+            }
+            PropertyDeclaration::Right(_) => PropertyDeclarationId::Longhand(LonghandId::Right),
+            PropertyDeclaration::Top(_) => PropertyDeclarationId::Longhand(LonghandId::Top),
+            PropertyDeclaration::CssWideKeyword(wide_keyword_decl) => {
+                PropertyDeclarationId::Longhand(wide_keyword_decl.id)
+            }
+            PropertyDeclaration::WithVariables(var_decl) => {
+                PropertyDeclarationId::Longhand(var_decl.id)
+            }
+            PropertyDeclaration::Custom(custom_decl) => {
+                PropertyDeclarationId::Custom(&custom_decl.name)
+            }
+        }
     }
 }
 
@@ -590,10 +1301,10 @@ pub enum ShorthandId {
     Background = 0,
     //    /// background-position
     //    BackgroundPosition = 1,
-    //    /// border-color
-    //    BorderColor = 2,
-    //    /// border-style
-    //    BorderStyle = 3,
+    /// border-color
+    BorderColor = 2,
+    /// border-style
+    BorderStyle = 3,
     /// border-width
     BorderWidth = 4,
     /// border-top
@@ -604,36 +1315,36 @@ pub enum ShorthandId {
     BorderBottom = 7,
     /// border-left
     BorderLeft = 8,
-    //    /// border-block-start
-    //    BorderBlockStart = 9,
-    //    /// border-block-end
-    //    BorderBlockEnd = 10,
-    //    /// border-inline-start
-    //    BorderInlineStart = 11,
-    //    /// border-inline-end
-    //    BorderInlineEnd = 12,
+    /// border-block-start
+    BorderBlockStart = 9,
+    /// border-block-end
+    BorderBlockEnd = 10,
+    /// border-inline-start
+    BorderInlineStart = 11,
+    /// border-inline-end
+    BorderInlineEnd = 12,
     /// border
     Border = 13,
-    //    /// border-radius
-    //    BorderRadius = 14,
+    /// border-radius
+    BorderRadius = 14,
     //    /// border-image
     //    BorderImage = 15,
-    //    /// border-block-width
-    //    BorderBlockWidth = 16,
-    //    /// border-block-style
-    //    BorderBlockStyle = 17,
-    //    /// border-block-color
-    //    BorderBlockColor = 18,
-    //    /// border-inline-width
-    //    BorderInlineWidth = 19,
-    //    /// border-inline-style
-    //    BorderInlineStyle = 20,
-    //    /// border-inline-color
-    //    BorderInlineColor = 21,
-    //    /// border-block
-    //    BorderBlock = 22,
-    //    /// border-inline
-    //    BorderInline = 23,
+    /// border-block-width
+    BorderBlockWidth = 16,
+    /// border-block-style
+    BorderBlockStyle = 17,
+    /// border-block-color
+    BorderBlockColor = 18,
+    /// border-inline-width
+    BorderInlineWidth = 19,
+    /// border-inline-style
+    BorderInlineStyle = 20,
+    /// border-inline-color
+    BorderInlineColor = 21,
+    /// border-block
+    BorderBlock = 22,
+    /// border-inline
+    BorderInline = 23,
     //    /// overflow
     //    Overflow = 24,
     //    /// transition
@@ -642,38 +1353,206 @@ pub enum ShorthandId {
     //    Animation = 26,
     //    /// columns
     //    Columns = 27,
-    //    /// font
-    //    Font = 28,
+    /// font
+    Font = 28,
     //    /// font-variant
     //    FontVariant = 29,
     //    /// list-style
     //    ListStyle = 30,
     /// margin
     Margin = 31,
-    //    /// margin-block
-    //    MarginBlock = 32,
-    //    /// margin-inline
-    //    MarginInline = 33,
+    /// margin-block
+    MarginBlock = 32,
+    /// margin-inline
+    MarginInline = 33,
     //    /// outline
     //    Outline = 34,
     /// padding
     Padding = 35,
-    //    /// padding-block
-    //    PaddingBlock = 36,
-    //    /// padding-inline
-    //    PaddingInline = 37,
+    /// padding-block
+    PaddingBlock = 36,
+    /// padding-inline
+    PaddingInline = 37,
     //    /// flex-flow
     //    FlexFlow = 38,
     //    /// flex
     //    Flex = 39,
-    //    /// inset
-    //    Inset = 40,
-    //    /// inset-block
-    //    InsetBlock = 41,
-    //    /// inset-inline
-    //    InsetInline = 42,
+    /// inset
+    Inset = 40,
+    /// inset-block
+    InsetBlock = 41,
+    /// inset-inline
+    InsetInline = 42,
     //    /// text-decoration
     //    TextDecoration = 43,
-    //    /// all
-    //    All = 44,
+    /// all
+    All = 44,
+}
+
+impl ShorthandId {
+    /// The longhands this shorthand sets.
+    pub fn longhands(self) -> Vec<LonghandId> {
+        match self {
+            // `all` resets every property except `direction` and `unicode-bidi`.
+            // https://www.w3.org/TR/css-cascade-4/#all-shorthand
+            ShorthandId::All => LonghandId::iter()
+                .filter(|longhand| {
+                    *longhand != LonghandId::Direction && *longhand != LonghandId::UnicodeBidi
+                })
+                .collect(),
+            ShorthandId::Background => vec![LonghandId::BackgroundColor],
+            // `font` also resets the `font-variant` longhands, which aren't supported yet.
+            ShorthandId::Font => vec![
+                LonghandId::FontStyle,
+                LonghandId::FontWeight,
+                LonghandId::FontStretch,
+                LonghandId::FontSize,
+                LonghandId::LineHeight,
+                LonghandId::FontFamily,
+            ],
+            ShorthandId::BorderColor => vec![
+                LonghandId::BorderTopColor,
+                LonghandId::BorderRightColor,
+                LonghandId::BorderBottomColor,
+                LonghandId::BorderLeftColor,
+            ],
+            ShorthandId::BorderStyle => vec![
+                LonghandId::BorderTopStyle,
+                LonghandId::BorderRightStyle,
+                LonghandId::BorderBottomStyle,
+                LonghandId::BorderLeftStyle,
+            ],
+            ShorthandId::BorderWidth => vec![
+                LonghandId::BorderTopWidth,
+                LonghandId::BorderRightWidth,
+                LonghandId::BorderBottomWidth,
+                LonghandId::BorderLeftWidth,
+            ],
+            ShorthandId::BorderTop => vec![
+                LonghandId::BorderTopWidth,
+                LonghandId::BorderTopStyle,
+                LonghandId::BorderTopColor,
+            ],
+            ShorthandId::BorderRight => vec![
+                LonghandId::BorderRightWidth,
+                LonghandId::BorderRightStyle,
+                LonghandId::BorderRightColor,
+            ],
+            ShorthandId::BorderBottom => vec![
+                LonghandId::BorderBottomWidth,
+                LonghandId::BorderBottomStyle,
+                LonghandId::BorderBottomColor,
+            ],
+            ShorthandId::BorderLeft => vec![
+                LonghandId::BorderLeftWidth,
+                LonghandId::BorderLeftStyle,
+                LonghandId::BorderLeftColor,
+            ],
+            ShorthandId::BorderBlockStart => vec![
+                LonghandId::BorderBlockStartWidth,
+                LonghandId::BorderBlockStartStyle,
+                LonghandId::BorderBlockStartColor,
+            ],
+            ShorthandId::BorderBlockEnd => vec![
+                LonghandId::BorderBlockEndWidth,
+                LonghandId::BorderBlockEndStyle,
+                LonghandId::BorderBlockEndColor,
+            ],
+            ShorthandId::BorderInlineStart => vec![
+                LonghandId::BorderInlineStartWidth,
+                LonghandId::BorderInlineStartStyle,
+                LonghandId::BorderInlineStartColor,
+            ],
+            ShorthandId::BorderInlineEnd => vec![
+                LonghandId::BorderInlineEndWidth,
+                LonghandId::BorderInlineEndStyle,
+                LonghandId::BorderInlineEndColor,
+            ],
+            ShorthandId::Border => [
+                ShorthandId::BorderTop,
+                ShorthandId::BorderRight,
+                ShorthandId::BorderBottom,
+                ShorthandId::BorderLeft,
+            ]
+            .iter()
+            .flat_map(|side| side.longhands())
+            .collect(),
+            ShorthandId::BorderRadius => vec![
+                LonghandId::BorderTopLeftRadius,
+                LonghandId::BorderTopRightRadius,
+                LonghandId::BorderBottomRightRadius,
+                LonghandId::BorderBottomLeftRadius,
+            ],
+            ShorthandId::BorderBlockWidth => vec![
+                LonghandId::BorderBlockStartWidth,
+                LonghandId::BorderBlockEndWidth,
+            ],
+            ShorthandId::BorderBlockStyle => vec![
+                LonghandId::BorderBlockStartStyle,
+                LonghandId::BorderBlockEndStyle,
+            ],
+            ShorthandId::BorderBlockColor => vec![
+                LonghandId::BorderBlockStartColor,
+                LonghandId::BorderBlockEndColor,
+            ],
+            ShorthandId::BorderInlineWidth => vec![
+                LonghandId::BorderInlineStartWidth,
+                LonghandId::BorderInlineEndWidth,
+            ],
+            ShorthandId::BorderInlineStyle => vec![
+                LonghandId::BorderInlineStartStyle,
+                LonghandId::BorderInlineEndStyle,
+            ],
+            ShorthandId::BorderInlineColor => vec![
+                LonghandId::BorderInlineStartColor,
+                LonghandId::BorderInlineEndColor,
+            ],
+            ShorthandId::BorderBlock => {
+                [ShorthandId::BorderBlockStart, ShorthandId::BorderBlockEnd]
+                    .iter()
+                    .flat_map(|side| side.longhands())
+                    .collect()
+            }
+            ShorthandId::BorderInline => {
+                [ShorthandId::BorderInlineStart, ShorthandId::BorderInlineEnd]
+                    .iter()
+                    .flat_map(|side| side.longhands())
+                    .collect()
+            }
+            ShorthandId::Margin => vec![
+                LonghandId::MarginTop,
+                LonghandId::MarginRight,
+                LonghandId::MarginBottom,
+                LonghandId::MarginLeft,
+            ],
+            ShorthandId::MarginBlock => {
+                vec![LonghandId::MarginBlockStart, LonghandId::MarginBlockEnd]
+            }
+            ShorthandId::MarginInline => {
+                vec![LonghandId::MarginInlineStart, LonghandId::MarginInlineEnd]
+            }
+            ShorthandId::Padding => vec![
+                LonghandId::PaddingTop,
+                LonghandId::PaddingRight,
+                LonghandId::PaddingBottom,
+                LonghandId::PaddingLeft,
+            ],
+            ShorthandId::PaddingBlock => {
+                vec![LonghandId::PaddingBlockStart, LonghandId::PaddingBlockEnd]
+            }
+            ShorthandId::PaddingInline => {
+                vec![LonghandId::PaddingInlineStart, LonghandId::PaddingInlineEnd]
+            }
+            ShorthandId::Inset => vec![
+                LonghandId::Top,
+                LonghandId::Right,
+                LonghandId::Bottom,
+                LonghandId::Left,
+            ],
+            ShorthandId::InsetBlock => vec![LonghandId::InsetBlockStart, LonghandId::InsetBlockEnd],
+            ShorthandId::InsetInline => {
+                vec![LonghandId::InsetInlineStart, LonghandId::InsetInlineEnd]
+            }
+        }
+    }
 }
@@ -4,27 +4,36 @@ use std::mem;
 
 use cssparser::{
     parse_important, AtRuleParser, CowRcStr, DeclarationListParser, DeclarationParser, Delimiter,
-    ParseError, Parser, SourceLocation,
+    ParseError, Parser, ParserInput, SourceLocation,
 };
 use smallbitvec::SmallBitVec;
 
-use crate::style::properties::id::{LonghandId, PropertyId};
+use crate::style::custom_properties::{
+    self, CustomDeclaration, CustomDeclarationValue, CustomPropertiesMap, Name, VariableValue,
+};
+use crate::style::properties::id::{LonghandId, PropertyDeclarationId, PropertyId, ShorthandId};
 use crate::style::select::Specificity;
 use crate::style::values::computed::direction::WritingMode;
-use crate::style::values::computed::{Direction, Display, LineStyle};
+use crate::style::values::computed::{
+    BoxSizing, Clear, Direction, Display, Float, FontFamily, FontStretch, FontStyle, Hyphens,
+    LineStyle, OverflowWrap, Position, UnicodeBidi, WhiteSpace, WordBreak,
+};
 use crate::style::values::specified::border::{
-    BorderBottomColor, BorderLeftColor, BorderRightColor, BorderTopColor,
+    BorderBottomColor, BorderLeftColor, BorderRightColor, BorderTopColor, LineWidth,
 };
 use crate::style::values::specified::{
-    BackgroundColor, BorderBottomWidth, BorderLeftWidth, BorderRightWidth, BorderTopWidth, Color,
-    FontSize, Height, MarginBottom, MarginLeft, MarginRight, MarginTop, PaddingBottom, PaddingLeft,
-    PaddingRight, PaddingTop, Width,
+    BackgroundColor, BorderBottomWidth, BorderCornerRadius, BorderLeftWidth, BorderRightWidth,
+    BorderTopWidth, Bottom, Color, ColorUnit, FontSize, FontWeight, Height, Left, LengthPercentage,
+    LengthPercentageOrAuto, LineHeight, MarginBottom, MarginLeft, MarginRight, MarginTop,
+    MaxHeight, MaxWidth, MinHeight, MinWidth, PaddingBottom, PaddingLeft, PaddingRight, PaddingTop,
+    Right, Top, Width,
 };
 use crate::style::CascadeOrigin;
-use crate::style::{CssOrigin, StyleParseErrorKind};
-use std::mem::discriminant;
+use crate::style::{CssOrigin, CssWideKeywords, StyleParseErrorKind};
+use crate::{LogicalDirection, Side};
 
 pub mod id;
+mod serialize;
 
 /// Parses raw parser input into a block of property declarations.
 pub fn parse_property_declaration_list(input: &mut Parser) -> PropertyDeclarationBlock {
@@ -116,7 +125,7 @@ impl PropertyDeclarationBlock {
     ) {
         let mut swap_index = None;
         for (i, existing_decl) in self.declarations.iter().enumerate() {
-            if mem::discriminant(existing_decl) == mem::discriminant(&new_decl) {
+            if existing_decl.id() == new_decl.id() {
                 // the props are the same "type", e.g. both `font-size, both `display`, etc
                 // take the `new_decl`, since the latest/newest prop should always be taken
                 swap_index = Some(i);
@@ -160,11 +169,75 @@ impl PropertyDeclaration {
         id: PropertyId,
         input: &mut Parser<'i, 't>,
     ) -> Result<(), ParseError<'i, StyleParseErrorKind<'i>>> {
+        // Any property accepts a CSS-wide keyword as its entire value.
+        if let Ok(keyword) =
+            input.try_parse(|input| -> Result<_, ParseError<StyleParseErrorKind>> {
+                let keyword = CssWideKeywords::parse(input)?;
+                input.expect_exhausted()?;
+                Ok(keyword)
+            })
+        {
+            let longhands = match id {
+                PropertyId::Longhand(longhand) => vec![longhand],
+                PropertyId::Shorthand(short_id) => short_id.longhands(),
+                PropertyId::Custom(name) => {
+                    declarations.push(PropertyDeclaration::Custom(CustomDeclaration {
+                        name,
+                        value: CustomDeclarationValue::CssWideKeyword(keyword),
+                    }));
+                    return Ok(());
+                }
+            };
+            declarations.extend(longhands.into_iter().map(|id| {
+                PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration { id, keyword })
+            }));
+            return Ok(());
+        }
+        if let PropertyId::Custom(name) = id {
+            declarations.push(PropertyDeclaration::Custom(CustomDeclaration {
+                name,
+                value: CustomDeclarationValue::Value(VariableValue::parse(input)?),
+            }));
+            return Ok(());
+        }
+        // Values referencing custom properties can't be parsed until those references have been
+        // substituted at computed-value time, so keep them around unparsed until then.
+        let state = input.state();
+        if let Ok(value) = VariableValue::parse(input) {
+            if value.has_references() {
+                let (longhands, from_shorthand) = match id {
+                    PropertyId::Longhand(longhand) => (vec![longhand], None),
+                    PropertyId::Shorthand(short_id) => (short_id.longhands(), Some(short_id)),
+                    PropertyId::Custom(_) => unreachable!(),
+                };
+                declarations.extend(longhands.into_iter().map(|id| {
+                    PropertyDeclaration::WithVariables(VariableDeclaration {
+                        id,
+                        from_shorthand,
+                        value: value.clone(),
+                    })
+                }));
+                return Ok(());
+            }
+        }
+        input.reset(&state);
         match id {
             PropertyId::Longhand(longhand) => match longhand {
                 LonghandId::BackgroundColor => declarations.push(
                     PropertyDeclaration::BackgroundColor(BackgroundColor::parse(input)?),
                 ),
+                LonghandId::BorderBlockEndColor => declarations.push(
+                    PropertyDeclaration::BorderBlockEndColor(ColorUnit::parse(input)?),
+                ),
+                LonghandId::BorderBlockStartColor => declarations.push(
+                    PropertyDeclaration::BorderBlockStartColor(ColorUnit::parse(input)?),
+                ),
+                LonghandId::BorderInlineEndColor => declarations.push(
+                    PropertyDeclaration::BorderInlineEndColor(ColorUnit::parse(input)?),
+                ),
+                LonghandId::BorderInlineStartColor => declarations.push(
+                    PropertyDeclaration::BorderInlineStartColor(ColorUnit::parse(input)?),
+                ),
                 LonghandId::BorderBottomColor => declarations.push(
                     PropertyDeclaration::BorderBottomColor(BorderBottomColor::parse(input)?),
                 ),
@@ -177,6 +250,30 @@ impl PropertyDeclaration {
                 LonghandId::BorderTopColor => declarations.push(
                     PropertyDeclaration::BorderTopColor(BorderTopColor::parse(input)?),
                 ),
+                LonghandId::BorderBottomLeftRadius => declarations.push(
+                    PropertyDeclaration::BorderBottomLeftRadius(BorderCornerRadius::parse(input)?),
+                ),
+                LonghandId::BorderBottomRightRadius => declarations.push(
+                    PropertyDeclaration::BorderBottomRightRadius(BorderCornerRadius::parse(input)?),
+                ),
+                LonghandId::BorderTopLeftRadius => declarations.push(
+                    PropertyDeclaration::BorderTopLeftRadius(BorderCornerRadius::parse(input)?),
+                ),
+                LonghandId::BorderTopRightRadius => declarations.push(
+                    PropertyDeclaration::BorderTopRightRadius(BorderCornerRadius::parse(input)?),
+                ),
+                LonghandId::BorderBlockEndStyle => declarations.push(
+                    PropertyDeclaration::BorderBlockEndStyle(LineStyle::parse(input)?),
+                ),
+                LonghandId::BorderBlockStartStyle => declarations.push(
+                    PropertyDeclaration::BorderBlockStartStyle(LineStyle::parse(input)?),
+                ),
+                LonghandId::BorderInlineEndStyle => declarations.push(
+                    PropertyDeclaration::BorderInlineEndStyle(LineStyle::parse(input)?),
+                ),
+                LonghandId::BorderInlineStartStyle => declarations.push(
+                    PropertyDeclaration::BorderInlineStartStyle(LineStyle::parse(input)?),
+                ),
                 LonghandId::BorderBottomStyle => declarations.push(
                     PropertyDeclaration::BorderBottomStyle(LineStyle::parse(input)?),
                 ),
@@ -189,6 +286,18 @@ impl PropertyDeclaration {
                 LonghandId::BorderTopStyle => declarations.push(
                     PropertyDeclaration::BorderTopStyle(LineStyle::parse(input)?),
                 ),
+                LonghandId::BorderBlockEndWidth => declarations.push(
+                    PropertyDeclaration::BorderBlockEndWidth(LineWidth::parse(input)?),
+                ),
+                LonghandId::BorderBlockStartWidth => declarations.push(
+                    PropertyDeclaration::BorderBlockStartWidth(LineWidth::parse(input)?),
+                ),
+                LonghandId::BorderInlineEndWidth => declarations.push(
+                    PropertyDeclaration::BorderInlineEndWidth(LineWidth::parse(input)?),
+                ),
+                LonghandId::BorderInlineStartWidth => declarations.push(
+                    PropertyDeclaration::BorderInlineStartWidth(LineWidth::parse(input)?),
+                ),
                 LonghandId::BorderBottomWidth => declarations.push(
                     PropertyDeclaration::BorderBottomWidth(BorderBottomWidth::parse(input)?),
                 ),
@@ -201,6 +310,15 @@ impl PropertyDeclaration {
                 LonghandId::BorderTopWidth => declarations.push(
                     PropertyDeclaration::BorderTopWidth(BorderTopWidth::parse(input)?),
                 ),
+                LonghandId::Bottom => {
+                    declarations.push(PropertyDeclaration::Bottom(Bottom::parse(input)?))
+                }
+                LonghandId::BoxSizing => {
+                    declarations.push(PropertyDeclaration::BoxSizing(BoxSizing::parse(input)?))
+                }
+                LonghandId::Clear => {
+                    declarations.push(PropertyDeclaration::Clear(Clear::parse(input)?))
+                }
                 LonghandId::Color => {
                     declarations.push(PropertyDeclaration::Color(Color::parse(input)?))
                 }
@@ -210,17 +328,62 @@ impl PropertyDeclaration {
                 LonghandId::Display => {
                     declarations.push(PropertyDeclaration::Display(Display::parse(input)?))
                 }
+                LonghandId::Float => {
+                    declarations.push(PropertyDeclaration::Float(Float::parse(input)?))
+                }
+                LonghandId::FontFamily => {
+                    declarations.push(PropertyDeclaration::FontFamily(FontFamily::parse(input)?));
+                }
                 LonghandId::FontSize => {
                     declarations.push(PropertyDeclaration::FontSize(FontSize::parse(input)?));
                 }
+                LonghandId::FontStretch => {
+                    declarations.push(PropertyDeclaration::FontStretch(FontStretch::parse(input)?))
+                }
+                LonghandId::FontStyle => {
+                    declarations.push(PropertyDeclaration::FontStyle(FontStyle::parse(input)?));
+                }
+                LonghandId::FontWeight => {
+                    declarations.push(PropertyDeclaration::FontWeight(FontWeight::parse(input)?));
+                }
                 LonghandId::Height => {
                     declarations.push(PropertyDeclaration::Height(Height::parse(input)?));
                 }
+                LonghandId::InsetBlockEnd => declarations.push(PropertyDeclaration::InsetBlockEnd(
+                    LengthPercentageOrAuto::parse(input)?,
+                )),
+                LonghandId::InsetBlockStart => declarations.push(
+                    PropertyDeclaration::InsetBlockStart(LengthPercentageOrAuto::parse(input)?),
+                ),
+                LonghandId::InsetInlineEnd => declarations.push(
+                    PropertyDeclaration::InsetInlineEnd(LengthPercentageOrAuto::parse(input)?),
+                ),
+                LonghandId::InsetInlineStart => declarations.push(
+                    PropertyDeclaration::InsetInlineStart(LengthPercentageOrAuto::parse(input)?),
+                ),
+                LonghandId::Left => {
+                    declarations.push(PropertyDeclaration::Left(Left::parse(input)?))
+                }
+                LonghandId::LineHeight => {
+                    declarations.push(PropertyDeclaration::LineHeight(LineHeight::parse(input)?))
+                }
+                LonghandId::MarginBlockEnd => declarations.push(
+                    PropertyDeclaration::MarginBlockEnd(LengthPercentageOrAuto::parse(input)?),
+                ),
+                LonghandId::MarginBlockStart => declarations.push(
+                    PropertyDeclaration::MarginBlockStart(LengthPercentageOrAuto::parse(input)?),
+                ),
                 LonghandId::MarginBottom => {
                     declarations.push(PropertyDeclaration::MarginBottom(MarginBottom::parse(
                         input,
                     )?));
                 }
+                LonghandId::MarginInlineEnd => declarations.push(
+                    PropertyDeclaration::MarginInlineEnd(LengthPercentageOrAuto::parse(input)?),
+                ),
+                LonghandId::MarginInlineStart => declarations.push(
+                    PropertyDeclaration::MarginInlineStart(LengthPercentageOrAuto::parse(input)?),
+                ),
                 LonghandId::MarginLeft => {
                     declarations.push(PropertyDeclaration::MarginLeft(MarginLeft::parse(input)?));
                 }
@@ -230,11 +393,35 @@ impl PropertyDeclaration {
                 LonghandId::MarginTop => {
                     declarations.push(PropertyDeclaration::MarginTop(MarginTop::parse(input)?));
                 }
+                LonghandId::MaxHeight => {
+                    declarations.push(PropertyDeclaration::MaxHeight(MaxHeight::parse(input)?));
+                }
+                LonghandId::MaxWidth => {
+                    declarations.push(PropertyDeclaration::MaxWidth(MaxWidth::parse(input)?));
+                }
+                LonghandId::MinHeight => {
+                    declarations.push(PropertyDeclaration::MinHeight(MinHeight::parse(input)?));
+                }
+                LonghandId::MinWidth => {
+                    declarations.push(PropertyDeclaration::MinWidth(MinWidth::parse(input)?));
+                }
+                LonghandId::PaddingBlockEnd => declarations.push(
+                    PropertyDeclaration::PaddingBlockEnd(LengthPercentage::parse(input)?),
+                ),
+                LonghandId::PaddingBlockStart => declarations.push(
+                    PropertyDeclaration::PaddingBlockStart(LengthPercentage::parse(input)?),
+                ),
                 LonghandId::PaddingBottom => {
                     declarations.push(PropertyDeclaration::PaddingBottom(PaddingBottom::parse(
                         input,
                     )?));
                 }
+                LonghandId::PaddingInlineEnd => declarations.push(
+                    PropertyDeclaration::PaddingInlineEnd(LengthPercentage::parse(input)?),
+                ),
+                LonghandId::PaddingInlineStart => declarations.push(
+                    PropertyDeclaration::PaddingInlineStart(LengthPercentage::parse(input)?),
+                ),
                 LonghandId::PaddingLeft => {
                     declarations.push(PropertyDeclaration::PaddingLeft(PaddingLeft::parse(input)?));
                 }
@@ -246,9 +433,33 @@ impl PropertyDeclaration {
                 LonghandId::PaddingTop => {
                     declarations.push(PropertyDeclaration::PaddingTop(PaddingTop::parse(input)?));
                 }
+                LonghandId::Position => {
+                    declarations.push(PropertyDeclaration::Position(Position::parse(input)?))
+                }
+                LonghandId::Right => {
+                    declarations.push(PropertyDeclaration::Right(Right::parse(input)?))
+                }
+                LonghandId::Top => declarations.push(PropertyDeclaration::Top(Top::parse(input)?)),
+                LonghandId::UnicodeBidi => {
+                    declarations.push(PropertyDeclaration::UnicodeBidi(UnicodeBidi::parse(input)?));
+                }
+                LonghandId::WhiteSpace => {
+                    declarations.push(PropertyDeclaration::WhiteSpace(WhiteSpace::parse(input)?));
+                }
                 LonghandId::Width => {
                     declarations.push(PropertyDeclaration::Width(Width::parse(input)?));
                 }
+                LonghandId::WordBreak => {
+                    declarations.push(PropertyDeclaration::WordBreak(WordBreak::parse(input)?));
+                }
+                LonghandId::Hyphens => {
+                    declarations.push(PropertyDeclaration::Hyphens(Hyphens::parse(input)?));
+                }
+                LonghandId::OverflowWrap => {
+                    declarations.push(PropertyDeclaration::OverflowWrap(OverflowWrap::parse(
+                        input,
+                    )?));
+                }
                 LonghandId::WritingMode => {
                     declarations.push(PropertyDeclaration::WritingMode(WritingMode::parse(input)?));
                 }
@@ -257,43 +468,712 @@ impl PropertyDeclaration {
                     format!("value default by longhand for id: {:?}", longhand)
                 ),
             },
-            PropertyId::Shorthand(_short_id) => {}
+            PropertyId::Shorthand(short_id) => match short_id {
+                ShorthandId::Background => {
+                    // `background-color` is the only background longhand currently supported, so
+                    // it's the only value this shorthand can set.
+                    declarations.push(PropertyDeclaration::BackgroundColor(
+                        BackgroundColor::parse(input)?,
+                    ))
+                }
+                ShorthandId::Font => declarations.extend(parse_font(input)?),
+                ShorthandId::Margin => {
+                    let [top, right, bottom, left] =
+                        parse_sides(input, LengthPercentageOrAuto::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::MarginTop(MarginTop { lp_or_auto: top }),
+                        PropertyDeclaration::MarginRight(MarginRight { lp_or_auto: right }),
+                        PropertyDeclaration::MarginBottom(MarginBottom { lp_or_auto: bottom }),
+                        PropertyDeclaration::MarginLeft(MarginLeft { lp_or_auto: left }),
+                    ]);
+                }
+                ShorthandId::MarginBlock => {
+                    let [start, end] = parse_start_end(input, LengthPercentageOrAuto::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::MarginBlockStart(start),
+                        PropertyDeclaration::MarginBlockEnd(end),
+                    ]);
+                }
+                ShorthandId::MarginInline => {
+                    let [start, end] = parse_start_end(input, LengthPercentageOrAuto::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::MarginInlineStart(start),
+                        PropertyDeclaration::MarginInlineEnd(end),
+                    ]);
+                }
+                ShorthandId::Padding => {
+                    let [top, right, bottom, left] = parse_sides(input, LengthPercentage::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::PaddingTop(PaddingTop {
+                            length_percentage: top,
+                        }),
+                        PropertyDeclaration::PaddingRight(PaddingRight {
+                            length_percentage: right,
+                        }),
+                        PropertyDeclaration::PaddingBottom(PaddingBottom {
+                            length_percentage: bottom,
+                        }),
+                        PropertyDeclaration::PaddingLeft(PaddingLeft {
+                            length_percentage: left,
+                        }),
+                    ]);
+                }
+                ShorthandId::PaddingBlock => {
+                    let [start, end] = parse_start_end(input, LengthPercentage::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::PaddingBlockStart(start),
+                        PropertyDeclaration::PaddingBlockEnd(end),
+                    ]);
+                }
+                ShorthandId::PaddingInline => {
+                    let [start, end] = parse_start_end(input, LengthPercentage::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::PaddingInlineStart(start),
+                        PropertyDeclaration::PaddingInlineEnd(end),
+                    ]);
+                }
+                ShorthandId::Inset => {
+                    let [top, right, bottom, left] =
+                        parse_sides(input, LengthPercentageOrAuto::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::Top(Top { lp_or_auto: top }),
+                        PropertyDeclaration::Right(Right { lp_or_auto: right }),
+                        PropertyDeclaration::Bottom(Bottom { lp_or_auto: bottom }),
+                        PropertyDeclaration::Left(Left { lp_or_auto: left }),
+                    ]);
+                }
+                ShorthandId::InsetBlock => {
+                    let [start, end] = parse_start_end(input, LengthPercentageOrAuto::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::InsetBlockStart(start),
+                        PropertyDeclaration::InsetBlockEnd(end),
+                    ]);
+                }
+                ShorthandId::InsetInline => {
+                    let [start, end] = parse_start_end(input, LengthPercentageOrAuto::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::InsetInlineStart(start),
+                        PropertyDeclaration::InsetInlineEnd(end),
+                    ]);
+                }
+                ShorthandId::BorderColor => {
+                    let [top, right, bottom, left] = parse_sides(input, ColorUnit::parse)?;
+                    declarations.extend(vec![
+                        border_color_declaration(Side::Top, top),
+                        border_color_declaration(Side::Right, right),
+                        border_color_declaration(Side::Bottom, bottom),
+                        border_color_declaration(Side::Left, left),
+                    ]);
+                }
+                ShorthandId::BorderStyle => {
+                    let [top, right, bottom, left] = parse_sides(input, LineStyle::parse)?;
+                    declarations.extend(vec![
+                        border_style_declaration(Side::Top, top),
+                        border_style_declaration(Side::Right, right),
+                        border_style_declaration(Side::Bottom, bottom),
+                        border_style_declaration(Side::Left, left),
+                    ]);
+                }
+                ShorthandId::BorderWidth => {
+                    let [top, right, bottom, left] = parse_sides(input, LineWidth::parse)?;
+                    declarations.extend(vec![
+                        border_width_declaration(Side::Top, top),
+                        border_width_declaration(Side::Right, right),
+                        border_width_declaration(Side::Bottom, bottom),
+                        border_width_declaration(Side::Left, left),
+                    ]);
+                }
+                ShorthandId::BorderTop => declarations.extend(border_side_declarations(
+                    Side::Top,
+                    parse_border_side(input)?,
+                )),
+                ShorthandId::BorderRight => declarations.extend(border_side_declarations(
+                    Side::Right,
+                    parse_border_side(input)?,
+                )),
+                ShorthandId::BorderBottom => declarations.extend(border_side_declarations(
+                    Side::Bottom,
+                    parse_border_side(input)?,
+                )),
+                ShorthandId::BorderLeft => declarations.extend(border_side_declarations(
+                    Side::Left,
+                    parse_border_side(input)?,
+                )),
+                ShorthandId::BorderBlockStart => {
+                    let (line_width, style, color) = parse_border_side(input)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderBlockStartWidth(line_width),
+                        PropertyDeclaration::BorderBlockStartStyle(style),
+                        PropertyDeclaration::BorderBlockStartColor(color),
+                    ]);
+                }
+                ShorthandId::BorderBlockEnd => {
+                    let (line_width, style, color) = parse_border_side(input)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderBlockEndWidth(line_width),
+                        PropertyDeclaration::BorderBlockEndStyle(style),
+                        PropertyDeclaration::BorderBlockEndColor(color),
+                    ]);
+                }
+                ShorthandId::BorderInlineStart => {
+                    let (line_width, style, color) = parse_border_side(input)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderInlineStartWidth(line_width),
+                        PropertyDeclaration::BorderInlineStartStyle(style),
+                        PropertyDeclaration::BorderInlineStartColor(color),
+                    ]);
+                }
+                ShorthandId::BorderInlineEnd => {
+                    let (line_width, style, color) = parse_border_side(input)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderInlineEndWidth(line_width),
+                        PropertyDeclaration::BorderInlineEndStyle(style),
+                        PropertyDeclaration::BorderInlineEndColor(color),
+                    ]);
+                }
+                ShorthandId::Border => {
+                    let side = parse_border_side(input)?;
+                    for &physical_side in &[Side::Top, Side::Right, Side::Bottom, Side::Left] {
+                        declarations.extend(border_side_declarations(physical_side, side));
+                    }
+                }
+                ShorthandId::BorderRadius => {
+                    let horizontal = parse_sides(input, LengthPercentage::parse)?;
+                    let vertical = if input.try_parse(|input| input.expect_delim('/')).is_ok() {
+                        parse_sides(input, LengthPercentage::parse)?
+                    } else {
+                        horizontal.clone()
+                    };
+                    // Corners are listed clockwise from the top left, like sides from the top.
+                    let corner = |i: usize| BorderCornerRadius {
+                        horizontal: horizontal[i].clone(),
+                        vertical: vertical[i].clone(),
+                    };
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderTopLeftRadius(corner(0)),
+                        PropertyDeclaration::BorderTopRightRadius(corner(1)),
+                        PropertyDeclaration::BorderBottomRightRadius(corner(2)),
+                        PropertyDeclaration::BorderBottomLeftRadius(corner(3)),
+                    ]);
+                }
+                ShorthandId::BorderBlockWidth => {
+                    let [start, end] = parse_start_end(input, LineWidth::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderBlockStartWidth(start),
+                        PropertyDeclaration::BorderBlockEndWidth(end),
+                    ]);
+                }
+                ShorthandId::BorderBlockStyle => {
+                    let [start, end] = parse_start_end(input, LineStyle::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderBlockStartStyle(start),
+                        PropertyDeclaration::BorderBlockEndStyle(end),
+                    ]);
+                }
+                ShorthandId::BorderBlockColor => {
+                    let [start, end] = parse_start_end(input, ColorUnit::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderBlockStartColor(start),
+                        PropertyDeclaration::BorderBlockEndColor(end),
+                    ]);
+                }
+                ShorthandId::BorderInlineWidth => {
+                    let [start, end] = parse_start_end(input, LineWidth::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderInlineStartWidth(start),
+                        PropertyDeclaration::BorderInlineEndWidth(end),
+                    ]);
+                }
+                ShorthandId::BorderInlineStyle => {
+                    let [start, end] = parse_start_end(input, LineStyle::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderInlineStartStyle(start),
+                        PropertyDeclaration::BorderInlineEndStyle(end),
+                    ]);
+                }
+                ShorthandId::BorderInlineColor => {
+                    let [start, end] = parse_start_end(input, ColorUnit::parse)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderInlineStartColor(start),
+                        PropertyDeclaration::BorderInlineEndColor(end),
+                    ]);
+                }
+                ShorthandId::BorderBlock => {
+                    let (line_width, style, color) = parse_border_side(input)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderBlockStartWidth(line_width),
+                        PropertyDeclaration::BorderBlockStartStyle(style),
+                        PropertyDeclaration::BorderBlockStartColor(color),
+                        PropertyDeclaration::BorderBlockEndWidth(line_width),
+                        PropertyDeclaration::BorderBlockEndStyle(style),
+                        PropertyDeclaration::BorderBlockEndColor(color),
+                    ]);
+                }
+                ShorthandId::BorderInline => {
+                    let (line_width, style, color) = parse_border_side(input)?;
+                    declarations.extend(vec![
+                        PropertyDeclaration::BorderInlineStartWidth(line_width),
+                        PropertyDeclaration::BorderInlineStartStyle(style),
+                        PropertyDeclaration::BorderInlineStartColor(color),
+                        PropertyDeclaration::BorderInlineEndWidth(line_width),
+                        PropertyDeclaration::BorderInlineEndStyle(style),
+                        PropertyDeclaration::BorderInlineEndColor(color),
+                    ]);
+                }
+                // `all` only accepts CSS-wide keywords, which were handled above.
+                ShorthandId::All => return Err(input.new_error_for_next_token()),
+            },
+            PropertyId::Custom(_) => unreachable!(),
         }
         Ok(())
     }
 }
 
+/// Parses the one to four values of a shorthand like `margin` into its `[top, right, bottom, left]`
+/// sides.  Omitted sides are copied from the opposite side, and if only one value is given, it
+/// applies to every side.
+///
+/// https://www.w3.org/TR/2018/WD-css-box-3-20181218/#margin-shorthand
+fn parse_sides<'i, 't, T, F>(
+    input: &mut Parser<'i, 't>,
+    parse: F,
+) -> Result<[T; 4], ParseError<'i, StyleParseErrorKind<'i>>>
+where
+    T: Clone,
+    F: Fn(&mut Parser<'i, 't>) -> Result<T, ParseError<'i, StyleParseErrorKind<'i>>>,
+{
+    let top = parse(input)?;
+    let right = match input.try_parse(&parse) {
+        Ok(right) => right,
+        Err(_) => return Ok([top.clone(), top.clone(), top.clone(), top]),
+    };
+    let bottom = match input.try_parse(&parse) {
+        Ok(bottom) => bottom,
+        Err(_) => return Ok([top.clone(), right.clone(), top, right]),
+    };
+    let left = input.try_parse(&parse).unwrap_or_else(|_| right.clone());
+    Ok([top, right, bottom, left])
+}
+
+/// Parses the `<line-width> || <line-style> || <color>` value of a shorthand like `border-top`.
+/// The components may appear in any order, and those that are omitted are set to their initial
+/// values.
+///
+/// https://www.w3.org/TR/2017/CR-css-backgrounds-3-20171017/#the-border-shorthands
+fn parse_border_side<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<(LineWidth, LineStyle, ColorUnit), ParseError<'i, StyleParseErrorKind<'i>>> {
+    let (mut line_width, mut style, mut color) = (None, None, None);
+    loop {
+        if line_width.is_none() {
+            if let Ok(value) = input.try_parse(LineWidth::parse) {
+                line_width = Some(value);
+                continue;
+            }
+        }
+        if style.is_none() {
+            if let Ok(value) = input.try_parse(LineStyle::parse) {
+                style = Some(value);
+                continue;
+            }
+        }
+        if color.is_none() {
+            if let Ok(value) = input.try_parse(ColorUnit::parse) {
+                color = Some(value);
+                continue;
+            }
+        }
+        break;
+    }
+    if line_width.is_none() && style.is_none() && color.is_none() {
+        return Err(input.new_error_for_next_token());
+    }
+    Ok((
+        line_width.unwrap_or(LineWidth::Medium),
+        style.unwrap_or(LineStyle::None),
+        color.unwrap_or(ColorUnit::CurrentColor),
+    ))
+}
+
+/// Parses the `font` shorthand into declarations of the longhands it sets.
+///
+/// `font-variant` isn't supported yet, so `small-caps` is rejected.
+///
+/// https://drafts.csswg.org/css-fonts-4/#font-prop
+fn parse_font<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Vec<PropertyDeclaration>, ParseError<'i, StyleParseErrorKind<'i>>> {
+    let (mut style, mut weight, mut stretch) = (None, None, None);
+    // Any of the values preceding the size may be `normal`, which leaves its property (whichever
+    // that ends up being) at its initial value.
+    let mut preceding_values = 0;
+    while preceding_values < 4 {
+        preceding_values += 1;
+        if input
+            .try_parse(|i| i.expect_ident_matching("normal"))
+            .is_ok()
+        {
+            continue;
+        }
+        if style.is_none() {
+            if let Ok(value) = input.try_parse(FontStyle::parse) {
+                style = Some(value);
+                continue;
+            }
+        }
+        if weight.is_none() {
+            if let Ok(value) = input.try_parse(FontWeight::parse) {
+                weight = Some(value);
+                continue;
+            }
+        }
+        if stretch.is_none() {
+            if let Ok(value) = input.try_parse(FontStretch::parse_keyword) {
+                stretch = Some(value);
+                continue;
+            }
+        }
+        break;
+    }
+    let size = FontSize::parse(input)?;
+    let line_height = if input.try_parse(|i| i.expect_delim('/')).is_ok() {
+        LineHeight::parse(input)?
+    } else {
+        LineHeight::Normal
+    };
+    let family = FontFamily::parse(input)?;
+    Ok(vec![
+        PropertyDeclaration::FontStyle(style.unwrap_or_else(FontStyle::initial_value)),
+        PropertyDeclaration::FontWeight(weight.unwrap_or(FontWeight::Absolute(400.))),
+        PropertyDeclaration::FontStretch(stretch.unwrap_or_else(FontStretch::initial_value)),
+        PropertyDeclaration::FontSize(size),
+        PropertyDeclaration::LineHeight(line_height),
+        PropertyDeclaration::FontFamily(family),
+    ])
+}
+
+/// The declarations of the `border-<side>-width`, `border-<side>-style` and `border-<side>-color`
+/// longhands for the given side.
+fn border_side_declarations(
+    side: Side,
+    (line_width, style, color): (LineWidth, LineStyle, ColorUnit),
+) -> Vec<PropertyDeclaration> {
+    vec![
+        border_width_declaration(side, line_width),
+        border_style_declaration(side, style),
+        border_color_declaration(side, color),
+    ]
+}
+
+/// Parses the one or two values of a flow-relative shorthand like `margin-inline` into its
+/// `[start, end]` sides.  If only one value is given, it applies to both sides.
+///
+/// https://drafts.csswg.org/css-logical/#margin-properties
+fn parse_start_end<'i, 't, T, F>(
+    input: &mut Parser<'i, 't>,
+    parse: F,
+) -> Result<[T; 2], ParseError<'i, StyleParseErrorKind<'i>>>
+where
+    T: Clone,
+    F: Fn(&mut Parser<'i, 't>) -> Result<T, ParseError<'i, StyleParseErrorKind<'i>>>,
+{
+    let start = parse(input)?;
+    let end = input.try_parse(&parse).unwrap_or_else(|_| start.clone());
+    Ok([start, end])
+}
+
 #[derive(Clone, Debug)]
 #[repr(u16)]
 pub enum PropertyDeclaration {
     BackgroundColor(crate::style::values::specified::BackgroundColor),
+    BorderBlockEndColor(crate::style::values::specified::ColorUnit),
+    BorderBlockStartColor(crate::style::values::specified::ColorUnit),
+    BorderInlineEndColor(crate::style::values::specified::ColorUnit),
+    BorderInlineStartColor(crate::style::values::specified::ColorUnit),
     BorderBottomColor(crate::style::values::specified::BorderBottomColor),
     BorderLeftColor(crate::style::values::specified::BorderLeftColor),
     BorderRightColor(crate::style::values::specified::BorderRightColor),
     BorderTopColor(crate::style::values::specified::BorderTopColor),
+    BorderBottomLeftRadius(crate::style::values::specified::BorderCornerRadius),
+    BorderBottomRightRadius(crate::style::values::specified::BorderCornerRadius),
+    BorderTopLeftRadius(crate::style::values::specified::BorderCornerRadius),
+    BorderTopRightRadius(crate::style::values::specified::BorderCornerRadius),
+    BorderBlockEndStyle(crate::style::values::computed::LineStyle),
+    BorderBlockStartStyle(crate::style::values::computed::LineStyle),
+    BorderInlineEndStyle(crate::style::values::computed::LineStyle),
+    BorderInlineStartStyle(crate::style::values::computed::LineStyle),
     BorderBottomStyle(crate::style::values::computed::LineStyle),
     BorderLeftStyle(crate::style::values::computed::LineStyle),
     BorderRightStyle(crate::style::values::computed::LineStyle),
     BorderTopStyle(crate::style::values::computed::LineStyle),
+    BorderBlockEndWidth(crate::style::values::specified::border::LineWidth),
+    BorderBlockStartWidth(crate::style::values::specified::border::LineWidth),
+    BorderInlineEndWidth(crate::style::values::specified::border::LineWidth),
+    BorderInlineStartWidth(crate::style::values::specified::border::LineWidth),
     BorderBottomWidth(crate::style::values::specified::BorderBottomWidth),
     BorderLeftWidth(crate::style::values::specified::BorderLeftWidth),
     BorderRightWidth(crate::style::values::specified::BorderRightWidth),
     BorderTopWidth(crate::style::values::specified::BorderTopWidth),
+    Bottom(crate::style::values::specified::Bottom),
+    BoxSizing(crate::style::values::computed::BoxSizing),
+    Clear(crate::style::values::computed::Clear),
     Color(crate::style::values::specified::Color),
     Direction(crate::style::values::computed::Direction),
     Display(crate::style::values::computed::Display),
+    Float(crate::style::values::computed::Float),
+    FontFamily(crate::style::values::computed::FontFamily),
     FontSize(crate::style::values::specified::FontSize),
+    FontStretch(crate::style::values::computed::FontStretch),
+    FontStyle(crate::style::values::computed::FontStyle),
+    FontWeight(crate::style::values::specified::FontWeight),
     Height(crate::style::values::specified::Height),
+    InsetBlockEnd(crate::style::values::specified::LengthPercentageOrAuto),
+    InsetBlockStart(crate::style::values::specified::LengthPercentageOrAuto),
+    InsetInlineEnd(crate::style::values::specified::LengthPercentageOrAuto),
+    InsetInlineStart(crate::style::values::specified::LengthPercentageOrAuto),
+    Left(crate::style::values::specified::Left),
+    LineHeight(crate::style::values::specified::LineHeight),
+    MarginBlockEnd(crate::style::values::specified::LengthPercentageOrAuto),
+    MarginBlockStart(crate::style::values::specified::LengthPercentageOrAuto),
     MarginBottom(crate::style::values::specified::MarginBottom),
+    MarginInlineEnd(crate::style::values::specified::LengthPercentageOrAuto),
+    MarginInlineStart(crate::style::values::specified::LengthPercentageOrAuto),
     MarginLeft(crate::style::values::specified::MarginLeft),
     MarginRight(crate::style::values::specified::MarginRight),
     MarginTop(crate::style::values::specified::MarginTop),
+    MaxHeight(crate::style::values::specified::MaxHeight),
+    MaxWidth(crate::style::values::specified::MaxWidth),
+    MinHeight(crate::style::values::specified::MinHeight),
+    MinWidth(crate::style::values::specified::MinWidth),
+    PaddingBlockEnd(crate::style::values::specified::LengthPercentage),
+    PaddingBlockStart(crate::style::values::specified::LengthPercentage),
     PaddingBottom(crate::style::values::specified::PaddingBottom),
+    PaddingInlineEnd(crate::style::values::specified::LengthPercentage),
+    PaddingInlineStart(crate::style::values::specified::LengthPercentage),
     PaddingLeft(crate::style::values::specified::PaddingLeft),
     PaddingRight(crate::style::values::specified::PaddingRight),
     PaddingTop(crate::style::values::specified::PaddingTop),
+    Position(crate::style::values::computed::Position),
+    Right(crate::style::values::specified::Right),
+    Top(crate::style::values::specified::Top),
+    UnicodeBidi(crate::style::values::computed::UnicodeBidi),
+    WhiteSpace(crate::style::values::computed::WhiteSpace),
     Width(crate::style::values::specified::Width),
+    WordBreak(crate::style::values::computed::WordBreak),
+    Hyphens(crate::style::values::computed::Hyphens),
+    OverflowWrap(crate::style::values::computed::OverflowWrap),
     WritingMode(crate::style::values::computed::WritingMode),
+    CssWideKeyword(WideKeywordDeclaration),
+    WithVariables(VariableDeclaration),
+    Custom(CustomDeclaration),
+}
+
+impl PropertyDeclaration {
+    /// Converts a declaration of a flow-relative longhand into a declaration of the physical
+    /// longhand it maps to for an element with the given `writing_mode` and `direction`.  Any
+    /// other declaration is returned unchanged.
+    ///
+    /// https://drafts.csswg.org/css-logical/#box
+    pub fn to_physical(
+        self,
+        writing_mode: WritingMode,
+        direction: Direction,
+    ) -> PropertyDeclaration {
+        use LogicalDirection::{BlockEnd, BlockStart, InlineEnd, InlineStart};
+        let side = |logical: LogicalDirection| logical.physical_side(writing_mode, direction);
+        match self {
+            PropertyDeclaration::BorderBlockEndColor(color) => {
+                border_color_declaration(side(BlockEnd), color)
+            }
+            PropertyDeclaration::BorderBlockStartColor(color) => {
+                border_color_declaration(side(BlockStart), color)
+            }
+            PropertyDeclaration::BorderInlineEndColor(color) => {
+                border_color_declaration(side(InlineEnd), color)
+            }
+            PropertyDeclaration::BorderInlineStartColor(color) => {
+                border_color_declaration(side(InlineStart), color)
+            }
+            PropertyDeclaration::BorderBlockEndStyle(style) => {
+                border_style_declaration(side(BlockEnd), style)
+            }
+            PropertyDeclaration::BorderBlockStartStyle(style) => {
+                border_style_declaration(side(BlockStart), style)
+            }
+            PropertyDeclaration::BorderInlineEndStyle(style) => {
+                border_style_declaration(side(InlineEnd), style)
+            }
+            PropertyDeclaration::BorderInlineStartStyle(style) => {
+                border_style_declaration(side(InlineStart), style)
+            }
+            PropertyDeclaration::BorderBlockEndWidth(line_width) => {
+                border_width_declaration(side(BlockEnd), line_width)
+            }
+            PropertyDeclaration::BorderBlockStartWidth(line_width) => {
+                border_width_declaration(side(BlockStart), line_width)
+            }
+            PropertyDeclaration::BorderInlineEndWidth(line_width) => {
+                border_width_declaration(side(InlineEnd), line_width)
+            }
+            PropertyDeclaration::BorderInlineStartWidth(line_width) => {
+                border_width_declaration(side(InlineStart), line_width)
+            }
+            PropertyDeclaration::InsetBlockEnd(lp_or_auto) => {
+                inset_declaration(side(BlockEnd), lp_or_auto)
+            }
+            PropertyDeclaration::InsetBlockStart(lp_or_auto) => {
+                inset_declaration(side(BlockStart), lp_or_auto)
+            }
+            PropertyDeclaration::InsetInlineEnd(lp_or_auto) => {
+                inset_declaration(side(InlineEnd), lp_or_auto)
+            }
+            PropertyDeclaration::InsetInlineStart(lp_or_auto) => {
+                inset_declaration(side(InlineStart), lp_or_auto)
+            }
+            PropertyDeclaration::MarginBlockEnd(lp_or_auto) => {
+                margin_declaration(side(BlockEnd), lp_or_auto)
+            }
+            PropertyDeclaration::MarginBlockStart(lp_or_auto) => {
+                margin_declaration(side(BlockStart), lp_or_auto)
+            }
+            PropertyDeclaration::MarginInlineEnd(lp_or_auto) => {
+                margin_declaration(side(InlineEnd), lp_or_auto)
+            }
+            PropertyDeclaration::MarginInlineStart(lp_or_auto) => {
+                margin_declaration(side(InlineStart), lp_or_auto)
+            }
+            PropertyDeclaration::PaddingBlockEnd(length_percentage) => {
+                padding_declaration(side(BlockEnd), length_percentage)
+            }
+            PropertyDeclaration::PaddingBlockStart(length_percentage) => {
+                padding_declaration(side(BlockStart), length_percentage)
+            }
+            PropertyDeclaration::PaddingInlineEnd(length_percentage) => {
+                padding_declaration(side(InlineEnd), length_percentage)
+            }
+            PropertyDeclaration::PaddingInlineStart(length_percentage) => {
+                padding_declaration(side(InlineStart), length_percentage)
+            }
+            PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration { id, keyword }) => {
+                PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+                    id: id.to_physical(writing_mode, direction),
+                    keyword,
+                })
+            }
+            decl => decl,
+        }
+    }
+}
+
+fn border_color_declaration(side: Side, color: ColorUnit) -> PropertyDeclaration {
+    match side {
+        Side::Bottom => PropertyDeclaration::BorderBottomColor(BorderBottomColor { color }),
+        Side::Left => PropertyDeclaration::BorderLeftColor(BorderLeftColor { color }),
+        Side::Right => PropertyDeclaration::BorderRightColor(BorderRightColor { color }),
+        Side::Top => PropertyDeclaration::BorderTopColor(BorderTopColor { color }),
+    }
+}
+
+fn border_style_declaration(side: Side, style: LineStyle) -> PropertyDeclaration {
+    match side {
+        Side::Bottom => PropertyDeclaration::BorderBottomStyle(style),
+        Side::Left => PropertyDeclaration::BorderLeftStyle(style),
+        Side::Right => PropertyDeclaration::BorderRightStyle(style),
+        Side::Top => PropertyDeclaration::BorderTopStyle(style),
+    }
+}
+
+fn border_width_declaration(side: Side, line_width: LineWidth) -> PropertyDeclaration {
+    match side {
+        Side::Bottom => PropertyDeclaration::BorderBottomWidth(BorderBottomWidth { line_width }),
+        Side::Left => PropertyDeclaration::BorderLeftWidth(BorderLeftWidth { line_width }),
+        Side::Right => PropertyDeclaration::BorderRightWidth(BorderRightWidth { line_width }),
+        Side::Top => PropertyDeclaration::BorderTopWidth(BorderTopWidth { line_width }),
+    }
+}
+
+fn inset_declaration(side: Side, lp_or_auto: LengthPercentageOrAuto) -> PropertyDeclaration {
+    match side {
+        Side::Bottom => PropertyDeclaration::Bottom(Bottom { lp_or_auto }),
+        Side::Left => PropertyDeclaration::Left(Left { lp_or_auto }),
+        Side::Right => PropertyDeclaration::Right(Right { lp_or_auto }),
+        Side::Top => PropertyDeclaration::Top(Top { lp_or_auto }),
+    }
+}
+
+fn margin_declaration(side: Side, lp_or_auto: LengthPercentageOrAuto) -> PropertyDeclaration {
+    match side {
+        Side::Bottom => PropertyDeclaration::MarginBottom(MarginBottom { lp_or_auto }),
+        Side::Left => PropertyDeclaration::MarginLeft(MarginLeft { lp_or_auto }),
+        Side::Right => PropertyDeclaration::MarginRight(MarginRight { lp_or_auto }),
+        Side::Top => PropertyDeclaration::MarginTop(MarginTop { lp_or_auto }),
+    }
+}
+
+fn padding_declaration(side: Side, length_percentage: LengthPercentage) -> PropertyDeclaration {
+    match side {
+        Side::Bottom => PropertyDeclaration::PaddingBottom(PaddingBottom { length_percentage }),
+        Side::Left => PropertyDeclaration::PaddingLeft(PaddingLeft { length_percentage }),
+        Side::Right => PropertyDeclaration::PaddingRight(PaddingRight { length_percentage }),
+        Side::Top => PropertyDeclaration::PaddingTop(PaddingTop { length_percentage }),
+    }
+}
+
+/// A declaration of a longhand whose value is a CSS-wide keyword, such as `width: inherit`.
+#[derive(Clone, Copy, Debug)]
+pub struct WideKeywordDeclaration {
+    pub id: LonghandId,
+    pub keyword: CssWideKeywords,
+}
+
+/// A declaration of a longhand whose value references custom properties, such as
+/// `width: var(--width)`.  Its value can only be parsed once those references are substituted.
+#[derive(Clone, Debug)]
+pub struct VariableDeclaration {
+    pub id: LonghandId,
+    /// The shorthand this declaration was expanded from, if any.  The substituted value must be
+    /// parsed as this shorthand rather than as the longhand itself.
+    pub from_shorthand: Option<ShorthandId>,
+    pub value: VariableValue,
+}
+
+impl VariableDeclaration {
+    /// Substitutes the `var()` references in this declaration's value and parses the result.  If
+    /// that fails, the declaration is invalid at computed-value time and behaves as `unset`.
+    ///
+    /// https://www.w3.org/TR/css-variables-1/#invalid-at-computed-value-time
+    pub fn substitute_variables(
+        &self,
+        custom_properties: &CustomPropertiesMap,
+    ) -> PropertyDeclaration {
+        let unset = PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+            id: self.id,
+            keyword: CssWideKeywords::Unset,
+        });
+        let css = match custom_properties::substitute(self.value.css(), custom_properties) {
+            Some(css) => css,
+            None => return unset,
+        };
+        let property_id = match self.from_shorthand {
+            Some(short_id) => PropertyId::Shorthand(short_id),
+            None => PropertyId::Longhand(self.id),
+        };
+        let mut parser_input = ParserInput::new(&css);
+        let mut input = Parser::new(&mut parser_input);
+        let mut declarations = Vec::new();
+        if input
+            .parse_entirely(|input| {
+                PropertyDeclaration::parse_into(&mut declarations, property_id, input)
+            })
+            .is_err()
+        {
+            return unset;
+        }
+        declarations
+            .into_iter()
+            .find(|decl| decl.id() == PropertyDeclarationId::Longhand(self.id))
+            .unwrap_or(unset)
+    }
 }
 
 pub struct ComputedPropertyDeclarations {}
@@ -309,12 +1189,36 @@ pub struct ContextualPropertyDeclaration {
     pub specificity: Specificity,
 }
 
+impl ContextualPropertyDeclaration {
+    /// The cascade origin this declaration belongs to.  Inline and embedded styles are author
+    /// styles.
+    pub fn cascade_origin(&self) -> CascadeOrigin {
+        match &self.origin {
+            CssOrigin::Inline | CssOrigin::Embedded => CascadeOrigin::Author,
+            CssOrigin::Sheet(sheet_origin) => sheet_origin.cascade_origin,
+        }
+    }
+
+    fn is_revert(&self) -> bool {
+        matches!(
+            self.inner_decl,
+            PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+                keyword: CssWideKeywords::Revert,
+                ..
+            }) | PropertyDeclaration::Custom(CustomDeclaration {
+                value: CustomDeclarationValue::CssWideKeyword(CssWideKeywords::Revert),
+                ..
+            })
+        )
+    }
+}
+
 /// Wrapper over a Vec<PropertyDeclaration> to provide efficient helpers over common operations
 /// such as determining the existence of a type of property declaration.
 #[derive(Clone, Debug)]
 pub struct ContextualPropertyDeclarations {
-    /// The actual context property declarations.
-    decls: Vec<ContextualPropertyDeclaration>,
+    /// The actual context property declarations, each paired with its order of appearance.
+    decls: Vec<(usize, ContextualPropertyDeclaration)>,
     /// The LonghandIds present in this container.
     longhands: HashSet<LonghandId>,
     /// Whether or not the property declarations are entirely sorted according to the cascade
@@ -337,57 +1241,18 @@ impl ContextualPropertyDeclarations {
     /// https://www.w3.org/TR/2018/CR-css-cascade-3-20180828/#cascading
     #[inline]
     pub fn cascade_sort(&mut self) {
-        // ContextualPropertyDeclarations override `Ord`, so this will sort by origin, importance,
-        // specificity.
-        self.decls.as_mut_slice().sort();
-        // Now sort by order of appearance.
-        let mut swaps: Vec<(usize, usize)> = Vec::new();
-        for (outer_idx, outer_decl) in self.decls.iter().enumerate() {
-            for (inner_idx, inner_decl) in self.decls.iter().enumerate().rev() {
-                // Since inner_idx starts from .rev(), stop looking for possible swaps when outer
-                // and inner indexes meet. If there was a swap to be found, we already would've
-                // found it, and won't find any more swaps by continuing.  Also, there's no point in swapping an element with itself.
-                if inner_idx == outer_idx {
-                    break;
-                }
-
-                if eq_discrim_and_ord(outer_decl, inner_decl)
-                    && !either_index_already_in_swap(outer_idx, inner_idx, &swaps)
-                {
-                    swaps.push((outer_idx, inner_idx));
-                    break;
-                }
-            }
-        }
-
-        for swap in swaps.iter() {
-            self.decls.as_mut_slice().swap(swap.0, swap.1);
-        }
+        // Group declarations by property, then sort each group so that the declaration winning
+        // the cascade comes first.  ContextualPropertyDeclarations override `Ord`, so this will
+        // sort by origin, importance, and specificity.  Declarations that are otherwise equal are
+        // ordered from latest to earliest appearance, since the latest declaration wins.
+        self.decls.sort_by(|(a_order, a), (b_order, b)| {
+            a.inner_decl
+                .id()
+                .cmp(&b.inner_decl.id())
+                .then_with(|| b.cmp(a))
+                .then_with(|| b_order.cmp(a_order))
+        });
         self.is_sorted = true;
-
-        // We only want to swap properties of the same type/discriminant (e.g. only swapping `font-size`s with `font-size`s),
-        // and from there only swapping properties that are otherwise `Ordering::Equal`, leaving only order of appearance.
-        fn eq_discrim_and_ord(
-            a: &ContextualPropertyDeclaration,
-            b: &ContextualPropertyDeclaration,
-        ) -> bool {
-            discriminant(&a.inner_decl) == discriminant(&b.inner_decl)
-                && a.cmp(b) == Ordering::Equal
-        }
-
-        // We don't want to swap indices that already set to be swapped.
-        fn either_index_already_in_swap(
-            new_a: usize,
-            new_b: usize,
-            swaps: &[(usize, usize)],
-        ) -> bool {
-            for (existing_a, existing_b) in swaps.iter() {
-                if new_a == *existing_a || new_b == *existing_b {
-                    return true;
-                };
-            }
-            false
-        }
     }
 
     #[inline]
@@ -396,22 +1261,94 @@ impl ContextualPropertyDeclarations {
     }
 
     /// Finds the first matching `ContextualPropertyDeclaration` by `LonghandId`.
+    ///
+    /// If that declaration is `revert`, the search continues with declarations from lower cascade
+    /// origins.  `None` is returned if there is nothing left to revert to, in which case the
+    /// property should be defaulted as if it were `unset`.
     #[inline]
     pub fn get_by_longhand(&self, longhand: LonghandId) -> Option<&ContextualPropertyDeclaration> {
         if !self.contains(longhand) {
-            None
-        } else {
-            self.decls
-                .iter()
-                .find(|decl| LonghandId::from(*decl).eq(&longhand))
+            return None;
         }
+        self.get_by_id(PropertyDeclarationId::Longhand(longhand))
+            .map(|(_, decl)| decl)
+    }
+
+    /// Finds the declaration winning the cascade among declarations of several longhands that all
+    /// set the same value, such as `margin-left` and the flow-relative longhand mapping to it.
+    /// Such declarations cascade together, so the last resort is their order of appearance rather
+    /// than which longhand they declare.
+    ///
+    /// https://drafts.csswg.org/css-logical/#box
+    pub fn get_by_longhands(
+        &self,
+        longhands: impl Iterator<Item = LonghandId>,
+    ) -> Option<&ContextualPropertyDeclaration> {
+        longhands
+            .filter(|longhand| self.contains(*longhand))
+            .filter_map(|longhand| self.get_by_id(PropertyDeclarationId::Longhand(longhand)))
+            .max_by(|(a_order, a), (b_order, b)| {
+                a.cmp_precedence(b).then_with(|| a_order.cmp(b_order))
+            })
+            .map(|(_, decl)| decl)
+    }
+
+    /// The custom property declarations winning the cascade, one per declared custom property.
+    pub fn custom_declarations(&self) -> Vec<&CustomDeclaration> {
+        let names = self
+            .decls
+            .iter()
+            .filter_map(|(_, decl)| match &decl.inner_decl {
+                PropertyDeclaration::Custom(custom_decl) => Some(&custom_decl.name),
+                _ => None,
+            })
+            .collect::<HashSet<&Name>>();
+        names
+            .into_iter()
+            .filter_map(|name| {
+                match &self
+                    .get_by_id(PropertyDeclarationId::Custom(name))?
+                    .1
+                    .inner_decl
+                {
+                    PropertyDeclaration::Custom(custom_decl) => Some(custom_decl),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Finds the winning declaration for `id`, along with its order of appearance.
+    fn get_by_id(
+        &self,
+        id: PropertyDeclarationId,
+    ) -> Option<&(usize, ContextualPropertyDeclaration)> {
+        let mut candidates = self
+            .decls
+            .iter()
+            .filter(|(_, decl)| decl.inner_decl.id() == id);
+        let mut winner = candidates.next()?;
+        while winner.1.is_revert() {
+            let reverted_origin = winner.1.cascade_origin();
+            winner = candidates.find(|(_, decl)| decl.cascade_origin() < reverted_origin)?;
+        }
+        Some(winner)
+    }
+
+    /// Removes all declarations.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.decls.clear();
+        self.longhands.clear();
+        self.is_sorted = true;
     }
 
     #[inline]
     pub fn add(&mut self, new_decl: ContextualPropertyDeclaration) {
-        self.longhands
-            .insert(LonghandId::from(&new_decl.inner_decl));
-        self.decls.push(new_decl);
+        if let PropertyDeclarationId::Longhand(longhand) = new_decl.inner_decl.id() {
+            self.longhands.insert(longhand);
+        }
+        self.decls.push((self.decls.len(), new_decl));
         self.is_sorted = false;
     }
 }
@@ -452,26 +1389,50 @@ impl Default for ContextualPropertyDeclarations {
 ///         * Declarations from style attributes are ordered according to the document order of the element the style attribute appears on, and are all placed after any style sheets.
 impl Ord for ContextualPropertyDeclaration {
     fn cmp(&self, other: &Self) -> Ordering {
-        if mem::discriminant(&self.inner_decl) == mem::discriminant(&other.inner_decl) {
-            if self.important && !other.important {
-                return Ordering::Greater;
-            } else if !self.important && other.important {
-                return Ordering::Less;
-            } else if self.important && other.important {
-                match cmp_important_origins(&self.origin, &other.origin) {
-                    Ordering::Greater => return Ordering::Greater,
-                    Ordering::Less => return Ordering::Less,
-                    Ordering::Equal => return self.specificity.cmp(&other.specificity),
-                }
-            } else if !self.important && !other.important {
-                return match cmp_important_origins(&self.origin, &other.origin) {
-                    Ordering::Less => Ordering::Greater,
-                    Ordering::Greater => Ordering::Less,
-                    Ordering::Equal => return self.specificity.cmp(&other.specificity),
-                };
+        if self.inner_decl.id() == other.inner_decl.id() {
+            self.cmp_precedence(other)
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+impl ContextualPropertyDeclaration {
+    /// Compares the origin, importance, and specificity of two declarations, regardless of which
+    /// properties they declare.  This is needed where declarations of different longhands compete
+    /// to set the same value, such as `margin-inline-start` and `margin-left`.
+    fn cmp_precedence(&self, other: &Self) -> Ordering {
+        if self.important && !other.important {
+            return Ordering::Greater;
+        } else if !self.important && other.important {
+            return Ordering::Less;
+        } else if self.important && other.important {
+            match cmp_important_origins(&self.origin, &other.origin) {
+                Ordering::Greater => return Ordering::Greater,
+                Ordering::Less => return Ordering::Less,
+                Ordering::Equal => return cmp_specificity(self, other),
+            }
+        }
+        return match cmp_important_origins(&self.origin, &other.origin) {
+            Ordering::Less => Ordering::Greater,
+            Ordering::Greater => Ordering::Less,
+            Ordering::Equal => cmp_specificity(self, other),
+        };
+
+        // Declarations from style attributes behave as if they had a specificity higher than that
+        // of any selector.
+        // https://www.w3.org/TR/css-style-attr/#interpret
+        fn cmp_specificity(
+            a: &ContextualPropertyDeclaration,
+            b: &ContextualPropertyDeclaration,
+        ) -> Ordering {
+            match (&a.origin, &b.origin) {
+                (CssOrigin::Inline, CssOrigin::Inline) => a.specificity.cmp(&b.specificity),
+                (CssOrigin::Inline, _) => Ordering::Greater,
+                (_, CssOrigin::Inline) => Ordering::Less,
+                _ => a.specificity.cmp(&b.specificity),
             }
         }
-        return Ordering::Equal;
 
         fn cmp_important_origins(a: &CssOrigin, b: &CssOrigin) -> Ordering {
             match (a, b) {
@@ -524,8 +1485,7 @@ impl Eq for ContextualPropertyDeclaration {}
 
 impl PartialEq for ContextualPropertyDeclaration {
     fn eq(&self, other: &Self) -> bool {
-        mem::discriminant(&self.inner_decl) == mem::discriminant(&other.inner_decl)
-            && self.origin == other.origin
+        self.inner_decl.id() == other.inner_decl.id() && self.origin == other.origin
     }
 }
 
@@ -557,10 +1517,16 @@ mod tests {
     use crate::style::test_utils::{display_by_type, font_size_px, font_size_px_or_panic};
 
     use super::*;
+    use crate::style::values::computed;
+    use crate::style::values::computed::length::{CSSPixelLength, LengthPercentageOrAuto};
     use crate::style::values::computed::Display;
-    use crate::style::values::specified::{AbsoluteLength, LengthPercentage, NoCalcLength};
+    use crate::style::values::specified;
+    use crate::style::values::specified::{
+        AbsoluteLength, FontRelativeLength, LengthPercentage, NoCalcLength,
+    };
     use crate::style::StylesheetOrigin;
     use std::clone::Clone;
+    use strum::IntoEnumIterator;
 
     #[test]
     fn decl_cmp_specificity() {
@@ -683,6 +1649,58 @@ mod tests {
         assert_eq!(author_decl.cmp(&author_decl.clone()), Ordering::Equal);
     }
 
+    #[test]
+    fn decl_cmp_inline_beats_specificity() {
+        let inline_decl = ContextualPropertyDeclaration {
+            inner_decl: PropertyDeclaration::FontSize(FontSize::Length(LengthPercentage::Length(
+                NoCalcLength::Absolute(AbsoluteLength::Px(12.0)),
+            ))),
+            important: false,
+            origin: CssOrigin::Inline,
+            source_location: None,
+            specificity: Specificity::new(0),
+        };
+        let mut author_decl = inline_decl.clone();
+        author_decl.origin = CssOrigin::Sheet(StylesheetOrigin {
+            sheet_name: "file.css".to_owned(),
+            cascade_origin: CascadeOrigin::Author,
+        });
+        author_decl.specificity = Specificity::new(2049);
+        let mut ua_decl = author_decl.clone();
+        ua_decl.origin = CssOrigin::Sheet(StylesheetOrigin {
+            sheet_name: "file.css".to_owned(),
+            cascade_origin: CascadeOrigin::UserAgent,
+        });
+
+        assert!(inline_decl > author_decl);
+        assert!(author_decl < inline_decl);
+        assert!(inline_decl > ua_decl);
+
+        let mut important_author_decl = author_decl.clone();
+        important_author_decl.important = true;
+        let mut important_inline_decl = inline_decl.clone();
+        important_inline_decl.important = true;
+        assert!(important_author_decl > inline_decl);
+        assert!(important_inline_decl > important_author_decl);
+    }
+
+    #[test]
+    fn decl_sort_highest_precedence_first() {
+        let mut decls = ContextualPropertyDeclarations::new();
+        let mut high_spec = font_size_px(20.0);
+        high_spec.specificity = Specificity::new(10);
+        decls.add(high_spec);
+        decls.add(display_by_type(Display::Block));
+        decls.add(font_size_px(12.0));
+
+        decls.cascade_sort();
+        let first_font_size = decls
+            .get_by_longhand(LonghandId::FontSize)
+            .expect("decl_sort_highest_precedence_first should get font_size");
+        // The more specific declaration wins even though it appeared earlier.
+        assert_eq!(font_size_px_or_panic(&first_font_size.inner_decl), &20.0);
+    }
+
     #[test]
     fn decl_sort_order_of_appearance() {
         let mut decls = ContextualPropertyDeclarations::new();
@@ -758,4 +1776,566 @@ mod tests {
         assert_eq!(decl_block.declarations.len(), 1);
         assert_eq!(&24.0, font_size_px_or_panic(&decl_block.declarations[0]));
     }
+
+    fn parse_decl_block(css: &str) -> PropertyDeclarationBlock {
+        let mut input = cssparser::ParserInput::new(css);
+        parse_property_declaration_list(&mut Parser::new(&mut input))
+    }
+
+    fn wide_keyword_decls(block: &PropertyDeclarationBlock) -> Vec<(LonghandId, CssWideKeywords)> {
+        block
+            .declarations()
+            .iter()
+            .map(|decl| match decl {
+                PropertyDeclaration::CssWideKeyword(wide_keyword_decl) => {
+                    (wide_keyword_decl.id, wide_keyword_decl.keyword)
+                }
+                _ => panic!("should always be a `CssWideKeyword` property decl"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_css_wide_keywords_for_longhands_and_shorthands() {
+        let block = parse_decl_block("width: inherit; margin: INITIAL; color: revert !important");
+        assert_eq!(
+            wide_keyword_decls(&block),
+            vec![
+                (LonghandId::Width, CssWideKeywords::Inherit),
+                (LonghandId::MarginTop, CssWideKeywords::Initial),
+                (LonghandId::MarginRight, CssWideKeywords::Initial),
+                (LonghandId::MarginBottom, CssWideKeywords::Initial),
+                (LonghandId::MarginLeft, CssWideKeywords::Initial),
+                (LonghandId::Color, CssWideKeywords::Revert),
+            ]
+        );
+        assert_eq!(block.declarations_importance().get(5), Some(true));
+
+        // A keyword must be the entire value.
+        assert!(parse_decl_block("width: inherit 10px")
+            .declarations()
+            .is_empty());
+    }
+
+    #[test]
+    fn all_shorthand_sets_every_longhand_but_direction_and_unicode_bidi() {
+        let block = parse_decl_block("all: unset");
+        let decls = wide_keyword_decls(&block);
+        assert_eq!(decls.len(), LonghandId::iter().count() - 2);
+        assert!(decls
+            .iter()
+            .all(|(id, keyword)| *id != LonghandId::Direction
+                && *id != LonghandId::UnicodeBidi
+                && *keyword == CssWideKeywords::Unset));
+
+        // `all` only accepts CSS-wide keywords.
+        assert!(parse_decl_block("all: 10px").declarations().is_empty());
+    }
+
+    #[test]
+    fn revert_rolls_back_to_lower_origin() {
+        let sheet_decl = |inner_decl: PropertyDeclaration, cascade_origin: CascadeOrigin| {
+            ContextualPropertyDeclaration {
+                inner_decl,
+                important: false,
+                origin: CssOrigin::Sheet(StylesheetOrigin {
+                    sheet_name: "file.css".to_owned(),
+                    cascade_origin,
+                }),
+                source_location: None,
+                specificity: Specificity::new(0),
+            }
+        };
+        let revert = PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+            id: LonghandId::FontSize,
+            keyword: CssWideKeywords::Revert,
+        });
+        let px = |px| {
+            PropertyDeclaration::FontSize(FontSize::Length(LengthPercentage::Length(
+                NoCalcLength::Absolute(AbsoluteLength::Px(px)),
+            )))
+        };
+
+        let mut decls = ContextualPropertyDeclarations::new();
+        decls.add(sheet_decl(px(12.0), CascadeOrigin::UserAgent));
+        decls.add(sheet_decl(px(14.0), CascadeOrigin::Author));
+        decls.add(sheet_decl(revert.clone(), CascadeOrigin::Author));
+        decls.cascade_sort();
+        // Both author declarations are rolled back, leaving the user-agent declaration.
+        let winner = decls.get_by_longhand(LonghandId::FontSize).unwrap();
+        assert_eq!(&12.0, font_size_px_or_panic(&winner.inner_decl));
+
+        let mut decls = ContextualPropertyDeclarations::new();
+        decls.add(sheet_decl(px(12.0), CascadeOrigin::Author));
+        decls.add(sheet_decl(revert, CascadeOrigin::Author));
+        decls.cascade_sort();
+        // There's nothing in a lower origin to revert to, so the property is left to be defaulted
+        // as if it were unset.
+        assert!(decls.get_by_longhand(LonghandId::FontSize).is_none());
+    }
+
+    #[test]
+    fn defers_parsing_values_with_variables() {
+        let block = parse_decl_block("--bg: blue; background: var(--bg); width: var(--bg)");
+        let var_decls = block
+            .declarations()
+            .iter()
+            .filter_map(|decl| match decl {
+                PropertyDeclaration::WithVariables(var_decl) => Some(var_decl),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(var_decls.len(), 2);
+        assert!(matches!(
+            block.declarations()[0].id(),
+            PropertyDeclarationId::Custom(name) if name == "bg"
+        ));
+
+        let mut custom_properties = CustomPropertiesMap::new();
+        let mut input = cssparser::ParserInput::new("blue");
+        custom_properties.insert(
+            "bg".to_owned(),
+            VariableValue::parse(&mut Parser::new(&mut input)).unwrap(),
+        );
+        assert!(matches!(
+            var_decls[0].substitute_variables(&custom_properties),
+            PropertyDeclaration::BackgroundColor(_)
+        ));
+        // `blue` isn't a valid width, so the declaration is invalid at computed-value time.
+        assert!(matches!(
+            var_decls[1].substitute_variables(&custom_properties),
+            PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+                id: LonghandId::Width,
+                keyword: CssWideKeywords::Unset,
+            })
+        ));
+    }
+
+    #[test]
+    fn margin_shorthand_expands_one_to_four_values() {
+        let margins = |css: &str| {
+            parse_decl_block(css)
+                .declarations()
+                .iter()
+                .map(|decl| match decl {
+                    PropertyDeclaration::MarginTop(MarginTop { lp_or_auto })
+                    | PropertyDeclaration::MarginRight(MarginRight { lp_or_auto })
+                    | PropertyDeclaration::MarginBottom(MarginBottom { lp_or_auto })
+                    | PropertyDeclaration::MarginLeft(MarginLeft { lp_or_auto }) => {
+                        lp_or_auto.clone()
+                    }
+                    _ => panic!("should always be a margin property decl"),
+                })
+                .collect::<Vec<_>>()
+        };
+        let px = |px| {
+            specified::LengthPercentageOrAuto::LengthPercentage(LengthPercentage::Length(
+                NoCalcLength::Absolute(AbsoluteLength::Px(px)),
+            ))
+        };
+        let em = |em| {
+            specified::LengthPercentageOrAuto::LengthPercentage(LengthPercentage::Length(
+                NoCalcLength::FontRelative(FontRelativeLength::Em(em)),
+            ))
+        };
+        let auto = specified::LengthPercentageOrAuto::Auto;
+
+        assert_eq!(margins("margin: 1px"), vec![px(1.), px(1.), px(1.), px(1.)]);
+        assert_eq!(
+            margins("margin: .67em 0"),
+            vec![em(0.67), px(0.), em(0.67), px(0.)]
+        );
+        assert_eq!(
+            margins("margin: 1px auto 3px"),
+            vec![px(1.), auto.clone(), px(3.), auto]
+        );
+        assert_eq!(
+            margins("margin: 1px 2px 3px 4px"),
+            vec![px(1.), px(2.), px(3.), px(4.)]
+        );
+        assert!(margins("margin: 1px 2px 3px 4px 5px").is_empty());
+    }
+
+    #[test]
+    fn display_parses_one_and_two_keyword_values() {
+        let display = |css: &str| {
+            parse_decl_block(css)
+                .declarations()
+                .iter()
+                .map(|decl| match decl {
+                    PropertyDeclaration::Display(display) => *display,
+                    _ => panic!("should always be a display property decl"),
+                })
+                .next()
+        };
+
+        assert_eq!(display("display: flow-root"), Some(Display::FlowRoot));
+        assert_eq!(display("display: inline-block"), Some(Display::InlineBlock));
+        assert_eq!(display("display: block flow"), Some(Display::Block));
+        assert_eq!(display("display: flow"), Some(Display::Block));
+        assert_eq!(
+            display("display: inline flow-root"),
+            Some(Display::InlineBlock)
+        );
+        assert_eq!(
+            display("display: flow-root inline"),
+            Some(Display::InlineBlock)
+        );
+        assert_eq!(display("display: block flow-root"), Some(Display::FlowRoot));
+        assert_eq!(display("display: inline flow"), Some(Display::Inline));
+        assert_eq!(display("display: block inline"), None);
+        assert_eq!(display("display: none flow"), None);
+    }
+
+    #[test]
+    fn float_and_clear_parse_physical_and_logical_keywords() {
+        let block = parse_decl_block("float: inline-end; clear: BOTH; float: sideways");
+        let decls = block.declarations();
+        assert_eq!(decls.len(), 2);
+        assert!(matches!(
+            decls[0],
+            PropertyDeclaration::Float(Float::InlineEnd)
+        ));
+        assert!(matches!(decls[1], PropertyDeclaration::Clear(Clear::Both)));
+    }
+
+    #[test]
+    fn position_parses_keywords_and_rejects_others() {
+        let block = parse_decl_block("position: STICKY; position: center");
+        let decls = block.declarations();
+        assert_eq!(decls.len(), 1);
+        assert!(matches!(
+            decls[0],
+            PropertyDeclaration::Position(Position::Sticky)
+        ));
+    }
+
+    #[test]
+    fn flow_relative_shorthands_expand_to_start_and_end() {
+        let px = |px| LengthPercentage::Length(NoCalcLength::Absolute(AbsoluteLength::Px(px)));
+        let lp_or_auto = |css: &str| {
+            parse_decl_block(css)
+                .declarations()
+                .iter()
+                .map(|decl| match decl {
+                    PropertyDeclaration::MarginInlineStart(lp_or_auto)
+                    | PropertyDeclaration::MarginInlineEnd(lp_or_auto)
+                    | PropertyDeclaration::Top(Top { lp_or_auto })
+                    | PropertyDeclaration::Right(Right { lp_or_auto })
+                    | PropertyDeclaration::Bottom(Bottom { lp_or_auto })
+                    | PropertyDeclaration::Left(Left { lp_or_auto }) => lp_or_auto.clone(),
+                    _ => panic!("should always be a margin-inline or inset property decl"),
+                })
+                .collect::<Vec<_>>()
+        };
+        let auto = specified::LengthPercentageOrAuto::Auto;
+        let lp = specified::LengthPercentageOrAuto::LengthPercentage;
+
+        assert_eq!(
+            lp_or_auto("margin-inline: 1px 2px"),
+            vec![lp(px(1.)), lp(px(2.))]
+        );
+        assert_eq!(
+            lp_or_auto("margin-inline: auto"),
+            vec![auto.clone(), auto.clone()]
+        );
+        assert_eq!(
+            lp_or_auto("inset: 1px auto"),
+            vec![lp(px(1.)), auto.clone(), lp(px(1.)), auto]
+        );
+        assert!(lp_or_auto("margin-inline: 1px 2px 3px").is_empty());
+
+        let block = parse_decl_block("padding-block: 3px");
+        match block.declarations() {
+            [PropertyDeclaration::PaddingBlockStart(start), PropertyDeclaration::PaddingBlockEnd(end)] =>
+            {
+                assert_eq!((start, end), (&px(3.), &px(3.)));
+            }
+            decls => panic!("unexpected padding-block expansion: {:?}", decls),
+        }
+    }
+
+    #[test]
+    fn flow_relative_and_physical_longhands_cascade_together() {
+        let author_decl =
+            |inner_decl: PropertyDeclaration, specificity: u32| ContextualPropertyDeclaration {
+                inner_decl,
+                important: false,
+                origin: CssOrigin::Embedded,
+                source_location: None,
+                specificity: Specificity::new(specificity),
+            };
+        let px = |px| {
+            specified::LengthPercentageOrAuto::LengthPercentage(LengthPercentage::Length(
+                NoCalcLength::Absolute(AbsoluteLength::Px(px)),
+            ))
+        };
+        let margin_left = |px| PropertyDeclaration::MarginLeft(MarginLeft { lp_or_auto: px });
+        let margin_inline_start = |px| PropertyDeclaration::MarginInlineStart(px);
+        fn winner(decls: &ContextualPropertyDeclarations) -> PropertyDeclarationId<'_> {
+            let longhands = [LonghandId::MarginLeft, LonghandId::MarginInlineStart];
+            decls
+                .get_by_longhands(longhands.iter().copied())
+                .unwrap()
+                .inner_decl
+                .id()
+        }
+
+        // Otherwise equal declarations are decided by order of appearance, regardless of
+        // whether they are physical or flow-relative.
+        let mut decls = ContextualPropertyDeclarations::new();
+        decls.add(author_decl(margin_inline_start(px(1.)), 0));
+        decls.add(author_decl(margin_left(px(2.)), 0));
+        decls.cascade_sort();
+        assert_eq!(
+            winner(&decls),
+            PropertyDeclarationId::Longhand(LonghandId::MarginLeft)
+        );
+
+        decls.clear();
+        decls.add(author_decl(margin_left(px(2.)), 0));
+        decls.add(author_decl(margin_inline_start(px(1.)), 0));
+        decls.cascade_sort();
+        assert_eq!(
+            winner(&decls),
+            PropertyDeclarationId::Longhand(LonghandId::MarginInlineStart)
+        );
+
+        // Specificity still comes first.
+        decls.clear();
+        decls.add(author_decl(margin_inline_start(px(1.)), 1));
+        decls.add(author_decl(margin_left(px(2.)), 0));
+        decls.cascade_sort();
+        assert_eq!(
+            winner(&decls),
+            PropertyDeclarationId::Longhand(LonghandId::MarginInlineStart)
+        );
+    }
+
+    #[test]
+    fn flow_relative_declarations_map_to_physical_sides() {
+        let px = |px| LengthPercentage::Length(NoCalcLength::Absolute(AbsoluteLength::Px(px)));
+        let physical_id = |decl: PropertyDeclaration, writing_mode, direction| match decl
+            .to_physical(writing_mode, direction)
+            .id()
+        {
+            PropertyDeclarationId::Longhand(longhand) => longhand,
+            PropertyDeclarationId::Custom(_) => panic!("should be a longhand"),
+        };
+
+        let padding_inline_start = || PropertyDeclaration::PaddingInlineStart(px(1.));
+        assert_eq!(
+            physical_id(
+                padding_inline_start(),
+                WritingMode::HorizontalTb,
+                Direction::Ltr
+            ),
+            LonghandId::PaddingLeft
+        );
+        assert_eq!(
+            physical_id(
+                padding_inline_start(),
+                WritingMode::HorizontalTb,
+                Direction::Rtl
+            ),
+            LonghandId::PaddingRight
+        );
+        assert_eq!(
+            physical_id(
+                padding_inline_start(),
+                WritingMode::VerticalLr,
+                Direction::Ltr
+            ),
+            LonghandId::PaddingTop
+        );
+
+        let border_block_end_width = PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+            id: LonghandId::BorderBlockEndWidth,
+            keyword: CssWideKeywords::Inherit,
+        });
+        assert_eq!(
+            physical_id(
+                border_block_end_width,
+                WritingMode::VerticalRl,
+                Direction::Ltr
+            ),
+            LonghandId::BorderLeftWidth
+        );
+    }
+
+    #[test]
+    fn border_shorthands_expand_to_longhands() {
+        let ids = |css: &str| {
+            parse_decl_block(css)
+                .declarations()
+                .iter()
+                .map(|decl| match decl.id() {
+                    PropertyDeclarationId::Longhand(longhand) => longhand,
+                    PropertyDeclarationId::Custom(_) => panic!("should be a longhand"),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids("border: 1px solid red").len(), 12);
+        assert_eq!(ids("border: solid").len(), 12);
+        assert_eq!(
+            ids("border-block-start: red 2px"),
+            vec![
+                LonghandId::BorderBlockStartWidth,
+                LonghandId::BorderBlockStartStyle,
+                LonghandId::BorderBlockStartColor,
+            ]
+        );
+        assert_eq!(
+            ids("border-inline-color: red blue"),
+            vec![
+                LonghandId::BorderInlineStartColor,
+                LonghandId::BorderInlineEndColor,
+            ]
+        );
+        assert!(ids("border: 1px solid red dashed").is_empty());
+
+        let px = |px| LengthPercentage::Length(NoCalcLength::Absolute(AbsoluteLength::Px(px)));
+        let radius = |horizontal, vertical| specified::BorderCornerRadius {
+            horizontal: px(horizontal),
+            vertical: px(vertical),
+        };
+        match parse_decl_block("border-radius: 1px 2px / 3px").declarations() {
+            [PropertyDeclaration::BorderTopLeftRadius(top_left), PropertyDeclaration::BorderTopRightRadius(top_right), PropertyDeclaration::BorderBottomRightRadius(bottom_right), PropertyDeclaration::BorderBottomLeftRadius(bottom_left)] =>
+            {
+                assert_eq!(top_left, &radius(1., 3.));
+                assert_eq!(top_right, &radius(2., 3.));
+                assert_eq!(bottom_right, &radius(1., 3.));
+                assert_eq!(bottom_left, &radius(2., 3.));
+            }
+            decls => panic!("unexpected border-radius expansion: {:?}", decls),
+        }
+    }
+
+    #[test]
+    fn shorthands_serialize_computed_values() {
+        let mut cv = computed::ComputedValues::default();
+        let red = cssparser::RGBA::new(255, 0, 0, 255);
+        let one_px = CSSPixelLength::new(1.);
+        cv.border_top_width.size = one_px;
+        cv.border_right_width.size = one_px;
+        cv.border_bottom_width.size = one_px;
+        cv.border_left_width.size = one_px;
+        cv.border_top_style = LineStyle::Solid;
+        cv.border_right_style = LineStyle::Solid;
+        cv.border_bottom_style = LineStyle::Solid;
+        cv.border_left_style = LineStyle::Solid;
+        cv.border_top_color.rgba = red;
+        cv.border_right_color.rgba = red;
+        cv.border_bottom_color.rgba = red;
+        cv.border_left_color.rgba = red;
+        assert_eq!(
+            ShorthandId::Border.serialize(&cv).as_deref(),
+            Some("1px solid rgb(255, 0, 0)")
+        );
+        assert_eq!(
+            ShorthandId::BorderWidth.serialize(&cv).as_deref(),
+            Some("1px")
+        );
+
+        cv.border_bottom_width.size = CSSPixelLength::new(2.);
+        assert_eq!(ShorthandId::Border.serialize(&cv), None);
+        assert_eq!(
+            ShorthandId::BorderInline.serialize(&cv).as_deref(),
+            Some("1px solid rgb(255, 0, 0)")
+        );
+        assert_eq!(
+            ShorthandId::BorderWidth.serialize(&cv).as_deref(),
+            Some("1px 1px 2px")
+        );
+        assert_eq!(
+            ShorthandId::BorderBlockWidth.serialize(&cv).as_deref(),
+            Some("1px 2px")
+        );
+
+        cv.margin_left.size = LengthPercentageOrAuto::Auto;
+        cv.margin_right.size = LengthPercentageOrAuto::Auto;
+        assert_eq!(
+            ShorthandId::Margin.serialize(&cv).as_deref(),
+            Some("0px auto")
+        );
+
+        cv.border_top_left_radius.vertical = computed::length::LengthPercentage::new_len(3.);
+        assert_eq!(
+            ShorthandId::BorderRadius.serialize(&cv).as_deref(),
+            Some("0px / 3px 0px 0px")
+        );
+        assert_eq!(ShorthandId::All.serialize(&cv), None);
+    }
+
+    #[test]
+    fn font_shorthand_expands_to_longhands() {
+        let block = parse_decl_block("font: italic bold condensed 12px/1.5 Arial, sans-serif");
+        match block.declarations() {
+            [PropertyDeclaration::FontStyle(style), PropertyDeclaration::FontWeight(weight), PropertyDeclaration::FontStretch(stretch), PropertyDeclaration::FontSize(size), PropertyDeclaration::LineHeight(line_height), PropertyDeclaration::FontFamily(family)] =>
+            {
+                assert_eq!(style, &FontStyle::Italic);
+                assert_eq!(weight, &FontWeight::Absolute(700.));
+                assert_eq!(stretch.keyword(), Some("condensed"));
+                assert_eq!(
+                    size,
+                    &FontSize::Length(LengthPercentage::Length(NoCalcLength::Absolute(
+                        AbsoluteLength::Px(12.)
+                    )))
+                );
+                assert_eq!(line_height, &LineHeight::Number(1.5));
+                assert_eq!(family.families.len(), 2);
+            }
+            decls => panic!("unexpected font expansion: {:?}", decls),
+        }
+
+        // Values preceding the size default to `normal`, whether they're omitted or not.
+        match parse_decl_block("font: normal 300 large serif").declarations() {
+            [PropertyDeclaration::FontStyle(style), PropertyDeclaration::FontWeight(weight), PropertyDeclaration::FontStretch(stretch), ..] =>
+            {
+                assert_eq!(style, &FontStyle::Normal);
+                assert_eq!(weight, &FontWeight::Absolute(300.));
+                assert_eq!(stretch, &FontStretch::initial_value());
+            }
+            decls => panic!("unexpected font expansion: {:?}", decls),
+        }
+
+        // The size and family are required, and `font-stretch` percentages aren't allowed.
+        assert!(parse_decl_block("font: bold serif")
+            .declarations()
+            .is_empty());
+        assert!(parse_decl_block("font: 12px").declarations().is_empty());
+        assert!(parse_decl_block("font: 50% 12px serif")
+            .declarations()
+            .is_empty());
+    }
+
+    #[test]
+    fn font_shorthand_serializes_computed_values() {
+        let mut cv = computed::ComputedValues::default();
+        assert_eq!(
+            ShorthandId::Font.serialize(&cv).as_deref(),
+            Some("16px sans-serif")
+        );
+
+        cv.font_weight = computed::FontWeight(700.);
+        cv.font_style = FontStyle::Oblique;
+        cv.font_family.families = vec![
+            computed::font::SingleFontFamily::FamilyName("Times New Roman".to_owned()),
+            computed::font::SingleFontFamily::Generic(computed::font::GenericFontFamily::Serif),
+        ];
+        assert_eq!(
+            ShorthandId::Font.serialize(&cv).as_deref(),
+            Some("oblique 700 16px \"Times New Roman\", serif")
+        );
+
+        cv.line_height = computed::LineHeight::Number(1.2);
+        assert_eq!(
+            ShorthandId::Font.serialize(&cv).as_deref(),
+            Some("oblique 700 16px/1.2 \"Times New Roman\", serif")
+        );
+
+        cv.font_stretch = FontStretch(computed::Percentage(0.8));
+        assert_eq!(ShorthandId::Font.serialize(&cv), None);
+    }
 }
@@ -0,0 +1,200 @@
+//! Serialization of computed values back into shorthand form, so that, for example, a style dump
+//! can print `border: 1px solid rgb(255, 0, 0)` rather than the twelve longhands it sets.
+//!
+//! https://drafts.csswg.org/cssom/#serializing-css-values
+
+use crate::style::properties::id::ShorthandId;
+use crate::style::values::computed::length::CSSPixelLength;
+use crate::style::values::computed::{
+    ComputedValues, FontStretch, FontStyle, FontWeight, LineHeight, LineStyle,
+};
+use crate::{LogicalDirection, Side};
+use cssparser::{ToCss, RGBA};
+
+impl ShorthandId {
+    /// Serializes the computed values of this shorthand's longhands as a value of this shorthand.
+    ///
+    /// Returns `None` if those values can't be expressed by the shorthand — for example, `border`
+    /// sets every side to the same width, style and color, so it can't describe a box whose sides
+    /// differ.
+    pub fn serialize(self, computed_values: &ComputedValues) -> Option<String> {
+        let cv = computed_values;
+        let logical_side =
+            |logical: LogicalDirection| logical.physical_side(cv.writing_mode, cv.direction);
+        let start_end = |axis_start: LogicalDirection, axis_end: LogicalDirection| {
+            [logical_side(axis_start), logical_side(axis_end)]
+        };
+        let block = start_end(LogicalDirection::BlockStart, LogicalDirection::BlockEnd);
+        let inline = start_end(LogicalDirection::InlineStart, LogicalDirection::InlineEnd);
+        const SIDES: [Side; 4] = [Side::Top, Side::Right, Side::Bottom, Side::Left];
+
+        Some(match self {
+            ShorthandId::All => return None,
+            ShorthandId::Background => cv.background_color.rgba().to_css_string(),
+            ShorthandId::BorderColor => {
+                serialize_sides(SIDES.map(|side| cv.border_color_rgba(side)))
+            }
+            ShorthandId::BorderStyle => serialize_sides(SIDES.map(|side| cv.border_style(side))),
+            ShorthandId::BorderWidth => serialize_sides(SIDES.map(|side| cv.border_width(side))),
+            ShorthandId::BorderTop => serialize_border_side(border_side(cv, Side::Top)),
+            ShorthandId::BorderRight => serialize_border_side(border_side(cv, Side::Right)),
+            ShorthandId::BorderBottom => serialize_border_side(border_side(cv, Side::Bottom)),
+            ShorthandId::BorderLeft => serialize_border_side(border_side(cv, Side::Left)),
+            ShorthandId::BorderBlockStart => serialize_border_side(border_side(cv, block[0])),
+            ShorthandId::BorderBlockEnd => serialize_border_side(border_side(cv, block[1])),
+            ShorthandId::BorderInlineStart => serialize_border_side(border_side(cv, inline[0])),
+            ShorthandId::BorderInlineEnd => serialize_border_side(border_side(cv, inline[1])),
+            ShorthandId::Border => serialize_border_sides(cv, &SIDES)?,
+            ShorthandId::BorderRadius => {
+                let corners = [
+                    &cv.border_top_left_radius,
+                    &cv.border_top_right_radius,
+                    &cv.border_bottom_right_radius,
+                    &cv.border_bottom_left_radius,
+                ];
+                let horizontal = corners.map(|corner| corner.horizontal.clone());
+                let vertical = corners.map(|corner| corner.vertical.clone());
+                if horizontal == vertical {
+                    serialize_sides(horizontal)
+                } else {
+                    format!(
+                        "{} / {}",
+                        serialize_sides(horizontal),
+                        serialize_sides(vertical)
+                    )
+                }
+            }
+            ShorthandId::BorderBlockWidth => {
+                serialize_start_end(block.map(|side| cv.border_width(side)))
+            }
+            ShorthandId::BorderBlockStyle => {
+                serialize_start_end(block.map(|side| cv.border_style(side)))
+            }
+            ShorthandId::BorderBlockColor => {
+                serialize_start_end(block.map(|side| cv.border_color_rgba(side)))
+            }
+            ShorthandId::BorderInlineWidth => {
+                serialize_start_end(inline.map(|side| cv.border_width(side)))
+            }
+            ShorthandId::BorderInlineStyle => {
+                serialize_start_end(inline.map(|side| cv.border_style(side)))
+            }
+            ShorthandId::BorderInlineColor => {
+                serialize_start_end(inline.map(|side| cv.border_color_rgba(side)))
+            }
+            ShorthandId::BorderBlock => serialize_border_sides(cv, &block)?,
+            ShorthandId::BorderInline => serialize_border_sides(cv, &inline)?,
+            ShorthandId::Font => serialize_font(cv)?,
+            ShorthandId::Margin => serialize_sides(SIDES.map(|side| cv.margin(side).clone())),
+            ShorthandId::MarginBlock => {
+                serialize_start_end(block.map(|side| cv.margin(side).clone()))
+            }
+            ShorthandId::MarginInline => {
+                serialize_start_end(inline.map(|side| cv.margin(side).clone()))
+            }
+            ShorthandId::Padding => serialize_sides(SIDES.map(|side| cv.padding(side).clone())),
+            ShorthandId::PaddingBlock => {
+                serialize_start_end(block.map(|side| cv.padding(side).clone()))
+            }
+            ShorthandId::PaddingInline => {
+                serialize_start_end(inline.map(|side| cv.padding(side).clone()))
+            }
+            ShorthandId::Inset => serialize_sides(SIDES.map(|side| cv.inset(side).clone())),
+            ShorthandId::InsetBlock => {
+                serialize_start_end(block.map(|side| cv.inset(side).clone()))
+            }
+            ShorthandId::InsetInline => {
+                serialize_start_end(inline.map(|side| cv.inset(side).clone()))
+            }
+        })
+    }
+}
+
+/// The computed width, style and color of one side's border.
+fn border_side(cv: &ComputedValues, side: Side) -> (CSSPixelLength, LineStyle, RGBA) {
+    (
+        cv.border_width(side),
+        cv.border_style(side),
+        cv.border_color_rgba(side),
+    )
+}
+
+/// Serializes a border side as `<line-width> <line-style> <color>`.
+fn serialize_border_side((width, style, color): (CSSPixelLength, LineStyle, RGBA)) -> String {
+    format!(
+        "{} {} {}",
+        width.to_css_string(),
+        style.to_css_string(),
+        color.to_css_string()
+    )
+}
+
+/// Serializes the border shared by all of `sides`, or `None` if their borders differ.
+fn serialize_border_sides(cv: &ComputedValues, sides: &[Side]) -> Option<String> {
+    let first = border_side(cv, sides[0]);
+    if sides[1..]
+        .iter()
+        .all(|side| border_side(cv, *side) == first)
+    {
+        Some(serialize_border_side(first))
+    } else {
+        None
+    }
+}
+
+/// Serializes the `font` shorthand, omitting the values preceding the size, and the line height,
+/// that are `normal`.
+/// Returns `None` if `font-stretch` isn't one of the keywords the shorthand accepts.
+fn serialize_font(cv: &ComputedValues) -> Option<String> {
+    let mut values = Vec::new();
+    if cv.font_style != FontStyle::initial_value() {
+        values.push(cv.font_style.to_css_string());
+    }
+    if cv.font_weight != FontWeight::initial_value() {
+        values.push(cv.font_weight.to_css_string());
+    }
+    if cv.font_stretch != FontStretch::initial_value() {
+        values.push(cv.font_stretch.keyword()?.to_owned());
+    }
+    if cv.line_height == LineHeight::initial_value() {
+        values.push(cv.font_size.size.to_css_string());
+    } else {
+        values.push(format!(
+            "{}/{}",
+            cv.font_size.size.to_css_string(),
+            cv.line_height.to_css_string()
+        ));
+    }
+    values.push(cv.font_family.to_css_string());
+    Some(values.join(" "))
+}
+
+/// Serializes `[top, right, bottom, left]` values as the shortest list of one to four values that
+/// a shorthand like `margin` expands back into them.
+fn serialize_sides<T: ToCss + PartialEq>(sides: [T; 4]) -> String {
+    let [top, right, bottom, left] = &sides;
+    let len = if left != right {
+        4
+    } else if bottom != top {
+        3
+    } else if right != top {
+        2
+    } else {
+        1
+    };
+    join(&sides[..len])
+}
+
+/// Serializes `[start, end]` values as a single value if they are equal, or both otherwise.
+fn serialize_start_end<T: ToCss + PartialEq>(sides: [T; 2]) -> String {
+    let len = if sides[0] == sides[1] { 1 } else { 2 };
+    join(&sides[..len])
+}
+
+fn join<T: ToCss>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_css_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -1,35 +1,198 @@
-use std::mem::discriminant;
+use std::path::{Path, PathBuf};
 
 use cssparser::{ParseError, Parser, ParserInput, RuleListParser};
 
 use crate::dom::tree::NodeRef;
+use crate::style::font_face::FontFaceRule;
+use crate::style::media_queries::Device;
 use crate::style::properties::ContextualPropertyDeclaration;
 use crate::style::{
     CascadeOrigin, CssOrigin, CssRule, StyleParseErrorKind, StylesheetOrigin, TopLevelRuleParser,
 };
 
-/// Parses string containing CSS into StyleRules.
+/// Parses string containing CSS into StyleRules.  Sheets referenced by `@import` rules are loaded
+/// using the given `import_context`.  Invalid rules are skipped, keeping the rest of the sheet.
 pub fn parse_css_to_stylesheet(
     sheet_name: Option<String>,
     css_str: &mut str,
-) -> Result<Stylesheet, (ParseError<StyleParseErrorKind>, &str)> {
+    import_context: ImportContext,
+) -> Stylesheet {
     let input = &mut ParserInput::new(css_str);
     let parser = &mut Parser::new(input);
-    let rule_parser = RuleListParser::new_for_stylesheet(parser, TopLevelRuleParser {});
+    let rule_parser =
+        RuleListParser::new_for_stylesheet(parser, TopLevelRuleParser::new(import_context));
     let mut sheet = if let Some(name) = sheet_name {
         Stylesheet::new_with_name(name)
     } else {
         Stylesheet::new()
     };
     for rule in rule_parser {
-        sheet.add_rule(rule?);
+        match rule {
+            Ok(rule) => sheet.add_rule(rule),
+            // This includes an @import after other rules, which is ignored rather than
+            // invalidating the whole sheet.  https://drafts.csswg.org/css-cascade-4/#at-import
+            Err((parse_err, _)) => {
+                dbg!(parse_err);
+            }
+        }
+    }
+    sheet
+}
+
+/// Describes where a stylesheet's CSS came from, so that the sheets it `@import`s can be found.
+#[derive(Clone, Debug, Default)]
+pub struct ImportContext {
+    /// The directory relative import URLs are resolved against.
+    base_dir: PathBuf,
+    /// The files of the sheets currently being loaded, outermost first.  Importing any of these
+    /// again would create a cycle.
+    import_chain: Vec<PathBuf>,
+}
+
+impl ImportContext {
+    /// For CSS that doesn't live in a file of its own, e.g. the contents of a `<style>` element.
+    pub fn with_base_dir(base_dir: &Path) -> Self {
+        ImportContext {
+            base_dir: base_dir.to_path_buf(),
+            import_chain: Vec::new(),
+        }
+    }
+
+    /// For CSS read from the file at `path`.
+    pub fn for_file(path: &Path) -> Self {
+        ImportContext::default().for_import(path)
+    }
+
+    /// The directory relative URLs are resolved against.
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    /// Whether the file at `path` is one of the sheets currently being loaded.
+    fn is_loading(&self, path: &Path) -> bool {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.import_chain.contains(&path)
+    }
+
+    /// The context of a sheet imported from `path` by the sheet this context belongs to.
+    fn for_import(&self, path: &Path) -> Self {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let mut import_chain = self.import_chain.clone();
+        import_chain.push(path.clone());
+        ImportContext {
+            base_dir: path.parent().unwrap_or_else(|| Path::new("")).to_path_buf(),
+            import_chain,
+        }
+    }
+}
+
+/// A stylesheet referenced from within the document itself, rather than one supplied externally
+/// (e.g. via the CLI).
+#[derive(Clone, Debug)]
+pub enum DocumentStylesheet {
+    /// A sheet found within a `<style>` element.
+    Embedded(Stylesheet),
+    /// A sheet loaded from the `href` of a `<link rel="stylesheet">` element.
+    Linked(Stylesheet),
+}
+
+/// Collects the stylesheets referenced by `<style>` and `<link rel="stylesheet">` elements in the
+/// given DOM, in tree order.  Linked sheets are resolved against the local filesystem relative to
+/// `base_dir`, which should be the directory containing the HTML file.
+///
+/// Sheets that fail to load are skipped.
+pub fn document_stylesheets(dom: &NodeRef, base_dir: &Path) -> Vec<DocumentStylesheet> {
+    dom.inclusive_descendants()
+        .filter_map(|node| {
+            let element_data = node.as_element()?;
+            match element_data.name.local {
+                local_name!("style") => {
+                    Some(DocumentStylesheet::Embedded(parse_css_to_stylesheet(
+                        None,
+                        &mut node.text_contents(),
+                        ImportContext::with_base_dir(base_dir),
+                    )))
+                }
+                local_name!("link") => {
+                    let attrs = element_data.attributes.borrow();
+                    if !is_stylesheet_link_type(attrs.get(local_name!("rel"))?) {
+                        return None;
+                    }
+                    let href = attrs.get(local_name!("href"))?;
+                    load_linked_stylesheet(href, base_dir).map(DocumentStylesheet::Linked)
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Whether the given `rel` attribute value marks a link as a (non-alternate) stylesheet.
+/// https://html.spec.whatwg.org/multipage/links.html#link-type-stylesheet
+fn is_stylesheet_link_type(rel: &str) -> bool {
+    let mut link_types = rel.split_ascii_whitespace();
+    let has_type = |link_types: &mut std::str::SplitAsciiWhitespace, link_type: &str| {
+        link_types.any(|t| t.eq_ignore_ascii_case(link_type))
+    };
+    has_type(&mut link_types.clone(), "stylesheet") && !has_type(&mut link_types, "alternate")
+}
+
+fn load_linked_stylesheet(href: &str, base_dir: &Path) -> Option<Stylesheet> {
+    load_imported_stylesheet(href, &ImportContext::with_base_dir(base_dir))
+}
+
+/// Loads the sheet referenced by an `@import` rule, relative to the importing sheet.  Returns
+/// `None` if the sheet can't be loaded, or if it is already being imported further up the chain.
+pub fn load_imported_stylesheet(url: &str, import_context: &ImportContext) -> Option<Stylesheet> {
+    if url.contains("://") {
+        // Only stylesheets on the local filesystem can be loaded.
+        dbg!(url);
+        return None;
+    }
+    let path = import_context.base_dir.join(url);
+    if import_context.is_loading(&path) {
+        // The sheet is already being loaded further up the import chain.
+        dbg!(&path);
+        return None;
     }
-    Ok(sheet)
+    let mut css_str = match std::fs::read_to_string(&path) {
+        Ok(css_str) => css_str,
+        Err(io_err) => {
+            dbg!(&path, io_err);
+            return None;
+        }
+    };
+    let sheet_name = path.to_string_lossy().into_owned();
+    let sheet_context = import_context.for_import(&path);
+    Some(parse_css_to_stylesheet(
+        Some(sheet_name),
+        &mut css_str,
+        sheet_context,
+    ))
+}
+
+pub fn apply_stylesheet_to_node(
+    node: &NodeRef,
+    sheet: &Stylesheet,
+    origin: CascadeOrigin,
+    device: &Device,
+) {
+    let css_origin = CssOrigin::Sheet(StylesheetOrigin {
+        sheet_name: sheet.name.clone(),
+        cascade_origin: origin,
+    });
+    apply_rules_to_node(node, sheet.rules(), &css_origin, device);
 }
 
-pub fn apply_stylesheet_to_node(node: &NodeRef, sheet: &Stylesheet, origin: CascadeOrigin) {
-    sheet.rules().iter().for_each(|rule| {
-        if let CssRule::Style(style_rule) = rule {
+/// Applies a stylesheet embedded in the document via a `<style>` element.  Embedded sheets
+/// cascade as author sheets.
+pub fn apply_embedded_stylesheet_to_node(node: &NodeRef, sheet: &Stylesheet, device: &Device) {
+    apply_rules_to_node(node, sheet.rules(), &CssOrigin::Embedded, device);
+}
+
+fn apply_rules_to_node(node: &NodeRef, rules: &[CssRule], origin: &CssOrigin, device: &Device) {
+    rules.iter().for_each(|rule| match rule {
+        CssRule::Style(style_rule) => {
             node.select(&style_rule.selectors)
                 .for_each(|matching_node| {
                     style_rule
@@ -45,10 +208,7 @@ pub fn apply_stylesheet_to_node(node: &NodeRef, sheet: &Stylesheet, origin: Casc
                                     .declarations_importance()
                                     .get(index)
                                     .expect("important bit not set for declaration"),
-                                origin: CssOrigin::Sheet(StylesheetOrigin {
-                                    sheet_name: sheet.name.clone(),
-                                    cascade_origin: origin.clone(),
-                                }),
+                                origin: origin.clone(),
                                 source_location: Some(style_rule.source_location),
                                 specificity: style_rule
                                     .selectors
@@ -59,6 +219,46 @@ pub fn apply_stylesheet_to_node(node: &NodeRef, sheet: &Stylesheet, origin: Casc
                         });
                 });
         }
+        CssRule::Media(media_rule) => {
+            if media_rule.media_queries.evaluate(device) {
+                apply_rules_to_node(node, &media_rule.rules, origin, device);
+            }
+        }
+        CssRule::Import(import_rule) => {
+            // Imported rules cascade as if they were written in place of the @import, so they
+            // keep the origin of the importing sheet and precede its remaining rules.
+            if let Some(imported_sheet) = &import_rule.stylesheet {
+                if import_rule.media_queries.evaluate(device) {
+                    apply_rules_to_node(node, imported_sheet.rules(), origin, device);
+                }
+            }
+        }
+        CssRule::FontFace(_) | CssRule::None => {}
+    });
+}
+
+/// Collects the @font-face rules among `rules` that apply to `device`, in order, including those in
+/// matching @media rules and imported sheets.
+pub fn collect_font_face_rules<'a>(
+    rules: &'a [CssRule],
+    device: &Device,
+    font_face_rules: &mut Vec<&'a FontFaceRule>,
+) {
+    rules.iter().for_each(|rule| match rule {
+        CssRule::FontFace(font_face_rule) => font_face_rules.push(font_face_rule),
+        CssRule::Media(media_rule) => {
+            if media_rule.media_queries.evaluate(device) {
+                collect_font_face_rules(&media_rule.rules, device, font_face_rules);
+            }
+        }
+        CssRule::Import(import_rule) => {
+            if let Some(imported_sheet) = &import_rule.stylesheet {
+                if import_rule.media_queries.evaluate(device) {
+                    collect_font_face_rules(imported_sheet.rules(), device, font_face_rules);
+                }
+            }
+        }
+        CssRule::Style(_) | CssRule::None => {}
     });
 }
 
@@ -119,7 +319,7 @@ impl Stylesheet {
                                     existing_style.block.declarations().iter().enumerate()
                                 {
                                     for new_prop in new_style.block.declarations() {
-                                        if discriminant(new_prop) == discriminant(existing_prop) {
+                                        if new_prop.id() == existing_prop.id() {
                                             // the props are the same "type", e.g. both `font-size, both `display`, etc
                                             // take the `new_prop`, since the latest/newest prop should always be taken
                                             obsolete_prop_indices.push(prop_index);
@@ -136,7 +336,10 @@ impl Stylesheet {
                                 }
                             }
                         }
-                        CssRule::None => {}
+                        CssRule::Media(_)
+                        | CssRule::Import(_)
+                        | CssRule::FontFace(_)
+                        | CssRule::None => {}
                     }
                 }
 
@@ -144,7 +347,7 @@ impl Stylesheet {
                     self.rules.remove(index);
                 }
             }
-            CssRule::None => {}
+            CssRule::Media(_) | CssRule::Import(_) | CssRule::FontFace(_) | CssRule::None => {}
         }
         self.rules.push(new_rule);
     }
@@ -152,7 +355,8 @@ impl Stylesheet {
 
 #[cfg(test)]
 mod tests {
-    use crate::style::test_utils::font_size_px_or_panic;
+    use crate::style::properties::id::LonghandId;
+    use crate::style::test_utils::{font_size_px_or_panic, get_div};
 
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
@@ -160,11 +364,17 @@ mod tests {
     #[test]
     // TODO: Create integration test that exercises this as well
     fn selects_last_rules_prop_in_dupes_across_rules() {
-        let mut sheet_a = parse_css_to_stylesheet(None, &mut ".a { font-size: 12px; }".to_owned())
-            .expect("failed getting sheet_a for cross-block deduping test");
+        let mut sheet_a = parse_css_to_stylesheet(
+            None,
+            &mut ".a { font-size: 12px; }".to_owned(),
+            ImportContext::default(),
+        );
         // We won't actually use this sheet — just extract the `font-size` rule from it
-        let mut sheet_b = parse_css_to_stylesheet(None, &mut ".a { font-size: 16px; }".to_owned())
-            .expect("failed getting sheet_b for cross-block deduping test");
+        let mut sheet_b = parse_css_to_stylesheet(
+            None,
+            &mut ".a { font-size: 16px; }".to_owned(),
+            ImportContext::default(),
+        );
         sheet_a.add_rule(sheet_b.rules.remove(0));
 
         // The only PropertyDeclaration in the first rule, `font-size: 12px`, is obsoleted by the
@@ -179,4 +389,92 @@ mod tests {
             _ => panic!("should always be a `StyleRule` CssRule"),
         }
     }
+
+    #[test]
+    fn import_rules_must_come_first() {
+        let sheet = parse_css_to_stylesheet(
+            None,
+            &mut r#"@import "a.css"; @import "b.css"; .a { font-size: 12px; }"#.to_owned(),
+            ImportContext::default(),
+        );
+        assert_eq!(sheet.rules.len(), 3);
+
+        // An @import after other rules is skipped, but the rules around it are kept.
+        let sheet = parse_css_to_stylesheet(
+            None,
+            &mut r#".a { font-size: 12px; } @import "a.css"; .b { font-size: 16px; }"#.to_owned(),
+            ImportContext::default(),
+        );
+        let font_sizes = sheet
+            .rules
+            .iter()
+            .map(|rule| match rule {
+                CssRule::Style(style_rule) => {
+                    *font_size_px_or_panic(&style_rule.block.declarations()[0])
+                }
+                _ => panic!("the misplaced @import shouldn't be kept"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(font_sizes, vec![12.0, 16.0]);
+    }
+
+    #[test]
+    fn invalid_top_level_rules_are_skipped() {
+        let sheet = parse_css_to_stylesheet(
+            None,
+            &mut r#"
+                .a { font-size: 12px; }
+                %% { font-size: 14px; }
+                @unknown-rule { font-size: 16px; }
+                .b { display: none; }
+            "#
+            .to_owned(),
+            ImportContext::default(),
+        );
+        assert_eq!(sheet.rules.len(), 2);
+        let div = get_div("a b", "");
+        apply_embedded_stylesheet_to_node(&div, &sheet, &Device::new(1920., 1080., 1.));
+        let decls = div.contextual_decls();
+        assert!(decls.contains(LonghandId::FontSize));
+        assert!(decls.contains(LonghandId::Display));
+    }
+
+    #[test]
+    fn invalid_rules_inside_media_rules_are_skipped() {
+        let sheet = parse_css_to_stylesheet(
+            None,
+            &mut r#"
+                @media screen {
+                    .a { font-size: 12px; }
+                    @import "a.css";
+                    %% { font-size: 14px; }
+                    .b { display: none; }
+                }
+            "#
+            .to_owned(),
+            ImportContext::default(),
+        );
+        let div = get_div("a b", "");
+        apply_embedded_stylesheet_to_node(&div, &sheet, &Device::new(1920., 1080., 1.));
+        let decls = div.contextual_decls();
+        assert!(decls.contains(LonghandId::FontSize));
+        assert!(decls.contains(LonghandId::Display));
+    }
+
+    #[test]
+    fn import_cycles_are_not_followed() {
+        let sheet = load_imported_stylesheet(
+            "cycle/a.css",
+            &ImportContext::with_base_dir(Path::new("tests/websrc/import")),
+        )
+        .expect("failed loading cycle/a.css");
+        let imported_sheet = |sheet: &Stylesheet| match &sheet.rules[0] {
+            CssRule::Import(import_rule) => import_rule.stylesheet.clone(),
+            _ => panic!("first rule should be an `ImportRule`"),
+        };
+        // a.css imports b.css, which imports a.css again.  The second import of a.css is cyclic,
+        // so it shouldn't be loaded.
+        let sheet_b = imported_sheet(&sheet).expect("b.css should have been imported");
+        assert!(imported_sheet(&sheet_b).is_none());
+    }
 }
@@ -1,11 +1,10 @@
-use crate::style::values::computed::length::CSSPixelLength;
-use crate::style::values::computed::{
-    ComputeContext, ComputeValue, ComputeValueWithContext, ValueDefault,
-};
+use crate::style::values::computed::length::{CSSPixelLength, LengthPercentage};
+use crate::style::values::computed::{ComputeContext, ComputeValueWithContext, ValueDefault};
 use crate::style::values::specified;
 use crate::style::values::specified::border::LineWidth;
 use crate::style::StyleParseErrorKind;
-use cssparser::{ParseError, Parser, RGBA};
+use cssparser::{ParseError, Parser, ToCss, RGBA};
+use std::fmt;
 
 /// The intitial value for `border-<side>-style` properties.  This would normally be handled by
 /// an `impl ValueDefault`, but `border-<side>-style` properties are not complex enough to require
@@ -30,12 +29,12 @@ pub fn compute_border_side_color(
 }
 
 pub fn compute_border_side_width(
-    self_line_width: LineWidth,
+    line_width: CSSPixelLength,
     computed_side_style: LineStyle,
 ) -> CSSPixelLength {
     match computed_side_style {
         LineStyle::None | LineStyle::Hidden => CSSPixelLength::new(0.),
-        _ => self_line_width.compute_value(),
+        _ => line_width,
     }
 }
 
@@ -215,7 +214,10 @@ impl BorderBottomWidth {
     pub fn initial_value(computed_bottom_style: LineStyle) -> BorderBottomWidth {
         BorderBottomWidth {
             size: compute_border_side_width(
-                specified::BorderBottomWidth::initial_value().line_width,
+                specified::BorderBottomWidth::initial_value()
+                    .line_width
+                    .keyword_width()
+                    .expect("initial line width should be a keyword"),
                 computed_bottom_style,
             ),
         }
@@ -227,7 +229,10 @@ impl ComputeValueWithContext for specified::BorderBottomWidth {
 
     fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
         BorderBottomWidth {
-            size: compute_border_side_width(self.line_width, context.border_bottom_style()),
+            size: compute_border_side_width(
+                self.line_width.compute_value_with_context(context),
+                context.border_bottom_style(),
+            ),
         }
     }
 }
@@ -252,7 +257,10 @@ impl BorderLeftWidth {
     pub fn initial_value(computed_left_style: LineStyle) -> BorderLeftWidth {
         BorderLeftWidth {
             size: compute_border_side_width(
-                specified::BorderLeftWidth::initial_value().line_width,
+                specified::BorderLeftWidth::initial_value()
+                    .line_width
+                    .keyword_width()
+                    .expect("initial line width should be a keyword"),
                 computed_left_style,
             ),
         }
@@ -264,7 +272,10 @@ impl ComputeValueWithContext for specified::BorderLeftWidth {
 
     fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
         BorderLeftWidth {
-            size: compute_border_side_width(self.line_width, context.border_left_style()),
+            size: compute_border_side_width(
+                self.line_width.compute_value_with_context(context),
+                context.border_left_style(),
+            ),
         }
     }
 }
@@ -289,7 +300,10 @@ impl BorderRightWidth {
     pub fn initial_value(computed_right_style: LineStyle) -> BorderRightWidth {
         BorderRightWidth {
             size: compute_border_side_width(
-                specified::BorderRightWidth::initial_value().line_width,
+                specified::BorderRightWidth::initial_value()
+                    .line_width
+                    .keyword_width()
+                    .expect("initial line width should be a keyword"),
                 computed_right_style,
             ),
         }
@@ -301,7 +315,10 @@ impl ComputeValueWithContext for specified::BorderRightWidth {
 
     fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
         BorderRightWidth {
-            size: compute_border_side_width(self.line_width, context.border_right_style()),
+            size: compute_border_side_width(
+                self.line_width.compute_value_with_context(context),
+                context.border_right_style(),
+            ),
         }
     }
 }
@@ -326,7 +343,10 @@ impl BorderTopWidth {
     pub fn initial_value(computed_top_style: LineStyle) -> BorderTopWidth {
         BorderTopWidth {
             size: compute_border_side_width(
-                specified::BorderTopWidth::initial_value().line_width,
+                specified::BorderTopWidth::initial_value()
+                    .line_width
+                    .keyword_width()
+                    .expect("initial line width should be a keyword"),
                 computed_top_style,
             ),
         }
@@ -338,7 +358,10 @@ impl ComputeValueWithContext for specified::BorderTopWidth {
 
     fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
         BorderTopWidth {
-            size: compute_border_side_width(self.line_width, context.border_top_style()),
+            size: compute_border_side_width(
+                self.line_width.compute_value_with_context(context),
+                context.border_top_style(),
+            ),
         }
     }
 }
@@ -387,15 +410,83 @@ impl LineStyle {
     }
 }
 
-impl ComputeValue for LineWidth {
+impl ToCss for LineStyle {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        dest.write_str(match self {
+            LineStyle::None => "none",
+            LineStyle::Hidden => "hidden",
+            LineStyle::Dotted => "dotted",
+            LineStyle::Dashed => "dashed",
+            LineStyle::Solid => "solid",
+            LineStyle::Double => "double",
+            LineStyle::Groove => "groove",
+            LineStyle::Ridge => "ridge",
+            LineStyle::Inset => "inset",
+            LineStyle::Outset => "outset",
+        })
+    }
+}
+
+impl LineWidth {
+    /// The width of the `thin`, `medium` and `thick` keywords, which can be computed without any
+    /// context.  `None` for explicit lengths.
+    pub fn keyword_width(&self) -> Option<CSSPixelLength> {
+        match self {
+            LineWidth::Thin => Some(CSSPixelLength::new(1.)),
+            LineWidth::Medium => Some(CSSPixelLength::new(3.)),
+            LineWidth::Thick => Some(CSSPixelLength::new(5.)),
+            LineWidth::Length(_) => None,
+        }
+    }
+}
+
+impl ComputeValueWithContext for LineWidth {
     type ComputedValue = CSSPixelLength;
 
-    fn compute_value(&self) -> Self::ComputedValue {
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
         match self {
-            LineWidth::Thin => CSSPixelLength::new(1.),
-            LineWidth::Medium => CSSPixelLength::new(3.),
-            LineWidth::Thick => CSSPixelLength::new(5.),
-            LineWidth::Length(no_calc_len) => no_calc_len.compute_value(),
+            LineWidth::Length(no_calc_len) => no_calc_len.compute_value_with_context(context),
+            keyword => keyword.keyword_width().unwrap(),
+        }
+    }
+}
+
+/// Computed `border-<corner>-radius`.
+///
+/// https://www.w3.org/TR/2017/CR-css-backgrounds-3-20171017/#the-border-radius
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderCornerRadius {
+    pub horizontal: LengthPercentage,
+    pub vertical: LengthPercentage,
+}
+
+impl BorderCornerRadius {
+    pub fn initial_value() -> BorderCornerRadius {
+        BorderCornerRadius {
+            horizontal: LengthPercentage::new_len(0.),
+            vertical: LengthPercentage::new_len(0.),
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::BorderCornerRadius {
+    type ComputedValue = BorderCornerRadius;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        BorderCornerRadius {
+            horizontal: self.horizontal.compute_value_with_context(context),
+            vertical: self.vertical.compute_value_with_context(context),
         }
     }
 }
+
+impl ValueDefault for specified::BorderCornerRadius {
+    type ComputedValue = BorderCornerRadius;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        BorderCornerRadius::initial_value()
+    }
+}
@@ -0,0 +1,35 @@
+use crate::style::values::computed::{ComputeContext, ValueDefault};
+use crate::style::StyleParseErrorKind;
+use cssparser::{ParseError, Parser};
+
+/// Whether `width`, `height` and their min/max counterparts size the content box or the border box.
+///
+/// https://drafts.csswg.org/css-sizing-3/#box-sizing
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BoxSizing {
+    ContentBox,
+    BorderBox,
+}
+
+impl BoxSizing {
+    pub fn initial_value() -> BoxSizing {
+        BoxSizing::ContentBox
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        try_match_ident_ignore_ascii_case! { input,
+            "content-box" => Ok(BoxSizing::ContentBox),
+            "border-box" => Ok(BoxSizing::BorderBox),
+        }
+    }
+}
+
+impl ValueDefault for BoxSizing {
+    type ComputedValue = BoxSizing;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        BoxSizing::initial_value()
+    }
+}
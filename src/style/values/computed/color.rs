@@ -52,7 +52,6 @@ impl ComputeValueWithContext for specified::Color {
 
     fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
         match self {
-            specified::Color::Inherit => context.parent_computed_values.color,
             specified::Color::Unit(color_unit) => {
                 Color(color_unit.compute_value_with_context(context))
             }
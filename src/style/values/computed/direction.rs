@@ -32,6 +32,47 @@ impl ValueDefault for Direction {
     }
 }
 
+/// How an element's text is embedded in, or isolated from, the bidirectional text around it,
+/// and whether its `direction` overrides that of the characters inside it.
+///
+/// https://drafts.csswg.org/css-writing-modes-4/#unicode-bidi
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UnicodeBidi {
+    Normal,
+    Embed,
+    Isolate,
+    BidiOverride,
+    IsolateOverride,
+    Plaintext,
+}
+
+impl UnicodeBidi {
+    pub fn initial_value() -> UnicodeBidi {
+        UnicodeBidi::Normal
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        try_match_ident_ignore_ascii_case! { input,
+            "normal" => Ok(UnicodeBidi::Normal),
+            "embed" => Ok(UnicodeBidi::Embed),
+            "isolate" => Ok(UnicodeBidi::Isolate),
+            "bidi-override" => Ok(UnicodeBidi::BidiOverride),
+            "isolate-override" => Ok(UnicodeBidi::IsolateOverride),
+            "plaintext" => Ok(UnicodeBidi::Plaintext),
+        }
+    }
+}
+
+impl ValueDefault for UnicodeBidi {
+    type ComputedValue = UnicodeBidi;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        UnicodeBidi::initial_value()
+    }
+}
+
 /// Computed `writing-mode` values, which determine the block-flow direction, writing mode, and
 /// typographic mode.
 ///
@@ -21,9 +21,9 @@ use cssparser::{ParseError, Parser};
 pub enum Display {
     None,
     Block,
-    //    FlowRoot,
+    FlowRoot,
     Inline,
-    //    InlineBlock,
+    InlineBlock,
     //    ListItem,
     //    Table,
     //    InlineTable,
@@ -41,16 +41,80 @@ pub enum Display {
     //    InlineGrid,
 }
 
+/// How a box takes part in the formatting context it's in.
+///
+/// https://drafts.csswg.org/css-display-3/#outer-role
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum DisplayOutside {
+    Block,
+    Inline,
+}
+
+/// How the contents of a box are laid out.
+///
+/// https://drafts.csswg.org/css-display-3/#inner-model
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum DisplayInside {
+    Flow,
+    FlowRoot,
+}
+
 /// https://www.w3.org/TR/2019/CR-css-display-3-20190711/#property-index
 impl Display {
+    /// Parses either a single keyword, or the outer and inner display types as two keywords in
+    /// either order.  A missing outer type is `block` and a missing inner type is `flow`.
+    ///
+    /// https://drafts.csswg.org/css-display-3/#the-display-properties
     pub fn parse<'i, 't>(
         input: &mut Parser<'i, 't>,
     ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
-        try_match_ident_ignore_ascii_case! { input,
-            "none" => Ok(Display::None),
-            "block" => Ok(Display::Block),
-            "inline" => Ok(Display::Inline),
+        if let Ok(display) =
+            input.try_parse(|input| -> Result<_, ParseError<StyleParseErrorKind>> {
+                try_match_ident_ignore_ascii_case! { input,
+                    "none" => Ok(Display::None),
+                    "inline-block" => Ok(Display::InlineBlock),
+                }
+            })
+        {
+            return Ok(display);
+        }
+
+        let location = input.current_source_location();
+        let mut outside = None;
+        let mut inside = None;
+        while let Ok(ident) = input.try_parse(|input| input.expect_ident_cloned()) {
+            let invalid =
+                || location.new_custom_error(StyleParseErrorKind::OtherInvalidValue(ident.clone()));
+            let (new_outside, new_inside) = match_ignore_ascii_case! { &ident,
+                "block" => (Some(DisplayOutside::Block), None),
+                "inline" => (Some(DisplayOutside::Inline), None),
+                "flow" => (None, Some(DisplayInside::Flow)),
+                "flow-root" => (None, Some(DisplayInside::FlowRoot)),
+                _ => return Err(invalid()),
+            };
+            // Each display type may only be given once.
+            if (outside.is_some() && new_outside.is_some())
+                || (inside.is_some() && new_inside.is_some())
+            {
+                return Err(invalid());
+            }
+            outside = outside.or(new_outside);
+            inside = inside.or(new_inside);
+        }
+        if outside.is_none() && inside.is_none() {
+            return Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError));
         }
+        Ok(
+            match (
+                outside.unwrap_or(DisplayOutside::Block),
+                inside.unwrap_or(DisplayInside::Flow),
+            ) {
+                (DisplayOutside::Block, DisplayInside::Flow) => Display::Block,
+                (DisplayOutside::Block, DisplayInside::FlowRoot) => Display::FlowRoot,
+                (DisplayOutside::Inline, DisplayInside::Flow) => Display::Inline,
+                (DisplayOutside::Inline, DisplayInside::FlowRoot) => Display::InlineBlock,
+            },
+        )
     }
 
     pub fn initial_value() -> Self {
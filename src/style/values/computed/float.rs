@@ -0,0 +1,85 @@
+use crate::style::values::computed::{ComputeContext, ValueDefault};
+use crate::style::StyleParseErrorKind;
+use cssparser::{ParseError, Parser};
+
+/// Computed `float` values, which take a box out of the normal flow and shift it to one side of
+/// its containing block, letting line boxes flow around it.
+///
+/// https://www.w3.org/TR/CSS2/visuren.html#float-position
+/// https://drafts.csswg.org/css-logical-1/#float-clear
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Float {
+    None,
+    Left,
+    Right,
+    InlineStart,
+    InlineEnd,
+}
+
+impl Float {
+    pub fn initial_value() -> Float {
+        Float::None
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        try_match_ident_ignore_ascii_case! { input,
+            "none" => Ok(Float::None),
+            "left" => Ok(Float::Left),
+            "right" => Ok(Float::Right),
+            "inline-start" => Ok(Float::InlineStart),
+            "inline-end" => Ok(Float::InlineEnd),
+        }
+    }
+}
+
+impl ValueDefault for Float {
+    type ComputedValue = Float;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        Float::initial_value()
+    }
+}
+
+/// Computed `clear` values, which determine the sides of a box that may not be next to floats
+/// placed earlier in the same block formatting context.
+///
+/// https://www.w3.org/TR/CSS2/visuren.html#flow-control
+/// https://drafts.csswg.org/css-logical-1/#float-clear
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Clear {
+    None,
+    Left,
+    Right,
+    Both,
+    InlineStart,
+    InlineEnd,
+}
+
+impl Clear {
+    pub fn initial_value() -> Clear {
+        Clear::None
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        try_match_ident_ignore_ascii_case! { input,
+            "none" => Ok(Clear::None),
+            "left" => Ok(Clear::Left),
+            "right" => Ok(Clear::Right),
+            "both" => Ok(Clear::Both),
+            "inline-start" => Ok(Clear::InlineStart),
+            "inline-end" => Ok(Clear::InlineEnd),
+        }
+    }
+}
+
+impl ValueDefault for Clear {
+    type ComputedValue = Clear;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        Clear::initial_value()
+    }
+}
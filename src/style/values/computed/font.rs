@@ -1,11 +1,16 @@
 use crate::style::values::computed::length::CSSPixelLength;
 use crate::style::values::computed::{
-    ComputeContext, ComputeValue, ComputeValueWithContext, ValueDefault,
+    ComputeContext, ComputeValueWithContext, Percentage, ValueDefault,
 };
 use crate::style::values::specified;
 use crate::style::values::specified::font::KeywordSize;
-use crate::style::values::specified::{LengthPercentage, NoCalcLength};
+use crate::style::values::specified::LengthPercentage;
+use crate::style::values::used::ToPx;
+use crate::style::values::CSSFloat;
+use crate::style::StyleParseErrorKind;
 use app_units::Au;
+use cssparser::{serialize_string, ParseError, Parser, ToCss, Token};
+use std::fmt;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 /// The computed value of font-size
@@ -50,16 +55,24 @@ impl ComputeValueWithContext for specified::FontSize {
                 keyword_size.compute_value_with_context(&context),
                 Some(*keyword_size),
             ),
-            specified::FontSize::Length(LengthPercentage::Length(NoCalcLength::Absolute(
-                abs_len,
-            ))) => (abs_len.compute_value(), None),
-            specified::FontSize::Length(specified::LengthPercentage::Percentage(percentage)) => {
+            // `em` units in `font-size` are relative to the parent's font size, since this
+            // node's font size is what's being computed.
+            specified::FontSize::Length(LengthPercentage::Length(no_calc_length)) => {
+                (no_calc_length.compute_value_with_context(context), None)
+            }
+            specified::FontSize::Length(LengthPercentage::Percentage(percentage)) => {
                 let parent_font = context.parent_computed_values.font_size;
                 (
                     CSSPixelLength::from(Au::from(parent_font.size).scale_by(percentage.0)),
                     None,
                 )
             }
+            // Percentages in `font-size` are relative to the parent's font size.
+            specified::FontSize::Length(LengthPercentage::Calc(calc)) => (
+                calc.compute_value_with_context(context)
+                    .resolve(context.parent_computed_values.font_size.size),
+                None,
+            ),
         };
 
         FontSize {
@@ -91,3 +104,424 @@ impl ComputeValueWithContext for KeywordSize {
         .into()
     }
 }
+
+/// Computed `font-family` values: a prioritized list of font families, the first of which that is
+/// available (and has a glyph for a given character) is used.  Computed as specified.
+///
+/// https://drafts.csswg.org/css-fonts-4/#font-family-prop
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontFamily {
+    pub families: Vec<SingleFontFamily>,
+}
+
+impl FontFamily {
+    /// The initial value depends on the user agent.  Kosmonaut uses the system's sans-serif font.
+    pub fn initial_value() -> Self {
+        FontFamily {
+            families: vec![SingleFontFamily::Generic(GenericFontFamily::SansSerif)],
+        }
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        Ok(FontFamily {
+            families: input.parse_comma_separated(SingleFontFamily::parse)?,
+        })
+    }
+}
+
+impl ValueDefault for FontFamily {
+    type ComputedValue = FontFamily;
+
+    fn value_default(context: &ComputeContext) -> Self::ComputedValue {
+        context.parent_computed_values.font_family.clone()
+    }
+}
+
+impl ToCss for FontFamily {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        for (i, family) in self.families.iter().enumerate() {
+            if i > 0 {
+                dest.write_str(", ")?;
+            }
+            family.to_css(dest)?;
+        }
+        Ok(())
+    }
+}
+
+/// A single entry of a `font-family` list.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SingleFontFamily {
+    /// The name of a font family, e.g. `Helvetica Neue`.
+    FamilyName(String),
+    /// A generic family keyword, e.g. `serif`.
+    Generic(GenericFontFamily),
+}
+
+impl SingleFontFamily {
+    /// Parses a family name — either a string or a sequence of identifiers — or a generic family.
+    /// Generic family keywords are only recognized on their own and unquoted, so `"serif"` and
+    /// `serif bold` are both family names.
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        if let Ok(name) = input.try_parse(|i| i.expect_string_cloned()) {
+            return Ok(SingleFontFamily::FamilyName(name.as_ref().to_owned()));
+        }
+        let first_ident = input.expect_ident_cloned()?;
+        let mut name = first_ident.as_ref().to_owned();
+        while let Ok(ident) = input.try_parse(|i| i.expect_ident_cloned()) {
+            name.push(' ');
+            name.push_str(&ident);
+        }
+        if name.len() == first_ident.len() {
+            if let Some(generic) = GenericFontFamily::from_ident(&name) {
+                return Ok(SingleFontFamily::Generic(generic));
+            }
+        }
+        Ok(SingleFontFamily::FamilyName(name))
+    }
+}
+
+impl ToCss for SingleFontFamily {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        match self {
+            SingleFontFamily::FamilyName(name) => serialize_string(name, dest),
+            SingleFontFamily::Generic(generic) => generic.to_css(dest),
+        }
+    }
+}
+
+/// https://drafts.csswg.org/css-fonts-4/#generic-font-families
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GenericFontFamily {
+    Cursive,
+    Fantasy,
+    Monospace,
+    SansSerif,
+    Serif,
+    SystemUi,
+}
+
+impl GenericFontFamily {
+    fn from_ident(ident: &str) -> Option<GenericFontFamily> {
+        Some(match_ignore_ascii_case! { ident,
+            "cursive" => GenericFontFamily::Cursive,
+            "fantasy" => GenericFontFamily::Fantasy,
+            "monospace" => GenericFontFamily::Monospace,
+            "sans-serif" => GenericFontFamily::SansSerif,
+            "serif" => GenericFontFamily::Serif,
+            "system-ui" => GenericFontFamily::SystemUi,
+            _ => return None,
+        })
+    }
+}
+
+impl ToCss for GenericFontFamily {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        dest.write_str(match self {
+            GenericFontFamily::Cursive => "cursive",
+            GenericFontFamily::Fantasy => "fantasy",
+            GenericFontFamily::Monospace => "monospace",
+            GenericFontFamily::SansSerif => "sans-serif",
+            GenericFontFamily::Serif => "serif",
+            GenericFontFamily::SystemUi => "system-ui",
+        })
+    }
+}
+
+/// The computed value of `font-weight`: a number between 1 and 1000.
+///
+/// https://drafts.csswg.org/css-fonts-4/#font-weight-prop
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct FontWeight(pub CSSFloat);
+
+impl FontWeight {
+    pub fn normal() -> Self {
+        FontWeight(400.)
+    }
+
+    pub fn initial_value() -> Self {
+        FontWeight::normal()
+    }
+
+    /// The weight `bolder` resolves to relative to this weight.
+    ///
+    /// https://drafts.csswg.org/css-fonts-4/#relative-weights
+    pub fn bolder(self) -> Self {
+        FontWeight(match self.0 {
+            weight if weight < 350. => 400.,
+            weight if weight < 550. => 700.,
+            weight if weight < 900. => 900.,
+            weight => weight,
+        })
+    }
+
+    /// The weight `lighter` resolves to relative to this weight.
+    pub fn lighter(self) -> Self {
+        FontWeight(match self.0 {
+            weight if weight < 100. => weight,
+            weight if weight < 550. => 100.,
+            weight if weight < 750. => 400.,
+            _ => 700.,
+        })
+    }
+}
+
+impl ValueDefault for specified::FontWeight {
+    type ComputedValue = FontWeight;
+
+    fn value_default(context: &ComputeContext) -> Self::ComputedValue {
+        context.parent_computed_values.font_weight
+    }
+}
+
+impl ComputeValueWithContext for specified::FontWeight {
+    type ComputedValue = FontWeight;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        let parent_weight = context.parent_computed_values.font_weight;
+        match self {
+            specified::FontWeight::Absolute(weight) => FontWeight(*weight),
+            specified::FontWeight::Bolder => parent_weight.bolder(),
+            specified::FontWeight::Lighter => parent_weight.lighter(),
+        }
+    }
+}
+
+impl ToCss for FontWeight {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        write!(dest, "{}", self.0)
+    }
+}
+
+/// Computed `font-style` values.  Oblique angles aren't supported, so `oblique` always means the
+/// font's default slant.
+///
+/// https://drafts.csswg.org/css-fonts-4/#font-style-prop
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FontStyle {
+    Normal,
+    Italic,
+    Oblique,
+}
+
+impl FontStyle {
+    pub fn initial_value() -> Self {
+        FontStyle::Normal
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        try_match_ident_ignore_ascii_case! { input,
+            "normal" => Ok(FontStyle::Normal),
+            "italic" => Ok(FontStyle::Italic),
+            "oblique" => Ok(FontStyle::Oblique),
+        }
+    }
+}
+
+impl ValueDefault for FontStyle {
+    type ComputedValue = FontStyle;
+
+    fn value_default(context: &ComputeContext) -> Self::ComputedValue {
+        context.parent_computed_values.font_style
+    }
+}
+
+impl ToCss for FontStyle {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        dest.write_str(match self {
+            FontStyle::Normal => "normal",
+            FontStyle::Italic => "italic",
+            FontStyle::Oblique => "oblique",
+        })
+    }
+}
+
+/// Computed `font-stretch` values: the width of the font face to select, as a percentage of its
+/// normal width.  Keywords compute to the percentages they stand for.
+///
+/// https://drafts.csswg.org/css-fonts-4/#font-stretch-prop
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct FontStretch(pub Percentage);
+
+/// The `font-stretch` keywords and the percentages they map to.
+const FONT_STRETCH_KEYWORDS: [(&str, CSSFloat); 9] = [
+    ("ultra-condensed", 0.5),
+    ("extra-condensed", 0.625),
+    ("condensed", 0.75),
+    ("semi-condensed", 0.875),
+    ("normal", 1.),
+    ("semi-expanded", 1.125),
+    ("expanded", 1.25),
+    ("extra-expanded", 1.5),
+    ("ultra-expanded", 2.),
+];
+
+impl FontStretch {
+    pub fn initial_value() -> Self {
+        FontStretch(Percentage::hundred())
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let location = input.current_source_location();
+        if let Ok(percentage) = input.try_parse(|i| i.expect_percentage()) {
+            if percentage >= 0. {
+                return Ok(FontStretch(Percentage(percentage)));
+            }
+            return Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+        }
+        FontStretch::parse_keyword(input)
+    }
+
+    /// Parses only the keyword values of `font-stretch`, which are all the `font` shorthand
+    /// accepts.
+    pub fn parse_keyword<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let location = input.current_source_location();
+        let ident = input.expect_ident_cloned()?;
+        FONT_STRETCH_KEYWORDS
+            .iter()
+            .find(|(keyword, _)| ident.eq_ignore_ascii_case(keyword))
+            .map(|(_, percentage)| FontStretch(Percentage(*percentage)))
+            .ok_or_else(|| location.new_unexpected_token_error(Token::Ident(ident.clone())))
+    }
+
+    /// The keyword this stretch maps to, if any.
+    pub fn keyword(self) -> Option<&'static str> {
+        FONT_STRETCH_KEYWORDS
+            .iter()
+            .find(|(_, percentage)| *percentage == (self.0).0)
+            .map(|(keyword, _)| *keyword)
+    }
+}
+
+impl ValueDefault for FontStretch {
+    type ComputedValue = FontStretch;
+
+    fn value_default(context: &ComputeContext) -> Self::ComputedValue {
+        context.parent_computed_values.font_stretch
+    }
+}
+
+/// Computed `line-height` values.  Lengths and percentages compute to absolute lengths, while
+/// numbers are kept as they are so that descendants apply them to their own font size.
+///
+/// https://drafts.csswg.org/css2/#propdef-line-height
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineHeight {
+    Normal,
+    Number(CSSFloat),
+    Length(CSSPixelLength),
+}
+
+impl LineHeight {
+    pub fn initial_value() -> Self {
+        LineHeight::Normal
+    }
+}
+
+impl ValueDefault for specified::LineHeight {
+    type ComputedValue = LineHeight;
+
+    fn value_default(context: &ComputeContext) -> Self::ComputedValue {
+        context.parent_computed_values.line_height
+    }
+}
+
+impl ComputeValueWithContext for specified::LineHeight {
+    type ComputedValue = LineHeight;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        match self {
+            specified::LineHeight::Normal => LineHeight::Normal,
+            specified::LineHeight::Number(number) => LineHeight::Number(*number),
+            specified::LineHeight::Length(lp) => LineHeight::Length(
+                lp.compute_value_with_context(context)
+                    .to_px(context.font_size().size),
+            ),
+        }
+    }
+}
+
+impl ToCss for LineHeight {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        match self {
+            LineHeight::Normal => dest.write_str("normal"),
+            LineHeight::Number(number) => write!(dest, "{}", number),
+            LineHeight::Length(length) => length.to_css(dest),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cssparser::ParserInput;
+
+    fn parse_family(css: &str) -> Result<Vec<SingleFontFamily>, ()> {
+        let mut input = ParserInput::new(css);
+        Parser::new(&mut input)
+            .parse_entirely(FontFamily::parse)
+            .map(|font_family| font_family.families)
+            .map_err(|_| ())
+    }
+
+    #[test]
+    fn parses_family_names_and_generic_families() {
+        let name = |name: &str| SingleFontFamily::FamilyName(name.to_owned());
+        assert_eq!(
+            parse_family("\"Helvetica Neue\", Times  New Roman, Serif"),
+            Ok(vec![
+                name("Helvetica Neue"),
+                name("Times New Roman"),
+                SingleFontFamily::Generic(GenericFontFamily::Serif),
+            ])
+        );
+        // Generic family keywords are only keywords when unquoted and on their own.
+        assert_eq!(
+            parse_family("'monospace', system-ui ui"),
+            Ok(vec![name("monospace"), name("system-ui ui")])
+        );
+        assert!(parse_family("Arial,").is_err());
+    }
+
+    #[test]
+    fn relative_weights() {
+        let bolder = |weight| FontWeight(weight).bolder().0;
+        let lighter = |weight| FontWeight(weight).lighter().0;
+        assert_eq!(
+            [50., 300., 400., 600., 800., 950.].map(bolder),
+            [400., 400., 700., 900., 900., 950.]
+        );
+        assert_eq!(
+            [50., 300., 400., 600., 800., 950.].map(lighter),
+            [50., 100., 100., 400., 700., 700.]
+        );
+    }
+}
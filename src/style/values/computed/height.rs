@@ -1,11 +1,9 @@
-use crate::style::values::computed::length::{LengthPercentage, LengthPercentageOrAuto};
-use crate::style::values::computed::{
-    ComputeContext, ComputeValue, ComputeValueWithContext, ValueDefault,
-};
+use crate::style::values::computed::length::{LengthPercentageOrAuto, LengthPercentageOrNone};
+use crate::style::values::computed::{ComputeContext, ComputeValueWithContext, ValueDefault};
 use crate::style::values::specified;
 
 /// Computed value of a `height`.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Height {
     pub size: LengthPercentageOrAuto,
 }
@@ -21,27 +19,11 @@ impl Height {
 impl ComputeValueWithContext for specified::Height {
     type ComputedValue = Height;
 
-    fn compute_value_with_context(&self, _context: &ComputeContext) -> Self::ComputedValue {
-        let computed_lp_auto: LengthPercentageOrAuto = match self {
-            // TODO: I think we repeat computing the value of specified::LengthPercentageOrAuto a lot...eventually consider
-            // simply implementing `ComputeValueWithContext` for specified::LengthPercentageOrAuto.
-            specified::Height::LengthPercentageOrAuto(lp_auto) => match lp_auto {
-                specified::LengthPercentageOrAuto::Auto => LengthPercentageOrAuto::Auto,
-                specified::LengthPercentageOrAuto::LengthPercentage(lp) => match lp {
-                    specified::LengthPercentage::Length(no_calc_length) => {
-                        no_calc_length.compute_value().into()
-                    }
-                    specified::LengthPercentage::Percentage(percentage) => {
-                        LengthPercentageOrAuto::LengthPercentage(LengthPercentage::Percentage(
-                            *percentage,
-                        ))
-                    }
-                },
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        match self {
+            specified::Height::LengthPercentageOrAuto(lp_auto) => Height {
+                size: lp_auto.compute_value_with_context(context),
             },
-        };
-
-        Height {
-            size: computed_lp_auto,
         }
     }
 }
@@ -53,3 +35,73 @@ impl ValueDefault for specified::Height {
         Height::initial_value()
     }
 }
+
+/// Computed value of a `min-height`.
+///
+/// For block boxes, `auto` behaves as zero.  https://www.w3.org/TR/css-sizing-3/#min-size-auto
+#[derive(Clone, Debug)]
+pub struct MinHeight {
+    pub size: LengthPercentageOrAuto,
+}
+
+impl MinHeight {
+    pub fn initial_value() -> MinHeight {
+        MinHeight {
+            size: LengthPercentageOrAuto::Auto,
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::MinHeight {
+    type ComputedValue = MinHeight;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        match self {
+            specified::MinHeight::LengthPercentageOrAuto(lp_auto) => MinHeight {
+                size: lp_auto.compute_value_with_context(context),
+            },
+        }
+    }
+}
+
+impl ValueDefault for specified::MinHeight {
+    type ComputedValue = MinHeight;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        MinHeight::initial_value()
+    }
+}
+
+/// Computed value of a `max-height`.
+#[derive(Clone, Debug)]
+pub struct MaxHeight {
+    pub size: LengthPercentageOrNone,
+}
+
+impl MaxHeight {
+    pub fn initial_value() -> MaxHeight {
+        MaxHeight {
+            size: LengthPercentageOrNone::None,
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::MaxHeight {
+    type ComputedValue = MaxHeight;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        match self {
+            specified::MaxHeight::LengthPercentageOrNone(lp_none) => MaxHeight {
+                size: lp_none.compute_value_with_context(context),
+            },
+        }
+    }
+}
+
+impl ValueDefault for specified::MaxHeight {
+    type ComputedValue = MaxHeight;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        MaxHeight::initial_value()
+    }
+}
@@ -0,0 +1,131 @@
+use crate::style::values::computed::length::LengthPercentageOrAuto;
+use crate::style::values::computed::{ComputeContext, ComputeValueWithContext, ValueDefault};
+use crate::style::values::specified;
+
+/// Computed value of `bottom`.
+#[derive(Clone, Debug)]
+pub struct Bottom {
+    pub size: LengthPercentageOrAuto,
+}
+
+impl Bottom {
+    pub fn initial_value() -> Bottom {
+        Bottom {
+            size: LengthPercentageOrAuto::Auto,
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::Bottom {
+    type ComputedValue = Bottom;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        Bottom {
+            size: self.lp_or_auto.compute_value_with_context(context),
+        }
+    }
+}
+
+impl ValueDefault for specified::Bottom {
+    type ComputedValue = Bottom;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        Bottom::initial_value()
+    }
+}
+
+/// Computed value of `left`.
+#[derive(Clone, Debug)]
+pub struct Left {
+    pub size: LengthPercentageOrAuto,
+}
+
+impl Left {
+    pub fn initial_value() -> Left {
+        Left {
+            size: LengthPercentageOrAuto::Auto,
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::Left {
+    type ComputedValue = Left;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        Left {
+            size: self.lp_or_auto.compute_value_with_context(context),
+        }
+    }
+}
+
+impl ValueDefault for specified::Left {
+    type ComputedValue = Left;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        Left::initial_value()
+    }
+}
+
+/// Computed value of `right`.
+#[derive(Clone, Debug)]
+pub struct Right {
+    pub size: LengthPercentageOrAuto,
+}
+
+impl Right {
+    pub fn initial_value() -> Right {
+        Right {
+            size: LengthPercentageOrAuto::Auto,
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::Right {
+    type ComputedValue = Right;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        Right {
+            size: self.lp_or_auto.compute_value_with_context(context),
+        }
+    }
+}
+
+impl ValueDefault for specified::Right {
+    type ComputedValue = Right;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        Right::initial_value()
+    }
+}
+
+/// Computed value of `top`.
+#[derive(Clone, Debug)]
+pub struct Top {
+    pub size: LengthPercentageOrAuto,
+}
+
+impl Top {
+    pub fn initial_value() -> Top {
+        Top {
+            size: LengthPercentageOrAuto::Auto,
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::Top {
+    type ComputedValue = Top;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        Top {
+            size: self.lp_or_auto.compute_value_with_context(context),
+        }
+    }
+}
+
+impl ValueDefault for specified::Top {
+    type ComputedValue = Top;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        Top::initial_value()
+    }
+}
@@ -1,8 +1,12 @@
-use crate::style::values::computed::{ComputeValue, Percentage};
+use crate::style::values::computed::{
+    ComputeContext, ComputeValue, ComputeValueWithContext, Percentage,
+};
 use crate::style::values::{specified, CSSFloat};
 use app_units::Au;
+use cssparser::ToCss;
 use std::cmp::Ordering;
-use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub};
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
 
 /// The computed `<length>` value.
 #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
@@ -108,6 +112,13 @@ impl Sub for CSSPixelLength {
     }
 }
 
+impl SubAssign for CSSPixelLength {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0;
+    }
+}
+
 impl Sub<CSSFloat> for CSSPixelLength {
     type Output = Self;
 
@@ -186,8 +197,17 @@ impl CSSPixelLength {
     }
 }
 
+impl ToCss for CSSPixelLength {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        write!(dest, "{}px", self.0)
+    }
+}
+
 /// A computed `<length>` value, a computed `<percentage>` value, or the `auto` keyword.
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub enum LengthPercentageOrAuto {
     LengthPercentage(LengthPercentage),
     Auto,
@@ -203,6 +223,41 @@ impl LengthPercentageOrAuto {
     pub fn new_len_px(px_len: CSSPixelLength) -> LengthPercentageOrAuto {
         LengthPercentageOrAuto::LengthPercentage(LengthPercentage::Length(px_len))
     }
+
+    pub fn as_length_percentage(&self) -> Option<&LengthPercentage> {
+        match self {
+            LengthPercentageOrAuto::LengthPercentage(lp) => Some(lp),
+            LengthPercentageOrAuto::Auto => None,
+        }
+    }
+}
+
+impl ToCss for LengthPercentageOrAuto {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        match self {
+            LengthPercentageOrAuto::LengthPercentage(lp) => lp.to_css(dest),
+            LengthPercentageOrAuto::Auto => dest.write_str("auto"),
+        }
+    }
+}
+
+/// A computed `<length>` value, a computed `<percentage>` value, or the `none` keyword.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum LengthPercentageOrNone {
+    LengthPercentage(LengthPercentage),
+    None,
+}
+
+impl LengthPercentageOrNone {
+    pub fn as_length_percentage(&self) -> Option<&LengthPercentage> {
+        match self {
+            LengthPercentageOrNone::LengthPercentage(lp) => Some(lp),
+            LengthPercentageOrNone::None => None,
+        }
+    }
 }
 
 impl From<CSSPixelLength> for LengthPercentageOrAuto {
@@ -211,11 +266,13 @@ impl From<CSSPixelLength> for LengthPercentageOrAuto {
     }
 }
 
-/// A computed `<length>` value, or a computed `<percentage>` value.
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+/// A computed `<length>` value, a computed `<percentage>` value, or a `calc()` expression mixing
+/// the two.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub enum LengthPercentage {
     Length(CSSPixelLength),
     Percentage(Percentage),
+    Calc(Box<CalcLengthPercentage>),
 }
 
 impl LengthPercentage {
@@ -224,12 +281,282 @@ impl LengthPercentage {
     }
 }
 
+impl ToCss for LengthPercentage {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        match self {
+            LengthPercentage::Length(length) => length.to_css(dest),
+            LengthPercentage::Percentage(percentage) => percentage.to_css(dest),
+            LengthPercentage::Calc(calc) => calc.to_css(dest),
+        }
+    }
+}
+
 impl From<CSSPixelLength> for LengthPercentage {
     fn from(px_length: CSSPixelLength) -> Self {
         LengthPercentage::Length(px_length)
     }
 }
 
+/// A computed math function.  Lengths have been resolved to pixels, but percentages can't be
+/// resolved until layout, when the size they are relative to is known.
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum CalcLengthPercentage {
+    /// `length + percentage`.  Expressions without `min()`, `max()` or `clamp()` always simplify to
+    /// this form.
+    Linear {
+        length: CSSPixelLength,
+        percentage: Percentage,
+    },
+    Sum(Vec<CalcLengthPercentage>),
+    Min(Vec<CalcLengthPercentage>),
+    Max(Vec<CalcLengthPercentage>),
+}
+
+impl CalcLengthPercentage {
+    fn length(length: CSSPixelLength) -> Self {
+        CalcLengthPercentage::Linear {
+            length,
+            percentage: Percentage(0.),
+        }
+    }
+
+    fn percentage(percentage: Percentage) -> Self {
+        CalcLengthPercentage::Linear {
+            length: CSSPixelLength::new(0.),
+            percentage,
+        }
+    }
+
+    /// Resolves this expression to a pixel length, with percentages relative to `basis`.
+    pub fn resolve(&self, basis: CSSPixelLength) -> CSSPixelLength {
+        match self {
+            CalcLengthPercentage::Linear { length, percentage } => {
+                *length + percentage.px_relative_to(basis)
+            }
+            CalcLengthPercentage::Sum(terms) => {
+                terms.iter().fold(CSSPixelLength::new(0.), |sum, term| {
+                    sum + term.resolve(basis)
+                })
+            }
+            CalcLengthPercentage::Min(args) => args
+                .iter()
+                .map(|arg| arg.resolve(basis))
+                .fold(CSSPixelLength::new(CSSFloat::INFINITY), CSSPixelLength::min),
+            CalcLengthPercentage::Max(args) => args.iter().map(|arg| arg.resolve(basis)).fold(
+                CSSPixelLength::new(CSSFloat::NEG_INFINITY),
+                CSSPixelLength::max,
+            ),
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        match (self, other) {
+            (
+                CalcLengthPercentage::Linear { length, percentage },
+                CalcLengthPercentage::Linear {
+                    length: other_length,
+                    percentage: other_percentage,
+                },
+            ) => CalcLengthPercentage::Linear {
+                length: length + other_length,
+                percentage: Percentage(percentage.0 + other_percentage.0),
+            },
+            (CalcLengthPercentage::Sum(mut terms), other) => {
+                terms.push(other);
+                CalcLengthPercentage::Sum(terms)
+            }
+            (lhs, rhs) => CalcLengthPercentage::Sum(vec![lhs, rhs]),
+        }
+    }
+
+    fn scale(self, factor: CSSFloat) -> Self {
+        let scale_all = |args: Vec<Self>| args.into_iter().map(|arg| arg.scale(factor)).collect();
+        match self {
+            CalcLengthPercentage::Linear { length, percentage } => CalcLengthPercentage::Linear {
+                length: length * factor,
+                percentage: Percentage(percentage.0 * factor),
+            },
+            CalcLengthPercentage::Sum(terms) => CalcLengthPercentage::Sum(scale_all(terms)),
+            // Scaling by a negative factor flips which argument is the smallest.
+            CalcLengthPercentage::Min(args) if factor < 0. => {
+                CalcLengthPercentage::Max(scale_all(args))
+            }
+            CalcLengthPercentage::Max(args) if factor < 0. => {
+                CalcLengthPercentage::Min(scale_all(args))
+            }
+            CalcLengthPercentage::Min(args) => CalcLengthPercentage::Min(scale_all(args)),
+            CalcLengthPercentage::Max(args) => CalcLengthPercentage::Max(scale_all(args)),
+        }
+    }
+
+    /// Computes `min()` or `max()`, which can be resolved right away if its arguments are all
+    /// lengths, or all percentages.
+    fn min_or_max(args: Vec<Self>, is_min: bool) -> Self {
+        let pick = |a: CSSFloat, b: CSSFloat| if is_min { a.min(b) } else { a.max(b) };
+        let mut linear_args = args.iter().map(|arg| match arg {
+            CalcLengthPercentage::Linear { length, percentage } => Some((*length, *percentage)),
+            _ => None,
+        });
+        if let Some(Some((first_length, first_percentage))) = linear_args.next() {
+            let rest = linear_args.collect::<Option<Vec<_>>>();
+            if let Some(rest) = rest {
+                if first_percentage.0 == 0. && rest.iter().all(|(_, p)| p.0 == 0.) {
+                    let length = rest
+                        .iter()
+                        .fold(first_length.px(), |acc, (length, _)| pick(acc, length.px()));
+                    return CalcLengthPercentage::length(CSSPixelLength::new(length));
+                }
+                if first_length == 0. && rest.iter().all(|(l, _)| *l == 0.) {
+                    let percentage = rest
+                        .iter()
+                        .fold(first_percentage.0, |acc, (_, percentage)| {
+                            pick(acc, percentage.0)
+                        });
+                    return CalcLengthPercentage::percentage(Percentage(percentage));
+                }
+            }
+        }
+        if is_min {
+            CalcLengthPercentage::Min(args)
+        } else {
+            CalcLengthPercentage::Max(args)
+        }
+    }
+}
+
+impl ToCss for CalcLengthPercentage {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        let (function, separator, args) = match self {
+            CalcLengthPercentage::Linear { length, percentage } => {
+                dest.write_str("calc(")?;
+                length.to_css(dest)?;
+                dest.write_str(" + ")?;
+                percentage.to_css(dest)?;
+                return dest.write_str(")");
+            }
+            CalcLengthPercentage::Sum(terms) => ("calc(", " + ", terms),
+            CalcLengthPercentage::Min(args) => ("min(", ", ", args),
+            CalcLengthPercentage::Max(args) => ("max(", ", ", args),
+        };
+        dest.write_str(function)?;
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                dest.write_str(separator)?;
+            }
+            arg.to_css(dest)?;
+        }
+        dest.write_str(")")
+    }
+}
+
+impl ComputeValueWithContext for specified::CalcLengthPercentage {
+    type ComputedValue = CalcLengthPercentage;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        let compute_all = |args: &[specified::CalcLengthPercentage]| {
+            args.iter()
+                .map(|arg| arg.compute_value_with_context(context))
+                .collect::<Vec<_>>()
+        };
+        match self {
+            specified::CalcLengthPercentage::Length(length) => {
+                CalcLengthPercentage::length(length.compute_value_with_context(context))
+            }
+            specified::CalcLengthPercentage::Percentage(percentage) => {
+                CalcLengthPercentage::percentage(Percentage(*percentage))
+            }
+            specified::CalcLengthPercentage::Sum(terms) => terms
+                .iter()
+                .map(|term| term.compute_value_with_context(context))
+                .fold(
+                    CalcLengthPercentage::length(CSSPixelLength::new(0.)),
+                    |sum, term| sum.add(term),
+                ),
+            specified::CalcLengthPercentage::Scale(calc, factor) => {
+                calc.compute_value_with_context(context).scale(*factor)
+            }
+            specified::CalcLengthPercentage::Min(args) => {
+                CalcLengthPercentage::min_or_max(compute_all(args), true)
+            }
+            specified::CalcLengthPercentage::Max(args) => {
+                CalcLengthPercentage::min_or_max(compute_all(args), false)
+            }
+            // `clamp(MIN, VAL, MAX)` is `max(MIN, min(VAL, MAX))`.
+            specified::CalcLengthPercentage::Clamp { min, center, max } => {
+                let upper_bounded = CalcLengthPercentage::min_or_max(
+                    vec![
+                        center.compute_value_with_context(context),
+                        max.compute_value_with_context(context),
+                    ],
+                    true,
+                );
+                CalcLengthPercentage::min_or_max(
+                    vec![min.compute_value_with_context(context), upper_bounded],
+                    false,
+                )
+            }
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::LengthPercentage {
+    type ComputedValue = LengthPercentage;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        match self {
+            specified::LengthPercentage::Length(no_calc_length) => {
+                LengthPercentage::Length(no_calc_length.compute_value_with_context(context))
+            }
+            specified::LengthPercentage::Percentage(percentage) => {
+                LengthPercentage::Percentage(*percentage)
+            }
+            specified::LengthPercentage::Calc(calc) => {
+                match calc.compute_value_with_context(context) {
+                    CalcLengthPercentage::Linear { length, percentage } if percentage.0 == 0. => {
+                        LengthPercentage::Length(length)
+                    }
+                    CalcLengthPercentage::Linear { length, percentage } if length == 0. => {
+                        LengthPercentage::Percentage(percentage)
+                    }
+                    calc => LengthPercentage::Calc(Box::new(calc)),
+                }
+            }
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::LengthPercentageOrAuto {
+    type ComputedValue = LengthPercentageOrAuto;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        match self {
+            specified::LengthPercentageOrAuto::Auto => LengthPercentageOrAuto::Auto,
+            specified::LengthPercentageOrAuto::LengthPercentage(lp) => {
+                LengthPercentageOrAuto::LengthPercentage(lp.compute_value_with_context(context))
+            }
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::LengthPercentageOrNone {
+    type ComputedValue = LengthPercentageOrNone;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        match self {
+            specified::LengthPercentageOrNone::None => LengthPercentageOrNone::None,
+            specified::LengthPercentageOrNone::LengthPercentage(lp) => {
+                LengthPercentageOrNone::LengthPercentage(lp.compute_value_with_context(context))
+            }
+        }
+    }
+}
+
 impl ComputeValue for specified::AbsoluteLength {
     type ComputedValue = CSSPixelLength;
 
@@ -238,12 +565,33 @@ impl ComputeValue for specified::AbsoluteLength {
     }
 }
 
-impl ComputeValue for specified::NoCalcLength {
+impl ComputeValueWithContext for specified::FontRelativeLength {
     type ComputedValue = CSSPixelLength;
 
-    fn compute_value(&self) -> Self::ComputedValue {
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        CSSPixelLength::new(self.to_px(
+            context.em_basis().px(),
+            context.rem_basis().px(),
+            context.font_metrics(),
+        ))
+    }
+}
+
+impl ComputeValueWithContext for specified::NoCalcLength {
+    type ComputedValue = CSSPixelLength;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
         match self {
             specified::NoCalcLength::Absolute(abs_len) => abs_len.compute_value(),
+            specified::NoCalcLength::FontRelative(font_relative_len) => {
+                font_relative_len.compute_value_with_context(context)
+            }
+            specified::NoCalcLength::ViewportPercentage(viewport_percentage_len) => {
+                CSSPixelLength::new(viewport_percentage_len.to_px(
+                    context.device.viewport_width(),
+                    context.device.viewport_height(),
+                ))
+            }
         }
     }
 }
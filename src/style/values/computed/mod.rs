@@ -2,49 +2,65 @@
 /// Kosmonaut complies with Servo's license, the Mozilla Public License 2.0.
 pub mod background;
 pub mod border;
+pub mod box_sizing;
 pub mod color;
 pub mod direction;
 pub mod display;
+pub mod float;
 pub mod font;
 pub mod height;
+pub mod inset;
 pub mod length;
 pub mod margin;
 pub mod padding;
 pub mod percentage;
+pub mod position;
+pub mod text;
 pub mod width;
 
-use crate::style::values::computed::height::Height;
+use crate::style::values::computed::height::{Height, MaxHeight, MinHeight};
+pub use crate::style::values::computed::inset::{Bottom, Left, Right, Top};
 pub use crate::style::values::computed::margin::{
     MarginBottom, MarginLeft, MarginRight, MarginTop,
 };
 pub use crate::style::values::computed::padding::{
     PaddingBottom, PaddingLeft, PaddingRight, PaddingTop,
 };
-use crate::style::values::computed::width::Width;
+use crate::style::values::computed::width::{MaxWidth, MinWidth, Width};
 
 use crate::dom::tree::NodeRef;
+use crate::gfx::font::{FontHandle, FontMetrics, FontQuery};
+use crate::style::custom_properties::{compute_custom_properties, CustomPropertiesMap};
+use crate::style::media_queries::Device;
 use crate::style::properties::id::LonghandId;
-use crate::style::properties::PropertyDeclaration;
+use crate::style::properties::{PropertyDeclaration, WideKeywordDeclaration};
 use crate::style::values::specified;
+use crate::style::CssWideKeywords;
 
 pub use crate::style::values::computed::direction::WritingMode;
 use crate::style::values::computed::length::{
-    CSSPixelLength, LengthPercentage, LengthPercentageOrAuto,
+    CSSPixelLength, LengthPercentage, LengthPercentageOrAuto, LengthPercentageOrNone,
 };
 use crate::Side;
 pub use background::BackgroundColor;
 pub use border::LineStyle;
 pub use border::{
-    border_side_initial_style, BorderBottomColor, BorderBottomWidth, BorderLeftColor,
-    BorderLeftWidth, BorderRightColor, BorderRightWidth, BorderTopColor, BorderTopWidth,
+    border_side_initial_style, BorderBottomColor, BorderBottomWidth, BorderCornerRadius,
+    BorderLeftColor, BorderLeftWidth, BorderRightColor, BorderRightWidth, BorderTopColor,
+    BorderTopWidth,
 };
+pub use box_sizing::BoxSizing;
 pub use color::Color;
 use cssparser::RGBA;
-pub use direction::Direction;
+pub use direction::{Direction, UnicodeBidi};
 pub use display::Display;
-pub use font::FontSize;
+pub use float::{Clear, Float};
+pub use font::{FontFamily, FontSize, FontStretch, FontStyle, FontWeight, LineHeight};
 pub use percentage::Percentage;
+pub use position::Position;
+use std::sync::Arc;
 use strum::IntoEnumIterator;
+pub use text::{Hyphens, OverflowWrap, WhiteSpace, WordBreak};
 
 /// A trait to represent the conversion between computed and specified values where a context is
 /// required to properly compute the specified value.
@@ -100,21 +116,49 @@ pub struct ComputedValues {
     pub border_left_width: BorderLeftWidth,
     pub border_right_width: BorderRightWidth,
     pub border_top_width: BorderTopWidth,
+    pub border_bottom_left_radius: BorderCornerRadius,
+    pub border_bottom_right_radius: BorderCornerRadius,
+    pub border_top_left_radius: BorderCornerRadius,
+    pub border_top_right_radius: BorderCornerRadius,
+    pub bottom: Bottom,
+    pub box_sizing: BoxSizing,
+    pub clear: Clear,
     pub color: Color,
     pub direction: Direction,
     pub display: Display,
+    pub float: Float,
+    pub font_family: FontFamily,
     pub font_size: FontSize,
+    pub font_stretch: FontStretch,
+    pub font_style: FontStyle,
+    pub font_weight: FontWeight,
     pub height: Height,
+    pub hyphens: Hyphens,
+    pub left: Left,
+    pub line_height: LineHeight,
     pub margin_bottom: MarginBottom,
     pub margin_left: MarginLeft,
     pub margin_right: MarginRight,
     pub margin_top: MarginTop,
+    pub max_height: MaxHeight,
+    pub max_width: MaxWidth,
+    pub min_height: MinHeight,
+    pub min_width: MinWidth,
+    pub overflow_wrap: OverflowWrap,
     pub padding_bottom: PaddingBottom,
     pub padding_left: PaddingLeft,
     pub padding_right: PaddingRight,
     pub padding_top: PaddingTop,
+    pub position: Position,
+    pub right: Right,
+    pub top: Top,
+    pub unicode_bidi: UnicodeBidi,
+    pub white_space: WhiteSpace,
     pub width: Width,
+    pub word_break: WordBreak,
     pub writing_mode: WritingMode,
+    /// The custom properties of this node, with any `var()` references already substituted.
+    pub custom_properties: Arc<CustomPropertiesMap>,
 }
 
 impl ComputedValues {
@@ -136,19 +180,87 @@ impl ComputedValues {
         }
     }
 
+    pub fn border_width(&self, side: Side) -> CSSPixelLength {
+        match side {
+            Side::Bottom => self.border_bottom_width.size,
+            Side::Left => self.border_left_width.size,
+            Side::Right => self.border_right_width.size,
+            Side::Top => self.border_top_width.size,
+        }
+    }
+
+    pub fn margin(&self, side: Side) -> &LengthPercentageOrAuto {
+        match side {
+            Side::Bottom => &self.margin_bottom.size,
+            Side::Left => &self.margin_left.size,
+            Side::Right => &self.margin_right.size,
+            Side::Top => &self.margin_top.size,
+        }
+    }
+
+    pub fn padding(&self, side: Side) -> &LengthPercentage {
+        match side {
+            Side::Bottom => &self.padding_bottom.size,
+            Side::Left => &self.padding_left.size,
+            Side::Right => &self.padding_right.size,
+            Side::Top => &self.padding_top.size,
+        }
+    }
+
+    pub fn inset(&self, side: Side) -> &LengthPercentageOrAuto {
+        match side {
+            Side::Bottom => &self.bottom.size,
+            Side::Left => &self.left.size,
+            Side::Right => &self.right.size,
+            Side::Top => &self.top.size,
+        }
+    }
+
     pub fn logical_width(&self) -> LengthPercentageOrAuto {
         if self.writing_mode.is_horizontal() {
-            self.width.size
+            self.width.size.clone()
         } else {
-            self.height.size
+            self.height.size.clone()
         }
     }
 
     pub fn logical_height(&self) -> LengthPercentageOrAuto {
         if self.writing_mode.is_horizontal() {
-            self.height.size
+            self.height.size.clone()
+        } else {
+            self.width.size.clone()
+        }
+    }
+
+    pub fn logical_min_width(&self) -> LengthPercentageOrAuto {
+        if self.writing_mode.is_horizontal() {
+            self.min_width.size.clone()
+        } else {
+            self.min_height.size.clone()
+        }
+    }
+
+    pub fn logical_min_height(&self) -> LengthPercentageOrAuto {
+        if self.writing_mode.is_horizontal() {
+            self.min_height.size.clone()
+        } else {
+            self.min_width.size.clone()
+        }
+    }
+
+    pub fn logical_max_width(&self) -> LengthPercentageOrNone {
+        if self.writing_mode.is_horizontal() {
+            self.max_width.size.clone()
+        } else {
+            self.max_height.size.clone()
+        }
+    }
+
+    pub fn logical_max_height(&self) -> LengthPercentageOrNone {
+        if self.writing_mode.is_horizontal() {
+            self.max_height.size.clone()
         } else {
-            self.width.size
+            self.max_width.size.clone()
         }
     }
 
@@ -157,30 +269,30 @@ impl ComputedValues {
         match side {
             Side::Bottom => {
                 if horizontal_mode {
-                    self.padding_bottom.size
+                    self.padding_bottom.size.clone()
                 } else {
-                    self.padding_right.size
+                    self.padding_right.size.clone()
                 }
             }
             Side::Left => {
                 if horizontal_mode {
-                    self.padding_left.size
+                    self.padding_left.size.clone()
                 } else {
-                    self.padding_top.size
+                    self.padding_top.size.clone()
                 }
             }
             Side::Right => {
                 if horizontal_mode {
-                    self.padding_right.size
+                    self.padding_right.size.clone()
                 } else {
-                    self.padding_bottom.size
+                    self.padding_bottom.size.clone()
                 }
             }
             Side::Top => {
                 if horizontal_mode {
-                    self.padding_top.size
+                    self.padding_top.size.clone()
                 } else {
-                    self.padding_left.size
+                    self.padding_left.size.clone()
                 }
             }
         }
@@ -225,30 +337,30 @@ impl ComputedValues {
         match side {
             Side::Bottom => {
                 if horizontal_mode {
-                    self.margin_bottom.size
+                    self.margin_bottom.size.clone()
                 } else {
-                    self.margin_right.size
+                    self.margin_right.size.clone()
                 }
             }
             Side::Left => {
                 if horizontal_mode {
-                    self.margin_left.size
+                    self.margin_left.size.clone()
                 } else {
-                    self.margin_top.size
+                    self.margin_top.size.clone()
                 }
             }
             Side::Right => {
                 if horizontal_mode {
-                    self.margin_right.size
+                    self.margin_right.size.clone()
                 } else {
-                    self.margin_bottom.size
+                    self.margin_bottom.size.clone()
                 }
             }
             Side::Top => {
                 if horizontal_mode {
-                    self.margin_top.size
+                    self.margin_top.size.clone()
                 } else {
-                    self.margin_left.size
+                    self.margin_left.size.clone()
                 }
             }
         }
@@ -277,21 +389,48 @@ impl Default for ComputedValues {
             border_left_width: BorderLeftWidth::initial_value(initial_border_style),
             border_right_width: BorderRightWidth::initial_value(initial_border_style),
             border_top_width: BorderTopWidth::initial_value(initial_border_style),
+            border_bottom_left_radius: BorderCornerRadius::initial_value(),
+            border_bottom_right_radius: BorderCornerRadius::initial_value(),
+            border_top_left_radius: BorderCornerRadius::initial_value(),
+            border_top_right_radius: BorderCornerRadius::initial_value(),
+            bottom: Bottom::initial_value(),
+            box_sizing: BoxSizing::initial_value(),
+            clear: Clear::initial_value(),
             color: initial_color_prop,
             direction: Direction::initial_value(),
             display: Display::initial_value(),
+            float: Float::initial_value(),
+            font_family: FontFamily::initial_value(),
             font_size: FontSize::initial_value(),
+            font_stretch: FontStretch::initial_value(),
+            font_style: FontStyle::initial_value(),
+            font_weight: FontWeight::initial_value(),
             height: Height::initial_value(),
+            hyphens: Hyphens::initial_value(),
+            left: Left::initial_value(),
+            line_height: LineHeight::initial_value(),
             margin_bottom: MarginBottom::initial_value(),
             margin_left: MarginLeft::initial_value(),
             margin_right: MarginRight::initial_value(),
             margin_top: MarginTop::initial_value(),
+            max_height: MaxHeight::initial_value(),
+            max_width: MaxWidth::initial_value(),
+            min_height: MinHeight::initial_value(),
+            min_width: MinWidth::initial_value(),
+            overflow_wrap: OverflowWrap::initial_value(),
             padding_bottom: PaddingBottom::initial_value(),
             padding_left: PaddingLeft::initial_value(),
             padding_right: PaddingRight::initial_value(),
             padding_top: PaddingTop::initial_value(),
+            position: Position::initial_value(),
+            right: Right::initial_value(),
+            top: Top::initial_value(),
+            unicode_bidi: UnicodeBidi::initial_value(),
+            white_space: WhiteSpace::initial_value(),
             width: Width::initial_value(),
+            word_break: WordBreak::initial_value(),
             writing_mode: WritingMode::initial_value(),
+            custom_properties: Arc::new(CustomPropertiesMap::new()),
         }
     }
 }
@@ -299,7 +438,9 @@ impl Default for ComputedValues {
 /// A `ComputeContext` is all the data a specified value could ever need to compute
 /// itself and be transformed to a computed value.
 pub struct ComputeContext<'a> {
-    // TODO: Viewport dimensions will be needed
+    /// The device styles are being computed for.
+    pub device: &'a Device,
+
     /// The computed values of the parent for cases where inheritance is necessary.  If the current
     /// node has no parent (it is the root node), this is `ComputedValues::default()`.
     pub parent_computed_values: &'a ComputedValues,
@@ -310,12 +451,43 @@ pub struct ComputeContext<'a> {
     /// `None` if `color` has not been computed yet.
     pub computed_color: Option<Color>,
 
+    /// The computed value of the `font-size` property for the node being computed, which
+    /// font-relative lengths such as `em` are relative to.
+    ///
+    /// `None` if `font-size` has not been computed yet.
+    pub computed_font_size: Option<FontSize>,
+
+    /// The computed values of the `writing-mode` and `direction` properties for the node being
+    /// computed.  Flow-relative properties such as `margin-inline-start` map to physical ones
+    /// according to these.
+    ///
+    /// `None` if these values haven't been computed yet.
+    pub computed_writing_mode: Option<WritingMode>,
+    pub computed_direction: Option<Direction>,
+
+    /// The query for the first available font of the node being computed, which `ex` and `ch`
+    /// units are relative to.
+    ///
+    /// `None` if the font properties haven't been computed yet.
+    pub computed_font_query: Option<FontQuery>,
+
+    /// The font handle the first available font is loaded through.
+    pub font_handle: &'a FontHandle,
+
+    /// The computed font size of the root element, which `rem` units are relative to.  `None` if
+    /// the node being computed is the root element (or the document itself).
+    pub root_font_size: Option<CSSPixelLength>,
+
     /// The computed value of the `border-<side>-style` properties for the node being computed.
     /// The computed values of `border-<side>-width` properties depend on the associated border
     /// style — namely, if the computed style is "none" or "hidden", then the border width is zero.
     ///
     /// `None` if these values haven't been computed yet.
     pub computed_border_styles: Option<BorderSideStyleContext>,
+
+    /// The computed custom properties of the node being computed, used to substitute `var()`
+    /// references in its declarations.
+    pub custom_properties: Arc<CustomPropertiesMap>,
 }
 
 impl ComputeContext<'_> {
@@ -324,6 +496,50 @@ impl ComputeContext<'_> {
             .expect("color property not yet computed and applied to compute context")
     }
 
+    pub fn font_size(&self) -> FontSize {
+        self.computed_font_size
+            .expect("font-size property not yet computed and applied to compute context")
+    }
+
+    pub fn writing_mode(&self) -> WritingMode {
+        self.computed_writing_mode
+            .expect("writing-mode property not yet computed and applied to compute context")
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.computed_direction
+            .expect("direction property not yet computed and applied to compute context")
+    }
+
+    /// The font size `em` units are relative to.  While `font-size` itself is being computed,
+    /// this is the parent's font size.
+    pub fn em_basis(&self) -> CSSPixelLength {
+        self.computed_font_size
+            .unwrap_or(self.parent_computed_values.font_size)
+            .size
+    }
+
+    /// Metrics of the first available font `ex` and `ch` units are relative to, or `None` if it
+    /// can't be loaded.  While `font-size` itself is being computed, this is the parent's font.
+    pub fn font_metrics(&self) -> Option<FontMetrics> {
+        let query = self
+            .computed_font_query
+            .clone()
+            .unwrap_or_else(|| FontQuery::new(self.parent_computed_values));
+        let font = self.font_handle.get_font(&query).ok()?;
+        Some(FontMetrics::new(&font))
+    }
+
+    /// The font size `rem` units are relative to.  For the root element, this is its own font
+    /// size, or the initial font size while `font-size` itself is being computed.
+    pub fn rem_basis(&self) -> CSSPixelLength {
+        self.root_font_size.unwrap_or_else(|| {
+            self.computed_font_size
+                .unwrap_or_else(FontSize::initial_value)
+                .size
+        })
+    }
+
     pub fn border_bottom_style(&self) -> LineStyle {
         self.border_styles().bottom
     }
@@ -352,7 +568,7 @@ pub struct BorderSideStyleContext {
     pub top: LineStyle,
 }
 
-pub fn compute_values(node: NodeRef) {
+pub fn compute_values(node: NodeRef, device: &Device, font_handle: &FontHandle) {
     let mut cv_builder = ComputedValuesBuilder::default();
     let parent = node.parent();
     // If this is the root node (aka there is no parent to inherit properties from), just default all properties to
@@ -361,17 +577,44 @@ pub fn compute_values(node: NodeRef) {
         // TODO: This _could_ be an expensive clone when we actually support all CSS properties.
         p.computed_values().clone()
     });
+    let custom_properties = {
+        let contextual_decls = node.contextual_decls();
+        let custom_decls = contextual_decls.custom_declarations();
+        if custom_decls.is_empty() {
+            parent_computed_values.custom_properties.clone()
+        } else {
+            Arc::new(compute_custom_properties(
+                &parent_computed_values.custom_properties,
+                custom_decls.into_iter(),
+            ))
+        }
+    };
+    cv_builder.custom_properties(custom_properties.clone());
     let mut context = ComputeContext {
+        device,
         parent_computed_values: &parent_computed_values,
         computed_color: None,
+        computed_font_size: None,
+        computed_writing_mode: None,
+        computed_direction: None,
         computed_border_styles: None,
+        computed_font_query: None,
+        font_handle,
+        root_font_size: root_element(&node)
+            .filter(|root| *root != node)
+            .map(|root| root.computed_values().font_size.size),
+        custom_properties,
     };
     compute_early_properties(node.clone(), &mut context);
 
     LonghandId::iter().for_each(|longhand: LonghandId| {
-        match node.contextual_decls().get_by_longhand(longhand) {
-            Some(contextual_decl) => {
-                match &contextual_decl.inner_decl {
+        if longhand.is_logical() {
+            // Cascaded as part of the physical longhand it maps to.
+            return;
+        }
+        match cascaded_declaration(&node, longhand, &context) {
+            Some(decl) => {
+                match &decl {
                     PropertyDeclaration::BackgroundColor(background_color) => {
                         cv_builder.background_color(
                             background_color.compute_value_with_context(&context),
@@ -429,11 +672,40 @@ pub fn compute_values(node: NodeRef) {
                             border_top_width.compute_value_with_context(&context),
                         );
                     }
+                    PropertyDeclaration::BorderBottomLeftRadius(border_bottom_left_radius) => {
+                        cv_builder.border_bottom_left_radius(
+                            border_bottom_left_radius.compute_value_with_context(&context),
+                        );
+                    }
+                    PropertyDeclaration::BorderBottomRightRadius(border_bottom_right_radius) => {
+                        cv_builder.border_bottom_right_radius(
+                            border_bottom_right_radius.compute_value_with_context(&context),
+                        );
+                    }
+                    PropertyDeclaration::BorderTopLeftRadius(border_top_left_radius) => {
+                        cv_builder.border_top_left_radius(
+                            border_top_left_radius.compute_value_with_context(&context),
+                        );
+                    }
+                    PropertyDeclaration::BorderTopRightRadius(border_top_right_radius) => {
+                        cv_builder.border_top_right_radius(
+                            border_top_right_radius.compute_value_with_context(&context),
+                        );
+                    }
+                    PropertyDeclaration::Bottom(bottom) => {
+                        cv_builder.bottom(bottom.compute_value_with_context(&context));
+                    }
+                    PropertyDeclaration::BoxSizing(box_sizing) => {
+                        cv_builder.box_sizing(*box_sizing);
+                    }
+                    PropertyDeclaration::Clear(clear) => {
+                        cv_builder.clear(*clear);
+                    }
                     PropertyDeclaration::Color(_) => {
                         cv_builder.color(context.color());
                     }
-                    PropertyDeclaration::Direction(direction) => {
-                        cv_builder.direction(*direction);
+                    PropertyDeclaration::Direction(_) => {
+                        cv_builder.direction(context.direction());
                     }
                     PropertyDeclaration::Display(display) => {
                         // TODO: Should we copying `display` here (taking the specified value), rather than computing the value?
@@ -441,11 +713,32 @@ pub fn compute_values(node: NodeRef) {
                         // Computing display might not be straightforward — see: https://github.com/w3c/csswg-drafts/issues/1716
                         cv_builder.display(*display);
                     }
+                    PropertyDeclaration::Float(float) => {
+                        cv_builder.float(*float);
+                    }
                     PropertyDeclaration::Height(height) => {
                         cv_builder.height(height.compute_value_with_context(&context));
                     }
-                    PropertyDeclaration::FontSize(font_size) => {
-                        cv_builder.font_size(font_size.compute_value_with_context(&context));
+                    PropertyDeclaration::FontFamily(font_family) => {
+                        cv_builder.font_family(font_family.clone());
+                    }
+                    PropertyDeclaration::FontSize(_) => {
+                        cv_builder.font_size(context.font_size());
+                    }
+                    PropertyDeclaration::FontStretch(font_stretch) => {
+                        cv_builder.font_stretch(*font_stretch);
+                    }
+                    PropertyDeclaration::FontStyle(font_style) => {
+                        cv_builder.font_style(*font_style);
+                    }
+                    PropertyDeclaration::FontWeight(font_weight) => {
+                        cv_builder.font_weight(font_weight.compute_value_with_context(&context));
+                    }
+                    PropertyDeclaration::Left(left) => {
+                        cv_builder.left(left.compute_value_with_context(&context));
+                    }
+                    PropertyDeclaration::LineHeight(line_height) => {
+                        cv_builder.line_height(line_height.compute_value_with_context(&context));
                     }
                     PropertyDeclaration::MarginBottom(margin_bottom) => {
                         cv_builder
@@ -460,6 +753,18 @@ pub fn compute_values(node: NodeRef) {
                     PropertyDeclaration::MarginTop(margin_top) => {
                         cv_builder.margin_top(margin_top.compute_value_with_context(&context));
                     }
+                    PropertyDeclaration::MaxHeight(max_height) => {
+                        cv_builder.max_height(max_height.compute_value_with_context(&context));
+                    }
+                    PropertyDeclaration::MaxWidth(max_width) => {
+                        cv_builder.max_width(max_width.compute_value_with_context(&context));
+                    }
+                    PropertyDeclaration::MinHeight(min_height) => {
+                        cv_builder.min_height(min_height.compute_value_with_context(&context));
+                    }
+                    PropertyDeclaration::MinWidth(min_width) => {
+                        cv_builder.min_width(min_width.compute_value_with_context(&context));
+                    }
                     PropertyDeclaration::PaddingBottom(padding_bottom) => {
                         cv_builder
                             .padding_bottom(padding_bottom.compute_value_with_context(&context));
@@ -474,12 +779,47 @@ pub fn compute_values(node: NodeRef) {
                     PropertyDeclaration::PaddingTop(padding_top) => {
                         cv_builder.padding_top(padding_top.compute_value_with_context(&context));
                     }
+                    PropertyDeclaration::Right(right) => {
+                        cv_builder.right(right.compute_value_with_context(&context));
+                    }
+                    PropertyDeclaration::Top(top) => {
+                        cv_builder.top(top.compute_value_with_context(&context));
+                    }
+                    PropertyDeclaration::UnicodeBidi(unicode_bidi) => {
+                        cv_builder.unicode_bidi(*unicode_bidi);
+                    }
+                    PropertyDeclaration::WhiteSpace(white_space) => {
+                        cv_builder.white_space(*white_space);
+                    }
                     PropertyDeclaration::Width(width) => {
                         cv_builder.width(width.compute_value_with_context(&context));
                     }
-                    PropertyDeclaration::WritingMode(writing_mode) => {
-                        cv_builder.writing_mode(*writing_mode);
+                    PropertyDeclaration::WordBreak(word_break) => {
+                        cv_builder.word_break(*word_break);
+                    }
+                    PropertyDeclaration::Hyphens(hyphens) => {
+                        cv_builder.hyphens(*hyphens);
+                    }
+                    PropertyDeclaration::OverflowWrap(overflow_wrap) => {
+                        cv_builder.overflow_wrap(*overflow_wrap);
+                    }
+                    PropertyDeclaration::Position(position) => {
+                        cv_builder.position(*position);
+                    }
+                    PropertyDeclaration::WritingMode(_) => {
+                        cv_builder.writing_mode(context.writing_mode());
+                    }
+                    PropertyDeclaration::CssWideKeyword(wide_keyword_decl) => {
+                        longhand.compute_css_wide_keyword(
+                            wide_keyword_decl.keyword,
+                            &mut cv_builder,
+                            &context,
+                        );
+                    }
+                    PropertyDeclaration::WithVariables(_) | PropertyDeclaration::Custom(_) => {
+                        unreachable!("cascaded longhand declarations have no variables")
                     }
+                    _ => unreachable!("cascaded longhand declarations are physical"),
                 }
             }
             None => {
@@ -492,75 +832,228 @@ pub fn compute_values(node: NodeRef) {
         .expect("couldn't build computed values - maybe a field wasn't given to the builder?");
 }
 
+/// Returns the root element of the document `node` belongs to, or `None` if `node` isn't in a
+/// document with one.
+fn root_element(node: &NodeRef) -> Option<NodeRef> {
+    node.inclusive_ancestors()
+        .filter(|ancestor| ancestor.as_element().is_some())
+        .last()
+}
+
+/// Returns the declaration of `longhand` that won the cascade for `node`, with any `var()`
+/// references in it substituted.
+///
+/// Physical box-side longhands such as `margin-left` share their cascade with the flow-relative
+/// longhands mapping to them, so the winner may be a declaration of, say, `margin-inline-start`.
+/// It is converted into a declaration of `longhand` before being returned.
+fn cascaded_declaration(
+    node: &NodeRef,
+    longhand: LonghandId,
+    context: &ComputeContext,
+) -> Option<PropertyDeclaration> {
+    let contextual_decls = node.contextual_decls();
+    let substitute_variables = |decl: &PropertyDeclaration| match decl {
+        PropertyDeclaration::WithVariables(var_decl) => {
+            var_decl.substitute_variables(&context.custom_properties)
+        }
+        decl => decl.clone(),
+    };
+    match longhand.box_side_group() {
+        Some(group) => {
+            let (writing_mode, direction) = (context.writing_mode(), context.direction());
+            let winner = contextual_decls.get_by_longhands(
+                group
+                    .longhands()
+                    .filter(|id| id.to_physical(writing_mode, direction) == longhand),
+            )?;
+            Some(substitute_variables(&winner.inner_decl).to_physical(writing_mode, direction))
+        }
+        None => Some(substitute_variables(
+            &contextual_decls.get_by_longhand(longhand)?.inner_decl,
+        )),
+    }
+}
+
 /// Computes "early phase" properties and adds them to the compute context.  Early phase properties
 /// are those that are depended upon by other properties to compute properly (hence their addition
 /// to the compute context).
 fn compute_early_properties(node: NodeRef, context: &mut ComputeContext) {
-    if let Some(contextual_decl) = node.contextual_decls().get_by_longhand(LonghandId::Color) {
-        context.computed_color = match &contextual_decl.inner_decl {
+    context.computed_writing_mode = Some(
+        match cascaded_declaration(&node, LonghandId::WritingMode, context) {
+            Some(PropertyDeclaration::WritingMode(writing_mode)) => writing_mode,
+            Some(PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+                keyword: CssWideKeywords::Initial,
+                ..
+            })) => WritingMode::initial_value(),
+            // `writing-mode` is inherited, so `inherit`, `unset`, and an unresolved `revert` all
+            // take the parent's value.
+            Some(PropertyDeclaration::CssWideKeyword(_)) | None => {
+                WritingMode::value_default(context)
+            }
+            Some(_) => panic!("needed writing-mode property declaration"),
+        },
+    );
+    context.computed_direction = Some(
+        match cascaded_declaration(&node, LonghandId::Direction, context) {
+            Some(PropertyDeclaration::Direction(direction)) => direction,
+            Some(PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+                keyword: CssWideKeywords::Initial,
+                ..
+            })) => Direction::initial_value(),
+            // Likewise for `direction`.
+            Some(PropertyDeclaration::CssWideKeyword(_)) | None => {
+                Direction::value_default(context)
+            }
+            Some(_) => panic!("needed direction property declaration"),
+        },
+    );
+
+    context.computed_font_size = Some(
+        match cascaded_declaration(&node, LonghandId::FontSize, context) {
+            Some(PropertyDeclaration::FontSize(font_size)) => {
+                font_size.compute_value_with_context(context)
+            }
+            Some(PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+                keyword: CssWideKeywords::Initial,
+                ..
+            })) => FontSize::initial_value(),
+            // `font-size` is inherited, so `inherit`, `unset`, and an unresolved `revert` all
+            // take the parent's value.
+            Some(PropertyDeclaration::CssWideKeyword(_)) | None => {
+                specified::FontSize::value_default(context)
+            }
+            Some(_) => panic!("needed font-size property declaration"),
+        },
+    );
+    context.computed_font_query = Some(compute_font_query_early(&node, context));
+
+    if let Some(decl) = cascaded_declaration(&node, LonghandId::Color, context) {
+        context.computed_color = match &decl {
             PropertyDeclaration::Color(color) => Some(color.compute_value_with_context(&context)),
+            PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+                keyword: CssWideKeywords::Initial,
+                ..
+            }) => Some(Color::initial_value()),
+            // `color` is inherited, so `inherit`, `unset`, and an unresolved `revert` all take the
+            // parent's value.
+            PropertyDeclaration::CssWideKeyword(_) => {
+                Some(specified::Color::value_default(context))
+            }
             _ => panic!("needed color property declaration"),
         }
     } else {
         context.computed_color = Some(specified::Color::value_default(&context));
     }
 
-    context.computed_border_styles = Some(compute_border_styles_early(node));
+    context.computed_border_styles = Some(compute_border_styles_early(node, context));
 }
 
-fn compute_border_styles_early(node: NodeRef) -> BorderSideStyleContext {
-    let bottom = if let Some(contextual_decl) = node
-        .contextual_decls()
-        .get_by_longhand(LonghandId::BorderBottomStyle)
-    {
-        match &contextual_decl.inner_decl {
-            PropertyDeclaration::BorderBottomStyle(line_style) => *line_style,
-            _ => panic!("needed border-bottom-style property declaration"),
-        }
-    } else {
-        border_side_initial_style()
-    };
-
-    let left = if let Some(contextual_decl) = node
-        .contextual_decls()
-        .get_by_longhand(LonghandId::BorderLeftStyle)
-    {
-        match &contextual_decl.inner_decl {
-            PropertyDeclaration::BorderLeftStyle(line_style) => *line_style,
-            _ => panic!("needed border-left-style property declaration"),
-        }
-    } else {
-        border_side_initial_style()
-    };
+/// Computes the font properties that select the node's first available font.
+fn compute_font_query_early(node: &NodeRef, context: &ComputeContext) -> FontQuery {
+    let parent = context.parent_computed_values;
+    let font_family = early_inherited_value(
+        node,
+        LonghandId::FontFamily,
+        context,
+        FontFamily::initial_value(),
+        parent.font_family.clone(),
+        |decl| match decl {
+            PropertyDeclaration::FontFamily(font_family) => Some(font_family),
+            _ => None,
+        },
+    );
+    let font_stretch = early_inherited_value(
+        node,
+        LonghandId::FontStretch,
+        context,
+        FontStretch::initial_value(),
+        parent.font_stretch,
+        |decl| match decl {
+            PropertyDeclaration::FontStretch(font_stretch) => Some(font_stretch),
+            _ => None,
+        },
+    );
+    let font_style = early_inherited_value(
+        node,
+        LonghandId::FontStyle,
+        context,
+        FontStyle::initial_value(),
+        parent.font_style,
+        |decl| match decl {
+            PropertyDeclaration::FontStyle(font_style) => Some(font_style),
+            _ => None,
+        },
+    );
+    let font_weight = early_inherited_value(
+        node,
+        LonghandId::FontWeight,
+        context,
+        FontWeight::initial_value(),
+        parent.font_weight,
+        |decl| match decl {
+            PropertyDeclaration::FontWeight(font_weight) => {
+                Some(font_weight.compute_value_with_context(context))
+            }
+            _ => None,
+        },
+    );
+    FontQuery::new(&ComputedValues {
+        font_family,
+        font_stretch,
+        font_style,
+        font_weight,
+        ..parent.clone()
+    })
+}
 
-    let right = if let Some(contextual_decl) = node
-        .contextual_decls()
-        .get_by_longhand(LonghandId::BorderRightStyle)
-    {
-        match &contextual_decl.inner_decl {
-            PropertyDeclaration::BorderRightStyle(line_style) => *line_style,
-            _ => panic!("needed border-right-style property declaration"),
-        }
-    } else {
-        border_side_initial_style()
-    };
+/// Computes an inherited property ahead of the node's other properties.  `initial` is used for
+/// the `initial` keyword; the other CSS-wide keywords and no declaration at all take `inherited`.
+/// `computed_value` extracts the value from the property's declaration, and falls back to
+/// `inherited` too if the declaration is for some other property.
+fn early_inherited_value<T>(
+    node: &NodeRef,
+    longhand: LonghandId,
+    context: &ComputeContext,
+    initial: T,
+    inherited: T,
+    computed_value: impl FnOnce(PropertyDeclaration) -> Option<T>,
+) -> T {
+    match cascaded_declaration(node, longhand, context) {
+        Some(PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+            keyword: CssWideKeywords::Initial,
+            ..
+        })) => initial,
+        Some(PropertyDeclaration::CssWideKeyword(_)) | None => inherited,
+        Some(decl) => computed_value(decl).unwrap_or(inherited),
+    }
+}
 
-    let top = if let Some(contextual_decl) = node
-        .contextual_decls()
-        .get_by_longhand(LonghandId::BorderTopStyle)
-    {
-        match &contextual_decl.inner_decl {
-            PropertyDeclaration::BorderTopStyle(line_style) => *line_style,
-            _ => panic!("needed border-top-style property declaration"),
+fn compute_border_styles_early(node: NodeRef, context: &ComputeContext) -> BorderSideStyleContext {
+    let parent = context.parent_computed_values;
+    let border_style = |longhand: LonghandId, parent_style: LineStyle| {
+        match cascaded_declaration(&node, longhand, context) {
+            Some(decl) => match &decl {
+                PropertyDeclaration::BorderBottomStyle(line_style)
+                | PropertyDeclaration::BorderLeftStyle(line_style)
+                | PropertyDeclaration::BorderRightStyle(line_style)
+                | PropertyDeclaration::BorderTopStyle(line_style) => *line_style,
+                PropertyDeclaration::CssWideKeyword(WideKeywordDeclaration {
+                    keyword: CssWideKeywords::Inherit,
+                    ..
+                }) => parent_style,
+                // Border styles aren't inherited, so the remaining keywords all mean the initial
+                // value.
+                PropertyDeclaration::CssWideKeyword(_) => border_side_initial_style(),
+                _ => panic!("needed {:?} property declaration", longhand),
+            },
+            None => border_side_initial_style(),
         }
-    } else {
-        border_side_initial_style()
     };
 
     BorderSideStyleContext {
-        bottom,
-        left,
-        right,
-        top,
+        bottom: border_style(LonghandId::BorderBottomStyle, parent.border_bottom_style),
+        left: border_style(LonghandId::BorderLeftStyle, parent.border_left_style),
+        right: border_style(LonghandId::BorderRightStyle, parent.border_right_style),
+        top: border_style(LonghandId::BorderTopStyle, parent.border_top_style),
     }
 }
@@ -9,6 +9,8 @@ Kosmonaut matches Servo's license, MPL 2.0. https://mozilla.org/MPL/2.0/ */
 use crate::style::values::computed::length::CSSPixelLength;
 use crate::style::values::CSSFloat;
 use app_units::Au;
+use cssparser::ToCss;
+use std::fmt;
 
 /// A computed percentage.
 #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
@@ -38,3 +40,12 @@ impl Percentage {
         CSSPixelLength::from(Au::from(val).scale_by(self.0))
     }
 }
+
+impl ToCss for Percentage {
+    fn to_css<W>(&self, dest: &mut W) -> fmt::Result
+    where
+        W: fmt::Write,
+    {
+        write!(dest, "{}%", self.0 * 100.)
+    }
+}
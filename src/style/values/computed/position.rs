@@ -0,0 +1,49 @@
+use crate::style::values::computed::{ComputeContext, ValueDefault};
+use crate::style::StyleParseErrorKind;
+use cssparser::{ParseError, Parser};
+
+/// Computed `position` values, which determine the positioning scheme a box is laid out with, and
+/// whether the inset properties (`top`, `right`, `bottom` and `left`) apply to it.
+///
+/// https://www.w3.org/TR/CSS2/visuren.html#choose-position
+/// https://drafts.csswg.org/css-position-3/#position-property
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Position {
+    Static,
+    Relative,
+    Absolute,
+    Fixed,
+    Sticky,
+}
+
+impl Position {
+    pub fn initial_value() -> Position {
+        Position::Static
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        try_match_ident_ignore_ascii_case! { input,
+            "static" => Ok(Position::Static),
+            "relative" => Ok(Position::Relative),
+            "absolute" => Ok(Position::Absolute),
+            "fixed" => Ok(Position::Fixed),
+            "sticky" => Ok(Position::Sticky),
+        }
+    }
+
+    /// Whether boxes with this position are taken out of the normal flow, and placed by their
+    /// insets within their containing block instead.
+    pub fn is_absolutely_positioned(self) -> bool {
+        matches!(self, Position::Absolute | Position::Fixed)
+    }
+}
+
+impl ValueDefault for Position {
+    type ComputedValue = Position;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        Position::initial_value()
+    }
+}
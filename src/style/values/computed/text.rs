@@ -0,0 +1,166 @@
+use crate::style::values::computed::{ComputeContext, ValueDefault};
+use crate::style::StyleParseErrorKind;
+use cssparser::{ParseError, Parser};
+
+/// How white space inside an element is collapsed, and whether lines may wrap at it.
+///
+/// https://drafts.csswg.org/css-text-3/#white-space-property
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WhiteSpace {
+    Normal,
+    Pre,
+    Nowrap,
+    PreWrap,
+    BreakSpaces,
+    PreLine,
+}
+
+impl WhiteSpace {
+    pub fn initial_value() -> WhiteSpace {
+        WhiteSpace::Normal
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        try_match_ident_ignore_ascii_case! { input,
+            "normal" => Ok(WhiteSpace::Normal),
+            "pre" => Ok(WhiteSpace::Pre),
+            "nowrap" => Ok(WhiteSpace::Nowrap),
+            "pre-wrap" => Ok(WhiteSpace::PreWrap),
+            "break-spaces" => Ok(WhiteSpace::BreakSpaces),
+            "pre-line" => Ok(WhiteSpace::PreLine),
+        }
+    }
+
+    /// Whether sequences of spaces and tabs collapse into a single space.
+    pub fn collapses_spaces(self) -> bool {
+        matches!(
+            self,
+            WhiteSpace::Normal | WhiteSpace::Nowrap | WhiteSpace::PreLine
+        )
+    }
+
+    /// Whether segment breaks (newlines in the source) are kept as forced line breaks, rather
+    /// than being transformed into spaces.
+    pub fn preserves_segment_breaks(self) -> bool {
+        !matches!(self, WhiteSpace::Normal | WhiteSpace::Nowrap)
+    }
+
+    /// Whether lines may wrap at soft wrap opportunities.
+    pub fn wraps(self) -> bool {
+        !matches!(self, WhiteSpace::Nowrap | WhiteSpace::Pre)
+    }
+}
+
+impl ValueDefault for WhiteSpace {
+    type ComputedValue = WhiteSpace;
+
+    fn value_default(context: &ComputeContext) -> Self::ComputedValue {
+        context.parent_computed_values.white_space
+    }
+}
+
+/// Whether lines may break between letters within words.
+///
+/// https://drafts.csswg.org/css-text-3/#word-break-property
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WordBreak {
+    Normal,
+    BreakAll,
+    KeepAll,
+}
+
+impl WordBreak {
+    pub fn initial_value() -> WordBreak {
+        WordBreak::Normal
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        try_match_ident_ignore_ascii_case! { input,
+            "normal" => Ok(WordBreak::Normal),
+            "break-all" => Ok(WordBreak::BreakAll),
+            "keep-all" => Ok(WordBreak::KeepAll),
+        }
+    }
+}
+
+impl ValueDefault for WordBreak {
+    type ComputedValue = WordBreak;
+
+    fn value_default(context: &ComputeContext) -> Self::ComputedValue {
+        context.parent_computed_values.word_break
+    }
+}
+
+/// Whether a line may break within an otherwise unbreakable word to keep it from overflowing.
+/// `anywhere` and `break-word` only differ in how they affect min-content sizes.
+///
+/// https://drafts.csswg.org/css-text-3/#overflow-wrap-property
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OverflowWrap {
+    Normal,
+    Anywhere,
+    BreakWord,
+}
+
+impl OverflowWrap {
+    pub fn initial_value() -> OverflowWrap {
+        OverflowWrap::Normal
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        try_match_ident_ignore_ascii_case! { input,
+            "normal" => Ok(OverflowWrap::Normal),
+            "anywhere" => Ok(OverflowWrap::Anywhere),
+            "break-word" => Ok(OverflowWrap::BreakWord),
+        }
+    }
+}
+
+impl ValueDefault for OverflowWrap {
+    type ComputedValue = OverflowWrap;
+
+    fn value_default(context: &ComputeContext) -> Self::ComputedValue {
+        context.parent_computed_values.overflow_wrap
+    }
+}
+
+/// Whether words may be hyphenated when breaking lines.  There's no hyphenation dictionary, so
+/// `auto` only hyphenates where `manual` would, at soft hyphens (`&shy;`).
+///
+/// https://drafts.csswg.org/css-text-3/#hyphens-property
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Hyphens {
+    None,
+    Manual,
+    Auto,
+}
+
+impl Hyphens {
+    pub fn initial_value() -> Hyphens {
+        Hyphens::Manual
+    }
+
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        try_match_ident_ignore_ascii_case! { input,
+            "none" => Ok(Hyphens::None),
+            "manual" => Ok(Hyphens::Manual),
+            "auto" => Ok(Hyphens::Auto),
+        }
+    }
+}
+
+impl ValueDefault for Hyphens {
+    type ComputedValue = Hyphens;
+
+    fn value_default(context: &ComputeContext) -> Self::ComputedValue {
+        context.parent_computed_values.hyphens
+    }
+}
@@ -1,11 +1,9 @@
-use crate::style::values::computed::length::{LengthPercentage, LengthPercentageOrAuto};
-use crate::style::values::computed::{
-    ComputeContext, ComputeValue, ComputeValueWithContext, ValueDefault,
-};
+use crate::style::values::computed::length::{LengthPercentageOrAuto, LengthPercentageOrNone};
+use crate::style::values::computed::{ComputeContext, ComputeValueWithContext, ValueDefault};
 use crate::style::values::specified;
 
 /// Computed value of a `width`.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct Width {
     pub size: LengthPercentageOrAuto,
 }
@@ -21,27 +19,11 @@ impl Width {
 impl ComputeValueWithContext for specified::Width {
     type ComputedValue = Width;
 
-    fn compute_value_with_context(&self, _context: &ComputeContext) -> Self::ComputedValue {
-        let computed_lp_auto: LengthPercentageOrAuto = match self {
-            // TODO: I think we repeat computing the value of specified::LengthPercentageOrAuto a lot...eventually consider
-            // simply implementing `ComputeValueWithContext` for specified::LengthPercentageOrAuto.
-            specified::Width::LengthPercentageOrAuto(lp_auto) => match lp_auto {
-                specified::LengthPercentageOrAuto::Auto => LengthPercentageOrAuto::Auto,
-                specified::LengthPercentageOrAuto::LengthPercentage(lp) => match lp {
-                    specified::LengthPercentage::Length(no_calc_length) => {
-                        no_calc_length.compute_value().into()
-                    }
-                    specified::LengthPercentage::Percentage(percentage) => {
-                        LengthPercentageOrAuto::LengthPercentage(LengthPercentage::Percentage(
-                            *percentage,
-                        ))
-                    }
-                },
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        match self {
+            specified::Width::LengthPercentageOrAuto(lp_auto) => Width {
+                size: lp_auto.compute_value_with_context(context),
             },
-        };
-
-        Width {
-            size: computed_lp_auto,
         }
     }
 }
@@ -53,3 +35,73 @@ impl ValueDefault for specified::Width {
         Width::initial_value()
     }
 }
+
+/// Computed value of a `min-width`.
+///
+/// For block boxes, `auto` behaves as zero.  https://www.w3.org/TR/css-sizing-3/#min-size-auto
+#[derive(Clone, Debug)]
+pub struct MinWidth {
+    pub size: LengthPercentageOrAuto,
+}
+
+impl MinWidth {
+    pub fn initial_value() -> MinWidth {
+        MinWidth {
+            size: LengthPercentageOrAuto::Auto,
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::MinWidth {
+    type ComputedValue = MinWidth;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        match self {
+            specified::MinWidth::LengthPercentageOrAuto(lp_auto) => MinWidth {
+                size: lp_auto.compute_value_with_context(context),
+            },
+        }
+    }
+}
+
+impl ValueDefault for specified::MinWidth {
+    type ComputedValue = MinWidth;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        MinWidth::initial_value()
+    }
+}
+
+/// Computed value of a `max-width`.
+#[derive(Clone, Debug)]
+pub struct MaxWidth {
+    pub size: LengthPercentageOrNone,
+}
+
+impl MaxWidth {
+    pub fn initial_value() -> MaxWidth {
+        MaxWidth {
+            size: LengthPercentageOrNone::None,
+        }
+    }
+}
+
+impl ComputeValueWithContext for specified::MaxWidth {
+    type ComputedValue = MaxWidth;
+
+    fn compute_value_with_context(&self, context: &ComputeContext) -> Self::ComputedValue {
+        match self {
+            specified::MaxWidth::LengthPercentageOrNone(lp_none) => MaxWidth {
+                size: lp_none.compute_value_with_context(context),
+            },
+        }
+    }
+}
+
+impl ValueDefault for specified::MaxWidth {
+    type ComputedValue = MaxWidth;
+
+    fn value_default(_context: &ComputeContext) -> Self::ComputedValue {
+        MaxWidth::initial_value()
+    }
+}
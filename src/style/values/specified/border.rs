@@ -1,4 +1,4 @@
-use crate::style::values::specified::{ColorUnit, NoCalcLength};
+use crate::style::values::specified::{AbsoluteLength, ColorUnit, LengthPercentage, NoCalcLength};
 use crate::style::StyleParseErrorKind;
 use cssparser::{ParseError, Parser, Token};
 
@@ -221,6 +221,15 @@ impl LineWidth {
                 }
                 Err(_) => return Err(location.new_unexpected_token_error(token.clone())),
             },
+            // Unitless zero is allowed as a length, as in `border: 0`.
+            Token::Number { value, .. } => {
+                if value == 0. {
+                    return Ok(LineWidth::Length(NoCalcLength::Absolute(
+                        AbsoluteLength::Px(0.),
+                    )));
+                }
+                return Err(location.new_unexpected_token_error(token.clone()));
+            }
             _ => {}
         };
         input.reset(&start);
@@ -231,3 +240,28 @@ impl LineWidth {
         }
     }
 }
+
+/// Specified `border-<corner>-radius` values: the horizontal and vertical radii of the quarter
+/// ellipse defining the shape of a corner.  If only one radius is given, it is used for both.
+///
+/// https://www.w3.org/TR/2017/CR-css-backgrounds-3-20171017/#the-border-radius
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderCornerRadius {
+    pub horizontal: LengthPercentage,
+    pub vertical: LengthPercentage,
+}
+
+impl BorderCornerRadius {
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let horizontal = LengthPercentage::parse(input)?;
+        let vertical = input
+            .try_parse(LengthPercentage::parse)
+            .unwrap_or_else(|_| horizontal.clone());
+        Ok(BorderCornerRadius {
+            horizontal,
+            vertical,
+        })
+    }
+}
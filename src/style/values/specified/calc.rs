@@ -0,0 +1,347 @@
+//! Specified `calc()`, `min()`, `max()` and `clamp()` math functions for lengths and percentages.
+//!
+//! https://drafts.csswg.org/css-values-4/#calc-notation
+
+use crate::style::values::specified::NoCalcLength;
+use crate::style::values::CSSFloat;
+use crate::style::StyleParseErrorKind;
+use cssparser::{ParseError, Parser, Token};
+
+/// A math function resolving to a `<length-percentage>`.
+///
+/// Numeric sub-expressions are folded while parsing, so multiplication and division only ever
+/// appear here as a `Scale` by a constant factor.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CalcLengthPercentage {
+    Length(NoCalcLength),
+    Percentage(CSSFloat),
+    Sum(Vec<CalcLengthPercentage>),
+    Scale(Box<CalcLengthPercentage>, CSSFloat),
+    Min(Vec<CalcLengthPercentage>),
+    Max(Vec<CalcLengthPercentage>),
+    Clamp {
+        min: Box<CalcLengthPercentage>,
+        center: Box<CalcLengthPercentage>,
+        max: Box<CalcLengthPercentage>,
+    },
+}
+
+/// The result of parsing a sub-expression, which may be a plain number until it is combined with
+/// a length or percentage.
+enum CalcNode {
+    Number(CSSFloat),
+    LengthPercentage(CalcLengthPercentage),
+}
+
+/// The math functions that can produce a `<length-percentage>`.
+#[derive(Clone, Copy, Debug)]
+enum MathFunction {
+    Calc,
+    Min,
+    Max,
+    Clamp,
+}
+
+impl MathFunction {
+    fn from_name(name: &str) -> Option<MathFunction> {
+        Some(match_ignore_ascii_case! { name,
+            "calc" => MathFunction::Calc,
+            "min" => MathFunction::Min,
+            "max" => MathFunction::Max,
+            "clamp" => MathFunction::Clamp,
+            _ => return None,
+        })
+    }
+}
+
+impl CalcLengthPercentage {
+    /// Whether `function_name` names a math function this module can parse.
+    pub fn is_math_function(function_name: &str) -> bool {
+        MathFunction::from_name(function_name).is_some()
+    }
+
+    /// Parses the arguments of the math function named `function_name`.  The parser should be
+    /// positioned just inside the function's parentheses.
+    pub fn parse_function<'i, 't>(
+        function_name: &str,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let location = input.current_source_location();
+        let function = MathFunction::from_name(function_name)
+            .ok_or_else(|| location.new_custom_error(StyleParseErrorKind::UnspecifiedError))?;
+        match CalcNode::parse_function_arguments(function, input)? {
+            CalcNode::LengthPercentage(calc) => Ok(calc),
+            // A math function resolving to a plain number isn't a valid `<length-percentage>`.
+            CalcNode::Number(_) => {
+                Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError))
+            }
+        }
+    }
+}
+
+impl CalcNode {
+    fn parse_function_arguments<'i, 't>(
+        function: MathFunction,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        match function {
+            MathFunction::Calc => CalcNode::parse_sum(input),
+            MathFunction::Min | MathFunction::Max => {
+                let location = input.current_source_location();
+                let args = input.parse_comma_separated(CalcNode::parse_sum)?;
+                let is_min = matches!(function, MathFunction::Min);
+                if args.iter().all(|arg| matches!(arg, CalcNode::Number(_))) {
+                    let numbers = args.into_iter().map(|arg| arg.number().unwrap());
+                    return Ok(CalcNode::Number(if is_min {
+                        numbers.fold(CSSFloat::INFINITY, CSSFloat::min)
+                    } else {
+                        numbers.fold(CSSFloat::NEG_INFINITY, CSSFloat::max)
+                    }));
+                }
+                let args = CalcNode::into_length_percentages(args).ok_or_else(|| {
+                    location.new_custom_error(StyleParseErrorKind::UnspecifiedError)
+                })?;
+                Ok(CalcNode::LengthPercentage(if is_min {
+                    CalcLengthPercentage::Min(args)
+                } else {
+                    CalcLengthPercentage::Max(args)
+                }))
+            }
+            MathFunction::Clamp => {
+                let location = input.current_source_location();
+                let min = CalcNode::parse_sum(input)?;
+                input.expect_comma()?;
+                let center = CalcNode::parse_sum(input)?;
+                input.expect_comma()?;
+                let max = CalcNode::parse_sum(input)?;
+                if let (CalcNode::Number(min), CalcNode::Number(center), CalcNode::Number(max)) =
+                    (&min, &center, &max)
+                {
+                    return Ok(CalcNode::Number(center.min(*max).max(*min)));
+                }
+                let mut args = CalcNode::into_length_percentages(vec![min, center, max])
+                    .ok_or_else(|| {
+                        location.new_custom_error(StyleParseErrorKind::UnspecifiedError)
+                    })?
+                    .into_iter()
+                    .map(Box::new);
+                Ok(CalcNode::LengthPercentage(CalcLengthPercentage::Clamp {
+                    min: args.next().unwrap(),
+                    center: args.next().unwrap(),
+                    max: args.next().unwrap(),
+                }))
+            }
+        }
+    }
+
+    /// Parses `<calc-sum> = <calc-product> [ [ '+' | '-' ] <calc-product> ]*`.
+    fn parse_sum<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let mut sum = CalcNode::parse_product(input)?;
+        loop {
+            let start = input.state();
+            // `+` and `-` must be surrounded by whitespace, otherwise they are part of a number.
+            match input.next_including_whitespace() {
+                Ok(&Token::WhiteSpace(_)) => {
+                    if input.is_exhausted() {
+                        break;
+                    }
+                }
+                _ => {
+                    input.reset(&start);
+                    break;
+                }
+            }
+            let location = input.current_source_location();
+            let negate = match *input.next()? {
+                Token::Delim('+') => false,
+                Token::Delim('-') => true,
+                ref token => return Err(location.new_unexpected_token_error(token.clone())),
+            };
+            let mut rhs = CalcNode::parse_product(input)?;
+            if negate {
+                rhs = rhs.scale(-1.);
+            }
+            sum = sum
+                .add(rhs)
+                .ok_or_else(|| location.new_custom_error(StyleParseErrorKind::UnspecifiedError))?;
+        }
+        Ok(sum)
+    }
+
+    /// Parses `<calc-product> = <calc-value> [ [ '*' | '/' ] <calc-value> ]*`.
+    fn parse_product<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let mut product = CalcNode::parse_value(input)?;
+        loop {
+            let start = input.state();
+            let location = input.current_source_location();
+            let divide = match input.next() {
+                Ok(&Token::Delim('*')) => false,
+                Ok(&Token::Delim('/')) => true,
+                _ => {
+                    input.reset(&start);
+                    break;
+                }
+            };
+            let rhs = CalcNode::parse_value(input)?;
+            product = match (product, rhs) {
+                (lhs, CalcNode::Number(divisor)) if divide => {
+                    if divisor == 0. {
+                        return Err(
+                            location.new_custom_error(StyleParseErrorKind::UnspecifiedError)
+                        );
+                    }
+                    lhs.scale(1. / divisor)
+                }
+                (CalcNode::Number(factor), rhs) if !divide => rhs.scale(factor),
+                (lhs, CalcNode::Number(factor)) if !divide => lhs.scale(factor),
+                // At least one side of a product, and the right side of a quotient, must be a
+                // number.
+                _ => return Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError)),
+            };
+        }
+        Ok(product)
+    }
+
+    /// Parses `<calc-value> = <number> | <dimension> | <percentage> | ( <calc-sum> )`, along with
+    /// nested math functions.
+    fn parse_value<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let location = input.current_source_location();
+        let token = input.next()?;
+        match *token {
+            Token::Number { value, .. } => Ok(CalcNode::Number(value)),
+            Token::Dimension {
+                value, ref unit, ..
+            } => NoCalcLength::parse_dimension(value, unit)
+                .map(|length| CalcNode::LengthPercentage(CalcLengthPercentage::Length(length)))
+                .map_err(|()| location.new_unexpected_token_error(token.clone())),
+            Token::Percentage { unit_value, .. } => Ok(CalcNode::LengthPercentage(
+                CalcLengthPercentage::Percentage(unit_value),
+            )),
+            Token::ParenthesisBlock => input.parse_nested_block(CalcNode::parse_sum),
+            Token::Function(ref name) => match MathFunction::from_name(name) {
+                Some(function) => input.parse_nested_block(|input| {
+                    CalcNode::parse_function_arguments(function, input)
+                }),
+                None => Err(location.new_unexpected_token_error(token.clone())),
+            },
+            _ => Err(location.new_unexpected_token_error(token.clone())),
+        }
+    }
+
+    fn number(&self) -> Option<CSSFloat> {
+        match self {
+            CalcNode::Number(number) => Some(*number),
+            CalcNode::LengthPercentage(_) => None,
+        }
+    }
+
+    fn scale(self, factor: CSSFloat) -> Self {
+        match self {
+            CalcNode::Number(number) => CalcNode::Number(number * factor),
+            CalcNode::LengthPercentage(calc) => {
+                CalcNode::LengthPercentage(CalcLengthPercentage::Scale(Box::new(calc), factor))
+            }
+        }
+    }
+
+    /// Adds two nodes, returning `None` if a number is added to a length or percentage.
+    fn add(self, other: Self) -> Option<Self> {
+        Some(match (self, other) {
+            (CalcNode::Number(lhs), CalcNode::Number(rhs)) => CalcNode::Number(lhs + rhs),
+            (CalcNode::LengthPercentage(lhs), CalcNode::LengthPercentage(rhs)) => {
+                let mut terms = match lhs {
+                    CalcLengthPercentage::Sum(terms) => terms,
+                    lhs => vec![lhs],
+                };
+                terms.push(rhs);
+                CalcNode::LengthPercentage(CalcLengthPercentage::Sum(terms))
+            }
+            _ => return None,
+        })
+    }
+
+    /// Unwraps a list of arguments that must all be lengths or percentages.
+    fn into_length_percentages(nodes: Vec<CalcNode>) -> Option<Vec<CalcLengthPercentage>> {
+        nodes
+            .into_iter()
+            .map(|node| match node {
+                CalcNode::LengthPercentage(calc) => Some(calc),
+                CalcNode::Number(_) => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::values::specified::AbsoluteLength;
+    use cssparser::ParserInput;
+
+    fn parse(css: &str) -> Result<CalcLengthPercentage, ()> {
+        let mut input = ParserInput::new(css);
+        let mut parser = Parser::new(&mut input);
+        parser
+            .parse_entirely(|input| {
+                let name = input.expect_function()?.clone();
+                input.parse_nested_block(|input| CalcLengthPercentage::parse_function(&name, input))
+            })
+            .map_err(|_| ())
+    }
+
+    fn px(px: CSSFloat) -> CalcLengthPercentage {
+        CalcLengthPercentage::Length(NoCalcLength::Absolute(AbsoluteLength::Px(px)))
+    }
+
+    #[test]
+    fn parses_sums_and_products() {
+        assert_eq!(
+            parse("calc(100% - 2 * 16px)"),
+            Ok(CalcLengthPercentage::Sum(vec![
+                CalcLengthPercentage::Percentage(1.),
+                CalcLengthPercentage::Scale(
+                    Box::new(CalcLengthPercentage::Scale(Box::new(px(16.)), 2.)),
+                    -1.
+                ),
+            ]))
+        );
+        // Numeric sub-expressions are folded.
+        assert_eq!(
+            parse("calc((1 + 3) * 10px / 2)"),
+            Ok(CalcLengthPercentage::Scale(
+                Box::new(CalcLengthPercentage::Scale(Box::new(px(10.)), 4.)),
+                0.5
+            ))
+        );
+        assert_eq!(
+            parse("min(50%, max(10px, 20px), calc(1px))"),
+            Ok(CalcLengthPercentage::Min(vec![
+                CalcLengthPercentage::Percentage(0.5),
+                CalcLengthPercentage::Max(vec![px(10.), px(20.)]),
+                px(1.),
+            ]))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        // Lengths and numbers can't be added.
+        assert!(parse("calc(10px + 2)").is_err());
+        // Two lengths can't be multiplied, nor can anything be divided by a length.
+        assert!(parse("calc(10px * 2px)").is_err());
+        assert!(parse("calc(2 / 10px)").is_err());
+        assert!(parse("calc(10px / 0)").is_err());
+        // Addition requires whitespace around the operator.
+        assert!(parse("calc(10px +2px)").is_err());
+        // A math function must resolve to a length or percentage, not a plain number.
+        assert!(parse("calc(2 * 3)").is_err());
+        assert!(parse("clamp(1px, 2px)").is_err());
+        assert!(parse("var(--a)").is_err());
+    }
+}
@@ -11,21 +11,13 @@ use cssparser::{
 pub enum Color {
     /// A `<color>`-unit.
     Unit(ColorUnit),
-    /// The `inherit` keyword.
-    Inherit,
 }
 
 impl Color {
     pub fn parse<'i, 't>(
         input: &mut Parser<'i, 't>,
     ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
-        if let Ok(color_unit) = input.try_parse(|i| ColorUnit::parse(i)) {
-            return Ok(Color::Unit(color_unit));
-        };
-
-        try_match_ident_ignore_ascii_case! { input,
-            "inherit" => Ok(Color::Inherit),
-        }
+        Ok(Color::Unit(ColorUnit::parse(input)?))
     }
 
     /// According to https://www.w3.org/TR/css-color-3/#foreground, the initial value of the `color`
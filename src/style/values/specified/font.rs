@@ -2,6 +2,7 @@ use crate::style::StyleParseErrorKind;
 use cssparser::{ParseError, Parser};
 
 use crate::style::values::specified::length::LengthPercentage;
+use crate::style::values::CSSFloat;
 
 /// The default font size.
 pub const FONT_MEDIUM_PX: i32 = 16;
@@ -67,3 +68,70 @@ impl FontSize {
         FontSize::Keyword(KeywordSize::Medium)
     }
 }
+
+/// A specified `font-weight` value.
+///
+/// https://drafts.csswg.org/css-fonts-4/#font-weight-prop
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FontWeight {
+    /// A numeric weight between 1 and 1000.  `normal` and `bold` are parsed as 400 and 700.
+    Absolute(CSSFloat),
+    /// One weight bolder than the parent's.
+    Bolder,
+    /// One weight lighter than the parent's.
+    Lighter,
+}
+
+impl FontWeight {
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let location = input.current_source_location();
+        if let Ok(weight) = input.try_parse(|i| i.expect_number()) {
+            if (1. ..=1000.).contains(&weight) {
+                return Ok(FontWeight::Absolute(weight));
+            }
+            return Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+        }
+        try_match_ident_ignore_ascii_case! { input,
+            "normal" => Ok(FontWeight::Absolute(400.)),
+            "bold" => Ok(FontWeight::Absolute(700.)),
+            "bolder" => Ok(FontWeight::Bolder),
+            "lighter" => Ok(FontWeight::Lighter),
+        }
+    }
+}
+
+/// A specified `line-height` value.
+///
+/// https://drafts.csswg.org/css2/#propdef-line-height
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineHeight {
+    /// A line height derived from the metrics of the used font.
+    Normal,
+    /// A multiple of the font size.  Descendants inherit the number itself, so it scales with
+    /// their own font size.
+    Number(CSSFloat),
+    /// A length, or a percentage of the font size.
+    Length(LengthPercentage),
+}
+
+impl LineHeight {
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        let location = input.current_source_location();
+        if let Ok(number) = input.try_parse(|i| i.expect_number()) {
+            if number < 0. {
+                return Err(location.new_custom_error(StyleParseErrorKind::UnspecifiedError));
+            }
+            return Ok(LineHeight::Number(number));
+        }
+        if let Ok(lp) = input.try_parse(LengthPercentage::parse) {
+            return Ok(LineHeight::Length(lp));
+        }
+        try_match_ident_ignore_ascii_case! { input,
+            "normal" => Ok(LineHeight::Normal),
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use crate::gfx::font::FontMetrics;
+use crate::style::values::specified::calc::CalcLengthPercentage;
 use crate::style::values::{computed, CSSFloat};
 use crate::style::StyleParseErrorKind;
 use cssparser::{ParseError, Parser, Token};
@@ -26,6 +28,14 @@ pub enum NoCalcLength {
     ///
     /// <https://drafts.csswg.org/css-values/#absolute-length>
     Absolute(AbsoluteLength),
+    /// A font-relative length
+    ///
+    /// <https://drafts.csswg.org/css-values/#font-relative-lengths>
+    FontRelative(FontRelativeLength),
+    /// A viewport-percentage length
+    ///
+    /// <https://drafts.csswg.org/css-values/#viewport-relative-lengths>
+    ViewportPercentage(ViewportPercentageLength),
 }
 
 /// Represents an absolute length with its unit
@@ -80,6 +90,91 @@ impl AbsoluteLength {
     }
 }
 
+/// Represents a font-relative length with its unit
+/// <https://drafts.csswg.org/css-values/#font-relative-lengths>
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FontRelativeLength {
+    /// A length relative to the font size of the element (em)
+    Em(CSSFloat),
+    /// A length relative to the font size of the root element (rem)
+    Rem(CSSFloat),
+    /// A length relative to the x-height of the element's font (ex)
+    Ex(CSSFloat),
+    /// A length relative to the advance of the "0" glyph in the element's font (ch)
+    Ch(CSSFloat),
+    /// A length relative to the line height of the element (lh)
+    Lh(CSSFloat),
+}
+
+/// The used value of `line-height: normal`, as a multiple of the font size.  Kosmonaut doesn't
+/// support `line-height` yet, so `lh` units always assume this value.
+pub const NORMAL_LINE_HEIGHT: CSSFloat = 1.2;
+
+impl FontRelativeLength {
+    /// Convert this into a pixel value, given the relevant font sizes in pixels.  If `metrics` are
+    /// unavailable, `ex` and `ch` fall back to half an `em`, as the spec allows.
+    pub fn to_px(
+        self,
+        font_size: CSSFloat,
+        root_font_size: CSSFloat,
+        metrics: Option<FontMetrics>,
+    ) -> CSSFloat {
+        let x_height = metrics.and_then(|metrics| metrics.x_height).unwrap_or(0.5);
+        let zero_advance = metrics
+            .and_then(|metrics| metrics.zero_advance)
+            .unwrap_or(0.5);
+        match self {
+            FontRelativeLength::Em(value) => value * font_size,
+            FontRelativeLength::Rem(value) => value * root_font_size,
+            FontRelativeLength::Ex(value) => value * x_height * font_size,
+            FontRelativeLength::Ch(value) => value * zero_advance * font_size,
+            FontRelativeLength::Lh(value) => value * NORMAL_LINE_HEIGHT * font_size,
+        }
+    }
+}
+
+/// Represents a viewport-percentage length with its unit
+/// <https://drafts.csswg.org/css-values/#viewport-relative-lengths>
+///
+/// The small (`sv*`), large (`lv*`) and dynamic (`dv*`) variants only differ when the UA has
+/// interface elements (like a retractable URL bar) that change the size of the viewport.  Kosmonaut
+/// has none of those, so every variant resolves against the same viewport as its unprefixed unit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ViewportPercentageLength {
+    /// 1% of the viewport width (vw, svw, lvw, dvw)
+    Vw(CSSFloat),
+    /// 1% of the viewport height (vh, svh, lvh, dvh)
+    Vh(CSSFloat),
+    /// 1% of the smaller of the viewport's dimensions (vmin, svmin, lvmin, dvmin)
+    Vmin(CSSFloat),
+    /// 1% of the larger of the viewport's dimensions (vmax, svmax, lvmax, dvmax)
+    Vmax(CSSFloat),
+}
+
+impl ViewportPercentageLength {
+    /// Parse a viewport-percentage unit, ignoring any `s`, `l` or `d` prefix.
+    fn parse_dimension(value: CSSFloat, unit: &str) -> Result<Self, ()> {
+        Ok(match_ignore_ascii_case! { unit,
+            "vw" | "svw" | "lvw" | "dvw" => ViewportPercentageLength::Vw(value),
+            "vh" | "svh" | "lvh" | "dvh" => ViewportPercentageLength::Vh(value),
+            "vmin" | "svmin" | "lvmin" | "dvmin" => ViewportPercentageLength::Vmin(value),
+            "vmax" | "svmax" | "lvmax" | "dvmax" => ViewportPercentageLength::Vmax(value),
+            _ => return Err(())
+        })
+    }
+
+    /// Convert this into a pixel value, given the size of the viewport in pixels.
+    pub fn to_px(self, viewport_width: CSSFloat, viewport_height: CSSFloat) -> CSSFloat {
+        let (value, basis) = match self {
+            ViewportPercentageLength::Vw(value) => (value, viewport_width),
+            ViewportPercentageLength::Vh(value) => (value, viewport_height),
+            ViewportPercentageLength::Vmin(value) => (value, viewport_width.min(viewport_height)),
+            ViewportPercentageLength::Vmax(value) => (value, viewport_width.max(viewport_height)),
+        };
+        value * basis / 100.
+    }
+}
+
 impl NoCalcLength {
     /// Parse a given absolute or relative dimension.
     pub fn parse_dimension(value: CSSFloat, unit: &str) -> Result<Self, ()> {
@@ -91,7 +186,14 @@ impl NoCalcLength {
             "q" => NoCalcLength::Absolute(AbsoluteLength::Q(value)),
             "pt" => NoCalcLength::Absolute(AbsoluteLength::Pt(value)),
             "pc" => NoCalcLength::Absolute(AbsoluteLength::Pc(value)),
-            _ => return Err(())
+            "em" => NoCalcLength::FontRelative(FontRelativeLength::Em(value)),
+            "rem" => NoCalcLength::FontRelative(FontRelativeLength::Rem(value)),
+            "ex" => NoCalcLength::FontRelative(FontRelativeLength::Ex(value)),
+            "ch" => NoCalcLength::FontRelative(FontRelativeLength::Ch(value)),
+            "lh" => NoCalcLength::FontRelative(FontRelativeLength::Lh(value)),
+            _ => NoCalcLength::ViewportPercentage(
+                ViewportPercentageLength::parse_dimension(value, unit)?
+            )
         })
     }
 }
@@ -99,15 +201,12 @@ impl NoCalcLength {
 /// A `<length-percentage>` value. This can be either a `<length>`, a
 /// `<percentage>`, or a combination of both via `calc()`.
 ///
-/// TODO: We don't yet support calc expressions.  If we did, we would need a specified::LengthPercentage
-/// that had a Calc(Box<CalcLengthPercentage>) variant.
-///
 /// https://drafts.csswg.org/css-values-4/#typedef-length-percentage
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum LengthPercentage {
     Length(NoCalcLength),
     Percentage(computed::Percentage),
-    //    Calc(Box<CalcLengthPercentage>),
+    Calc(Box<CalcLengthPercentage>),
 }
 
 impl LengthPercentage {
@@ -125,6 +224,22 @@ impl LengthPercentage {
             Token::Percentage { unit_value, .. } => Ok(LengthPercentage::Percentage(
                 computed::Percentage(unit_value),
             )),
+            // Unitless zero is a valid length.
+            Token::Number { value, .. } => {
+                if value == 0. {
+                    Ok(LengthPercentage::Length(NoCalcLength::Absolute(
+                        AbsoluteLength::Px(0.),
+                    )))
+                } else {
+                    Err(location.new_unexpected_token_error(token.clone()))
+                }
+            }
+            Token::Function(ref name) if CalcLengthPercentage::is_math_function(name) => {
+                let name = name.clone();
+                input
+                    .parse_nested_block(|i| CalcLengthPercentage::parse_function(&name, i))
+                    .map(|calc| LengthPercentage::Calc(Box::new(calc)))
+            }
             _ => Err(location.new_unexpected_token_error(token.clone())),
         }
     }
@@ -133,7 +248,7 @@ impl LengthPercentage {
 /// A `<length-percentage>` value, or the `auto` keyword.
 ///
 /// Some details on `auto`: https://www.w3.org/TR/css-sizing-3/#sizing-values
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum LengthPercentageOrAuto {
     LengthPercentage(LengthPercentage),
     Auto,
@@ -143,30 +258,33 @@ impl LengthPercentageOrAuto {
     pub fn parse<'i, 't>(
         input: &mut Parser<'i, 't>,
     ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
-        let start = input.state();
-        let location = input.current_source_location();
-        let token = input.next()?;
-        match *token {
-            Token::Dimension {
-                value, ref unit, ..
-            } => match NoCalcLength::parse_dimension(value, unit) {
-                Ok(no_calc_len) => {
-                    return Ok(LengthPercentageOrAuto::LengthPercentage(
-                        LengthPercentage::Length(no_calc_len),
-                    ))
-                }
-                Err(_) => return Err(location.new_unexpected_token_error(token.clone())),
-            },
-            Token::Percentage { unit_value, .. } => {
-                return Ok(LengthPercentageOrAuto::LengthPercentage(
-                    LengthPercentage::Percentage(computed::Percentage(unit_value)),
-                ));
-            }
-            _ => {}
-        };
-        input.reset(&start);
+        if let Ok(lp) = input.try_parse(LengthPercentage::parse) {
+            return Ok(LengthPercentageOrAuto::LengthPercentage(lp));
+        }
         try_match_ident_ignore_ascii_case! { input,
             "auto" => Ok(LengthPercentageOrAuto::Auto),
         }
     }
 }
+
+/// A `<length-percentage>` value, or the `none` keyword.
+///
+/// https://www.w3.org/TR/css-sizing-3/#max-size-properties
+#[derive(Clone, Debug, PartialEq)]
+pub enum LengthPercentageOrNone {
+    LengthPercentage(LengthPercentage),
+    None,
+}
+
+impl LengthPercentageOrNone {
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        if let Ok(lp) = input.try_parse(LengthPercentage::parse) {
+            return Ok(LengthPercentageOrNone::LengthPercentage(lp));
+        }
+        try_match_ident_ignore_ascii_case! { input,
+            "none" => Ok(LengthPercentageOrNone::None),
+        }
+    }
+}
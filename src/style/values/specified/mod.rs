@@ -1,8 +1,10 @@
 pub mod background;
 pub mod border;
+pub mod calc;
 pub mod color;
 pub mod font;
 pub mod height;
+pub mod inset;
 pub mod length;
 pub mod margin;
 pub mod padding;
@@ -12,6 +14,7 @@ pub use background::BackgroundColor;
 
 pub use border::BorderBottomColor;
 pub use border::BorderBottomWidth;
+pub use border::BorderCornerRadius;
 pub use border::BorderLeftColor;
 pub use border::BorderLeftWidth;
 pub use border::BorderRightColor;
@@ -19,18 +22,25 @@ pub use border::BorderRightWidth;
 pub use border::BorderTopColor;
 pub use border::BorderTopWidth;
 
+pub use calc::CalcLengthPercentage;
+
 pub use color::Color;
 pub use color::ColorUnit;
 
-pub use font::FontSize;
 pub use font::FONT_MEDIUM_PX;
+pub use font::{FontSize, FontWeight, LineHeight};
+
+pub use height::{Height, MaxHeight, MinHeight};
 
-pub use height::Height;
+pub use inset::{Bottom, Left, Right, Top};
 
 pub use length::AbsoluteLength;
+pub use length::FontRelativeLength;
 pub use length::LengthPercentage;
 pub use length::LengthPercentageOrAuto;
+pub use length::LengthPercentageOrNone;
 pub use length::NoCalcLength;
+pub use length::ViewportPercentageLength;
 
 pub use margin::MarginBottom;
 pub use margin::MarginLeft;
@@ -42,4 +52,4 @@ pub use padding::PaddingLeft;
 pub use padding::PaddingRight;
 pub use padding::PaddingTop;
 
-pub use width::Width;
+pub use width::{MaxWidth, MinWidth, Width};
@@ -1,4 +1,4 @@
-use crate::style::values::specified::LengthPercentageOrAuto;
+use crate::style::values::specified::{LengthPercentageOrAuto, LengthPercentageOrNone};
 use crate::style::StyleParseErrorKind;
 use cssparser::{ParseError, Parser};
 
@@ -6,7 +6,7 @@ use cssparser::{ParseError, Parser};
 ///
 /// https://www.w3.org/TR/css-sizing-3/#property-index
 // TODO: Need to support various other value types, such as `{min, max}-content`.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Width {
     LengthPercentageOrAuto(LengthPercentageOrAuto),
 }
@@ -20,3 +20,39 @@ impl Width {
             .map(Width::LengthPercentageOrAuto)
     }
 }
+
+/// Specified values for the `min-width` property.
+///
+/// https://www.w3.org/TR/css-sizing-3/#min-size-properties
+#[derive(Clone, Debug)]
+pub enum MinWidth {
+    LengthPercentageOrAuto(LengthPercentageOrAuto),
+}
+
+impl MinWidth {
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        input
+            .try_parse(|i| LengthPercentageOrAuto::parse(i))
+            .map(MinWidth::LengthPercentageOrAuto)
+    }
+}
+
+/// Specified values for the `max-width` property.
+///
+/// https://www.w3.org/TR/css-sizing-3/#max-size-properties
+#[derive(Clone, Debug)]
+pub enum MaxWidth {
+    LengthPercentageOrNone(LengthPercentageOrNone),
+}
+
+impl MaxWidth {
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self, ParseError<'i, StyleParseErrorKind<'i>>> {
+        input
+            .try_parse(|i| LengthPercentageOrNone::parse(i))
+            .map(MaxWidth::LengthPercentageOrNone)
+    }
+}
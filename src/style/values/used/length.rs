@@ -22,6 +22,7 @@ impl ToPx for LengthPercentage {
         match self {
             LengthPercentage::Length(len) => *len,
             LengthPercentage::Percentage(percentage) => percentage.px_relative_to(containing_size),
+            LengthPercentage::Calc(calc) => calc.resolve(containing_size),
         }
     }
 }
@@ -8,6 +8,29 @@ fn vertical_lr_block_box_only() {
         .arg("--files")
         .arg("tests/websrc/directional/writing_mode/vertical-lr-block-box-only.html")
         .arg("tests/websrc/directional/writing_mode/vertical-lr-block-box-only.css")
-        .succeeds();
+        .succeeds()
+        .no_stderr();
+    assert_snapshot!(dump_layout_cmd.stdout());
+}
+
+#[test]
+fn vertical_lr_min_max_sizes() {
+    let mut dump_layout_cmd = dump_layout_cmd();
+    dump_layout_cmd
+        .arg("--files")
+        .arg("tests/websrc/directional/writing_mode/vertical-lr-min-max-sizes.html")
+        .succeeds()
+        .no_stderr();
+    assert_snapshot!(dump_layout_cmd.stdout());
+}
+
+#[test]
+fn vertical_lr_logical_properties() {
+    let mut dump_layout_cmd = dump_layout_cmd();
+    dump_layout_cmd
+        .arg("--files")
+        .arg("tests/websrc/directional/writing_mode/vertical-lr-logical-properties.html")
+        .succeeds()
+        .no_stderr();
     assert_snapshot!(dump_layout_cmd.stdout());
 }
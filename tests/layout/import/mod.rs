@@ -0,0 +1,41 @@
+use crate::util::dump_layout_cmd_with_viewport;
+use insta::assert_snapshot;
+
+#[test]
+fn imports_cascade_before_importing_sheet() {
+    let mut dump_layout_cmd = dump_layout_cmd_with_viewport(500., 800., 1.);
+    dump_layout_cmd
+        .arg("--files")
+        .arg("tests/websrc/import/imports.html")
+        .succeeds()
+        .no_stderr();
+    assert_snapshot!(dump_layout_cmd.stdout());
+}
+
+#[test]
+fn media_qualified_import_applies_when_matching() {
+    let mut dump_layout_cmd = dump_layout_cmd_with_viewport(1200., 800., 1.);
+    dump_layout_cmd
+        .arg("--files")
+        .arg("tests/websrc/import/imports.html")
+        .succeeds()
+        .no_stderr();
+    assert_snapshot!(dump_layout_cmd.stdout());
+}
+
+#[test]
+fn import_cycles_are_broken() {
+    let mut dump_layout_cmd = dump_layout_cmd_with_viewport(500., 800., 1.);
+    dump_layout_cmd
+        .arg("--files")
+        .arg("tests/websrc/import/cycle.html")
+        .succeeds();
+    // The import that would close the cycle is logged as it's skipped.
+    let stderr = dump_layout_cmd.stderr();
+    assert!(
+        stderr.contains("cycle/a.css"),
+        "unexpected stderr: {}",
+        stderr
+    );
+    assert_snapshot!(dump_layout_cmd.stdout());
+}
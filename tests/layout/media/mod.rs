@@ -0,0 +1,33 @@
+use crate::util::dump_layout_cmd_with_viewport;
+use insta::assert_snapshot;
+
+fn dump_breakpoints_layout(width: f32, height: f32, scale_factor: f32) -> String {
+    let mut dump_layout_cmd = dump_layout_cmd_with_viewport(width, height, scale_factor);
+    dump_layout_cmd
+        .arg("--files")
+        .arg("tests/websrc/media/breakpoints.html")
+        .succeeds()
+        .no_stderr();
+    dump_layout_cmd.stdout().to_owned()
+}
+
+#[test]
+fn breakpoints_narrow_portrait() {
+    assert_snapshot!(dump_breakpoints_layout(500., 800., 1.));
+}
+
+#[test]
+fn breakpoints_medium() {
+    assert_snapshot!(dump_breakpoints_layout(1000., 800., 1.));
+}
+
+#[test]
+fn breakpoints_wide() {
+    assert_snapshot!(dump_breakpoints_layout(1920., 1080., 1.));
+}
+
+#[test]
+fn breakpoints_evaluated_in_css_pixels() {
+    // 1920 device pixels at a scale factor of 2 is a 960 CSS pixel wide viewport.
+    assert_snapshot!(dump_breakpoints_layout(1920., 1080., 2.));
+}
@@ -1,4 +1,7 @@
 pub mod directional;
+pub mod import;
+pub mod media;
+pub mod viewport;
 
 #[cfg(test)]
 mod tests {
@@ -28,4 +31,282 @@ mod tests {
             .no_stderr();
         assert_snapshot!(dump_layout_cmd.stdout());
     }
+
+    #[test]
+    fn inline_style_beats_selectors() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/inline-style.html")
+            .arg("tests/websrc/inline-style.css")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn embedded_styles_in_document_order() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/embedded-style.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn calc_math_functions() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/calc.html")
+            .succeeds();
+        // The invalid declaration is logged as it's dropped, and nothing else is.
+        let stderr = dump_layout_cmd.stderr();
+        assert!(
+            stderr.contains("calc(100% + 2)"),
+            "unexpected stderr: {}",
+            stderr
+        );
+        assert_eq!(
+            stderr.matches("parse_err").count(),
+            1,
+            "unexpected stderr: {}",
+            stderr
+        );
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn css_wide_keywords() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/css-wide-keywords.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn custom_properties() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/custom-properties.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn font_relative_lengths() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/font-relative-lengths.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn ex_and_ch_units() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/ex-and-ch-units.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn min_max_sizes() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/min-max-sizes.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn box_sizing() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/box-sizing.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+    #[test]
+    fn logical_properties() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/logical-properties.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn border_shorthands() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/border-shorthands.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn linked_styles_interleaved_with_embedded() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/linked-style.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn inline_formatting_context() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/inline-formatting-context.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn white_space_processing() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/white-space.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn line_breaking() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/line-breaking.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn block_in_inline() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/block-in-inline.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn margin_collapsing() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/margin-collapsing.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn inline_block_and_flow_root() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/inline-block-and-flow-root.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn floats() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/floats.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn block_formatting_contexts_below_floats() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/bfc-below-floats.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn positioning() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/positioning.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn positioning_static_and_inline() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/positioning-static-and-inline.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn scrolled_positioning() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--scroll_y")
+            .arg("200")
+            .arg("--files")
+            .arg("tests/websrc/scrolled-positioning.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
+
+    #[test]
+    fn bidi() {
+        let mut dump_layout_cmd = dump_layout_cmd();
+        dump_layout_cmd
+            .arg("--files")
+            .arg("tests/websrc/bidi.html")
+            .succeeds()
+            .no_stderr();
+        assert_snapshot!(dump_layout_cmd.stdout());
+    }
 }
@@ -0,0 +1,28 @@
+use crate::util::dump_layout_cmd_with_viewport;
+use insta::assert_snapshot;
+
+fn dump_viewport_percentage_layout(width: f32, height: f32, scale_factor: f32) -> String {
+    let mut dump_layout_cmd = dump_layout_cmd_with_viewport(width, height, scale_factor);
+    dump_layout_cmd
+        .arg("--files")
+        .arg("tests/websrc/viewport-percentage-lengths.html")
+        .succeeds()
+        .no_stderr();
+    dump_layout_cmd.stdout().to_owned()
+}
+
+#[test]
+fn viewport_percentage_lengths_landscape() {
+    assert_snapshot!(dump_viewport_percentage_layout(1000., 800., 1.));
+}
+
+#[test]
+fn viewport_percentage_lengths_portrait() {
+    assert_snapshot!(dump_viewport_percentage_layout(800., 1000., 1.));
+}
+
+#[test]
+fn viewport_percentage_lengths_in_css_pixels() {
+    // 1000 device pixels at a scale factor of 2 is a 500 CSS pixel wide viewport.
+    assert_snapshot!(dump_viewport_percentage_layout(1000., 800., 2.));
+}
@@ -177,6 +177,10 @@ impl CommandUnderTest {
     pub fn stdout(&self) -> &str {
         &self.stdout
     }
+
+    pub fn stderr(&self) -> &str {
+        &self.stderr
+    }
 }
 
 impl fmt::Debug for CommandUnderTest {
@@ -185,18 +189,30 @@ impl fmt::Debug for CommandUnderTest {
     }
 }
 
-pub fn dump_layout_cmd_scaled(scale_factor: f32) -> CommandUnderTest {
+pub fn dump_layout_cmd_with_viewport(
+    inner_window_width: f32,
+    inner_window_height: f32,
+    scale_factor: f32,
+) -> CommandUnderTest {
     let mut cmd = CommandUnderTest::new();
     cmd.arg("dump-layout");
     cmd.arg("--width");
-    cmd.arg(format!("{}", LAYOUT_DUMP_INNER_WINDOW_WIDTH_PX));
+    cmd.arg(format!("{}", inner_window_width));
     cmd.arg("--height");
-    cmd.arg(format!("{}", LAYOUT_DUMP_INNER_WINDOW_HEIGHT_PX));
+    cmd.arg(format!("{}", inner_window_height));
     cmd.arg("--scale_factor");
     cmd.arg(format!("{}", scale_factor));
     cmd
 }
 
+pub fn dump_layout_cmd_scaled(scale_factor: f32) -> CommandUnderTest {
+    dump_layout_cmd_with_viewport(
+        LAYOUT_DUMP_INNER_WINDOW_WIDTH_PX,
+        LAYOUT_DUMP_INNER_WINDOW_HEIGHT_PX,
+        scale_factor,
+    )
+}
+
 pub fn dump_layout_cmd() -> CommandUnderTest {
     dump_layout_cmd_scaled(LAYOUT_DUMP_DEFAULT_SCALE_FACTOR)
 }